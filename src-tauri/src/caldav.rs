@@ -0,0 +1,359 @@
+// src-tauri/src/caldav.rs
+
+// Klien CalDAV (RFC 4791) untuk sinkronisasi task dan acara dengan Radicale, Nextcloud, dll.
+// Perubahan di server dibaca lewat REPORT sync-collection (RFC 6578) supaya cukup mengambil yang
+// berubah; server tanpa dukungan itu dibaca lewat daftar ETag dari PROPFIND. Service sinkronisasi
+// hanya bergantung pada trait `CalDavClient`, jadi bisa dites dengan server tiruan.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use roxmltree::{Document, Node};
+use std::time::Duration;
+use url::Url;
+use crate::model::caldav::{CalDavConfig, RemoteCalendar};
+
+const DAV: &str = "DAV:";
+const CALDAV: &str = "urn:ietf:params:xml:ns:caldav";
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalDavError {
+    // Server membalas dengan status selain 2xx, mis. 412 jika ETag-nya sudah berubah
+    Status(u16),
+    // Gagal terhubung atau membaca balasan
+    Transport(String),
+    // Balasan bukan XML multistatus yang bisa dibaca
+    InvalidResponse(String),
+}
+
+impl std::fmt::Display for CalDavError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CalDavError::Status(401) => f.write_str("The CalDAV server rejected the username or password"),
+            CalDavError::Status(412) => f.write_str("The item was changed on the CalDAV server"),
+            CalDavError::Status(code) => write!(f, "The CalDAV server responded with status {}", code),
+            CalDavError::Transport(message) => write!(f, "Could not reach the CalDAV server: {}", message),
+            CalDavError::InvalidResponse(message) => write!(f, "Invalid response from the CalDAV server: {}", message),
+        }
+    }
+}
+
+// Resource di kalender beserta ETag-nya
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteRef {
+    pub href: String,
+    pub etag: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteChanges {
+    // Resource yang baru atau berubah
+    pub changed: Vec<RemoteRef>,
+    // Href resource yang dihapus sejak token sebelumnya
+    pub removed: Vec<String>,
+    // `changed` berisi seluruh isi kalender (sinkronisasi pertama atau token kedaluwarsa);
+    // resource yang tidak ada di daftar berarti sudah dihapus di server
+    pub complete: bool,
+    // Token untuk sinkronisasi berikutnya; kosong jika server tidak mendukung sync-collection
+    pub sync_token: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteItem {
+    pub href: String,
+    pub etag: Option<String>,
+    // Isi .ics
+    pub data: String,
+}
+
+pub trait CalDavClient {
+    // Kalender milik akun di alamat server
+    fn discover(&self) -> Result<Vec<RemoteCalendar>, CalDavError>;
+    // Perubahan sejak `sync_token`. Tanpa token, atau jika token sudah tidak berlaku,
+    // yang dikembalikan adalah seluruh isi kalender.
+    fn changes(&self, calendar: &str, sync_token: Option<&str>) -> Result<RemoteChanges, CalDavError>;
+    // Isi beberapa resource sekaligus; href yang tidak ditemukan dilewati
+    fn fetch(&self, calendar: &str, hrefs: &[String]) -> Result<Vec<RemoteItem>, CalDavError>;
+    // Buat resource baru; status 412 jika href sudah dipakai. Mengembalikan ETag baru jika dikirim server.
+    fn create(&self, href: &str, data: &str) -> Result<Option<String>, CalDavError>;
+    // Timpa resource. Dengan `etag`, status 412 jika resource sudah berubah di server.
+    fn update(&self, href: &str, data: &str, etag: Option<&str>) -> Result<Option<String>, CalDavError>;
+    // Hapus resource; resource yang sudah tidak ada dianggap berhasil dihapus
+    fn delete(&self, href: &str, etag: Option<&str>) -> Result<(), CalDavError>;
+}
+
+// Alamat server harus URL http/https lengkap
+pub fn parse_server_url(value: &str) -> Result<Url, String> {
+    let url = Url::parse(value.trim()).map_err(|_| format!("Invalid server URL '{}'", value.trim()))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err("The server URL must start with http:// or https://".to_string());
+    }
+    Ok(url)
+}
+
+// Implementasi lewat HTTP dengan Basic auth
+pub struct HttpCalDavClient {
+    agent: ureq::Agent,
+    base: Url,
+    authorization: Option<String>,
+}
+
+impl HttpCalDavClient {
+    pub fn new(config: &CalDavConfig) -> Result<Self, String> {
+        let base = parse_server_url(&config.server_url)?;
+        let authorization = (!config.username.is_empty()).then(|| {
+            format!("Basic {}", STANDARD.encode(format!("{}:{}", config.username, config.password)))
+        });
+        Ok(HttpCalDavClient {
+            agent: ureq::AgentBuilder::new().timeout(TIMEOUT).build(),
+            base,
+            authorization,
+        })
+    }
+
+    fn request(&self, method: &str, href: &str, headers: &[(&str, &str)], body: Option<&str>) -> Result<ureq::Response, CalDavError> {
+        let url = self.base.join(href).map_err(|e| CalDavError::InvalidResponse(format!("bad href '{}': {}", href, e)))?;
+        let mut request = self.agent.request_url(method, &url);
+        if let Some(authorization) = &self.authorization {
+            request = request.set("Authorization", authorization);
+        }
+        for (name, value) in headers {
+            request = request.set(name, value);
+        }
+        let result = match body {
+            Some(body) => request.send_string(body),
+            None => request.call(),
+        };
+        match result {
+            Ok(response) => Ok(response),
+            Err(ureq::Error::Status(code, _)) => Err(CalDavError::Status(code)),
+            Err(e) => Err(CalDavError::Transport(e.to_string())),
+        }
+    }
+
+    // PROPFIND atau REPORT; mengembalikan isi balasan multistatus
+    fn xml_request(&self, method: &str, href: &str, depth: &str, body: &str) -> Result<String, CalDavError> {
+        let headers = [("Content-Type", "application/xml; charset=utf-8"), ("Depth", depth)];
+        self.request(method, href, &headers, Some(body))?
+            .into_string()
+            .map_err(|e| CalDavError::Transport(e.to_string()))
+    }
+
+    fn propfind(&self, href: &str, depth: &str, props: &str) -> Result<String, CalDavError> {
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?><d:propfind xmlns:d="DAV:" xmlns:c="{}"><d:prop>{}</d:prop></d:propfind>"#,
+            CALDAV, props
+        );
+        self.xml_request("PROPFIND", href, depth, &body)
+    }
+
+    // Href di dalam properti `name` milik `href`, mis. current-user-principal
+    fn find_href(&self, href: &str, namespace: &str, name: &str, prop: &str) -> Result<Option<String>, CalDavError> {
+        let body = self.propfind(href, "0", prop)?;
+        let document = parse_xml(&body)?;
+        let found = responses(&document).iter()
+            .filter_map(|response| response.prop(namespace, name))
+            .find_map(|prop| child(prop, DAV, "href").and_then(|href| href.text()).map(normalize_href));
+        Ok(found)
+    }
+
+    fn sync_collection(&self, calendar: &str, sync_token: Option<&str>) -> Result<RemoteChanges, CalDavError> {
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?><d:sync-collection xmlns:d="DAV:"><d:sync-token>{}</d:sync-token><d:sync-level>1</d:sync-level><d:prop><d:getetag/></d:prop></d:sync-collection>"#,
+            escape_xml(sync_token.unwrap_or(""))
+        );
+        let body = self.xml_request("REPORT", calendar, "0", &body)?;
+        let document = parse_xml(&body)?;
+        let mut changes = RemoteChanges {
+            changed: Vec::new(),
+            removed: Vec::new(),
+            complete: sync_token.is_none(),
+            sync_token: child(document.root_element(), DAV, "sync-token").and_then(|t| t.text()).map(|t| t.trim().to_string()),
+        };
+        for response in responses(&document) {
+            if same_collection(&response.href, calendar) {
+                continue;
+            }
+            if response.status == Some(404) {
+                changes.removed.push(response.href);
+            } else {
+                let etag = response.text(DAV, "getetag");
+                changes.changed.push(RemoteRef { href: response.href, etag });
+            }
+        }
+        Ok(changes)
+    }
+
+    // Seluruh isi kalender lewat PROPFIND, untuk server tanpa sync-collection
+    fn list(&self, calendar: &str) -> Result<RemoteChanges, CalDavError> {
+        let body = self.propfind(calendar, "1", "<d:getetag/>")?;
+        let document = parse_xml(&body)?;
+        let changed = responses(&document).into_iter()
+            .filter(|response| !same_collection(&response.href, calendar))
+            .map(|response| RemoteRef { etag: response.text(DAV, "getetag"), href: response.href })
+            .collect();
+        Ok(RemoteChanges { changed, removed: Vec::new(), complete: true, sync_token: None })
+    }
+}
+
+impl CalDavClient for HttpCalDavClient {
+    fn discover(&self) -> Result<Vec<RemoteCalendar>, CalDavError> {
+        let start = self.base.path().to_string();
+        let principal = self.find_href(&start, DAV, "current-user-principal", "<d:current-user-principal/>")?
+            .unwrap_or(start);
+        let home = self.find_href(&principal, CALDAV, "calendar-home-set", "<c:calendar-home-set/>")?
+            .unwrap_or(principal);
+        let body = self.propfind(&home, "1", "<d:resourcetype/><d:displayname/><c:supported-calendar-component-set/>")?;
+        let document = parse_xml(&body)?;
+
+        let mut calendars = Vec::new();
+        for response in responses(&document) {
+            let is_calendar = response.prop(DAV, "resourcetype").is_some_and(|types| child(types, CALDAV, "calendar").is_some());
+            if !is_calendar {
+                continue;
+            }
+            let components: Vec<String> = response.prop(CALDAV, "supported-calendar-component-set")
+                .map(|set| set.children()
+                    .filter(|c| c.has_tag_name((CALDAV, "comp")))
+                    .filter_map(|c| c.attribute("name"))
+                    .map(|name| name.to_uppercase())
+                    .collect())
+                .unwrap_or_default();
+            // Tanpa daftar komponen, kalender dianggap menerima semuanya
+            let supports = |name: &str| components.is_empty() || components.iter().any(|c| c == name);
+            let name = response.text(DAV, "displayname")
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| response.href.trim_end_matches('/').rsplit('/').next().unwrap_or_default().to_string());
+            calendars.push(RemoteCalendar {
+                name,
+                supports_tasks: supports("VTODO"),
+                supports_events: supports("VEVENT"),
+                href: response.href,
+            });
+        }
+        calendars.sort_by(|a, b| (&a.name, &a.href).cmp(&(&b.name, &b.href)));
+        Ok(calendars)
+    }
+
+    fn changes(&self, calendar: &str, sync_token: Option<&str>) -> Result<RemoteChanges, CalDavError> {
+        match self.sync_collection(calendar, sync_token) {
+            // Token kedaluwarsa (precondition valid-sync-token): ambil ulang seluruh isi kalender
+            Err(CalDavError::Status(403 | 409)) if sync_token.is_some() => self.changes(calendar, None),
+            Err(CalDavError::Status(400 | 403 | 405 | 501)) => self.list(calendar),
+            result => result,
+        }
+    }
+
+    fn fetch(&self, calendar: &str, hrefs: &[String]) -> Result<Vec<RemoteItem>, CalDavError> {
+        if hrefs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let hrefs: String = hrefs.iter().map(|href| format!("<d:href>{}</d:href>", escape_xml(href))).collect();
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?><c:calendar-multiget xmlns:d="DAV:" xmlns:c="{}"><d:prop><d:getetag/><c:calendar-data/></d:prop>{}</c:calendar-multiget>"#,
+            CALDAV, hrefs
+        );
+        let body = self.xml_request("REPORT", calendar, "1", &body)?;
+        let document = parse_xml(&body)?;
+        let items = responses(&document).into_iter()
+            .filter_map(|response| {
+                let data = response.prop(CALDAV, "calendar-data")?.text()?.to_string();
+                Some(RemoteItem { etag: response.text(DAV, "getetag"), href: response.href, data })
+            })
+            .collect();
+        Ok(items)
+    }
+
+    fn create(&self, href: &str, data: &str) -> Result<Option<String>, CalDavError> {
+        let headers = [("Content-Type", "text/calendar; charset=utf-8"), ("If-None-Match", "*")];
+        let response = self.request("PUT", href, &headers, Some(data))?;
+        Ok(response.header("ETag").map(str::to_string))
+    }
+
+    fn update(&self, href: &str, data: &str, etag: Option<&str>) -> Result<Option<String>, CalDavError> {
+        let mut headers = vec![("Content-Type", "text/calendar; charset=utf-8")];
+        if let Some(etag) = etag {
+            headers.push(("If-Match", etag));
+        }
+        let response = self.request("PUT", href, &headers, Some(data))?;
+        Ok(response.header("ETag").map(str::to_string))
+    }
+
+    fn delete(&self, href: &str, etag: Option<&str>) -> Result<(), CalDavError> {
+        let headers: Vec<(&str, &str)> = etag.map(|etag| ("If-Match", etag)).into_iter().collect();
+        match self.request("DELETE", href, &headers, None) {
+            Ok(_) | Err(CalDavError::Status(404 | 410)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+// Satu <response> dari balasan multistatus
+struct DavResponse<'a, 'input> {
+    href: String,
+    // Status di level response, mis. 404 untuk resource yang sudah dihapus
+    status: Option<u16>,
+    // Properti dari propstat yang berstatus 2xx
+    props: Vec<Node<'a, 'input>>,
+}
+
+impl<'a, 'input> DavResponse<'a, 'input> {
+    fn prop(&self, namespace: &str, name: &str) -> Option<Node<'a, 'input>> {
+        self.props.iter().copied().find(|prop| prop.has_tag_name((namespace, name)))
+    }
+
+    fn text(&self, namespace: &str, name: &str) -> Option<String> {
+        self.prop(namespace, name).and_then(|prop| prop.text()).map(|text| text.trim().to_string())
+    }
+}
+
+fn parse_xml(body: &str) -> Result<Document<'_>, CalDavError> {
+    Document::parse(body).map_err(|e| CalDavError::InvalidResponse(e.to_string()))
+}
+
+fn responses<'a, 'input>(document: &'a Document<'input>) -> Vec<DavResponse<'a, 'input>> {
+    document.root_element().children()
+        .filter(|node| node.has_tag_name((DAV, "response")))
+        .filter_map(|response| {
+            let href = child(response, DAV, "href")?.text()?;
+            let mut props = Vec::new();
+            for propstat in response.children().filter(|node| node.has_tag_name((DAV, "propstat"))) {
+                let ok = child(propstat, DAV, "status").and_then(|s| s.text()).and_then(status_code).is_some_and(|code| (200..300).contains(&code));
+                if let Some(prop) = child(propstat, DAV, "prop").filter(|_| ok) {
+                    props.extend(prop.children().filter(|node| node.is_element()));
+                }
+            }
+            Some(DavResponse {
+                href: normalize_href(href),
+                status: child(response, DAV, "status").and_then(|s| s.text()).and_then(status_code),
+                props,
+            })
+        })
+        .collect()
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, namespace: &str, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name((namespace, name)))
+}
+
+// "HTTP/1.1 404 Not Found" -> 404
+fn status_code(line: &str) -> Option<u16> {
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+// Sebagian server mengirim href sebagai URL lengkap; simpan path-nya saja
+fn normalize_href(href: &str) -> String {
+    let href = href.trim();
+    match Url::parse(href) {
+        Ok(url) if url.has_host() => url.path().to_string(),
+        _ => href.to_string(),
+    }
+}
+
+fn same_collection(href: &str, collection: &str) -> bool {
+    href.trim_end_matches('/') == collection.trim_end_matches('/')
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
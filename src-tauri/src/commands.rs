@@ -1,132 +1,562 @@
 // src-tauri/src/commands.rs
 
-// --- PERUBAHAN DIMULAI ---
 // Import State dari Tauri untuk state management
 use tauri::State;
-// --- PERUBAHAN SELESAI ---
+use tauri::http::{header, Response, StatusCode};
+use chrono::{Local, NaiveDate, Utc};
+use rusqlite::Connection;
 
-use crate::services::task::{TaskServiceImpl, TaskService};
-use crate::repository::task::SqliteTaskRepository;
+use crate::services::AppServices;
+use crate::services::task::TaskService;
 use crate::model::task::{Task, TaskPayload};
+use crate::model::quick_add::{parse_quick_add, QuickAddPreview};
+use crate::services::scoring::ScoringService;
+use crate::model::scoring::{ScoredTask, ScoringWeights};
+use crate::services::time_tracking::TimeTrackingService;
+use crate::model::time_entry::{TimeEntry, TimeEntryPayload, TimeReport};
+use crate::services::planner::PlannerService;
+use crate::model::planner::{PlanResult, PlannerConfig, ScheduleBlock};
+use crate::services::calendar::CalendarService;
+use crate::model::calendar_event::{CalendarEvent, CalendarEventPayload, CalendarItem};
+use crate::services::caldav::CalDavService;
+use crate::model::caldav::{CalDavConfig, RemoteCalendar, SyncSummary};
+use crate::caldav::HttpCalDavClient;
+use crate::model::task_history::TaskChange;
 
-use crate::services::note::{NoteServiceImpl, NoteService};
-use crate::repository::note::SqliteNoteRepository;
+use crate::services::note::NoteService;
 use crate::model::note::{Note, NotePayload};
+use crate::model::note_revision::{NoteDiff, NoteRevision};
 
-use r2d2::Pool;
-use r2d2_sqlite::SqliteConnectionManager;
+use crate::services::vault::VaultService;
+use crate::services::render::RenderService;
+use crate::services::note_export::NoteExportService;
+use crate::model::note_export::{NoteExportFormat, NoteExportSummary};
+use crate::services::note_task::NoteTaskService;
+use crate::model::note_task::NoteTask;
+use crate::model::vault::VaultSyncSummary;
 
-// Definisi tipe alias untuk Pool (tetap sama)
-pub type DbPool = Pool<SqliteConnectionManager>;
+use crate::services::archive::ArchiveService;
+use crate::model::archive::{ExportSummary, ImportSummary};
 
-// Struct untuk state aplikasi yang akan dikelola Tauri (tetap sama)
+use crate::services::task_csv::TaskCsvService;
+use crate::model::task_csv::{TaskColumn, CsvImportOptions, CsvImportReport};
+
+use crate::services::backup::BackupService;
+use crate::model::backup::{BackupConfig, BackupInfo};
+
+use crate::services::history::HistoryService;
+use crate::model::journal::HistoryStatus;
+
+use crate::services::trash::TrashService;
+use crate::model::trash::{PurgeSummary, TrashConfig, TrashListing};
+
+use crate::services::attachment::AttachmentService;
+use crate::model::attachment::{is_inline_safe, Attachment, AttachmentGcSummary, AttachmentOwner};
+
+use crate::db::{run_blocking, run_blocking_io, Database};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+// Struct untuk state aplikasi yang akan dikelola Tauri
 pub struct AppState {
-    pub pool: DbPool,
+    // Pool koneksi dibungkus Database agar bisa dibuka ulang saat restore backup
+    pub db: Arc<Database>,
+    // Service dibuat sekali saat startup, bukan di setiap command
+    pub services: Arc<AppServices>,
+    // Folder tempat file markdown notes disimpan
+    pub vault_dir: PathBuf,
+    // Folder tempat file backup database disimpan
+    pub backup_dir: PathBuf,
+}
+
+impl AppState {
+    // Semua command berjalan async: query SQLite dan IO file dijalankan di thread pool
+    // blocking lewat `run_blocking` supaya UI tidak ikut tertahan.
+    async fn run<T, F>(&self, f: F) -> Result<T, String>
+    where
+        F: FnOnce(&AppServices, &Connection) -> Result<T, String> + Send + 'static,
+        T: Send + 'static,
+    {
+        let services = self.services.clone();
+        run_blocking(self.db.clone(), move |conn| f(&services, conn)).await
+    }
 }
 
 // Task Management
 #[tauri::command]
-// --- PERUBAHAN DIMULAI ---
-// Tambahkan `state: State<AppState>` sebagai argumen pertama
-pub fn create_task(state: State<AppState>, payload: TaskPayload) -> Result<(), String> {
-    // Hapus baris ini: let conn = rusqlite::Connection::open("nocture.db").map_err(|e| e.to_string())?;
+pub async fn create_task(state: State<'_, AppState>, payload: TaskPayload) -> Result<(), String> {
+    state.run(move |services, conn| services.task.create_task(conn, payload)).await
+}
 
-    // Dapatkan koneksi dari pool yang dikelola oleh Tauri state
-    let conn = state.pool.get().map_err(|e| format!("Gagal mendapatkan koneksi dari pool: {}", e))?;
+#[tauri::command]
+pub async fn fetch_tasks(state: State<'_, AppState>) -> Result<Vec<Task>, String> {
+    state.run(move |services, conn| services.task.fetch_tasks(conn)).await
+}
 
-    // Buat instance service (tidak perlu field `conn` lagi di struct)
-    let service = TaskServiceImpl {
-        repository: SqliteTaskRepository,
-    };
+// Pratinjau task dari satu baris teks, mis. "Submit essay fri 5pm !high #study". Payload-nya
+// bisa langsung dikirim ke create_task setelah dikonfirmasi user.
+#[tauri::command]
+pub async fn preview_quick_add(input: String) -> Result<QuickAddPreview, String> {
+    parse_quick_add(&input, Local::now().naive_local())
+}
+
+// Task terbuka dengan skor tertinggi beserta rincian skornya
+#[tauri::command]
+pub async fn next_tasks(state: State<'_, AppState>, limit: usize) -> Result<Vec<ScoredTask>, String> {
+    state.run(move |services, conn| services.scoring.next_tasks(conn, limit)).await
+}
 
-    // Panggil method service dengan `conn` dari pool sebagai argumen
-    service.create_task(&conn, payload)
-    // --- PERUBAHAN SELESAI ---
+#[tauri::command]
+pub async fn get_scoring_weights(state: State<'_, AppState>) -> Result<ScoringWeights, String> {
+    state.run(move |services, conn| services.scoring.load_weights(conn)).await
 }
 
 #[tauri::command]
-// --- PERUBAHAN DIMULAI ---
-// Tambahkan `state: State<AppState>` sebagai argumen pertama
-pub fn fetch_tasks(state: State<AppState>) -> Result<Vec<Task>, String> {
-    // Hapus baris ini: let conn = rusqlite::Connection::open("nocture.db").map_err(|e| e.to_string())?;
+pub async fn update_scoring_weights(state: State<'_, AppState>, weights: ScoringWeights) -> Result<(), String> {
+    state.run(move |services, conn| services.scoring.save_weights(conn, &weights)).await
+}
 
-    // Dapatkan koneksi dari pool
-    let conn = state.pool.get().map_err(|e| format!("Gagal mendapatkan koneksi dari pool: {}", e))?;
+// Task yang baru selesai mengembalikan hasil reflow jadwal, termasuk task yang tidak muat lagi
+#[tauri::command]
+pub async fn update_task(state: State<'_, AppState>, task: Task) -> Result<Option<PlanResult>, String> {
+    // updated_at diisi service dengan waktu simpan (UTC)
+    state.run(move |services, conn| services.task.update_task(conn, task)).await
+}
 
-    // Buat instance service
-    let service = TaskServiceImpl {
-        repository: SqliteTaskRepository,
-    };
+#[tauri::command]
+pub async fn delete_task(state: State<'_, AppState>, id: i32) -> Result<(), String> {
+    state.run(move |services, conn| services.task.delete_task(conn, id)).await
+}
 
-    // Panggil method service dengan `conn` dari pool
-    service.fetch_tasks(&conn)
-    // --- PERUBAHAN SELESAI ---
+#[tauri::command]
+pub async fn get_task_history(state: State<'_, AppState>, task_id: i32) -> Result<Vec<TaskChange>, String> {
+    state.run(move |services, conn| services.task.fetch_task_history(conn, task_id)).await
 }
 
+// Time Tracking
 #[tauri::command]
-// --- PERUBAHAN DIMULAI ---
-// Tambahkan `state: State<AppState>` sebagai argumen pertama
-pub fn update_task(state: State<AppState>, task: Task) -> Result<(), String> {
-    // Hapus baris ini: let conn = rusqlite::Connection::open("nocture.db").map_err(|e| e.to_string())?;
+pub async fn add_time_entry(state: State<'_, AppState>, payload: TimeEntryPayload) -> Result<TimeEntry, String> {
+    state.run(move |services, conn| services.time_tracking.add_entry(conn, payload)).await
+}
 
-    // Dapatkan koneksi dari pool
-    let conn = state.pool.get().map_err(|e| format!("Gagal mendapatkan koneksi dari pool: {}", e))?;
+#[tauri::command]
+pub async fn fetch_time_entries(state: State<'_, AppState>, task_id: i32) -> Result<Vec<TimeEntry>, String> {
+    state.run(move |services, conn| services.time_tracking.fetch_entries(conn, task_id)).await
+}
 
-    // Buat instance service
-    let service = TaskServiceImpl {
-        repository: SqliteTaskRepository,
-    };
+#[tauri::command]
+pub async fn delete_time_entry(state: State<'_, AppState>, id: i32) -> Result<(), String> {
+    state.run(move |services, conn| services.time_tracking.delete_entry(conn, id)).await
+}
 
-    // Panggil method service dengan `conn` dari pool
-    // Pastikan field updated_at di `task` sudah diperbarui di frontend atau sebelum memanggil command ini jika perlu
-    service.update_task(&conn, task)
-    // --- PERUBAHAN SELESAI ---
+#[tauri::command]
+pub async fn start_timer(state: State<'_, AppState>, task_id: i32) -> Result<TimeEntry, String> {
+    state.run(move |services, conn| services.time_tracking.start_timer(conn, task_id)).await
 }
 
 #[tauri::command]
-// --- PERUBAHAN DIMULAI ---
-// Tambahkan `state: State<AppState>` sebagai argumen pertama
-pub fn delete_task(state: State<AppState>, id: i32) -> Result<(), String> {
-    // Hapus baris ini: let conn = rusqlite::Connection::open("nocture.db").map_err(|e| e.to_string())?;
+pub async fn stop_timer(state: State<'_, AppState>) -> Result<Option<TimeEntry>, String> {
+    state.run(move |services, conn| services.time_tracking.stop_timer(conn)).await
+}
 
-    // Dapatkan koneksi dari pool
-    let conn = state.pool.get().map_err(|e| format!("Gagal mendapatkan koneksi dari pool: {}", e))?;
+#[tauri::command]
+pub async fn get_running_timer(state: State<'_, AppState>) -> Result<Option<TimeEntry>, String> {
+    state.run(move |services, conn| services.time_tracking.running_timer(conn)).await
+}
 
-    // Buat instance service
-    let service = TaskServiceImpl {
-        repository: SqliteTaskRepository,
-    };
+// Tanggal "YYYY-MM-DD", keduanya inklusif
+#[tauri::command]
+pub async fn get_time_report(state: State<'_, AppState>, from: NaiveDate, to: NaiveDate) -> Result<TimeReport, String> {
+    state.run(move |services, conn| services.time_tracking.time_report(conn, from, to)).await
+}
+
+// Planner
+// Usulan jadwal `days` hari mulai `from` ("YYYY-MM-DD"); simpan lewat accept_schedule
+#[tauri::command]
+pub async fn propose_schedule(state: State<'_, AppState>, from: NaiveDate, days: u32) -> Result<PlanResult, String> {
+    state.run(move |services, conn| services.planner.propose(conn, from, days, Local::now().naive_local())).await
+}
+
+#[tauri::command]
+pub async fn accept_schedule(state: State<'_, AppState>, blocks: Vec<ScheduleBlock>) -> Result<Vec<ScheduleBlock>, String> {
+    state.run(move |services, conn| services.planner.save_blocks(conn, blocks)).await
+}
+
+#[tauri::command]
+pub async fn fetch_schedule(state: State<'_, AppState>, from: NaiveDate, to: NaiveDate) -> Result<Vec<ScheduleBlock>, String> {
+    state.run(move |services, conn| services.planner.fetch_schedule(conn, from, to)).await
+}
+
+#[tauri::command]
+pub async fn delete_schedule_block(state: State<'_, AppState>, id: i32) -> Result<(), String> {
+    state.run(move |services, conn| services.planner.delete_block(conn, id)).await
+}
+
+#[tauri::command]
+pub async fn reflow_schedule(state: State<'_, AppState>) -> Result<PlanResult, String> {
+    state.run(move |services, conn| services.planner.reflow(conn, Local::now().naive_local())).await
+}
+
+#[tauri::command]
+pub async fn get_planner_config(state: State<'_, AppState>) -> Result<PlannerConfig, String> {
+    state.run(move |services, conn| services.planner.load_config(conn)).await
+}
+
+#[tauri::command]
+pub async fn update_planner_config(state: State<'_, AppState>, config: PlannerConfig) -> Result<(), String> {
+    state.run(move |services, conn| services.planner.save_config(conn, &config)).await
+}
+
+// Calendar
+#[tauri::command]
+pub async fn create_event(state: State<'_, AppState>, payload: CalendarEventPayload) -> Result<CalendarEvent, String> {
+    state.run(move |services, conn| services.calendar.create_event(conn, payload)).await
+}
+
+#[tauri::command]
+pub async fn fetch_events(state: State<'_, AppState>) -> Result<Vec<CalendarEvent>, String> {
+    state.run(move |services, conn| services.calendar.fetch_events(conn)).await
+}
+
+#[tauri::command]
+pub async fn update_event(state: State<'_, AppState>, id: i32, payload: CalendarEventPayload) -> Result<CalendarEvent, String> {
+    state.run(move |services, conn| services.calendar.update_event(conn, id, payload)).await
+}
+
+#[tauri::command]
+pub async fn delete_event(state: State<'_, AppState>, id: i32) -> Result<(), String> {
+    state.run(move |services, conn| services.calendar.delete_event(conn, id)).await
+}
+
+// Acara (beserta pengulangannya) dan deadline task untuk tampilan bulan/minggu.
+// Tanggal "YYYY-MM-DD", keduanya inklusif.
+#[tauri::command]
+pub async fn calendar_range(state: State<'_, AppState>, start: NaiveDate, end: NaiveDate) -> Result<Vec<CalendarItem>, String> {
+    state.run(move |services, conn| services.calendar.calendar_range(conn, start, end)).await
+}
+
+// CalDAV
+#[tauri::command]
+pub async fn get_caldav_config(state: State<'_, AppState>) -> Result<CalDavConfig, String> {
+    state.run(move |services, conn| services.caldav.load_config(conn)).await
+}
+
+#[tauri::command]
+pub async fn update_caldav_config(state: State<'_, AppState>, config: CalDavConfig) -> Result<(), String> {
+    state.run(move |services, conn| services.caldav.save_config(conn, &config)).await
+}
+
+// Kalender di server dari pengaturan yang tersimpan, untuk dipilih sebagai kalender task/acara
+#[tauri::command]
+pub async fn discover_caldav_calendars(state: State<'_, AppState>) -> Result<Vec<RemoteCalendar>, String> {
+    state.run(move |services, conn| {
+        let client = HttpCalDavClient::new(&services.caldav.load_config(conn)?)?;
+        services.caldav.discover_calendars(&client)
+    }).await
+}
 
-    // Panggil method service dengan `conn` dari pool
-    service.delete_task(&conn, id)
-    // --- PERUBAHAN SELESAI ---
+#[tauri::command]
+pub async fn sync_caldav(state: State<'_, AppState>) -> Result<SyncSummary, String> {
+    state.run(move |services, conn| {
+        let client = HttpCalDavClient::new(&services.caldav.load_config(conn)?)?;
+        services.caldav.sync(conn, &client, Utc::now().naive_utc())
+    }).await
 }
 
 // Notes
 #[tauri::command]
-pub fn create_note(state: State<AppState>, payload: NotePayload) -> Result<(), String> {
-    let conn = state.pool.get().map_err(|e| format!("Gagal mendapatkan koneksi dari pool: {}", e))?;
-    let service = NoteServiceImpl { repository: SqliteNoteRepository };
-    service.create_note(&conn, payload)
+pub async fn create_note(state: State<'_, AppState>, payload: NotePayload) -> Result<(), String> {
+    state.run(move |services, conn| services.note.create_note(conn, payload)).await
+}
+
+#[tauri::command]
+pub async fn fetch_notes(state: State<'_, AppState>) -> Result<Vec<Note>, String> {
+    state.run(move |services, conn| services.note.fetch_notes(conn)).await
+}
+
+// Isi markdown note tanpa front-matter
+#[tauri::command]
+pub async fn read_note(state: State<'_, AppState>, id: i32) -> Result<String, String> {
+    state.run(move |services, conn| services.note.read_note(conn, id)).await
+}
+
+// Dengan `extract_tasks`, checkbox `- [ ]` di isi note dijadikan task dan disinkronkan
+#[tauri::command]
+pub async fn update_note(
+    state: State<'_, AppState>,
+    note: Note,
+    new_content: String,
+    extract_tasks: Option<bool>,
+) -> Result<(), String> {
+    state.run(move |services, conn| {
+        if extract_tasks.unwrap_or(false) {
+            services.note.update_note_with_tasks(conn, note, &new_content).map(|_| ())
+        } else {
+            services.note.update_note(conn, note, &new_content)
+        }
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn delete_note(state: State<'_, AppState>, id: i32) -> Result<(), String> {
+    state.run(move |services, conn| services.note.delete_note(conn, id)).await
 }
 
 #[tauri::command]
-pub fn fetch_notes(state: State<AppState>) -> Result<Vec<Note>, String> {
-    let conn = state.pool.get().map_err(|e| format!("Gagal mendapatkan koneksi dari pool: {}", e))?;
-    let service = NoteServiceImpl { repository: SqliteNoteRepository };
-    service.fetch_notes(&conn)
+pub async fn list_note_revisions(state: State<'_, AppState>, note_id: i32) -> Result<Vec<NoteRevision>, String> {
+    state.run(move |services, conn| services.note.list_revisions(conn, note_id)).await
 }
 
+// `to` kosong berarti dibandingkan dengan isi note saat ini
 #[tauri::command]
-pub fn update_note(state: State<AppState>, note: Note, new_content: String) -> Result<(), String> {
-    let conn = state.pool.get().map_err(|e| format!("Gagal mendapatkan koneksi dari pool: {}", e))?;
-    let service = NoteServiceImpl { repository: SqliteNoteRepository };
-    service.update_note(&conn, note, &new_content)
+pub async fn diff_note_revisions(
+    state: State<'_, AppState>,
+    note_id: i32,
+    from: i32,
+    to: Option<i32>,
+) -> Result<NoteDiff, String> {
+    state.run(move |services, conn| services.note.diff_revisions(conn, note_id, from, to)).await
 }
 
 #[tauri::command]
-pub fn delete_note(state: State<AppState>, id: i32) -> Result<(), String> {
-    let conn = state.pool.get().map_err(|e| format!("Gagal mendapatkan koneksi dari pool: {}", e))?;
-    let service = NoteServiceImpl { repository: SqliteNoteRepository };
-    service.delete_note(&conn, id)
-}
\ No newline at end of file
+pub async fn restore_note_revision(state: State<'_, AppState>, note_id: i32, revision_id: i32) -> Result<(), String> {
+    state.run(move |services, conn| services.note.restore_revision(conn, note_id, revision_id)).await
+}
+
+// Note lain yang menautkan note ini lewat [[Judul]]
+#[tauri::command]
+pub async fn get_note_backlinks(state: State<'_, AppState>, note_id: i32) -> Result<Vec<Note>, String> {
+    state.run(move |services, conn| services.note.fetch_backlinks(conn, note_id)).await
+}
+
+// Note yang menautkan task ini lewat [[task:id]]
+#[tauri::command]
+pub async fn get_task_backlinks(state: State<'_, AppState>, task_id: i32) -> Result<Vec<Note>, String> {
+    state.run(move |services, conn| services.note.fetch_task_backlinks(conn, task_id)).await
+}
+
+// Note dan baris checkbox asal task, jika task dibuat dari checkbox note
+#[tauri::command]
+pub async fn get_task_source(state: State<'_, AppState>, task_id: i32) -> Result<Option<NoteTask>, String> {
+    state.run(move |services, conn| services.note_task.fetch_task_source(conn, task_id)).await
+}
+
+// HTML note yang sudah disanitasi; link relatif menunjuk ke protocol `vault://`
+#[tauri::command]
+pub async fn render_note(state: State<'_, AppState>, id: i32) -> Result<String, String> {
+    state.run(move |services, conn| services.render.render_note(conn, id)).await
+}
+
+// Ekspor note sesuai urutan `note_ids` ke satu file HTML mandiri atau PDF, lengkap dengan daftar isi
+#[tauri::command]
+pub async fn export_notes(
+    state: State<'_, AppState>,
+    note_ids: Vec<i32>,
+    format: NoteExportFormat,
+    dest_path: String,
+) -> Result<NoteExportSummary, String> {
+    state.run(move |services, conn| services.note_export.export_notes(conn, &note_ids, format, Path::new(&dest_path))).await
+}
+
+// Bangun ulang index notes dari front-matter semua file di vault
+#[tauri::command]
+pub async fn reindex_vault(state: State<'_, AppState>) -> Result<VaultSyncSummary, String> {
+    state.run(move |services, conn| services.vault.reindex(conn)).await
+}
+
+// Attachments
+// File di `source_path` disalin ke folder lampiran vault
+#[tauri::command]
+pub async fn add_attachment(
+    state: State<'_, AppState>,
+    owner: AttachmentOwner,
+    owner_id: i32,
+    source_path: String,
+) -> Result<Attachment, String> {
+    state.run(move |services, conn| services.attachment.add_attachment(conn, owner, owner_id, Path::new(&source_path))).await
+}
+
+#[tauri::command]
+pub async fn list_attachments(state: State<'_, AppState>, owner: AttachmentOwner, owner_id: i32) -> Result<Vec<Attachment>, String> {
+    state.run(move |services, conn| services.attachment.list_attachments(conn, owner, owner_id)).await
+}
+
+#[tauri::command]
+pub async fn remove_attachment(state: State<'_, AppState>, id: i32) -> Result<(), String> {
+    state.run(move |services, conn| services.attachment.remove_attachment(conn, id)).await
+}
+
+#[tauri::command]
+pub async fn collect_attachment_garbage(state: State<'_, AppState>) -> Result<AttachmentGcSummary, String> {
+    state.run(move |services, conn| services.attachment.collect_garbage(conn)).await
+}
+
+// Isi lampiran untuk protocol `attachment://localhost/<id>`, supaya frontend bisa
+// menampilkannya langsung di <img>/<a> tanpa lewat invoke
+pub fn attachment_response(db: &Database, services: &AppServices, path: &str) -> Response<Vec<u8>> {
+    let Ok(id) = path.trim_matches('/').parse::<i32>() else {
+        return protocol_response(StatusCode::BAD_REQUEST, "text/plain", b"Invalid attachment id".to_vec());
+    };
+    match db.get().and_then(|conn| services.attachment.read_attachment(&conn, id)) {
+        Ok((attachment, data)) => file_response(&attachment.mime_type, &attachment.file_name, data),
+        Err(e) => protocol_response(StatusCode::NOT_FOUND, "text/plain", e.into_bytes()),
+    }
+}
+
+// File di vault untuk protocol `vault://localhost/<path>`, dipakai gambar dan link relatif
+// di HTML hasil render_note
+pub fn vault_response(services: &AppServices, path: &str) -> Response<Vec<u8>> {
+    match services.render.read_vault_file(path) {
+        Ok((mime, data)) => {
+            let file_name = path.rsplit('/').next().unwrap_or_default();
+            file_response(&mime, &percent_encoding::percent_decode_str(file_name).decode_utf8_lossy(), data)
+        }
+        Err(e) => protocol_response(StatusCode::NOT_FOUND, "text/plain", e.into_bytes()),
+    }
+}
+
+// File lampiran dan vault berasal dari luar aplikasi, jadi tidak boleh menjalankan script
+// di webview: jenisnya tidak ditebak ulang browser, CSP menolak script, dan jenis yang
+// bisa menjalankan script dikirim sebagai unduhan application/octet-stream
+const FILE_CONTENT_SECURITY_POLICY: &str = "default-src 'none'; img-src 'self' data:; media-src 'self'; style-src 'unsafe-inline'";
+
+fn file_response(mime: &str, file_name: &str, body: Vec<u8>) -> Response<Vec<u8>> {
+    let inline = is_inline_safe(mime);
+    // Header hanya boleh berisi ASCII yang terlihat
+    let file_name: String = file_name
+        .chars()
+        .map(|c| if (c.is_ascii_graphic() && !matches!(c, '"' | '\\')) || c == ' ' { c } else { '_' })
+        .collect();
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, if inline { mime } else { "application/octet-stream" })
+        .header(header::CONTENT_DISPOSITION, format!(
+            "{}; filename=\"{}\"",
+            if inline { "inline" } else { "attachment" },
+            file_name
+        ))
+        .header(header::X_CONTENT_TYPE_OPTIONS, "nosniff")
+        .header(header::CONTENT_SECURITY_POLICY, FILE_CONTENT_SECURITY_POLICY)
+        .body(body)
+        .unwrap_or_default()
+}
+
+fn protocol_response(status: StatusCode, mime: &str, body: Vec<u8>) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, mime)
+        .body(body)
+        .unwrap_or_default()
+}
+
+// Backup & Restore
+#[tauri::command]
+pub async fn export_archive(state: State<'_, AppState>, dest_path: String) -> Result<ExportSummary, String> {
+    state.run(move |services, conn| services.archive.export_archive(conn, Path::new(&dest_path))).await
+}
+
+#[tauri::command]
+pub async fn import_archive(state: State<'_, AppState>, archive_path: String) -> Result<ImportSummary, String> {
+    let vault_dir = state.vault_dir.clone();
+    state.run(move |services, conn| services.archive.import_archive(conn, Path::new(&archive_path), &vault_dir)).await
+}
+
+// CSV
+#[tauri::command]
+pub async fn export_tasks_csv(state: State<'_, AppState>, dest_path: String, columns: Vec<TaskColumn>) -> Result<(), String> {
+    state.run(move |services, conn| {
+        let csv = services.task_csv.export_csv(conn, &columns)?;
+        std::fs::write(&dest_path, csv).map_err(|e| format!("Failed to write CSV file: {}", e))
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn import_tasks_csv(state: State<'_, AppState>, source_path: String, options: CsvImportOptions) -> Result<CsvImportReport, String> {
+    state.run(move |services, conn| {
+        let data = std::fs::read_to_string(&source_path).map_err(|e| format!("Failed to read CSV file: {}", e))?;
+        services.task_csv.import_csv(conn, &data, &options)
+    })
+    .await
+}
+
+// Database Backups
+#[tauri::command]
+pub async fn list_backups(state: State<'_, AppState>) -> Result<Vec<BackupInfo>, String> {
+    let services = state.services.clone();
+    let backup_dir = state.backup_dir.clone();
+    run_blocking_io(move || services.backup.list_backups(&backup_dir)).await
+}
+
+#[tauri::command]
+pub async fn create_backup(state: State<'_, AppState>) -> Result<BackupInfo, String> {
+    let backup_dir = state.backup_dir.clone();
+    state.run(move |services, conn| services.backup.backup_now(conn, &backup_dir)).await
+}
+
+#[tauri::command]
+pub async fn restore_backup(state: State<'_, AppState>, file_name: String) -> Result<(), String> {
+    let services = state.services.clone();
+    let db = state.db.clone();
+    let backup_dir = state.backup_dir.clone();
+    // Restore butuh akses ke seluruh pool, jadi koneksi tidak diambil lebih dulu
+    run_blocking_io(move || services.backup.restore_backup(&db, &backup_dir, &file_name)).await
+}
+
+#[tauri::command]
+pub async fn get_backup_config(state: State<'_, AppState>) -> Result<BackupConfig, String> {
+    state.run(move |services, conn| services.backup.load_config(conn)).await
+}
+
+#[tauri::command]
+pub async fn update_backup_config(state: State<'_, AppState>, config: BackupConfig) -> Result<(), String> {
+    state.run(move |services, conn| services.backup.save_config(conn, &config)).await
+}
+
+// Undo/Redo
+#[tauri::command]
+pub async fn undo(state: State<'_, AppState>) -> Result<HistoryStatus, String> {
+    state.run(move |services, conn| services.history.undo(conn)).await
+}
+
+#[tauri::command]
+pub async fn redo(state: State<'_, AppState>) -> Result<HistoryStatus, String> {
+    state.run(move |services, conn| services.history.redo(conn)).await
+}
+
+#[tauri::command]
+pub async fn get_history_status(state: State<'_, AppState>) -> Result<HistoryStatus, String> {
+    Ok(state.services.history.status())
+}
+
+// Trash
+#[tauri::command]
+pub async fn list_trash(state: State<'_, AppState>) -> Result<TrashListing, String> {
+    state.run(move |services, conn| services.trash.list_trash(conn)).await
+}
+
+#[tauri::command]
+pub async fn restore_task(state: State<'_, AppState>, id: i32) -> Result<(), String> {
+    state.run(move |services, conn| services.trash.restore_task(conn, id)).await
+}
+
+#[tauri::command]
+pub async fn restore_note(state: State<'_, AppState>, id: i32) -> Result<(), String> {
+    state.run(move |services, conn| services.trash.restore_note(conn, id)).await
+}
+
+#[tauri::command]
+pub async fn empty_trash(state: State<'_, AppState>) -> Result<PurgeSummary, String> {
+    state.run(move |services, conn| services.trash.empty_trash(conn)).await
+}
+
+#[tauri::command]
+pub async fn get_trash_config(state: State<'_, AppState>) -> Result<TrashConfig, String> {
+    state.run(move |services, conn| services.trash.load_config(conn)).await
+}
+
+#[tauri::command]
+pub async fn update_trash_config(state: State<'_, AppState>, config: TrashConfig) -> Result<(), String> {
+    state.run(move |services, conn| services.trash.save_config(conn, &config)).await
+}
@@ -0,0 +1,159 @@
+// src-tauri/src/db.rs
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use r2d2::{Pool, PooledConnection};
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::backup::Backup;
+use rusqlite::{Connection, OpenFlags};
+use crate::migrations::run_migrations;
+
+// Definisi tipe alias untuk Pool
+pub type DbPool = Pool<SqliteConnectionManager>;
+pub type DbConnection = PooledConnection<SqliteConnectionManager>;
+
+// Pengaturan pool dan koneksi SQLite
+#[derive(Debug, Clone)]
+pub struct DbConfig {
+    // Jumlah maksimum koneksi di pool
+    pub pool_size: u32,
+    // Berapa lama `get()` menunggu koneksi bebas sebelum menyerah
+    pub connection_timeout: Duration,
+    // Berapa lama SQLite menunggu lock dilepas sebelum mengembalikan SQLITE_BUSY
+    pub busy_timeout: Duration,
+}
+
+impl Default for DbConfig {
+    fn default() -> Self {
+        DbConfig {
+            pool_size: 8,
+            connection_timeout: Duration::from_secs(10),
+            busy_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+// Jumlah halaman yang disalin per langkah saat restore
+const RESTORE_PAGES_PER_STEP: i32 = 100;
+
+// Pembungkus pool koneksi yang bisa ditutup dan dibuka ulang (misalnya saat restore backup)
+pub struct Database {
+    path: PathBuf,
+    config: DbConfig,
+    pool: RwLock<DbPool>,
+}
+
+impl Database {
+    pub fn open(path: impl Into<PathBuf>) -> Result<Database, String> {
+        Database::open_with_config(path, DbConfig::default())
+    }
+
+    pub fn open_with_config(path: impl Into<PathBuf>, config: DbConfig) -> Result<Database, String> {
+        let path = path.into();
+        let pool = build_pool(&path, &config)?;
+        Ok(Database { path, config, pool: RwLock::new(pool) })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Dapatkan satu koneksi dari pool
+    pub fn get(&self) -> Result<DbConnection, String> {
+        let pool = self.pool.read().map_err(|_| "Pool koneksi DB tidak dapat diakses".to_string())?;
+        pool.get().map_err(|e| format!("Gagal mendapatkan koneksi dari pool: {}", e))
+    }
+
+    // Timpa isi database dengan file `src` memakai online backup API SQLite.
+    // Selama proses ini tidak ada command yang bisa mengambil koneksi baru; pool lama
+    // dibuang sebelum restore dan pool baru dibuat setelahnya.
+    pub fn restore_from(&self, src: &Path) -> Result<(), String> {
+        let mut pool = self.pool.write().map_err(|_| "Pool koneksi DB tidak dapat diakses".to_string())?;
+
+        // Ganti pool lama dengan pool sementara berukuran nol agar koneksi idle ditutup
+        let placeholder = Pool::builder()
+            .max_size(1)
+            .min_idle(Some(0))
+            .build_unchecked(SqliteConnectionManager::file(&self.path));
+        drop(std::mem::replace(&mut *pool, placeholder));
+
+        // Sumber dibuka read-only tanpa SQLITE_OPEN_CREATE: jika file sumber hilang, restore gagal
+        // alih-alih menimpa database dengan database kosong. Backup dari versi aplikasi lama
+        // dimigrasikan dulu supaya pool baru melihat skema terbaru.
+        let result = Connection::open_with_flags(src, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .and_then(|source| {
+                let mut conn = Connection::open(&self.path)?;
+                conn.busy_timeout(self.config.busy_timeout)?;
+                Backup::new(&source, &mut conn)?
+                    .run_to_completion(RESTORE_PAGES_PER_STEP, Duration::from_millis(250), None)?;
+                run_migrations(&conn)
+            })
+            .map_err(|e| format!("Gagal me-restore database: {}", e));
+
+        // Pool dibuka ulang baik restore berhasil maupun gagal
+        *pool = build_pool(&self.path, &self.config)?;
+        result
+    }
+}
+
+// Pengaturan yang dijalankan pada setiap koneksi baru di pool:
+// WAL supaya pembaca tidak menunggu penulis, foreign key aktif, dan busy timeout
+// supaya command yang berjalan bersamaan menunggu lock alih-alih langsung gagal.
+pub fn configure_connection(conn: &Connection, busy_timeout: Duration) -> rusqlite::Result<()> {
+    conn.busy_timeout(busy_timeout)?;
+    conn.execute_batch(
+        "PRAGMA journal_mode = WAL;
+        PRAGMA foreign_keys = ON;
+        PRAGMA synchronous = NORMAL;",
+    )
+}
+
+// Jalankan `f` di dalam satu transaksi. Jika `conn` sudah berada di dalam transaksi (service
+// dipanggil dari service lain), `f` ikut transaksi itu dan commit diserahkan ke pemanggil luar.
+pub fn in_transaction<T>(conn: &Connection, f: impl FnOnce(&Connection) -> Result<T, String>) -> Result<T, String> {
+    if !conn.is_autocommit() {
+        return f(conn);
+    }
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let value = f(&tx)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(value)
+}
+
+fn build_pool(path: &Path, config: &DbConfig) -> Result<DbPool, String> {
+    let busy_timeout = config.busy_timeout;
+    let manager = SqliteConnectionManager::file(path)
+        .with_init(move |conn| configure_connection(conn, busy_timeout));
+    Pool::builder()
+        .max_size(config.pool_size)
+        .connection_timeout(config.connection_timeout)
+        .build(manager)
+        .map_err(|e| format!("Gagal membuat pool koneksi DB: {}", e))
+}
+
+// Jalankan pekerjaan blocking (file IO, dsb.) di thread pool blocking tokio,
+// supaya command async tidak menahan thread utama / UI.
+pub async fn run_blocking_io<T, F>(f: F) -> Result<T, String>
+where
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| format!("Pekerjaan latar belakang gagal: {}", e))?
+}
+
+// Sama seperti `run_blocking_io`, tetapi sekaligus mengambil koneksi dari pool
+// di thread blocking (karena `get()` bisa menunggu koneksi bebas).
+pub async fn run_blocking<T, F>(db: Arc<Database>, f: F) -> Result<T, String>
+where
+    F: FnOnce(&Connection) -> Result<T, String> + Send + 'static,
+    T: Send + 'static,
+{
+    run_blocking_io(move || {
+        let conn = db.get()?;
+        f(&conn)
+    })
+    .await
+}
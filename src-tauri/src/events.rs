@@ -0,0 +1,91 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast::{self, error::RecvError, Receiver, Sender};
+use crate::model::note::Note;
+use crate::model::planner::PlanResult;
+use crate::model::task::Task;
+
+// Kapasitas antrean per subscriber; subscriber yang tertinggal lebih jauh akan melewatkan event lama
+const EVENT_BUS_CAPACITY: usize = 256;
+
+// Event domain yang dipublikasikan service setiap kali data berubah.
+// Serialisasi `untagged` supaya payload event Tauri langsung berupa entity yang berubah.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum DomainEvent {
+    TaskCreated(Task),
+    TaskUpdated(Task),
+    TaskDeleted { id: i32 },
+    NoteCreated(Note),
+    NoteUpdated(Note),
+    NoteDeleted { id: i32 },
+    // Jadwal disusun ulang karena sebuah task selesai; `unscheduled` berisi task yang tidak muat lagi
+    ScheduleReflowed(PlanResult),
+    // Isi database diganti (restore backup, impor arsip); window memuat ulang semua data
+    DataReloaded,
+}
+
+impl DomainEvent {
+    // Nama event Tauri yang didengarkan frontend, mis. `listen("task:created", ...)`
+    pub fn name(&self) -> &'static str {
+        match self {
+            DomainEvent::TaskCreated(_) => "task:created",
+            DomainEvent::TaskUpdated(_) => "task:updated",
+            DomainEvent::TaskDeleted { .. } => "task:deleted",
+            DomainEvent::NoteCreated(_) => "note:created",
+            DomainEvent::NoteUpdated(_) => "note:updated",
+            DomainEvent::NoteDeleted { .. } => "note:deleted",
+            DomainEvent::ScheduleReflowed(_) => "schedule:reflowed",
+            DomainEvent::DataReloaded => "data:reloaded",
+        }
+    }
+}
+
+// Bus event internal berbasis broadcast channel. Clone-nya berbagi channel yang sama,
+// jadi setiap service cukup menyimpan salinannya sendiri.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: Sender<DomainEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUS_CAPACITY);
+        EventBus { sender }
+    }
+
+    // Publikasikan event ke semua subscriber. Tidak ada subscriber bukan error.
+    pub fn publish(&self, event: DomainEvent) {
+        self.sender.send(event).ok();
+    }
+
+    pub fn subscribe(&self) -> Receiver<DomainEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        EventBus::new()
+    }
+}
+
+// Teruskan setiap event dari bus ke semua window sebagai event Tauri
+pub fn start_tauri_bridge(app: AppHandle, mut events: Receiver<DomainEvent>) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    if let Err(e) = app.emit(event.name(), &event) {
+                        eprintln!("Gagal mengirim event {}: {}", event.name(), e);
+                    }
+                }
+                // Bridge tertinggal: event lama hilang, frontend tetap menerima event berikutnya
+                Err(RecvError::Lagged(skipped)) => {
+                    eprintln!("Bridge event melewatkan {} event", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
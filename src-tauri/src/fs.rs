@@ -0,0 +1,220 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::OpenOptions;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+// Abstraksi filesystem untuk file markdown notes, supaya service bisa dites dengan implementasi palsu
+pub trait FileSystem {
+    fn exists(&self, path: &Path) -> bool;
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+    // Buat file kosong jika belum ada; isi file yang sudah ada tidak diubah
+    fn create_file(&self, path: &Path) -> io::Result<()>;
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+    // Timpa seluruh isi file (file dibuat jika belum ada)
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+    // Pindahkan file; folder tujuan harus sudah ada
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+    fn is_dir(&self, path: &Path) -> bool;
+    // Isi langsung sebuah folder (file dan subfolder), tidak rekursif
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+    // Waktu terakhir file diubah
+    fn modified(&self, path: &Path) -> io::Result<SystemTime>;
+}
+
+// Implementasi yang memakai filesystem asli
+pub struct StdFileSystem;
+
+impl FileSystem for StdFileSystem {
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        std::fs::create_dir_all(path)
+    }
+
+    fn create_file(&self, path: &Path) -> io::Result<()> {
+        OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .map(|_| ())
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        std::fs::read(path)
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        std::fs::write(path, contents)
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        std::fs::remove_file(path)
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        std::fs::rename(from, to)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        std::fs::read_dir(path)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect()
+    }
+
+    fn modified(&self, path: &Path) -> io::Result<SystemTime> {
+        std::fs::metadata(path)?.modified()
+    }
+}
+
+// Implementasi filesystem virtual di memori untuk test, tidak pernah menyentuh disk.
+// Clone-nya berbagi isi yang sama, jadi beberapa service bisa memakai "disk" yang sama.
+#[derive(Clone, Default)]
+pub struct InMemoryFileSystem {
+    files: Arc<Mutex<HashMap<PathBuf, Vec<u8>>>>,
+    dirs: Arc<Mutex<HashSet<PathBuf>>>,
+    // Waktu ubah per file; diisi saat file dibuat, ditulis, atau dipindahkan
+    modified: Arc<Mutex<HashMap<PathBuf, SystemTime>>>,
+}
+
+impl InMemoryFileSystem {
+    pub fn new() -> Self {
+        InMemoryFileSystem::default()
+    }
+
+    // Isi file sebagai teks, `None` jika file tidak ada
+    pub fn contents(&self, path: impl AsRef<Path>) -> Option<String> {
+        self.files.lock().unwrap()
+            .get(path.as_ref())
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    // Semua file langsung di bawah `dir`, terurut
+    pub fn list(&self, dir: impl AsRef<Path>) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self.files.lock().unwrap()
+            .keys()
+            .filter(|path| path.parent() == Some(dir.as_ref()))
+            .cloned()
+            .collect();
+        files.sort();
+        files
+    }
+
+    // Atur waktu ubah file secara manual, mis. untuk meniru edit dari aplikasi lain
+    pub fn set_modified(&self, path: impl AsRef<Path>, time: SystemTime) {
+        self.modified.lock().unwrap().insert(path.as_ref().to_path_buf(), time);
+    }
+
+    fn touch(&self, path: &Path) {
+        self.set_modified(path, SystemTime::now());
+    }
+
+    fn dir_exists(&self, path: &Path) -> bool {
+        // Path relatif tanpa parent (mis. "note.md") dianggap berada di folder kerja yang selalu ada
+        path.as_os_str().is_empty() || self.dirs.lock().unwrap().contains(path)
+    }
+
+    fn check_parent(&self, path: &Path) -> io::Result<()> {
+        match path.parent() {
+            Some(parent) if !self.dir_exists(parent) => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Folder {} tidak ada", parent.display()),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl FileSystem for InMemoryFileSystem {
+    fn exists(&self, path: &Path) -> bool {
+        self.files.lock().unwrap().contains_key(path) || self.dir_exists(path)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        let mut dirs = self.dirs.lock().unwrap();
+        for ancestor in path.ancestors().filter(|p| !p.as_os_str().is_empty()) {
+            dirs.insert(ancestor.to_path_buf());
+        }
+        Ok(())
+    }
+
+    fn create_file(&self, path: &Path) -> io::Result<()> {
+        self.check_parent(path)?;
+        let mut files = self.files.lock().unwrap();
+        if !files.contains_key(path) {
+            files.insert(path.to_path_buf(), Vec::new());
+            self.touch(path);
+        }
+        Ok(())
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.files.lock().unwrap()
+            .get(path)
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("File {} tidak ada", path.display())))
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        self.check_parent(path)?;
+        self.files.lock().unwrap().insert(path.to_path_buf(), contents.to_vec());
+        self.touch(path);
+        Ok(())
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        self.modified.lock().unwrap().remove(path);
+        self.files.lock().unwrap()
+            .remove(path)
+            .map(|_| ())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("File {} tidak ada", path.display())))
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.check_parent(to)?;
+        let contents = self.files.lock().unwrap()
+            .remove(from)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("File {} tidak ada", from.display())))?;
+        self.files.lock().unwrap().insert(to.to_path_buf(), contents);
+        let mut modified = self.modified.lock().unwrap();
+        if let Some(time) = modified.remove(from) {
+            modified.insert(to.to_path_buf(), time);
+        }
+        Ok(())
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.dir_exists(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        if !self.dir_exists(path) {
+            return Err(io::Error::new(io::ErrorKind::NotFound, format!("Folder {} tidak ada", path.display())));
+        }
+        let files = self.files.lock().unwrap();
+        let dirs = self.dirs.lock().unwrap();
+        let mut entries: Vec<PathBuf> = files.keys()
+            .chain(dirs.iter())
+            .filter(|p| p.parent() == Some(path))
+            .cloned()
+            .collect();
+        entries.sort();
+        Ok(entries)
+    }
+
+    fn modified(&self, path: &Path) -> io::Result<SystemTime> {
+        self.modified.lock().unwrap()
+            .get(path)
+            .copied()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("File {} tidak ada", path.display())))
+    }
+}
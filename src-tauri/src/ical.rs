@@ -0,0 +1,535 @@
+// src-tauri/src/ical.rs
+
+// Konversi task dan acara kalender ke/dari iCalendar (RFC 5545) untuk sinkronisasi CalDAV.
+// Waktu ditulis sebagai waktu lokal "floating" (tanpa zona waktu), sama seperti yang disimpan
+// aplikasi. Waktu UTC dari server diubah ke zona waktu lokal. TZID hanya dikenali jika berupa UTC
+// atau zona waktu lokal perangkat; zona lain ditolak karena aturan pergantian waktunya (DST) tidak
+// dihitung di sini, jadi komponennya tidak disinkronkan. Dari RRULE hanya FREQ, INTERVAL, BYDAY
+// (mingguan), UNTIL, dan COUNT yang dibaca.
+
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use crate::model::calendar_event::CalendarEvent;
+use crate::model::recurrence::{Frequency, Recurrence};
+use crate::model::task::{Category, Priority, Task};
+
+const PRODID: &str = "-//Nocture//Nocture//EN";
+// Baris yang lebih panjang dari ini (dalam byte) dilipat ke baris berikutnya
+const LINE_LIMIT: usize = 75;
+const DATE_FORMAT: &str = "%Y%m%d";
+const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+
+// Komponen VTODO atau VEVENT utama dari sebuah resource .ics. Id, `created_at`, dan
+// `updated_at` pada task/acara diisi nilai awal; pemanggil yang menentukan nilai sebenarnya.
+#[derive(Debug, Clone)]
+pub enum ICalComponent {
+    Todo { uid: String, last_modified: Option<NaiveDateTime>, task: Task },
+    Event { uid: String, last_modified: Option<NaiveDateTime>, event: CalendarEvent },
+}
+
+impl ICalComponent {
+    pub fn uid(&self) -> &str {
+        match self {
+            ICalComponent::Todo { uid, .. } | ICalComponent::Event { uid, .. } => uid,
+        }
+    }
+
+    // LAST-MODIFIED (atau DTSTAMP jika tidak ada) dalam UTC
+    pub fn last_modified(&self) -> Option<NaiveDateTime> {
+        match self {
+            ICalComponent::Todo { last_modified, .. } | ICalComponent::Event { last_modified, .. } => *last_modified,
+        }
+    }
+}
+
+pub fn task_to_ical(task: &Task, uid: &str) -> String {
+    let mut lines = vec![
+        "BEGIN:VTODO".to_string(),
+        format!("UID:{}", escape(uid)),
+        format!("DTSTAMP:{}", utc_stamp(task.updated_at)),
+        format!("CREATED:{}", utc_stamp(task.created_at)),
+        format!("LAST-MODIFIED:{}", utc_stamp(task.updated_at)),
+        format!("SUMMARY:{}", escape(&task.title)),
+        format!("CATEGORIES:{}", task.category),
+        format!("PRIORITY:{}", priority_number(&task.priority)),
+    ];
+    // Deadline pukul 00.00 dianggap deadline tanpa jam, sama seperti di tampilan kalender
+    if let Some(deadline) = task.deadline {
+        lines.push(time_property("DUE", deadline, deadline.time() == NaiveTime::MIN));
+    }
+    lines.push(format!("STATUS:{}", if task.completed { "COMPLETED" } else { "NEEDS-ACTION" }));
+    lines.push("END:VTODO".to_string());
+    calendar_object(lines)
+}
+
+pub fn event_to_ical(event: &CalendarEvent, uid: &str) -> String {
+    let mut lines = vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", escape(uid)),
+        format!("DTSTAMP:{}", utc_stamp(event.updated_at)),
+        format!("CREATED:{}", utc_stamp(event.created_at)),
+        format!("LAST-MODIFIED:{}", utc_stamp(event.updated_at)),
+        format!("SUMMARY:{}", escape(&event.title)),
+        // Untuk acara seharian DTEND sudah eksklusif, sama seperti `end` yang disimpan
+        time_property("DTSTART", event.start, event.all_day),
+        time_property("DTEND", event.end, event.all_day),
+    ];
+    if let Some(location) = &event.location {
+        lines.push(format!("LOCATION:{}", escape(location)));
+    }
+    if let Some(recurrence) = &event.recurrence {
+        lines.push(format!("RRULE:{}", rrule(recurrence, event.all_day)));
+    }
+    lines.push("END:VEVENT".to_string());
+    calendar_object(lines)
+}
+
+// Baca komponen utama dari isi resource .ics. `Ok(None)` jika tidak ada VTODO/VEVENT;
+// komponen pengganti untuk satu kemunculan (yang punya RECURRENCE-ID) diabaikan.
+pub fn parse_ical(data: &str, now: NaiveDateTime) -> Result<Option<ICalComponent>, String> {
+    let mut stack: Vec<String> = Vec::new();
+    let mut component: Option<Vec<Property>> = None;
+    let mut is_calendar = false;
+    for line in unfold(data) {
+        let Some(property) = parse_property(&line) else {
+            continue;
+        };
+        match property.name.as_str() {
+            "BEGIN" => {
+                stack.push(property.value.to_uppercase());
+                is_calendar |= stack.len() == 1 && stack[0] == "VCALENDAR";
+                if stack.len() == 2 && matches!(stack[1].as_str(), "VTODO" | "VEVENT") {
+                    component = Some(Vec::new());
+                }
+            }
+            "END" => {
+                if stack.len() == 2 {
+                    if let Some(properties) = component.take() {
+                        if find(&properties, "RECURRENCE-ID").is_none() {
+                            return build_component(&stack[1], &properties, now).map(Some);
+                        }
+                    }
+                }
+                stack.pop();
+            }
+            _ if stack.len() == 2 => {
+                if let Some(properties) = component.as_mut() {
+                    properties.push(property);
+                }
+            }
+            _ => {}
+        }
+    }
+    if !is_calendar {
+        return Err("Not an iCalendar object".to_string());
+    }
+    Ok(None)
+}
+
+fn build_component(name: &str, properties: &[Property], now: NaiveDateTime) -> Result<ICalComponent, String> {
+    let uid = find(properties, "UID")
+        .map(|p| unescape(&p.value))
+        .filter(|uid| !uid.trim().is_empty())
+        .ok_or("Calendar item has no UID")?;
+    let title = find(properties, "SUMMARY")
+        .map(|p| unescape(&p.value).trim().to_string())
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| "Untitled".to_string());
+    let last_modified = match find(properties, "LAST-MODIFIED").or_else(|| find(properties, "DTSTAMP")) {
+        Some(property) => Some(ICalTime::parse(property)?.utc()),
+        None => None,
+    };
+
+    if name == "VTODO" {
+        let category = find(properties, "CATEGORIES")
+            .and_then(|p| p.value.split(',').find_map(|c| unescape(c).parse::<Category>().ok()))
+            .unwrap_or(Category::Personal);
+        let priority = match find(properties, "PRIORITY").and_then(|p| p.value.trim().parse::<u8>().ok()) {
+            Some(1..=4) => Priority::High,
+            Some(6..=9) => Priority::Low,
+            _ => Priority::Medium,
+        };
+        let deadline = match find(properties, "DUE") {
+            Some(property) => Some(ICalTime::parse(property)?.local()),
+            None => None,
+        };
+        let completed = find(properties, "STATUS").is_some_and(|p| p.value.eq_ignore_ascii_case("COMPLETED"))
+            || find(properties, "COMPLETED").is_some();
+        let task = Task {
+            id: 0,
+            title,
+            category,
+            priority,
+            deadline,
+            completed,
+            created_at: now,
+            updated_at: now,
+            estimated_minutes: None,
+        };
+        return Ok(ICalComponent::Todo { uid, last_modified, task });
+    }
+
+    let start = ICalTime::parse(find(properties, "DTSTART").ok_or("Event has no start time")?)?;
+    let all_day = matches!(start, ICalTime::Date(_));
+    let start = start.local();
+    let end = match (find(properties, "DTEND"), find(properties, "DURATION")) {
+        (Some(property), _) => ICalTime::parse(property)?.local(),
+        (None, Some(property)) => {
+            start + parse_duration(&property.value).ok_or_else(|| format!("Invalid duration '{}'", property.value))?
+        }
+        // Tanpa DTEND dan DURATION: acara seharian berlangsung satu hari, acara berjam tanpa durasi
+        (None, None) if all_day => start + Duration::days(1),
+        (None, None) => start,
+    };
+    let recurrence = match find(properties, "RRULE") {
+        Some(property) => Some(parse_rrule(&property.value, start, end - start)?),
+        None => None,
+    };
+    let event = CalendarEvent {
+        id: 0,
+        title,
+        start,
+        end,
+        all_day,
+        location: find(properties, "LOCATION")
+            .map(|p| unescape(&p.value).trim().to_string())
+            .filter(|location| !location.is_empty()),
+        recurrence,
+        created_at: now,
+        updated_at: now,
+    };
+    Ok(ICalComponent::Event { uid, last_modified, event })
+}
+
+// Satu baris konten, mis. `DTSTART;VALUE=DATE:20261019`
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+}
+
+fn find<'a>(properties: &'a [Property], name: &str) -> Option<&'a Property> {
+    properties.iter().find(|p| p.name == name)
+}
+
+// Gabungkan baris lanjutan (diawali spasi atau tab) ke baris sebelumnya
+fn unfold(data: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in data.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(previous)) => previous.push_str(rest),
+            _ if line.trim().is_empty() => {}
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+fn parse_property(line: &str) -> Option<Property> {
+    // Titik dua dan titik koma di dalam tanda kutip adalah bagian dari nilai parameter
+    let mut in_quotes = false;
+    let mut separators = Vec::new();
+    let mut colon = None;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => separators.push(index),
+            ':' if !in_quotes => {
+                colon = Some(index);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let colon = colon?;
+    let name_end = separators.first().copied().unwrap_or(colon);
+    let mut params = Vec::new();
+    for (i, start) in separators.iter().enumerate() {
+        let end = separators.get(i + 1).copied().unwrap_or(colon);
+        if let Some((key, value)) = line[start + 1..end].split_once('=') {
+            params.push((key.trim().to_uppercase(), value.trim_matches('"').to_string()));
+        }
+    }
+    Some(Property {
+        name: line[..name_end].trim().to_uppercase(),
+        params,
+        value: line[colon + 1..].to_string(),
+    })
+}
+
+// Nilai DATE atau DATE-TIME
+enum ICalTime {
+    Date(NaiveDate),
+    Floating(NaiveDateTime),
+    Utc(NaiveDateTime),
+}
+
+impl ICalTime {
+    fn parse(property: &Property) -> Result<ICalTime, String> {
+        let value = property.value.trim();
+        let invalid = || format!("Invalid date '{}' in {}", value, property.name);
+        if property.param("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE")) || value.len() == 8 {
+            return NaiveDate::parse_from_str(value, DATE_FORMAT).map(ICalTime::Date).map_err(|_| invalid());
+        }
+        let zone = match (value.strip_suffix('Z'), property.param("TZID")) {
+            (Some(utc), _) => return NaiveDateTime::parse_from_str(utc, DATE_TIME_FORMAT).map(ICalTime::Utc).map_err(|_| invalid()),
+            (None, Some(tzid)) if is_utc_zone(tzid) => ICalTime::Utc,
+            (None, Some(tzid)) if !is_local_zone(tzid) => {
+                return Err(format!("Time zone '{}' in {} is not supported", tzid, property.name));
+            }
+            (None, _) => ICalTime::Floating,
+        };
+        NaiveDateTime::parse_from_str(value, DATE_TIME_FORMAT).map(zone).map_err(|_| invalid())
+    }
+
+    fn local(&self) -> NaiveDateTime {
+        match self {
+            ICalTime::Date(date) => date.and_time(NaiveTime::MIN),
+            ICalTime::Floating(time) => *time,
+            ICalTime::Utc(time) => Utc.from_utc_datetime(time).with_timezone(&Local).naive_local(),
+        }
+    }
+
+    fn utc(&self) -> NaiveDateTime {
+        match self {
+            ICalTime::Utc(time) => *time,
+            _ => {
+                let local = self.local();
+                Local.from_local_datetime(&local).earliest().map(|t| t.naive_utc()).unwrap_or(local)
+            }
+        }
+    }
+}
+
+fn is_utc_zone(tzid: &str) -> bool {
+    ["UTC", "Etc/UTC", "GMT", "Etc/GMT", "Z"].iter().any(|utc| tzid.eq_ignore_ascii_case(utc))
+}
+
+// TZID yang sama dengan zona waktu lokal dibaca sebagai waktu lokal. Sebagian klien menambahkan
+// awalan, mis. "/mozilla.org/20050126_1/Europe/Berlin".
+fn is_local_zone(tzid: &str) -> bool {
+    local_zone_name().is_some_and(|local| tzid == local || tzid.ends_with(&format!("/{}", local)))
+}
+
+// Nama zona waktu lokal (mis. "Asia/Jakarta"). TZ didahulukan, sama seperti chrono::Local.
+fn local_zone_name() -> Option<String> {
+    match std::env::var("TZ") {
+        Ok(tz) if !tz.is_empty() => Some(tz.trim_start_matches(':').to_string()),
+        _ => iana_time_zone::get_timezone().ok(),
+    }
+}
+
+// Durasi seperti "PT1H30M", "P1D", atau "P2W"
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in value.strip_prefix('P')?.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => in_time = true,
+            _ => {
+                let amount: i64 = number.parse().ok()?;
+                number.clear();
+                total += match (c, in_time) {
+                    ('W', false) => Duration::weeks(amount),
+                    ('D', false) => Duration::days(amount),
+                    ('H', true) => Duration::hours(amount),
+                    ('M', true) => Duration::minutes(amount),
+                    ('S', true) => Duration::seconds(amount),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(if negative { -total } else { total })
+}
+
+fn parse_rrule(value: &str, start: NaiveDateTime, duration: Duration) -> Result<Recurrence, String> {
+    let mut frequency = None;
+    let mut recurrence = Recurrence::new(Frequency::Daily, 1);
+    let mut count = None;
+    for part in value.split(';') {
+        let Some((key, value)) = part.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().to_uppercase();
+        match key.trim().to_uppercase().as_str() {
+            "FREQ" => {
+                frequency = Some(match value.as_str() {
+                    "DAILY" => Frequency::Daily,
+                    "WEEKLY" => Frequency::Weekly,
+                    "MONTHLY" => Frequency::Monthly,
+                    "YEARLY" => Frequency::Yearly,
+                    _ => return Err(format!("Unsupported recurrence frequency '{}'", value)),
+                })
+            }
+            "INTERVAL" => {
+                let interval: u32 = value.parse().map_err(|_| format!("Invalid recurrence interval '{}'", value))?;
+                recurrence.interval = interval.max(1);
+            }
+            "BYDAY" => recurrence.weekdays = value.split(',').filter_map(weekday_from_code).collect(),
+            "UNTIL" => {
+                let date = value.get(..8).and_then(|date| NaiveDate::parse_from_str(date, DATE_FORMAT).ok());
+                recurrence.until = Some(date.ok_or_else(|| format!("Invalid recurrence end '{}'", value))?);
+            }
+            "COUNT" => count = Some(value.parse::<usize>().map_err(|_| format!("Invalid recurrence count '{}'", value))?),
+            // BYMONTHDAY, BYSETPOS, dll. tidak didukung
+            _ => {}
+        }
+    }
+    recurrence.frequency = frequency.ok_or("Recurrence rule has no FREQ")?;
+    if recurrence.frequency != Frequency::Weekly {
+        recurrence.weekdays.clear();
+    }
+    // COUNT diubah menjadi tanggal kemunculan terakhir. Batas pencarian dibuat longgar karena
+    // tanggal 31 atau 29 Februari bisa dilewati.
+    if let Some(count) = count.filter(|count| *count > 0) {
+        let unit_days = match recurrence.frequency {
+            Frequency::Daily => 1,
+            Frequency::Weekly => 7,
+            Frequency::Monthly => 31,
+            Frequency::Yearly => 366,
+        };
+        let horizon = start + Duration::days(unit_days * recurrence.interval as i64 * count as i64 * 4 + 7);
+        let starts = recurrence.occurrences(start, duration, start, horizon);
+        recurrence.until = starts.get(count - 1).or(starts.last()).map(|last| last.date());
+    }
+    Ok(recurrence)
+}
+
+fn rrule(recurrence: &Recurrence, all_day: bool) -> String {
+    let frequency = match recurrence.frequency {
+        Frequency::Daily => "DAILY",
+        Frequency::Weekly => "WEEKLY",
+        Frequency::Monthly => "MONTHLY",
+        Frequency::Yearly => "YEARLY",
+    };
+    let mut rule = format!("FREQ={};INTERVAL={}", frequency, recurrence.interval.max(1));
+    if recurrence.frequency == Frequency::Weekly && !recurrence.weekdays.is_empty() {
+        let days: Vec<&str> = recurrence.weekdays.iter().map(weekday_code).collect();
+        rule.push_str(&format!(";BYDAY={}", days.join(",")));
+    }
+    // UNTIL harus bertipe sama dengan DTSTART; untuk acara berjam pakai akhir hari terakhir
+    if let Some(until) = recurrence.until {
+        if all_day {
+            rule.push_str(&format!(";UNTIL={}", until.format(DATE_FORMAT)));
+        } else {
+            let end_of_day = until.and_hms_opt(23, 59, 59).unwrap_or(until.and_time(NaiveTime::MIN));
+            rule.push_str(&format!(";UNTIL={}", end_of_day.format(DATE_TIME_FORMAT)));
+        }
+    }
+    rule
+}
+
+fn weekday_code(weekday: &Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+// Hanya kode polos seperti "MO"; bentuk "1MO" (Senin pertama) tidak didukung
+fn weekday_from_code(code: &str) -> Option<Weekday> {
+    match code.trim() {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn priority_number(priority: &Priority) -> u8 {
+    match priority {
+        Priority::High => 1,
+        Priority::Medium => 5,
+        Priority::Low => 9,
+    }
+}
+
+fn time_property(name: &str, value: NaiveDateTime, date_only: bool) -> String {
+    if date_only {
+        format!("{};VALUE=DATE:{}", name, value.format(DATE_FORMAT))
+    } else {
+        format!("{}:{}", name, value.format(DATE_TIME_FORMAT))
+    }
+}
+
+// `created_at`/`updated_at` disimpan sebagai UTC
+fn utc_stamp(value: NaiveDateTime) -> String {
+    format!("{}Z", value.format(DATE_TIME_FORMAT))
+}
+
+fn calendar_object(component: Vec<String>) -> String {
+    let mut lines = vec!["BEGIN:VCALENDAR".to_string(), "VERSION:2.0".to_string(), format!("PRODID:{}", PRODID)];
+    lines.extend(component);
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+// Pecah baris panjang tanpa memotong karakter UTF-8; baris lanjutan diawali satu spasi
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > LINE_LIMIT {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => {}
+        }
+    }
+    unescaped
+}
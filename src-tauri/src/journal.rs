@@ -0,0 +1,139 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use crate::events::{DomainEvent, EventBus};
+use crate::model::journal::{HistoryStatus, Operation};
+
+// Jumlah mutasi terakhir yang masih bisa di-undo
+pub const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+#[derive(Default)]
+struct JournalState {
+    undo: VecDeque<Operation>,
+    redo: Vec<Operation>,
+}
+
+// Journal mutasi task dan note untuk undo/redo. Seperti EventBus, clone-nya berbagi state
+// yang sama sehingga service yang mencatat dan service history melihat riwayat yang sama.
+#[derive(Clone)]
+pub struct Journal {
+    state: Arc<Mutex<JournalState>>,
+    limit: usize,
+}
+
+impl Journal {
+    pub fn new(limit: usize) -> Self {
+        Journal {
+            state: Arc::new(Mutex::new(JournalState::default())),
+            limit: limit.max(1),
+        }
+    }
+
+    // Catat mutasi baru. Riwayat redo dibuang, dan entri tertua keluar jika melebihi batas.
+    pub fn record(&self, operation: Operation) {
+        let mut state = self.state.lock().unwrap();
+        state.redo.clear();
+        self.push_undo_locked(&mut state, operation);
+    }
+
+    pub fn pop_undo(&self) -> Option<Operation> {
+        self.state.lock().unwrap().undo.pop_back()
+    }
+
+    pub fn pop_redo(&self) -> Option<Operation> {
+        self.state.lock().unwrap().redo.pop()
+    }
+
+    // Kembalikan operasi ke tumpukan undo tanpa menyentuh riwayat redo (dipakai setelah redo)
+    pub fn push_undo(&self, operation: Operation) {
+        let mut state = self.state.lock().unwrap();
+        self.push_undo_locked(&mut state, operation);
+    }
+
+    pub fn push_redo(&self, operation: Operation) {
+        self.state.lock().unwrap().redo.push(operation);
+    }
+
+    // Lupakan seluruh riwayat undo/redo, mis. setelah database diganti: id di entri lama
+    // tidak lagi menunjuk ke data yang sama
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.undo.clear();
+        state.redo.clear();
+    }
+
+    pub fn status(&self) -> HistoryStatus {
+        let state = self.state.lock().unwrap();
+        HistoryStatus {
+            undo: state.undo.back().map(Operation::label),
+            redo: state.redo.last().map(Operation::label),
+        }
+    }
+
+    fn push_undo_locked(&self, state: &mut JournalState, operation: Operation) {
+        state.undo.push_back(operation);
+        while state.undo.len() > self.limit {
+            state.undo.pop_front();
+        }
+    }
+}
+
+impl Default for Journal {
+    fn default() -> Self {
+        Journal::new(DEFAULT_HISTORY_LIMIT)
+    }
+}
+
+// Entri journal dan event dari satu transaksi. Service yang dipanggil di dalam transaksi service
+// lain menambahkannya ke sini; pemanggil paling luar mencatat dan mempublikasikannya setelah
+// commit, jadi perubahan yang di-rollback tidak pernah terlihat dan satu aksi menjadi satu
+// langkah undo.
+#[derive(Default)]
+pub struct Changes {
+    operations: Vec<Operation>,
+    events: Vec<DomainEvent>,
+    // Label langkah undo; tanpa ini dipakai label mutasi pertama
+    label: Option<String>,
+}
+
+impl Changes {
+    pub fn new() -> Self {
+        Changes::default()
+    }
+
+    pub fn record(&mut self, operation: Operation) {
+        self.operations.push(operation);
+    }
+
+    pub fn publish(&mut self, event: DomainEvent) {
+        self.events.push(event);
+    }
+
+    // Tambahkan perubahan aksi utama di belakang supaya dibalik lebih dulu saat undo.
+    // Labelnya menjadi label langkah undo.
+    pub fn extend(&mut self, other: Changes) {
+        self.label = other.label.or_else(|| other.operations.first().map(Operation::label));
+        self.operations.extend(other.operations);
+        self.events.extend(other.events);
+    }
+
+    // Catat semua mutasi sebagai satu langkah undo, lalu publikasikan event-nya
+    pub fn commit(mut self, journal: &Journal, events: &EventBus) {
+        match self.operations.len() {
+            0 => {}
+            1 => journal.record(self.operations.remove(0)),
+            _ => {
+                let operations = std::mem::take(&mut self.operations);
+                let label = self.label.take().unwrap_or_else(|| operations[0].label());
+                journal.record(Operation::Batch { label, operations });
+            }
+        }
+        self.publish_to(events);
+    }
+
+    // Untuk undo/redo: mutasinya sudah ada di journal, jadi hanya event yang dipublikasikan
+    pub fn publish_to(self, events: &EventBus) {
+        for event in self.events {
+            events.publish(event);
+        }
+    }
+}
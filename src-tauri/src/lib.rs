@@ -6,25 +6,111 @@ pub mod repository;
 pub mod services;
 pub mod commands;
 pub mod migrations;
+pub mod db;
+pub mod fs;
+pub mod events;
+pub mod journal;
+pub mod pdf;
+pub mod ical;
+pub mod caldav;
 
 // Import semua command yang relevan dan AppState dari module commands
 use commands::{
     // Task Management
     create_task,
     fetch_tasks,
+    preview_quick_add,
+    next_tasks,
+    get_scoring_weights,
+    update_scoring_weights,
     update_task,
     delete_task,
+    get_task_history,
+    // Time Tracking
+    add_time_entry,
+    fetch_time_entries,
+    delete_time_entry,
+    start_timer,
+    stop_timer,
+    get_running_timer,
+    get_time_report,
+    // Planner
+    propose_schedule,
+    accept_schedule,
+    fetch_schedule,
+    delete_schedule_block,
+    reflow_schedule,
+    get_planner_config,
+    update_planner_config,
+    // Calendar
+    create_event,
+    fetch_events,
+    update_event,
+    delete_event,
+    calendar_range,
+    // CalDAV
+    get_caldav_config,
+    update_caldav_config,
+    discover_caldav_calendars,
+    sync_caldav,
     // Notes
     create_note,
     fetch_notes,
+    read_note,
     update_note,
     delete_note,
+    list_note_revisions,
+    diff_note_revisions,
+    restore_note_revision,
+    get_note_backlinks,
+    get_task_backlinks,
+    get_task_source,
+    render_note,
+    export_notes,
+    reindex_vault,
+    // Attachments
+    add_attachment,
+    list_attachments,
+    remove_attachment,
+    collect_attachment_garbage,
+    attachment_response,
+    vault_response,
+    // Backup & Restore
+    export_archive,
+    import_archive,
+    // CSV
+    export_tasks_csv,
+    import_tasks_csv,
+    // Database Backups
+    list_backups,
+    create_backup,
+    restore_backup,
+    get_backup_config,
+    update_backup_config,
+    // Undo/Redo
+    undo,
+    redo,
+    get_history_status,
+    // Trash
+    list_trash,
+    restore_task,
+    restore_note,
+    empty_trash,
+    get_trash_config,
+    update_trash_config,
     AppState // Struct state yang berisi pool
 };
 
-// Import komponen untuk R2D2 (connection pool) dan SQLite
-use r2d2_sqlite::SqliteConnectionManager;
-use r2d2::Pool;
+use db::{Database, DbConfig};
+use events::{start_tauri_bridge, EventBus};
+use journal::{Journal, DEFAULT_HISTORY_LIMIT};
+use services::AppServices;
+use services::backup::start_backup_scheduler;
+use services::render::{ATTACHMENT_PROTOCOL, VAULT_PROTOCOL};
+use services::trash::start_trash_purge_scheduler;
+use services::vault::start_vault_watcher;
+use std::path::PathBuf;
+use std::sync::Arc;
 // Import fungsi migrasi
 use migrations::run_migrations;
 
@@ -34,42 +120,160 @@ pub fn run() {
     // Setup connection manager untuk SQLite, menunjuk ke file database di ROOT project.
     // Path relatif dari direktori kerja `src-tauri` saat `tauri dev` adalah `../`
     let db_path = "../nocture.db";
-    let manager = SqliteConnectionManager::file(db_path);
     // --- PERUBAHAN SELESAI ---
 
-    // Buat connection pool dari manager
-    let pool = Pool::new(manager).expect("Gagal membuat pool koneksi DB");
+    // Buat connection pool (dibungkus Database agar bisa dibuka ulang saat restore).
+    // Setiap koneksi memakai WAL, foreign key, dan busy timeout dari DbConfig.
+    let db = Arc::new(
+        Database::open_with_config(db_path, DbConfig::default()).expect("Gagal membuat pool koneksi DB"),
+    );
 
     // Blok ini memastikan koneksi yang didapat dari pool untuk migrasi
     // akan otomatis dikembalikan ke pool setelah selesai digunakan.
     {
         // Dapatkan satu koneksi dari pool untuk menjalankan migrasi
-        let conn = pool.get().expect("Gagal mendapatkan koneksi dari pool untuk migrasi");
+        let conn = db.get().expect("Gagal mendapatkan koneksi dari pool untuk migrasi");
         // Jalankan migrasi (misalnya, CREATE TABLE IF NOT EXISTS)
         run_migrations(&conn).expect("Gagal menjalankan migrasi database");
         // `conn` otomatis kembali ke pool di sini
     }
 
+    // Backup disimpan di folder `backups` di ROOT project, sejajar dengan database
+    let backup_dir = PathBuf::from("../backups");
+    // Bus event bersama: service mempublikasikan perubahan, bridge meneruskannya ke frontend
+    let events = EventBus::new();
+    // File markdown notes disimpan di folder `notes` di ROOT project, sejajar dengan database.
+    // Note yang dihapus ditampung di `notes/.trash` sampai di-restore atau di-purge.
+    let vault_dir = PathBuf::from("../notes");
+    let journal = Journal::new(DEFAULT_HISTORY_LIMIT);
+    let services = Arc::new(AppServices::new(events.clone(), journal, vault_dir.clone()));
+    // Snapshot saat startup, lalu lanjut sesuai jadwal di thread terpisah
+    start_backup_scheduler(db.clone(), services.clone(), backup_dir.clone());
+    // Item di trash yang melewati masa simpan dihapus permanen secara berkala
+    start_trash_purge_scheduler(db.clone(), services.clone());
+    // Perubahan file markdown dari editor lain (VS Code, Obsidian) disinkronkan ke tabel notes.
+    // Aplikasi tetap jalan tanpa watcher jika gagal dipasang.
+    if let Err(e) = start_vault_watcher(db.clone(), services.clone()) {
+        eprintln!("Gagal memantau folder notes: {}", e);
+    }
+
+    // Salinan untuk protocol `attachment://` dan `vault://`, yang dilayani di luar command
+    let protocol_db = db.clone();
+    let attachment_services = services.clone();
+    let vault_services = services.clone();
+
     // Buat instance AppState yang berisi pool koneksi
-    let state = AppState { pool };
+    let state = AppState {
+        db,
+        services,
+        vault_dir,
+        backup_dir,
+    };
 
     // Bangun (Build) aplikasi Tauri
     tauri::Builder::default()
         // Daftarkan AppState sebagai state yang dikelola Tauri.
         // Ini memungkinkan command untuk mengakses pool melalui `state: State<AppState>`
         .manage(state)
+        // Teruskan event domain (task/note berubah) ke semua window yang terbuka
+        .setup(move |app| {
+            start_tauri_bridge(app.handle().clone(), events.subscribe());
+            Ok(())
+        })
+        // Lampiran dan file vault dibaca di thread terpisah supaya file besar tidak menahan webview
+        .register_asynchronous_uri_scheme_protocol(ATTACHMENT_PROTOCOL, move |_ctx, request, responder| {
+            let db = protocol_db.clone();
+            let services = attachment_services.clone();
+            let path = request.uri().path().to_string();
+            std::thread::spawn(move || responder.respond(attachment_response(&db, &services, &path)));
+        })
+        .register_asynchronous_uri_scheme_protocol(VAULT_PROTOCOL, move |_ctx, request, responder| {
+            let services = vault_services.clone();
+            let path = request.uri().path().to_string();
+            std::thread::spawn(move || responder.respond(vault_response(&services, &path)));
+        })
         // Daftarkan semua fungsi command yang bisa dipanggil dari frontend (JavaScript/React)
         .invoke_handler(tauri::generate_handler![
             // Task Management
             create_task,
             fetch_tasks,
+            preview_quick_add,
+            next_tasks,
+            get_scoring_weights,
+            update_scoring_weights,
             update_task,
             delete_task,
+            get_task_history,
+            // Time Tracking
+            add_time_entry,
+            fetch_time_entries,
+            delete_time_entry,
+            start_timer,
+            stop_timer,
+            get_running_timer,
+            get_time_report,
+            // Planner
+            propose_schedule,
+            accept_schedule,
+            fetch_schedule,
+            delete_schedule_block,
+            reflow_schedule,
+            get_planner_config,
+            update_planner_config,
+            // Calendar
+            create_event,
+            fetch_events,
+            update_event,
+            delete_event,
+            calendar_range,
+            // CalDAV
+            get_caldav_config,
+            update_caldav_config,
+            discover_caldav_calendars,
+            sync_caldav,
             // Notes
             create_note,
             fetch_notes,
+            read_note,
             update_note,
-            delete_note
+            delete_note,
+            list_note_revisions,
+            diff_note_revisions,
+            restore_note_revision,
+            get_note_backlinks,
+            get_task_backlinks,
+            get_task_source,
+            render_note,
+            export_notes,
+            reindex_vault,
+            // Attachments
+            add_attachment,
+            list_attachments,
+            remove_attachment,
+            collect_attachment_garbage,
+            // Backup & Restore
+            export_archive,
+            import_archive,
+            // CSV
+            export_tasks_csv,
+            import_tasks_csv,
+            // Database Backups
+            list_backups,
+            create_backup,
+            restore_backup,
+            get_backup_config,
+            update_backup_config,
+            // Undo/Redo
+            undo,
+            redo,
+            get_history_status,
+            // Trash
+            list_trash,
+            restore_task,
+            restore_note,
+            empty_trash,
+            get_trash_config,
+            update_trash_config
         ])
         // Jalankan aplikasi Tauri dengan context yang digenerate
         .run(tauri::generate_context!())
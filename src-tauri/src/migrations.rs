@@ -1,6 +1,40 @@
 use rusqlite::Connection;
 
+// Setiap migrasi dijalankan sekali, berurutan. Versi skema disimpan di `PRAGMA user_version`,
+// jadi migrasi baru cukup ditambahkan di akhir daftar ini (jangan mengubah yang sudah ada).
+const MIGRATIONS: &[fn(&Connection) -> rusqlite::Result<()>] = &[
+    create_initial_tables,
+    add_soft_delete,
+    add_task_history,
+    add_note_revisions,
+    add_note_missing_flag,
+    add_note_metadata,
+    add_note_links,
+    add_attachments,
+    add_note_tasks,
+    add_task_estimates,
+    add_time_entries,
+    add_schedule_blocks,
+    add_events,
+    add_caldav_sync,
+    add_task_history_kind,
+    move_fixed_blocks_to_events,
+];
+
 pub fn run_migrations(conn: &Connection) -> rusqlite::Result<()> {
+    let current: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(current) {
+        let tx = conn.unchecked_transaction()?;
+        migration(&tx)?;
+        tx.pragma_update(None, "user_version", index + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+// Versi 1: tabel awal. Memakai IF NOT EXISTS karena database lama sudah punya tabel ini
+// sebelum user_version dipakai.
+fn create_initial_tables(conn: &Connection) -> rusqlite::Result<()> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS tasks (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -24,5 +58,255 @@ pub fn run_migrations(conn: &Connection) -> rusqlite::Result<()> {
         )",
         [],
     )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        )",
+        [],
+    )?;
     Ok(())
 }
+
+// Versi 2: soft delete. Tabel notes dibangun ulang supaya file_path hanya unik di antara
+// note yang belum dihapus; note di trash tidak menghalangi note baru dengan path yang sama.
+fn add_soft_delete(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE tasks ADD COLUMN deleted_at TEXT;
+        CREATE INDEX idx_tasks_deleted_at ON tasks(deleted_at);
+
+        CREATE TABLE notes_new (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            file_path TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            deleted_at DATETIME
+        );
+        INSERT INTO notes_new (id, title, file_path, created_at, updated_at)
+            SELECT id, title, file_path, created_at, updated_at FROM notes;
+        DROP TABLE notes;
+        ALTER TABLE notes_new RENAME TO notes;
+        CREATE UNIQUE INDEX idx_notes_live_file_path ON notes(file_path) WHERE deleted_at IS NULL;
+        CREATE INDEX idx_notes_deleted_at ON notes(deleted_at);",
+    )
+}
+
+// Versi 3: riwayat perubahan field task. Riwayat ikut terhapus saat task dihapus permanen.
+fn add_task_history(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE task_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+            field TEXT NOT NULL,
+            old_value TEXT,
+            new_value TEXT,
+            changed_at TEXT NOT NULL
+        );
+        CREATE INDEX idx_task_history_task_id ON task_history(task_id, changed_at);",
+    )
+}
+
+// Versi 4: versi lama isi note. Isi disimpan terkompresi (zlib) beserta hash SHA-256-nya.
+fn add_note_revisions(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE note_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            note_id INTEGER NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+            content_hash TEXT NOT NULL,
+            size INTEGER NOT NULL,
+            created_at TEXT NOT NULL,
+            data BLOB NOT NULL
+        );
+        CREATE INDEX idx_note_revisions_note_id ON note_revisions(note_id, created_at);",
+    )
+}
+
+// Versi 5: penanda note yang file markdown-nya hilang dari disk (dihapus di luar aplikasi)
+fn add_note_missing_flag(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch("ALTER TABLE notes ADD COLUMN missing BOOLEAN NOT NULL DEFAULT 0;")
+}
+
+// Versi 6: metadata note dari front-matter file markdown. Tag dan id task disimpan sebagai array JSON.
+fn add_note_metadata(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE notes ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';
+        ALTER TABLE notes ADD COLUMN color TEXT;
+        ALTER TABLE notes ADD COLUMN task_ids TEXT NOT NULL DEFAULT '[]';",
+    )
+}
+
+// Versi 7: link `[[...]]` antar note dan ke task. Link ke note disimpan berdasarkan judul yang
+// sudah dinormalisasi (huruf kecil), jadi bisa menunjuk note yang belum ada. Link ikut terhapus
+// saat note sumbernya dihapus permanen.
+fn add_note_links(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE note_links (
+            source_id INTEGER NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+            target_title TEXT,
+            target_task_id INTEGER,
+            CHECK ((target_title IS NULL) <> (target_task_id IS NULL))
+        );
+        CREATE INDEX idx_note_links_source ON note_links(source_id);
+        CREATE INDEX idx_note_links_title ON note_links(target_title);
+        CREATE INDEX idx_note_links_task ON note_links(target_task_id);",
+    )
+}
+
+// Versi 8: lampiran task dan note. Pemiliknya bisa task atau note, jadi tanpa foreign key;
+// baris yatim dibersihkan oleh garbage collector lampiran.
+fn add_attachments(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE attachments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            owner_kind TEXT NOT NULL,
+            owner_id INTEGER NOT NULL,
+            file_name TEXT NOT NULL,
+            content_hash TEXT NOT NULL,
+            size INTEGER NOT NULL,
+            mime_type TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            UNIQUE (owner_kind, owner_id, content_hash)
+        );
+        CREATE INDEX idx_attachments_content_hash ON attachments(content_hash);",
+    )
+}
+
+// Versi 9: task yang dibuat dari checkbox `- [ ]` di note, beserta baris dan teks checkbox-nya.
+// Satu task hanya berasal dari satu checkbox; tautannya ikut terhapus bersama task atau note-nya.
+fn add_note_tasks(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE note_tasks (
+            task_id INTEGER PRIMARY KEY REFERENCES tasks(id) ON DELETE CASCADE,
+            note_id INTEGER NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+            line INTEGER NOT NULL,
+            text TEXT NOT NULL
+        );
+        CREATE INDEX idx_note_tasks_note_id ON note_tasks(note_id);",
+    )
+}
+
+// Versi 10: perkiraan waktu pengerjaan task (menit), kosong jika belum diisi
+fn add_task_estimates(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch("ALTER TABLE tasks ADD COLUMN estimated_minutes INTEGER;")
+}
+
+// Versi 11: waktu yang dihabiskan per task, dari input manual atau sesi timer.
+// Timer yang masih berjalan belum punya `ended_at`; catatannya ikut terhapus bersama task-nya.
+fn add_time_entries(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE time_entries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+            source TEXT NOT NULL,
+            started_at TEXT NOT NULL,
+            ended_at TEXT,
+            minutes INTEGER NOT NULL,
+            note TEXT
+        );
+        CREATE INDEX idx_time_entries_task_id ON time_entries(task_id);
+        CREATE INDEX idx_time_entries_started_at ON time_entries(started_at);",
+    )
+}
+
+// Versi 12: jadwal harian dari planner. Blok tanpa task_id adalah acara kalender tetap;
+// blok task ikut terhapus bersama task-nya.
+fn add_schedule_blocks(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE schedule_blocks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id INTEGER REFERENCES tasks(id) ON DELETE CASCADE,
+            title TEXT NOT NULL,
+            start_at TEXT NOT NULL,
+            end_at TEXT NOT NULL
+        );
+        CREATE INDEX idx_schedule_blocks_start_at ON schedule_blocks(start_at);
+        CREATE INDEX idx_schedule_blocks_task_id ON schedule_blocks(task_id);",
+    )
+}
+
+// Versi 13: acara kalender yang bukan task. Aturan pengulangan disimpan sebagai JSON;
+// kemunculannya dihitung saat kalender dibaca.
+fn add_events(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            start_at TEXT NOT NULL,
+            end_at TEXT NOT NULL,
+            all_day BOOLEAN NOT NULL,
+            location TEXT,
+            recurrence TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );
+        CREATE INDEX idx_events_start_at ON events(start_at);",
+    )
+}
+
+// Versi 14: status sinkronisasi CalDAV. Satu baris per task/acara yang sudah ada di server
+// (tanpa foreign key karena local_id bisa menunjuk ke dua tabel), dan sync-token per kalender.
+fn add_caldav_sync(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE caldav_items (
+            kind TEXT NOT NULL,
+            local_id INTEGER NOT NULL,
+            calendar TEXT NOT NULL,
+            href TEXT NOT NULL UNIQUE,
+            uid TEXT NOT NULL,
+            etag TEXT,
+            local_updated_at TEXT NOT NULL,
+            PRIMARY KEY (kind, local_id)
+        );
+        CREATE TABLE caldav_sync_tokens (
+            kind TEXT NOT NULL,
+            calendar TEXT NOT NULL,
+            sync_token TEXT,
+            PRIMARY KEY (kind, calendar)
+        );",
+    )
+}
+
+// Versi 15: riwayat task juga mencatat task dibuat, dibuang ke trash, dan dipulihkan.
+// Entri seperti itu tidak punya field, jadi tabel dibangun ulang supaya `field` boleh NULL.
+fn add_task_history_kind(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE task_history_new (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+            kind TEXT NOT NULL DEFAULT 'updated',
+            field TEXT,
+            old_value TEXT,
+            new_value TEXT,
+            changed_at TEXT NOT NULL
+        );
+        INSERT INTO task_history_new (id, task_id, field, old_value, new_value, changed_at)
+            SELECT id, task_id, field, old_value, new_value, changed_at FROM task_history;
+        DROP TABLE task_history;
+        ALTER TABLE task_history_new RENAME TO task_history;
+        CREATE INDEX idx_task_history_task_id ON task_history(task_id, changed_at);",
+    )
+}
+
+// Versi 16: blok jadwal tanpa task (kuliah, rapat) dipindah ke tabel events supaya tampil di
+// kalender dan ikut sinkron CalDAV. Setelah itu setiap blok jadwal wajib punya task.
+fn move_fixed_blocks_to_events(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "INSERT INTO events (title, start_at, end_at, all_day, location, recurrence, created_at, updated_at)
+            SELECT title, start_at, end_at, 0, NULL, NULL, datetime('now'), datetime('now')
+            FROM schedule_blocks WHERE task_id IS NULL ORDER BY start_at, id;
+        CREATE TABLE schedule_blocks_new (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+            title TEXT NOT NULL,
+            start_at TEXT NOT NULL,
+            end_at TEXT NOT NULL
+        );
+        INSERT INTO schedule_blocks_new (id, task_id, title, start_at, end_at)
+            SELECT id, task_id, title, start_at, end_at FROM schedule_blocks WHERE task_id IS NOT NULL;
+        DROP TABLE schedule_blocks;
+        ALTER TABLE schedule_blocks_new RENAME TO schedule_blocks;
+        CREATE INDEX idx_schedule_blocks_start_at ON schedule_blocks(start_at);
+        CREATE INDEX idx_schedule_blocks_task_id ON schedule_blocks(task_id);",
+    )
+}
@@ -0,0 +1,43 @@
+use chrono::NaiveDateTime;
+use serde::{Serialize, Deserialize};
+use crate::model::task::Task;
+
+// Versi format arsip yang ditulis `export_archive`. Naikkan setiap kali format manifest berubah
+// sehingga tidak bisa dibaca versi aplikasi yang lebih lama.
+pub const ARCHIVE_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub schema_version: u32,
+    pub exported_at: NaiveDateTime,
+    pub tasks: Vec<Task>,
+    pub notes: Vec<ArchivedNote>,
+}
+
+// Baris note beserta lokasi isi markdown-nya di dalam arsip
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedNote {
+    pub id: i32,
+    pub title: String,
+    pub body_file: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdMapping {
+    pub old_id: i32,
+    pub new_id: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportSummary {
+    pub tasks_exported: usize,
+    pub notes_exported: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub tasks: Vec<IdMapping>,
+    pub notes: Vec<IdMapping>,
+}
@@ -0,0 +1,68 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+// Pemilik sebuah lampiran
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum AttachmentOwner {
+    Task,
+    Note,
+}
+
+impl AttachmentOwner {
+    // Nilai yang disimpan di kolom `owner_kind`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttachmentOwner::Task => "task",
+            AttachmentOwner::Note => "note",
+        }
+    }
+}
+
+impl std::str::FromStr for AttachmentOwner {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<AttachmentOwner, Self::Err> {
+        match input {
+            "task" => Ok(AttachmentOwner::Task),
+            "note" => Ok(AttachmentOwner::Note),
+            _ => Err(format!("Unknown attachment owner '{}'", input)),
+        }
+    }
+}
+
+// File yang dilampirkan ke task atau note. Isinya disimpan sekali per hash di folder
+// lampiran vault, jadi beberapa lampiran bisa menunjuk file yang sama.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Attachment {
+    pub id: i32,
+    pub owner: AttachmentOwner,
+    pub owner_id: i32,
+    // Nama file asli yang dipilih user
+    pub file_name: String,
+    // SHA-256 isi file, sekaligus nama file di folder lampiran
+    pub content_hash: String,
+    pub size: i64,
+    pub mime_type: String,
+    pub created_at: NaiveDateTime,
+}
+
+// Jenis file yang aman ditampilkan langsung di webview aplikasi. HTML, SVG, XML, dan jenis lain
+// yang bisa menjalankan script hanya dikirim sebagai unduhan.
+pub fn is_inline_safe(mime_type: &str) -> bool {
+    match mime_type.split_once('/') {
+        Some(("image", "svg+xml")) => false,
+        Some(("image" | "audio" | "video", _)) => true,
+        _ => matches!(mime_type, "application/pdf" | "text/plain" | "text/markdown" | "text/csv"),
+    }
+}
+
+// Hasil pembersihan lampiran yatim
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct AttachmentGcSummary {
+    // Baris lampiran yang task/note-nya sudah dihapus permanen
+    pub rows_removed: usize,
+    // File di folder lampiran yang tidak dipakai lampiran mana pun
+    pub files_removed: usize,
+    pub bytes_freed: u64,
+}
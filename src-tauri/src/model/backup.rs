@@ -0,0 +1,31 @@
+use chrono::NaiveDateTime;
+use serde::{Serialize, Deserialize};
+
+// Key di tabel settings untuk konfigurasi backup
+pub const BACKUP_SETTINGS_KEY: &str = "backup";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BackupConfig {
+    pub enabled: bool,
+    // Jarak antar backup otomatis, dalam menit
+    pub interval_minutes: u64,
+    // Jumlah file backup yang disimpan; yang lebih lama dihapus
+    pub keep: usize,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        BackupConfig {
+            enabled: true,
+            interval_minutes: 60,
+            keep: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub file_name: String,
+    pub created_at: NaiveDateTime,
+    pub size_bytes: u64,
+}
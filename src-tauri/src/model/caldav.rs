@@ -0,0 +1,108 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+// Key di tabel settings untuk koneksi CalDAV
+pub const CALDAV_SETTINGS_KEY: &str = "caldav";
+
+// Koneksi ke server CalDAV (Radicale, Nextcloud, dll.). Password disimpan apa adanya di
+// tabel settings, jadi sebaiknya pakai app password dari server, bukan password utama.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(default)]
+pub struct CalDavConfig {
+    // Alamat server atau akun, mis. "https://cloud.example.com/remote.php/dav/"
+    pub server_url: String,
+    pub username: String,
+    pub password: String,
+    // Href kalender hasil `discover_caldav_calendars`; kosong berarti jenis itu tidak disinkronkan.
+    // Task dan acara boleh memakai kalender yang sama.
+    pub task_calendar: Option<String>,
+    pub event_calendar: Option<String>,
+}
+
+// Kalender yang ditemukan di server
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RemoteCalendar {
+    pub href: String,
+    pub name: String,
+    // Bisa menyimpan VTODO (task) dan/atau VEVENT (acara)
+    pub supports_tasks: bool,
+    pub supports_events: bool,
+}
+
+// Jenis data lokal yang disinkronkan
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncKind {
+    // Task <-> VTODO
+    Task,
+    // Acara kalender <-> VEVENT
+    Event,
+}
+
+impl SyncKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncKind::Task => "task",
+            SyncKind::Event => "event",
+        }
+    }
+}
+
+impl std::str::FromStr for SyncKind {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<SyncKind, Self::Err> {
+        match input {
+            "task" => Ok(SyncKind::Task),
+            "event" => Ok(SyncKind::Event),
+            _ => Err(format!("Unknown sync kind '{}'", input)),
+        }
+    }
+}
+
+// Pasangan item lokal dengan resource di server, beserta status terakhir yang sudah sinkron
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncedItem {
+    pub kind: SyncKind,
+    pub local_id: i32,
+    // Href kalender tempat resource berada
+    pub calendar: String,
+    // Href resource .ics
+    pub href: String,
+    pub uid: String,
+    // ETag resource saat terakhir dibaca atau ditulis; kosong jika server tidak mengirimkannya
+    pub etag: Option<String>,
+    // `updated_at` item lokal saat terakhir sinkron. Jika berbeda, item lokal sudah diubah sejak itu.
+    pub local_updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictWinner {
+    Local,
+    Remote,
+}
+
+// Item yang berubah di kedua sisi sejak sinkronisasi terakhir
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SyncConflict {
+    pub kind: SyncKind,
+    // Id item lokal setelah konflik diselesaikan
+    pub local_id: i32,
+    pub title: String,
+    pub winner: ConflictWinner,
+}
+
+// Ringkasan satu kali sinkronisasi
+#[derive(Debug, Clone, Serialize, PartialEq, Default)]
+pub struct SyncSummary {
+    // Item lokal yang dibuat atau diubah dari server
+    pub pulled: usize,
+    // Resource di server yang dibuat atau diubah dari item lokal
+    pub pushed: usize,
+    // Task yang dipindahkan ke trash atau acara yang dihapus karena sudah dihapus di server
+    pub deleted_locally: usize,
+    // Resource di server yang dihapus karena item lokalnya sudah dihapus
+    pub deleted_remotely: usize,
+    pub conflicts: Vec<SyncConflict>,
+}
@@ -0,0 +1,75 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use crate::model::recurrence::Recurrence;
+
+// Acara kalender yang bukan to-do, mis. kuliah, ujian, atau rapat
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CalendarEvent {
+    pub id: i32,
+    pub title: String,
+    // Untuk acara seharian `start` pukul 00.00 hari pertama dan `end` pukul 00.00 setelah hari terakhir
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub all_day: bool,
+    pub location: Option<String>,
+    // Aturan pengulangan; `start`/`end` adalah kemunculan pertama
+    pub recurrence: Option<Recurrence>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl CalendarEvent {
+    // Waktu mulai dan selesai setiap kemunculan yang bersinggungan dengan `from`..`to`
+    pub fn occurrences(&self, from: NaiveDateTime, to: NaiveDateTime) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+        let duration = self.end - self.start;
+        match &self.recurrence {
+            Some(recurrence) => recurrence.occurrences(self.start, duration, from, to)
+                .into_iter()
+                .map(|start| (start, start + duration))
+                .collect(),
+            None if self.start < to && (self.start >= from || self.end > from) => vec![(self.start, self.end)],
+            None => Vec::new(),
+        }
+    }
+}
+
+// Data dari frontend untuk membuat atau mengubah acara
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarEventPayload {
+    pub title: String,
+    // "YYYY-MM-DDTHH:MM", atau "YYYY-MM-DD" untuk acara seharian (`end` = hari terakhir, inklusif)
+    pub start: String,
+    pub end: String,
+    #[serde(default)]
+    pub all_day: bool,
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CalendarItemKind {
+    Event,
+    // Deadline task
+    Task,
+}
+
+// Satu entri di tampilan bulan/minggu: kemunculan acara atau deadline task
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CalendarItem {
+    pub kind: CalendarItemKind,
+    // Id acara atau task
+    pub id: i32,
+    pub title: String,
+    pub start: NaiveDateTime,
+    // Sama dengan `start` untuk deadline task yang punya jam
+    pub end: NaiveDateTime,
+    pub all_day: bool,
+    pub location: Option<String>,
+    // Hanya untuk task
+    pub completed: bool,
+    // Kemunculan dari acara berulang
+    pub recurring: bool,
+}
@@ -0,0 +1,160 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_yaml::{Mapping, Value};
+use crate::model::note::Note;
+
+const DELIMITER: &str = "---";
+
+// The YAML block at the top of a note's markdown file. It is the source of truth for the
+// note's metadata; the notes table is an index built from it. Timestamps are UTC.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct FrontMatter {
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient")]
+    pub title: Option<String>,
+    // Accepts a single tag (`tags: rust`) as well as a list
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "string_or_list")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "datetime")]
+    pub created: Option<NaiveDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "datetime")]
+    pub updated: Option<NaiveDateTime>,
+    // Ids of the tasks linked to the note
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "lenient_list")]
+    pub tasks: Vec<i32>,
+    // Keys written by other tools (aliases, publish flags, ...) are kept as they are
+    #[serde(flatten)]
+    pub extra: Mapping,
+}
+
+impl FrontMatter {
+    // Splits a markdown file into its front-matter and body. A file without a front-matter
+    // block, or with one that isn't valid YAML, is returned whole as the body.
+    pub fn split(content: &str) -> (Option<FrontMatter>, &str) {
+        let Some(rest) = strip_delimiter_line(content) else {
+            return (None, content);
+        };
+        let mut offset = 0;
+        for line in rest.split_inclusive('\n') {
+            if line.trim_end() == DELIMITER || line.trim_end() == "..." {
+                let yaml = &rest[..offset];
+                let body = &rest[offset + line.len()..];
+                return match parse_yaml(yaml) {
+                    Some(front) => (Some(front), body),
+                    None => (None, content),
+                };
+            }
+            offset += line.len();
+        }
+        (None, content)
+    }
+
+    // Just the body of a markdown file
+    pub fn body(content: &str) -> &str {
+        FrontMatter::split(content).1
+    }
+
+    // Front-matter describing `note`. Keys from `existing` that the app doesn't manage are kept.
+    pub fn from_note(note: &Note, existing: Option<FrontMatter>) -> FrontMatter {
+        FrontMatter {
+            title: Some(note.title.clone()),
+            tags: note.tags.clone(),
+            color: note.color.clone(),
+            created: Some(note.created_at),
+            updated: Some(note.updated_at),
+            tasks: note.task_ids.clone(),
+            extra: existing.map(|front| front.extra).unwrap_or_default(),
+        }
+    }
+
+    // Copies the metadata found in the front-matter onto `note`; keys that are absent leave
+    // the note as it is
+    pub fn apply_to(&self, note: &mut Note) {
+        if let Some(title) = self.title.as_ref().filter(|title| !title.trim().is_empty()) {
+            note.title = title.trim().to_string();
+        }
+        note.tags = self.tags.clone();
+        note.color = self.color.clone();
+        note.task_ids = self.tasks.clone();
+        if let Some(created) = self.created {
+            note.created_at = created;
+        }
+        if let Some(updated) = self.updated {
+            note.updated_at = updated;
+        }
+    }
+
+    // The full file content: front-matter block followed by `body`
+    pub fn render(&self, body: &str) -> Result<String, String> {
+        let yaml = serde_yaml::to_string(self).map_err(|e| format!("Failed to write front-matter: {}", e))?;
+        Ok(format!("{}\n{}{}\n{}", DELIMITER, yaml, DELIMITER, body))
+    }
+}
+
+fn strip_delimiter_line(content: &str) -> Option<&str> {
+    let rest = content.strip_prefix(DELIMITER)?;
+    rest.strip_prefix('\n').or_else(|| rest.strip_prefix("\r\n"))
+}
+
+fn parse_yaml(yaml: &str) -> Option<FrontMatter> {
+    if yaml.trim().is_empty() {
+        return Some(FrontMatter::default());
+    }
+    match serde_yaml::from_str::<Value>(yaml).ok()? {
+        value @ Value::Mapping(_) => serde_yaml::from_value(value).ok(),
+        _ => None,
+    }
+}
+
+// Front-matter is often edited by hand, so a value of the wrong type is dropped instead of
+// making the whole block unreadable
+fn lenient<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    let value = Value::deserialize(deserializer)?;
+    Ok(serde_yaml::from_value(value).ok())
+}
+
+fn lenient_list<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Sequence(items) => items.into_iter().filter_map(|item| serde_yaml::from_value(item).ok()).collect(),
+        Value::Null => Vec::new(),
+        single => serde_yaml::from_value(single).into_iter().collect(),
+    })
+}
+
+fn string_or_list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    let tags: Vec<Value> = lenient_list(deserializer)?;
+    Ok(tags
+        .into_iter()
+        .filter_map(|tag| match tag {
+            Value::String(tag) => Some(tag),
+            Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        })
+        .map(|tag| tag.trim().trim_start_matches('#').to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect())
+}
+
+fn datetime<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<NaiveDateTime>, D::Error> {
+    let text: Option<String> = lenient(deserializer)?;
+    Ok(text.as_deref().map(str::trim).and_then(parse_datetime))
+}
+
+fn parse_datetime(text: &str) -> Option<NaiveDateTime> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(text) {
+        return Some(datetime.with_timezone(&Utc).naive_utc());
+    }
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok().and_then(|date| date.and_hms_opt(0, 0, 0)))
+}
@@ -0,0 +1,40 @@
+use serde::Serialize;
+use crate::model::note::Note;
+use crate::model::task::Task;
+
+// Satu mutasi yang tercatat di journal, lengkap dengan state sebelum/sesudah
+// sehingga bisa dibalik (undo) maupun diulang (redo). Penghapusan memakai trash,
+// jadi membalik create/delete cukup memindahkan item ke/dari trash.
+#[derive(Debug, Clone)]
+pub enum Operation {
+    TaskCreated { task: Task },
+    TaskUpdated { before: Task, after: Task },
+    TaskDeleted { task: Task },
+    NoteCreated { note: Note },
+    NoteUpdated { before: Note, after: Note, before_content: Vec<u8>, after_content: Vec<u8> },
+    NoteDeleted { note: Note },
+    // Beberapa mutasi dari satu aksi (mis. task selesai beserta checkbox di note-nya),
+    // dibalik bersama dalam satu langkah undo. `label` dari mutasi utamanya.
+    Batch { label: String, operations: Vec<Operation> },
+}
+
+impl Operation {
+    // Label singkat untuk tombol undo/redo di frontend
+    pub fn label(&self) -> String {
+        match self {
+            Operation::TaskCreated { task } => format!("Create task \"{}\"", task.title),
+            Operation::TaskUpdated { after, .. } => format!("Edit task \"{}\"", after.title),
+            Operation::TaskDeleted { task } => format!("Delete task \"{}\"", task.title),
+            Operation::NoteCreated { note } => format!("Create note \"{}\"", note.title),
+            Operation::NoteUpdated { after, .. } => format!("Edit note \"{}\"", after.title),
+            Operation::NoteDeleted { note } => format!("Delete note \"{}\"", note.title),
+            Operation::Batch { label, .. } => label.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryStatus {
+    pub undo: Option<String>,
+    pub redo: Option<String>,
+}
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+
+const TASK_PREFIX: &str = "task:";
+
+// What a `[[...]]` reference in a note points at. Note links are by title and matched
+// case-insensitively, so they can point at a note that doesn't exist (yet).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "target", rename_all = "lowercase")]
+pub enum LinkTarget {
+    Note(String),
+    Task(i32),
+}
+
+impl LinkTarget {
+    // All distinct links in a markdown body, in order of first appearance. Obsidian-style
+    // headings and aliases (`[[Title#Heading|label]]`) link to the note itself.
+    pub fn extract(markdown: &str) -> Vec<LinkTarget> {
+        let mut links = Vec::new();
+        for (_, inner) in wiki_links(markdown) {
+            if let Some(link) = LinkTarget::parse(inner) {
+                if !links.iter().any(|other: &LinkTarget| other.same_target(&link)) {
+                    links.push(link);
+                }
+            }
+        }
+        links
+    }
+
+    fn same_target(&self, other: &LinkTarget) -> bool {
+        match (self, other) {
+            (LinkTarget::Note(a), LinkTarget::Note(b)) => same_title(a, b),
+            (a, b) => a == b,
+        }
+    }
+
+    fn parse(inner: &str) -> Option<LinkTarget> {
+        let (name, _) = split_target(inner);
+        if let Some(id) = name.strip_prefix(TASK_PREFIX) {
+            return id.trim().parse().ok().map(LinkTarget::Task);
+        }
+        Some(LinkTarget::Note(name.to_string())).filter(|_| !name.is_empty())
+    }
+}
+
+// Rewrites every link to the note titled `old` so it points at `new`, keeping headings and
+// aliases. `None` when the markdown has no such link.
+pub fn rename_note_links(markdown: &str, old: &str, new: &str) -> Option<String> {
+    let mut result = String::with_capacity(markdown.len());
+    let mut last = 0;
+    let mut renamed = false;
+    for (start, inner) in wiki_links(markdown) {
+        let (name, rest) = split_target(inner);
+        if !same_title(name, old) {
+            continue;
+        }
+        result.push_str(&markdown[last..start]);
+        result.push_str("[[");
+        result.push_str(new);
+        result.push_str(rest);
+        result.push_str("]]");
+        last = start + inner.len() + 4;
+        renamed = true;
+    }
+    if !renamed {
+        return None;
+    }
+    result.push_str(&markdown[last..]);
+    Some(result)
+}
+
+// Note titles are matched trimmed and case-insensitive; this is the form links are stored in
+pub fn title_key(title: &str) -> String {
+    title.trim().to_lowercase()
+}
+
+fn same_title(a: &str, b: &str) -> bool {
+    title_key(a) == title_key(b)
+}
+
+// Byte offset and inner text of every `[[...]]` on a single line
+fn wiki_links(markdown: &str) -> Vec<(usize, &str)> {
+    let mut links = Vec::new();
+    let mut offset = 0;
+    while let Some(open) = markdown[offset..].find("[[") {
+        let start = offset + open;
+        let inner_start = start + 2;
+        let Some(close) = markdown[inner_start..].find("]]") else {
+            break;
+        };
+        let inner = &markdown[inner_start..inner_start + close];
+        if inner.contains('\n') || inner.contains("[[") {
+            // Unclosed on this line; look for the next opening bracket instead
+            offset = inner_start;
+            continue;
+        }
+        links.push((start, inner));
+        offset = inner_start + close + 2;
+    }
+    links
+}
+
+// Splits `Title#Heading|label` into the trimmed title and the untouched `#Heading|label` part
+fn split_target(inner: &str) -> (&str, &str) {
+    let end = inner.find(['#', '|']).unwrap_or(inner.len());
+    (inner[..end].trim(), &inner[end..])
+}
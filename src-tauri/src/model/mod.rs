@@ -1,2 +1,22 @@
 pub mod task;
-pub mod note;
\ No newline at end of file
+pub mod note;
+pub mod archive;
+pub mod task_csv;
+pub mod backup;
+pub mod journal;
+pub mod trash;
+pub mod task_history;
+pub mod note_revision;
+pub mod vault;
+pub mod front_matter;
+pub mod link;
+pub mod attachment;
+pub mod note_export;
+pub mod note_task;
+pub mod recurrence;
+pub mod quick_add;
+pub mod scoring;
+pub mod time_entry;
+pub mod planner;
+pub mod calendar_event;
+pub mod caldav;
@@ -1,13 +1,24 @@
 use chrono::NaiveDateTime;
 use serde::{Serialize, Deserialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Note {
     pub id: i32,
     pub title: String,
     pub file_path: String,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    // Set when the markdown file disappeared from disk outside the app
+    #[serde(default)]
+    pub missing: bool,
+    // The fields below mirror the file's front-matter, which is their source of truth
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+    // Ids of the tasks linked to the note
+    #[serde(default)]
+    pub task_ids: Vec<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteExportFormat {
+    // A single HTML file with inline styles and images embedded as data urls
+    Html,
+    Pdf,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct NoteExportSummary {
+    pub notes_exported: usize,
+    pub images_embedded: usize,
+    // Images left out: missing files and, in a PDF, web images and formats other than PNG/JPEG
+    pub images_skipped: usize,
+}
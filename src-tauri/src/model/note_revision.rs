@@ -0,0 +1,51 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+// A saved earlier version of a note's markdown. The content itself is stored compressed
+// and is only loaded for diffing or restoring.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NoteRevision {
+    pub id: i32,
+    pub note_id: i32,
+    // SHA-256 of the uncompressed content, used to skip saving identical versions
+    pub content_hash: String,
+    // Uncompressed size in bytes
+    pub size: i64,
+    pub created_at: NaiveDateTime,
+}
+
+// How many revisions are kept per note; whichever limit is hit first wins
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RevisionPolicy {
+    pub max_count: usize,
+    pub max_age_days: u32,
+}
+
+impl Default for RevisionPolicy {
+    fn default() -> Self {
+        RevisionPolicy { max_count: 50, max_age_days: 90 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum DiffTag {
+    Equal,
+    Insert,
+    Delete,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DiffLine {
+    pub tag: DiffTag,
+    // Line numbers are 1-based; None when the line does not exist on that side
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NoteDiff {
+    pub lines: Vec<DiffLine>,
+    pub insertions: usize,
+    pub deletions: usize,
+}
@@ -0,0 +1,76 @@
+use pulldown_cmark::{Event, Options, Parser};
+use serde::{Deserialize, Serialize};
+
+// A GFM task-list item (`- [ ] read chapter 3`) in a note's body
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkbox {
+    // 1-based line in the body, front-matter excluded
+    pub line: usize,
+    // The rest of the item's first line, trimmed
+    pub text: String,
+    pub checked: bool,
+}
+
+impl Checkbox {
+    // Every task-list item with some text, in order. Items inside code blocks aren't task
+    // lists for the parser, so they are left out.
+    pub fn extract(markdown: &str) -> Vec<Checkbox> {
+        markers(markdown)
+            .into_iter()
+            .filter_map(|(offset, checked)| {
+                let line_end = markdown[offset..].find('\n').map_or(markdown.len(), |end| offset + end);
+                // The marker is `[ ]` or `[x]`, three bytes long
+                let text = markdown.get(offset + 3..line_end).unwrap_or_default().trim();
+                if text.is_empty() {
+                    return None;
+                }
+                Some(Checkbox { line: line_number(markdown, offset), text: text.to_string(), checked })
+            })
+            .collect()
+    }
+}
+
+// Ticks or clears the checkbox on `line`. `None` when there is no checkbox on that line.
+pub fn set_checkbox(markdown: &str, line: usize, checked: bool) -> Option<String> {
+    let (offset, _) = markers(markdown).into_iter().find(|(offset, _)| line_number(markdown, *offset) == line)?;
+    let mut result = String::with_capacity(markdown.len());
+    result.push_str(&markdown[..offset + 1]);
+    result.push(if checked { 'x' } else { ' ' });
+    result.push_str(&markdown[offset + 2..]);
+    Some(result)
+}
+
+// Byte offset of the `[` of every task-list marker, with its state
+fn markers(markdown: &str) -> Vec<(usize, bool)> {
+    Parser::new_ext(markdown, Options::ENABLE_TASKLISTS)
+        .into_offset_iter()
+        .filter_map(|(event, range)| match event {
+            Event::TaskListMarker(checked) => Some((range.start, checked)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn line_number(markdown: &str, offset: usize) -> usize {
+    markdown[..offset].matches('\n').count() + 1
+}
+
+// Links a task created from a checkbox back to the note and line it came from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NoteTask {
+    pub task_id: i32,
+    pub note_id: i32,
+    pub line: usize,
+    // The checkbox's text when it was last synced, used to find it again after lines move
+    pub text: String,
+}
+
+// What syncing a note's checkboxes changed
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct NoteTaskSyncSummary {
+    pub tasks_created: usize,
+    // Tasks whose title or completion changed to match their checkbox
+    pub tasks_updated: usize,
+    // Checkboxes that were removed from the note; their tasks are kept
+    pub tasks_unlinked: usize,
+}
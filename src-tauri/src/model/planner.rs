@@ -0,0 +1,57 @@
+use chrono::{NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+// Key di tabel settings untuk jam kerja planner
+pub const PLANNER_SETTINGS_KEY: &str = "planner";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PlannerConfig {
+    // Jam kerja harian, mis. "09:00" sampai "17:00"
+    pub day_start: NaiveTime,
+    pub day_end: NaiveTime,
+    // Hari yang boleh diisi jadwal
+    pub work_days: Vec<Weekday>,
+    // Dipakai untuk task tanpa perkiraan waktu
+    pub default_task_minutes: u32,
+    // Task panjang dipecah menjadi blok paling lama segini
+    pub max_block_minutes: u32,
+    // Sisa waktu yang lebih pendek dari ini tidak dijadikan blok (kecuali sisa task-nya memang pendek)
+    pub min_block_minutes: u32,
+    // Jeda setelah setiap blok task
+    pub break_minutes: u32,
+}
+
+impl Default for PlannerConfig {
+    fn default() -> Self {
+        PlannerConfig {
+            day_start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            day_end: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+            work_days: vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri],
+            default_task_minutes: 60,
+            max_block_minutes: 90,
+            min_block_minutes: 15,
+            break_minutes: 10,
+        }
+    }
+}
+
+// Satu blok waktu untuk mengerjakan task. Acara tetap (kuliah, rapat) disimpan sebagai acara
+// kalender, bukan blok.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScheduleBlock {
+    pub id: i32,
+    pub task_id: i32,
+    // Judul task saat dijadwalkan
+    pub title: String,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+// Jadwal yang diusulkan atau hasil reflow
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct PlanResult {
+    pub blocks: Vec<ScheduleBlock>,
+    // Task yang tidak muat di jam kerja yang tersisa
+    pub unscheduled: Vec<i32>,
+}
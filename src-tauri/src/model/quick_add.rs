@@ -0,0 +1,538 @@
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use serde::Serialize;
+use crate::model::recurrence::{Frequency, Recurrence};
+use crate::model::task::{Category, Priority, TaskPayload};
+
+// Format deadline di TaskPayload: tanggal saja, atau tanggal dengan jam
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+pub const DATE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+// Kata penghubung sebelum tanggal/jam ("at 5pm", "pada senin") yang ikut dibuang dari judul
+const CONNECTORS: [&str; 7] = ["at", "on", "by", "due", "pada", "tanggal", "tgl"];
+// Kategori dan prioritas yang dipakai jika input tidak menyebutkannya
+const DEFAULT_CATEGORY: Category = Category::Personal;
+const DEFAULT_PRIORITY: Priority = Priority::Medium;
+// Jam untuk "tonight"/"nanti malam" tanpa jam eksplisit
+const EVENING_HOUR: u32 = 20;
+
+// Hasil parsing quick-add, ditampilkan sebagai pratinjau sebelum task dibuat
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct QuickAddPreview {
+    pub payload: TaskPayload,
+    // Hashtag yang bukan kategori, tanpa '#'
+    pub tags: Vec<String>,
+    pub recurrence: Option<Recurrence>,
+}
+
+// Parse input seperti "Submit essay fri 5pm !high #study" atau "Kumpul tugas besok jam 9 pagi #kuliah".
+// Tanggal relatif dihitung dari `now` (waktu lokal). Kata yang tidak dikenali menjadi judul; tanggal,
+// jam, prioritas, kategori, dan pengulangan kedua yang muncul juga dibiarkan di judul.
+pub fn parse_quick_add(input: &str, now: NaiveDateTime) -> Result<QuickAddPreview, String> {
+    let mut parser = QuickAddParser::new(input, now);
+    parser.run();
+    parser.finish()
+}
+
+struct QuickAddParser<'a> {
+    words: Vec<&'a str>,
+    // Kata dalam huruf kecil tanpa koma/titik koma di akhirnya
+    keys: Vec<String>,
+    used: Vec<bool>,
+    now: NaiveDateTime,
+    date: Option<NaiveDate>,
+    // Tanggal berasal dari nama hari saja ("fri"), jadi pindah ke minggu depan jika jatuh hari ini
+    // dan jamnya sudah lewat
+    bare_weekday: bool,
+    time: Option<NaiveTime>,
+    default_time: Option<NaiveTime>,
+    priority: Option<Priority>,
+    category: Option<Category>,
+    tags: Vec<String>,
+    recurrence: Option<Recurrence>,
+}
+
+impl<'a> QuickAddParser<'a> {
+    fn new(input: &'a str, now: NaiveDateTime) -> Self {
+        let words: Vec<&str> = input.split_whitespace().collect();
+        let keys = words.iter().map(|word| word.trim_end_matches([',', ';']).to_lowercase()).collect();
+        QuickAddParser {
+            used: vec![false; words.len()],
+            words,
+            keys,
+            now,
+            date: None,
+            bare_weekday: false,
+            time: None,
+            default_time: None,
+            priority: None,
+            category: None,
+            tags: Vec::new(),
+            recurrence: None,
+        }
+    }
+
+    fn run(&mut self) {
+        let mut index = 0;
+        while index < self.words.len() {
+            let consumed = self.phrase_at(index).or_else(|| {
+                // Penghubung hanya dibuang jika diikuti tanggal/jam
+                if CONNECTORS.contains(&self.key(index)?) {
+                    self.date_at(index + 1).or_else(|| self.time_at(index + 1)).map(|len| len + 1)
+                } else {
+                    None
+                }
+            });
+            match consumed {
+                Some(len) => {
+                    self.used[index..index + len].fill(true);
+                    index += len;
+                }
+                None => index += 1,
+            }
+        }
+    }
+
+    fn phrase_at(&mut self, index: usize) -> Option<usize> {
+        self.recurrence_at(index)
+            .or_else(|| self.date_at(index))
+            .or_else(|| self.time_at(index))
+            .or_else(|| self.marker_at(index))
+    }
+
+    fn finish(self) -> Result<QuickAddPreview, String> {
+        let title: Vec<&str> = self.words.iter().zip(&self.used)
+            .filter(|(_, used)| !**used)
+            .map(|(word, _)| *word)
+            .collect();
+        if title.is_empty() {
+            return Err("Task title is empty".to_string());
+        }
+
+        let today = self.now.date();
+        let time = self.time.or(self.default_time);
+        let weekdays = self.recurrence.as_ref().map(|r| r.weekdays.as_slice()).unwrap_or_default();
+        let date = match (self.date, time) {
+            (Some(date), Some(time)) if self.bare_weekday && date == today && time <= self.now.time() => {
+                Some(date + Duration::weeks(1))
+            }
+            (Some(date), _) => Some(date),
+            // Pengulangan pada hari tertentu dimulai di hari terdekat itu yang jamnya belum lewat
+            (None, _) if !weekdays.is_empty() => {
+                let start = if time.is_some_and(|time| time <= self.now.time()) { 1 } else { 0 };
+                (start..start + 7)
+                    .map(|days| today + Duration::days(days))
+                    .find(|date| weekdays.contains(&date.weekday()))
+            }
+            // Jam saja: hari ini, atau besok jika jam itu sudah lewat
+            (None, Some(time)) if time <= self.now.time() => Some(today + Duration::days(1)),
+            (None, Some(_)) => Some(today),
+            (None, None) if self.recurrence.is_some() => Some(today),
+            (None, None) => None,
+        };
+        let deadline = date.map(|date| match time {
+            Some(time) => date.and_time(time).format(DATE_TIME_FORMAT).to_string(),
+            None => date.format(DATE_FORMAT).to_string(),
+        });
+
+        Ok(QuickAddPreview {
+            payload: TaskPayload {
+                title: title.join(" "),
+                category: self.category.unwrap_or(DEFAULT_CATEGORY),
+                priority: self.priority.unwrap_or(DEFAULT_PRIORITY),
+                deadline,
+                estimated_minutes: None,
+            },
+            tags: self.tags,
+            recurrence: self.recurrence,
+        })
+    }
+
+    fn key(&self, index: usize) -> Option<&str> {
+        self.keys.get(index).map(String::as_str)
+    }
+
+    fn number(&self, index: usize) -> Option<u32> {
+        self.key(index)?.parse().ok().filter(|n| (1..1000).contains(n))
+    }
+
+    // "every 2 weeks", "setiap senin dan kamis", "tiap hari kerja", "daily", ...
+    fn recurrence_at(&mut self, index: usize) -> Option<usize> {
+        if self.recurrence.is_some() {
+            return None;
+        }
+        let frequency = match self.key(index)? {
+            "daily" | "harian" => Some(Frequency::Daily),
+            "weekly" | "mingguan" => Some(Frequency::Weekly),
+            "monthly" | "bulanan" => Some(Frequency::Monthly),
+            "yearly" | "annually" | "tahunan" => Some(Frequency::Yearly),
+            "every" | "each" | "setiap" | "tiap" => None,
+            _ => return None,
+        };
+        if let Some(frequency) = frequency {
+            self.recurrence = Some(Recurrence::new(frequency, 1));
+            return Some(1);
+        }
+
+        let mut pos = index + 1;
+        let mut interval = 1;
+        if self.key(pos) == Some("other") {
+            interval = 2;
+            pos += 1;
+        } else if let Some(n) = self.number(pos) {
+            interval = n;
+            pos += 1;
+        }
+        let word = self.key(pos)?;
+        if matches!(word, "weekday" | "weekdays") || (word == "hari" && self.key(pos + 1) == Some("kerja")) {
+            let len = pos + if word == "hari" { 2 } else { 1 } - index;
+            let mut recurrence = Recurrence::new(Frequency::Weekly, interval);
+            recurrence.weekdays = vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri];
+            self.recurrence = Some(recurrence);
+            return Some(len);
+        }
+        if let Some(frequency) = frequency_unit(word) {
+            self.recurrence = Some(Recurrence::new(frequency, interval));
+            return Some(pos + 1 - index);
+        }
+
+        let mut weekdays = vec![weekday(word)?];
+        loop {
+            let next = match self.key(pos + 1) {
+                Some("and" | "dan" | "&") => pos + 2,
+                _ => pos + 1,
+            };
+            match self.key(next).and_then(weekday) {
+                Some(day) => {
+                    if !weekdays.contains(&day) {
+                        weekdays.push(day);
+                    }
+                    pos = next;
+                }
+                None => break,
+            }
+        }
+        let mut recurrence = Recurrence::new(Frequency::Weekly, interval);
+        recurrence.weekdays = weekdays;
+        self.recurrence = Some(recurrence);
+        Some(pos + 1 - index)
+    }
+
+    fn date_at(&mut self, index: usize) -> Option<usize> {
+        if self.date.is_some() {
+            return None;
+        }
+        let (date, len) = self.relative_date_at(index).or_else(|| self.absolute_date_at(index))?;
+        self.date = Some(date);
+        Some(len)
+    }
+
+    fn relative_date_at(&mut self, index: usize) -> Option<(NaiveDate, usize)> {
+        let today = self.now.date();
+        let first = self.key(index)?;
+        let second = self.key(index + 1);
+        let third = self.key(index + 2);
+
+        if first == "tonight" || matches!((first, second), ("malam", Some("ini")) | ("nanti", Some("malam"))) {
+            let len = if first == "tonight" { 1 } else { 2 };
+            self.default_time = NaiveTime::from_hms_opt(EVENING_HOUR, 0, 0);
+            return Some((today, len));
+        }
+        match (first, second, third) {
+            ("day", Some("after"), Some("tomorrow")) => return Some((today + Duration::days(2), 3)),
+            ("in" | "dalam", _, Some(unit)) => {
+                let amount = if matches!(second, Some("a" | "an")) { Some(1) } else { self.number(index + 1) };
+                if let (Some(amount), Some(unit)) = (amount, frequency_unit(unit)) {
+                    return Some((add(today, unit, amount)?, 3));
+                }
+            }
+            (_, Some(unit), Some("lagi")) => {
+                if let (Some(amount), Some(unit)) = (self.number(index), frequency_unit(unit)) {
+                    return Some((add(today, unit, amount)?, 3));
+                }
+            }
+            _ => {}
+        }
+        let relative = match (first, second) {
+            ("hari", Some("ini")) => Some(today),
+            ("next", Some("week")) | ("minggu" | "pekan", Some("depan")) => Some(next_week(today, Weekday::Mon)),
+            ("next", Some("month")) | ("bulan", Some("depan")) => {
+                today.with_day(1).and_then(|first_day| first_day.checked_add_months(Months::new(1)))
+            }
+            ("next", Some("year")) | ("tahun", Some("depan")) => NaiveDate::from_ymd_opt(today.year() + 1, 1, 1),
+            ("next", Some(day)) => weekday(day).map(|day| next_week(today, day)),
+            ("this", Some(day)) => weekday(day).map(|day| upcoming(today, day)),
+            (day, Some("depan")) => weekday(day).map(|day| next_week(today, day)),
+            (day, Some("ini")) => weekday(day).map(|day| upcoming(today, day)),
+            _ => None,
+        };
+        if let Some(date) = relative {
+            return Some((date, 2));
+        }
+        let single = match first {
+            "today" | "tdy" => Some(today),
+            "tomorrow" | "tmr" | "tmrw" | "besok" => Some(today + Duration::days(1)),
+            "lusa" => Some(today + Duration::days(2)),
+            day => {
+                let date = weekday(day).filter(|_| self.weekday_is_date(index)).map(|day| upcoming(today, day));
+                self.bare_weekday = date.is_some();
+                date
+            }
+        };
+        single.map(|date| (date, 1))
+    }
+
+    // Nama hari tanpa kata lain baru dianggap tanggal jika ada di akhir input (boleh diikuti
+    // prioritas/kategori), setelah penghubung, atau bersebelahan dengan jam. Jadi "Read Sun Tzu"
+    // tetap menjadi judul, sedangkan "Quiz wed", "Gym 7am sat", dan "by fri" tetap tanggal.
+    fn weekday_is_date(&self, index: usize) -> bool {
+        let at_end = self.keys[index + 1..].iter().all(|key| key.starts_with(['!', '#']));
+        let after_connector = index > 0 && CONNECTORS.contains(&self.keys[index - 1].as_str());
+        let time_after = self.parse_time_at(index + 1).is_some()
+            || (self.key(index + 1).is_some_and(|key| CONNECTORS.contains(&key)) && self.parse_time_at(index + 2).is_some());
+        let time_before = (index.saturating_sub(3)..index)
+            .any(|start| self.parse_time_at(start).is_some_and(|(_, len)| start + len == index));
+        at_end || after_connector || time_after || time_before
+    }
+
+    // "2026-11-03", "3/11", "3/11/2026", "3 nov", "nov 3rd 2026", "3 November"
+    fn absolute_date_at(&self, index: usize) -> Option<(NaiveDate, usize)> {
+        let today = self.now.date();
+        let first = self.key(index)?;
+        if let Ok(date) = NaiveDate::parse_from_str(first, DATE_FORMAT) {
+            return Some((date, 1));
+        }
+        let parts: Vec<&str> = first.split('/').collect();
+        if let [day, month, rest @ ..] = parts.as_slice() {
+            let year = match rest {
+                [] => None,
+                [year] => Some(full_year(year)?),
+                _ => return None,
+            };
+            let date = dated(today, year, month.parse().ok()?, day.parse().ok()?)?;
+            return Some((date, 1));
+        }
+
+        let (day, month) = match (day_of_month(first), self.key(index + 1).and_then(month)) {
+            (Some(day), Some(month)) => (day, month),
+            _ => (self.key(index + 1).and_then(day_of_month)?, month(first)?),
+        };
+        match self.key(index + 2).and_then(|year| year.parse::<i32>().ok()).filter(|year| (1970..=2200).contains(year)) {
+            Some(year) => Some((dated(today, Some(year), month, day)?, 3)),
+            None => Some((dated(today, None, month, day)?, 2)),
+        }
+    }
+
+    // "5pm", "5:30 pm", "17:00", "17.30", "jam 9 pagi", "pukul 19.30", "7 malam", "noon"
+    fn time_at(&mut self, index: usize) -> Option<usize> {
+        if self.time.is_some() {
+            return None;
+        }
+        let (time, len) = self.parse_time_at(index)?;
+        self.time = Some(time);
+        Some(len)
+    }
+
+    // Jam di posisi `index` dan jumlah katanya, tanpa mengubah parser
+    fn parse_time_at(&self, index: usize) -> Option<(NaiveTime, usize)> {
+        let first = self.key(index)?;
+        let (time, len) = match first {
+            "noon" => (NaiveTime::from_hms_opt(12, 0, 0)?, 1),
+            "midnight" => (NaiveTime::from_hms_opt(0, 0, 0)?, 1),
+            "jam" | "pukul" => {
+                let (hour, minute, _) = clock(self.key(index + 1)?)?;
+                match self.key(index + 2).and_then(|period| with_period(hour, period)) {
+                    Some(hour) => (NaiveTime::from_hms_opt(hour, minute, 0)?, 3),
+                    None => (NaiveTime::from_hms_opt(hour, minute, 0)?, 2),
+                }
+            }
+            word => {
+                if let Some((clock_part, suffix)) = split_meridiem(word) {
+                    let (hour, minute, _) = clock(clock_part)?;
+                    (NaiveTime::from_hms_opt(with_period(hour, suffix)?, minute, 0)?, 1)
+                } else {
+                    let (hour, minute, has_minutes) = clock(word)?;
+                    match self.key(index + 1).and_then(|period| with_period(hour, period)) {
+                        Some(hour) => (NaiveTime::from_hms_opt(hour, minute, 0)?, 2),
+                        // Angka tanpa menit, am/pm, atau "jam" bukan jam
+                        None if has_minutes => (NaiveTime::from_hms_opt(hour, minute, 0)?, 1),
+                        None => return None,
+                    }
+                }
+            }
+        };
+        Some((time, len))
+    }
+
+    // "!high", "!!", "#study", "#ujian"
+    fn marker_at(&mut self, index: usize) -> Option<usize> {
+        let key = self.key(index)?;
+        if let Some(level) = key.strip_prefix('!') {
+            let priority = match level {
+                "high" | "h" | "tinggi" | "penting" | "urgent" | "!!" => Priority::High,
+                "medium" | "med" | "m" | "sedang" | "!" => Priority::Medium,
+                "low" | "l" | "rendah" => Priority::Low,
+                _ => return None,
+            };
+            if self.priority.is_some() {
+                return None;
+            }
+            self.priority = Some(priority);
+            return Some(1);
+        }
+
+        let tag = key.strip_prefix('#').filter(|tag| !tag.is_empty() && !tag.starts_with('#'))?;
+        let category = match tag {
+            "study" | "belajar" | "kuliah" | "school" | "sekolah" => Some(Category::Study),
+            "work" | "kerja" | "kantor" => Some(Category::Work),
+            "personal" | "pribadi" => Some(Category::Personal),
+            _ => None,
+        };
+        match category {
+            Some(category) if self.category.is_none() => self.category = Some(category),
+            _ => {
+                let tag = self.words[index].trim_start_matches('#').trim_end_matches([',', ';']).to_string();
+                if !self.tags.iter().any(|other| other.eq_ignore_ascii_case(&tag)) {
+                    self.tags.push(tag);
+                }
+            }
+        }
+        Some(1)
+    }
+}
+
+fn weekday(word: &str) -> Option<Weekday> {
+    match word {
+        "mon" | "monday" | "senin" => Some(Weekday::Mon),
+        "tue" | "tues" | "tuesday" | "selasa" => Some(Weekday::Tue),
+        "wed" | "wednesday" | "rabu" => Some(Weekday::Wed),
+        "thu" | "thur" | "thurs" | "thursday" | "kamis" => Some(Weekday::Thu),
+        "fri" | "friday" | "jumat" | "jum'at" => Some(Weekday::Fri),
+        "sat" | "saturday" | "sabtu" => Some(Weekday::Sat),
+        "sun" | "sunday" | "minggu" | "ahad" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn month(word: &str) -> Option<u32> {
+    match word.trim_end_matches('.') {
+        "jan" | "january" | "januari" => Some(1),
+        "feb" | "february" | "februari" | "pebruari" => Some(2),
+        "mar" | "march" | "maret" => Some(3),
+        "apr" | "april" => Some(4),
+        "may" | "mei" => Some(5),
+        "jun" | "june" | "juni" => Some(6),
+        "jul" | "july" | "juli" => Some(7),
+        "aug" | "august" | "agu" | "agt" | "agustus" => Some(8),
+        "sep" | "sept" | "september" => Some(9),
+        "oct" | "october" | "okt" | "oktober" => Some(10),
+        "nov" | "november" | "nopember" => Some(11),
+        "dec" | "december" | "des" | "desember" => Some(12),
+        _ => None,
+    }
+}
+
+fn frequency_unit(word: &str) -> Option<Frequency> {
+    match word {
+        "day" | "days" | "hari" => Some(Frequency::Daily),
+        "week" | "weeks" | "minggu" | "pekan" => Some(Frequency::Weekly),
+        "month" | "months" | "bulan" => Some(Frequency::Monthly),
+        "year" | "years" | "tahun" => Some(Frequency::Yearly),
+        _ => None,
+    }
+}
+
+fn add(date: NaiveDate, unit: Frequency, amount: u32) -> Option<NaiveDate> {
+    match unit {
+        Frequency::Daily => date.checked_add_signed(Duration::days(amount.into())),
+        Frequency::Weekly => date.checked_add_signed(Duration::weeks(amount.into())),
+        Frequency::Monthly => date.checked_add_months(Months::new(amount)),
+        Frequency::Yearly => date.checked_add_months(Months::new(amount.checked_mul(12)?)),
+    }
+}
+
+// Hari `day` terdekat mulai hari ini (hari ini termasuk)
+fn upcoming(today: NaiveDate, day: Weekday) -> NaiveDate {
+    let days = (day.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+    today + Duration::days(days.into())
+}
+
+// Hari `day` di minggu depan (minggu dimulai hari Senin)
+fn next_week(today: NaiveDate, day: Weekday) -> NaiveDate {
+    let monday = today + Duration::days((7 - today.weekday().num_days_from_monday()).into());
+    monday + Duration::days(day.num_days_from_monday().into())
+}
+
+// Tanggal tanpa tahun jatuh di tahun ini, atau tahun depan jika sudah lewat
+fn dated(today: NaiveDate, year: Option<i32>, month: u32, day: u32) -> Option<NaiveDate> {
+    match year {
+        Some(year) => NaiveDate::from_ymd_opt(year, month, day),
+        None => NaiveDate::from_ymd_opt(today.year(), month, day)
+            .filter(|date| *date >= today)
+            .or_else(|| NaiveDate::from_ymd_opt(today.year() + 1, month, day)),
+    }
+}
+
+fn full_year(word: &str) -> Option<i32> {
+    let year: i32 = word.parse().ok()?;
+    match word.len() {
+        2 => Some(2000 + year),
+        4 => Some(year),
+        _ => None,
+    }
+}
+
+// "3", "3rd", "21st"
+fn day_of_month(word: &str) -> Option<u32> {
+    let digits = word.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let suffix = &word[digits.len()..];
+    if !matches!(suffix, "" | "st" | "nd" | "rd" | "th") {
+        return None;
+    }
+    digits.parse().ok().filter(|day| (1..=31).contains(day))
+}
+
+// Jam dan menit dari "5", "17:00" atau "17.30", serta apakah menitnya ditulis
+fn clock(word: &str) -> Option<(u32, u32, bool)> {
+    let (hour, minute_text) = match word.split_once([':', '.']) {
+        Some((hour, minute)) if minute.len() == 2 => (hour, Some(minute)),
+        Some(_) => return None,
+        None => (word, None),
+    };
+    if hour.is_empty() || hour.len() > 2 || !hour.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let hour: u32 = hour.parse().ok().filter(|hour| *hour < 24)?;
+    let minute: u32 = match minute_text {
+        Some(minute) => minute.parse().ok().filter(|minute| *minute < 60)?,
+        None => 0,
+    };
+    Some((hour, minute, minute_text.is_some()))
+}
+
+// "5pm" -> ("5", "pm")
+fn split_meridiem(word: &str) -> Option<(&str, &str)> {
+    ["am", "pm"].iter().find_map(|suffix| {
+        word.strip_suffix(suffix).filter(|clock| !clock.is_empty()).map(|clock| (clock, *suffix))
+    })
+}
+
+// Jam 24-an dari jam 12-an dengan am/pm atau keterangan waktu (pagi, siang, sore, malam)
+fn with_period(hour: u32, period: &str) -> Option<u32> {
+    if !(1..=12).contains(&hour) {
+        return None;
+    }
+    match period {
+        "am" | "pagi" => Some(hour % 12),
+        "pm" => Some(hour % 12 + 12),
+        // "jam 11 siang" = 11.00, "jam 1 siang" = 13.00
+        "siang" => Some(if hour < 11 { hour + 12 } else { hour }),
+        "sore" => Some(hour % 12 + 12),
+        // "jam 8 malam" = 20.00, "jam 12 malam" = 00.00, "jam 2 malam" = 02.00
+        "malam" => Some(match hour {
+            12 => 0,
+            6..=11 => hour + 12,
+            _ => hour,
+        }),
+        _ => None,
+    }
+}
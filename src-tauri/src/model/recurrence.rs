@@ -0,0 +1,86 @@
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+// Satuan pengulangan
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+// Aturan pengulangan, mis. "setiap 2 minggu" atau "every mon and thu"
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    // Jarak antar pengulangan dalam satuan `frequency`, minimal 1
+    pub interval: u32,
+    // Hari-hari untuk pengulangan mingguan. Kosong berarti hari yang sama dengan tanggal awalnya.
+    #[serde(default)]
+    pub weekdays: Vec<Weekday>,
+    // Tanggal terakhir (inklusif) yang boleh ada kemunculan; kosong berarti tanpa akhir
+    #[serde(default)]
+    pub until: Option<NaiveDate>,
+}
+
+impl Recurrence {
+    pub fn new(frequency: Frequency, interval: u32) -> Self {
+        Recurrence { frequency, interval: interval.max(1), weekdays: Vec::new(), until: None }
+    }
+
+    // Waktu mulai setiap kemunculan, dihitung dari kemunculan pertama `first`, yang bersinggungan
+    // dengan `from`..`to`. Kemunculan yang dimulai sebelum `from` tetap ikut selama masih
+    // berlangsung (`duration`). Tanggal 31 atau 29 Februari dilewati di bulan/tahun yang tidak punya.
+    pub fn occurrences(&self, first: NaiveDateTime, duration: Duration, from: NaiveDateTime, to: NaiveDateTime) -> Vec<NaiveDateTime> {
+        let mut starts = Vec::new();
+        for period in 0.. {
+            let Some((period_start, candidates)) = self.period(first, period) else {
+                break;
+            };
+            if period_start.and_time(NaiveTime::MIN) >= to || self.until.is_some_and(|until| period_start > until) {
+                break;
+            }
+            for start in candidates {
+                let after_until = self.until.is_some_and(|until| start.date() > until);
+                if start < first || start >= to || after_until {
+                    continue;
+                }
+                if start >= from || start + duration > from {
+                    starts.push(start);
+                }
+            }
+        }
+        starts
+    }
+
+    // Tanggal awal periode ke-`index` (hari, minggu, bulan, atau tahun) beserta kemunculan di dalamnya
+    fn period(&self, first: NaiveDateTime, index: u32) -> Option<(NaiveDate, Vec<NaiveDateTime>)> {
+        let steps = index.checked_mul(self.interval.max(1))?;
+        let time = first.time();
+        match self.frequency {
+            Frequency::Daily => {
+                let date = first.date().checked_add_signed(Duration::days(i64::from(steps)))?;
+                Some((date, vec![date.and_time(time)]))
+            }
+            Frequency::Weekly => {
+                let monday = first.date() - Duration::days(i64::from(first.weekday().num_days_from_monday()));
+                let week = monday.checked_add_signed(Duration::weeks(i64::from(steps)))?;
+                let mut weekdays = if self.weekdays.is_empty() { vec![first.weekday()] } else { self.weekdays.clone() };
+                weekdays.sort_by_key(|day| day.num_days_from_monday());
+                weekdays.dedup();
+                let starts = weekdays.iter()
+                    .map(|day| (week + Duration::days(i64::from(day.num_days_from_monday()))).and_time(time))
+                    .collect();
+                Some((week, starts))
+            }
+            Frequency::Monthly | Frequency::Yearly => {
+                let months = if self.frequency == Frequency::Yearly { steps.checked_mul(12)? } else { steps };
+                let month = first.date().with_day(1)?.checked_add_months(Months::new(months))?;
+                let start = NaiveDate::from_ymd_opt(month.year(), month.month(), first.day()).map(|date| date.and_time(time));
+                Some((month, start.into_iter().collect()))
+            }
+        }
+    }
+}
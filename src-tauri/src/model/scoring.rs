@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use crate::model::task::Task;
+
+// Key di tabel settings untuk bobot skor task
+pub const SCORING_SETTINGS_KEY: &str = "scoring";
+
+// Bobot tiap komponen skor. Setiap komponen bernilai 0..1 sebelum dikali bobotnya, jadi
+// bobot 0 mematikan komponen itu.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ScoringWeights {
+    // Low = 0, Medium = 0.5, High = 1
+    pub priority: f64,
+    // Naik dari 0 menjadi 1 saat deadline mendekat dalam `urgency_horizon_days`
+    pub urgency: f64,
+    // Task yang cepat selesai didahulukan; task tanpa perkiraan dianggap di tengah
+    pub effort: f64,
+    // Task lama naik pelan-pelan supaya tidak terlupakan, penuh setelah `age_cap_days`
+    pub age: f64,
+    // Tambahan tetap untuk task yang sudah lewat deadline
+    pub overdue: f64,
+    pub urgency_horizon_days: u32,
+    // Perkiraan waktu (menit) yang dianggap paling berat
+    pub effort_cap_minutes: u32,
+    pub age_cap_days: u32,
+}
+
+impl Default for ScoringWeights {
+    fn default() -> Self {
+        ScoringWeights {
+            priority: 3.0,
+            urgency: 4.0,
+            effort: 1.0,
+            age: 1.0,
+            overdue: 5.0,
+            urgency_horizon_days: 14,
+            effort_cap_minutes: 240,
+            age_cap_days: 30,
+        }
+    }
+}
+
+// Kontribusi tiap komponen (sudah dikali bobot); `total` adalah jumlahnya
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct ScoreBreakdown {
+    pub priority: f64,
+    pub urgency: f64,
+    pub effort: f64,
+    pub age: f64,
+    pub overdue: f64,
+    pub total: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoredTask {
+    #[serde(flatten)]
+    pub task: Task,
+    pub score: ScoreBreakdown,
+}
@@ -13,26 +13,28 @@ pub enum Category {
 }
 
 // Implementasi untuk mengubah Category menjadi String
-impl ToString for Category {
-    fn to_string(&self) -> String {
-        match self {
-            Category::Study => "Study".to_string(),
-            Category::Work => "Work".to_string(),
-            Category::Personal => "Personal".to_string(),
-        }
+impl std::fmt::Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            Category::Study => "Study",
+            Category::Work => "Work",
+            Category::Personal => "Personal",
+        };
+        f.write_str(value)
     }
 }
 
 // Implementasi untuk mengubah String menjadi Category
 impl std::str::FromStr for Category {
-    type Err = ();
+    type Err = String;
 
+    // Pencocokan tidak peka huruf besar/kecil, jadi "study" dari file CSV tetap diterima
     fn from_str(input: &str) -> Result<Category, Self::Err> {
-        match input {
-            "Study" => Ok(Category::Study),
-            "Work" => Ok(Category::Work),
-            "Personal" => Ok(Category::Personal),
-            _ => Err(()), // Kembalikan error jika string tidak cocok
+        match input.trim().to_lowercase().as_str() {
+            "study" => Ok(Category::Study),
+            "work" => Ok(Category::Work),
+            "personal" => Ok(Category::Personal),
+            _ => Err(format!("Unknown category '{}'", input)), // Kembalikan error jika string tidak cocok
         }
     }
 }
@@ -46,40 +48,45 @@ pub enum Priority {
 }
 
 // Implementasi untuk mengubah Priority menjadi String
-impl ToString for Priority {
-    fn to_string(&self) -> String {
-        match self {
-            Priority::Low => "Low".to_string(),
-            Priority::Medium => "Medium".to_string(),
-            Priority::High => "High".to_string(),
-        }
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            Priority::Low => "Low",
+            Priority::Medium => "Medium",
+            Priority::High => "High",
+        };
+        f.write_str(value)
     }
 }
 
 // Implementasi untuk mengubah String menjadi Priority
 impl std::str::FromStr for Priority {
-    type Err = ();
+    type Err = String;
 
+    // Pencocokan tidak peka huruf besar/kecil, jadi "high" atau "HIGH" dari file CSV tetap diterima
     fn from_str(input: &str) -> Result<Priority, Self::Err> {
-        match input {
-            "Low" => Ok(Priority::Low),
-            "Medium" => Ok(Priority::Medium),
-            "High" => Ok(Priority::High),
-            _ => Err(()), // Kembalikan error jika string tidak cocok
+        match input.trim().to_lowercase().as_str() {
+            "low" => Ok(Priority::Low),
+            "medium" => Ok(Priority::Medium),
+            "high" => Ok(Priority::High),
+            _ => Err(format!("Unknown priority '{}'", input)), // Kembalikan error jika string tidak cocok
         }
     }
 }
 
 // Struct untuk payload data saat membuat task baru (data dari frontend)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TaskPayload {
     pub title: String,
     pub category: Category, // Enum Category
     pub priority: Priority, // Enum Priority
     // --- PERUBAHAN DIMULAI ---
-    // Terima deadline sebagai String opsional dari frontend
+    // Terima deadline sebagai String opsional dari frontend: "YYYY-MM-DD" atau "YYYY-MM-DDTHH:MM"
     pub deadline: Option<String>,
     // --- PERUBAHAN SELESAI ---
+    // Perkiraan waktu pengerjaan dalam menit (opsional)
+    #[serde(default)]
+    pub estimated_minutes: Option<i32>,
 }
 
 // Struct untuk representasi Task secara lengkap (termasuk data dari DB)
@@ -93,4 +100,7 @@ pub struct Task {
     pub completed: bool,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    // Perkiraan waktu pengerjaan dalam menit, dipakai skor prioritas
+    #[serde(default)]
+    pub estimated_minutes: Option<i32>,
 }
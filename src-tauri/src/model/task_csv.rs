@@ -0,0 +1,83 @@
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use crate::model::task::Task;
+
+// Kolom task yang bisa diekspor/diimpor lewat CSV
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum TaskColumn {
+    Id,
+    Title,
+    Category,
+    Priority,
+    Deadline,
+    Completed,
+    CreatedAt,
+    UpdatedAt,
+    EstimatedMinutes,
+}
+
+impl TaskColumn {
+    pub const ALL: [TaskColumn; 9] = [
+        TaskColumn::Id,
+        TaskColumn::Title,
+        TaskColumn::Category,
+        TaskColumn::Priority,
+        TaskColumn::Deadline,
+        TaskColumn::Completed,
+        TaskColumn::CreatedAt,
+        TaskColumn::UpdatedAt,
+        TaskColumn::EstimatedMinutes,
+    ];
+
+    // Nama header default yang dipakai saat ekspor
+    pub fn header(&self) -> &'static str {
+        match self {
+            TaskColumn::Id => "id",
+            TaskColumn::Title => "title",
+            TaskColumn::Category => "category",
+            TaskColumn::Priority => "priority",
+            TaskColumn::Deadline => "deadline",
+            TaskColumn::Completed => "completed",
+            TaskColumn::CreatedAt => "created_at",
+            TaskColumn::UpdatedAt => "updated_at",
+            TaskColumn::EstimatedMinutes => "estimated_minutes",
+        }
+    }
+
+    // Mencocokkan header CSV dengan kolom, mengabaikan huruf besar/kecil dan spasi
+    pub fn from_header(header: &str) -> Option<TaskColumn> {
+        let normalized = header.trim().to_lowercase().replace([' ', '-'], "_");
+        TaskColumn::ALL.into_iter().find(|c| c.header() == normalized)
+    }
+}
+
+// Opsi impor CSV dari frontend
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CsvImportOptions {
+    // Header CSV -> kolom task. Header yang tidak dipetakan dicocokkan otomatis dengan nama kolom.
+    #[serde(default)]
+    pub mapping: HashMap<String, TaskColumn>,
+    // Format tanggal chrono untuk kolom deadline; None berarti dideteksi otomatis
+    #[serde(default)]
+    pub date_format: Option<String>,
+    // Jika true, tidak ada yang disimpan ke database
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CsvRowError {
+    // Nomor baris di file CSV (header = baris 1)
+    pub row: usize,
+    pub column: Option<TaskColumn>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvImportReport {
+    // Task yang (akan) dibuat; saat dry run id-nya masih 0
+    pub tasks: Vec<Task>,
+    pub errors: Vec<CsvRowError>,
+    pub date_format: Option<String>,
+    pub dry_run: bool,
+}
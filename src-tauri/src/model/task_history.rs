@@ -0,0 +1,54 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use crate::model::task_csv::TaskColumn;
+
+// Jenis entri riwayat. Selain edit per field, siklus hidup task (dibuat, dibuang ke trash,
+// dipulihkan) juga masuk timeline.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskChangeKind {
+    Created,
+    Updated,
+    Deleted,
+    Restored,
+}
+
+impl TaskChangeKind {
+    // Nilai yang disimpan di kolom `kind`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskChangeKind::Created => "created",
+            TaskChangeKind::Updated => "updated",
+            TaskChangeKind::Deleted => "deleted",
+            TaskChangeKind::Restored => "restored",
+        }
+    }
+}
+
+impl std::str::FromStr for TaskChangeKind {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<TaskChangeKind, Self::Err> {
+        match input {
+            "created" => Ok(TaskChangeKind::Created),
+            "updated" => Ok(TaskChangeKind::Updated),
+            "deleted" => Ok(TaskChangeKind::Deleted),
+            "restored" => Ok(TaskChangeKind::Restored),
+            _ => Err(format!("Unknown task change kind '{}'", input)),
+        }
+    }
+}
+
+// Satu entri riwayat task, dicatat setiap kali task diubah lewat TaskService.
+// Untuk `Updated`, `field` berisi kolom yang berubah dan nilainya disimpan sebagai teks dengan
+// format yang sama seperti ekspor CSV; None berarti kosong. Entri lain tidak punya field.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TaskChange {
+    pub id: i32,
+    pub task_id: i32,
+    pub kind: TaskChangeKind,
+    pub field: Option<TaskColumn>,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub changed_at: NaiveDateTime,
+}
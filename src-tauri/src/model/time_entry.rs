@@ -0,0 +1,98 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use crate::model::task::Category;
+
+// Asal sebuah catatan waktu
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeEntrySource {
+    // Diisi sendiri oleh user
+    Manual,
+    // Hasil sesi timer (start/stop)
+    Timer,
+}
+
+impl TimeEntrySource {
+    // Nilai yang disimpan di kolom `source`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeEntrySource::Manual => "manual",
+            TimeEntrySource::Timer => "timer",
+        }
+    }
+}
+
+impl std::str::FromStr for TimeEntrySource {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<TimeEntrySource, Self::Err> {
+        match input {
+            "manual" => Ok(TimeEntrySource::Manual),
+            "timer" => Ok(TimeEntrySource::Timer),
+            _ => Err(format!("Unknown time entry source '{}'", input)),
+        }
+    }
+}
+
+// Waktu yang dihabiskan untuk satu task. Waktu disimpan dalam jam lokal supaya
+// pembagian per hari/minggu sesuai dengan yang dilihat user.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimeEntry {
+    pub id: i32,
+    pub task_id: i32,
+    pub source: TimeEntrySource,
+    pub started_at: NaiveDateTime,
+    // Kosong selama timer masih berjalan
+    pub ended_at: Option<NaiveDateTime>,
+    // 0 selama timer masih berjalan
+    pub minutes: i32,
+    pub note: Option<String>,
+}
+
+// Data dari frontend untuk mencatat waktu secara manual
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeEntryPayload {
+    pub task_id: i32,
+    // "YYYY-MM-DDTHH:MM" atau "YYYY-MM-DD" (dianggap mulai pukul 00.00)
+    pub started_at: String,
+    pub minutes: i32,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+// Perkiraan vs waktu sebenarnya untuk satu task
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TaskTime {
+    pub task_id: i32,
+    pub title: String,
+    pub category: Category,
+    pub estimated_minutes: Option<i32>,
+    pub actual_minutes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CategoryTime {
+    pub category: Category,
+    // Jumlah perkiraan task yang punya perkiraan
+    pub estimated_minutes: i64,
+    pub actual_minutes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct WeekTime {
+    // Senin di awal minggu
+    pub week_start: NaiveDate,
+    // Perkiraan task yang deadline-nya jatuh di minggu ini
+    pub estimated_minutes: i64,
+    // Waktu yang dicatat di minggu ini
+    pub actual_minutes: i64,
+}
+
+// Laporan waktu untuk rentang tanggal (inklusif). Task masuk laporan jika ada waktu yang
+// dicatat di rentang itu atau deadline-nya jatuh di rentang itu.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct TimeReport {
+    pub tasks: Vec<TaskTime>,
+    pub categories: Vec<CategoryTime>,
+    pub weeks: Vec<WeekTime>,
+}
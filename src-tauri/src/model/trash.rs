@@ -0,0 +1,45 @@
+use chrono::NaiveDateTime;
+use serde::{Serialize, Deserialize};
+use crate::model::note::Note;
+use crate::model::task::Task;
+
+// Key di tabel settings untuk konfigurasi trash
+pub const TRASH_SETTINGS_KEY: &str = "trash";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrashConfig {
+    // Item di trash yang lebih lama dari ini dihapus permanen
+    pub retention_days: u32,
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        TrashConfig { retention_days: 30 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashedTask {
+    #[serde(flatten)]
+    pub task: Task,
+    pub deleted_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashedNote {
+    #[serde(flatten)]
+    pub note: Note,
+    pub deleted_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashListing {
+    pub tasks: Vec<TrashedTask>,
+    pub notes: Vec<TrashedNote>,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct PurgeSummary {
+    pub tasks_purged: usize,
+    pub notes_purged: usize,
+}
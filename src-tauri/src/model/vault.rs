@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+// What a vault sync changed in the notes table
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct VaultSyncSummary {
+    // New markdown files that were registered as notes
+    pub registered: usize,
+    // Notes whose file was edited outside the app
+    pub updated: usize,
+    pub renamed: usize,
+    // Notes whose file disappeared
+    pub missing: usize,
+    // Previously missing notes whose file is back
+    pub recovered: usize,
+}
+
+impl VaultSyncSummary {
+    pub fn is_empty(&self) -> bool {
+        *self == VaultSyncSummary::default()
+    }
+}
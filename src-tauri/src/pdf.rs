@@ -0,0 +1,789 @@
+// src-tauri/src/pdf.rs
+
+// Penyusun PDF sederhana untuk export note. Teks memakai font standar PDF (Helvetica dan
+// Courier) sehingga tidak ada font yang perlu di-embed; karakter di luar WinAnsi diganti '?'.
+// Gambar PNG dan JPEG di-embed apa adanya, daftar isi dan bookmark dibuat dari heading.
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use pdf_writer::types::{ActionType, AnnotationType};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+use std::io::{Cursor, Write};
+
+// Ukuran A4 dalam point
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+const BODY_SIZE: f32 = 11.0;
+const CODE_SIZE: f32 = 9.5;
+const LINE_HEIGHT: f32 = 1.35;
+// Indentasi per level list/quote
+const INDENT: f32 = 18.0;
+const TOC_LINE: f32 = 16.0;
+// Heading sampai level ini masuk daftar isi
+const TOC_MAX_LEVEL: u8 = 3;
+
+// Lebar glyph Helvetica dan Helvetica-Bold (per 1000 unit) untuk karakter ASCII 32..=126
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontStyle {
+    Regular,
+    Bold,
+    Italic,
+    BoldItalic,
+    Mono,
+}
+
+impl FontStyle {
+    const ALL: [FontStyle; 5] = [FontStyle::Regular, FontStyle::Bold, FontStyle::Italic, FontStyle::BoldItalic, FontStyle::Mono];
+
+    fn resource_name(self) -> Name<'static> {
+        match self {
+            FontStyle::Regular => Name(b"F1"),
+            FontStyle::Bold => Name(b"F2"),
+            FontStyle::Italic => Name(b"F3"),
+            FontStyle::BoldItalic => Name(b"F4"),
+            FontStyle::Mono => Name(b"F5"),
+        }
+    }
+
+    fn base_font(self) -> Name<'static> {
+        match self {
+            FontStyle::Regular => Name(b"Helvetica"),
+            FontStyle::Bold => Name(b"Helvetica-Bold"),
+            FontStyle::Italic => Name(b"Helvetica-Oblique"),
+            FontStyle::BoldItalic => Name(b"Helvetica-BoldOblique"),
+            FontStyle::Mono => Name(b"Courier"),
+        }
+    }
+
+    fn bold(self) -> FontStyle {
+        match self {
+            FontStyle::Regular => FontStyle::Bold,
+            FontStyle::Italic => FontStyle::BoldItalic,
+            style => style,
+        }
+    }
+
+    // Lebar teks dalam point pada ukuran `size`
+    fn width(self, text: &str, size: f32) -> f32 {
+        let units: u32 = text.chars().map(|c| self.char_width(c) as u32).sum();
+        units as f32 * size / 1000.0
+    }
+
+    fn char_width(self, c: char) -> u16 {
+        let widths = match self {
+            FontStyle::Mono => return 600,
+            FontStyle::Regular | FontStyle::Italic => &HELVETICA_WIDTHS,
+            FontStyle::Bold | FontStyle::BoldItalic => &HELVETICA_BOLD_WIDTHS,
+        };
+        match c {
+            ' '..='~' => widths[c as usize - 32],
+            '\u{2018}' | '\u{2019}' => 222,
+            '\u{201C}' | '\u{201D}' => 333,
+            '\u{2022}' => 350,
+            '\u{2014}' | '\u{2026}' => 1000,
+            _ => 556,
+        }
+    }
+}
+
+// Potongan teks dengan satu gaya font
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub style: FontStyle,
+}
+
+impl Span {
+    pub fn new(text: impl Into<String>, style: FontStyle) -> Self {
+        Span { text: text.into(), style }
+    }
+}
+
+pub enum Block {
+    // Level 1 dipakai untuk judul note. Heading sampai level 3 masuk daftar isi dan bookmark.
+    Heading { level: u8, text: String },
+    // Paragraf. `marker` (bullet, nomor, checkbox, label footnote) digambar di kiri baris
+    // pertama; `indent` dalam level list/quote.
+    Text { spans: Vec<Span>, indent: usize, marker: Option<String>, quote: bool },
+    Code(String),
+    // Baris pertama dicetak tebal jika `header`
+    Table { rows: Vec<Vec<Vec<Span>>>, header: bool },
+    Image(PdfImage),
+    Rule,
+    // Mulai halaman baru, kecuali halaman sekarang masih kosong
+    PageBreak,
+}
+
+enum ImageEncoding {
+    Jpeg,
+    Flate,
+}
+
+pub struct PdfImage {
+    width: u32,
+    height: u32,
+    // Jumlah komponen warna: 1 (gray) atau 3 (RGB)
+    components: u8,
+    encoding: ImageEncoding,
+    data: Vec<u8>,
+    // Kanal alpha PNG yang sudah dikompresi, dipakai sebagai soft mask
+    alpha: Option<Vec<u8>>,
+}
+
+impl PdfImage {
+    // None jika bukan PNG/JPEG yang didukung
+    pub fn decode(bytes: &[u8]) -> Option<PdfImage> {
+        if bytes.starts_with(&[0x89, b'P', b'N', b'G']) {
+            decode_png(bytes)
+        } else if bytes.starts_with(&[0xFF, 0xD8]) {
+            decode_jpeg(bytes)
+        } else {
+            None
+        }
+    }
+}
+
+fn decode_png(bytes: &[u8]) -> Option<PdfImage> {
+    let mut decoder = png::Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info().ok()?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buffer).ok()?;
+    let pixels = &buffer[..frame.buffer_size()];
+    let (components, has_alpha) = match frame.color_type {
+        png::ColorType::Grayscale => (1, false),
+        png::ColorType::GrayscaleAlpha => (1, true),
+        png::ColorType::Rgb => (3, false),
+        png::ColorType::Rgba => (3, true),
+        png::ColorType::Indexed => return None,
+    };
+    let (color, alpha) = if has_alpha {
+        let stride = components + 1;
+        let color: Vec<u8> = pixels.chunks(stride).flat_map(|pixel| pixel[..components].to_vec()).collect();
+        let alpha: Vec<u8> = pixels.chunks(stride).map(|pixel| pixel[components]).collect();
+        (color, Some(deflate(&alpha)))
+    } else {
+        (pixels.to_vec(), None)
+    };
+    Some(PdfImage {
+        width: frame.width,
+        height: frame.height,
+        components: components as u8,
+        encoding: ImageEncoding::Flate,
+        data: deflate(&color),
+        alpha,
+    })
+}
+
+// JPEG disalin utuh (DCTDecode); ukurannya dibaca dari marker SOF
+fn decode_jpeg(bytes: &[u8]) -> Option<PdfImage> {
+    let mut pos = 2;
+    while pos + 4 <= bytes.len() {
+        if bytes[pos] != 0xFF {
+            return None;
+        }
+        let marker = bytes[pos + 1];
+        let length = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+        let is_frame = matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+        if is_frame {
+            let header = bytes.get(pos + 4..pos + 10)?;
+            let height = u16::from_be_bytes([header[1], header[2]]) as u32;
+            let width = u16::from_be_bytes([header[3], header[4]]) as u32;
+            // JPEG CMYK tidak didukung
+            let components = header[5];
+            if !matches!(components, 1 | 3) || width == 0 || height == 0 {
+                return None;
+            }
+            return Some(PdfImage {
+                width,
+                height,
+                components,
+                encoding: ImageEncoding::Jpeg,
+                data: bytes.to_vec(),
+                alpha: None,
+            });
+        }
+        pos += 2 + length;
+    }
+    None
+}
+
+pub struct PdfDocument {
+    pub title: String,
+    pub blocks: Vec<Block>,
+}
+
+impl PdfDocument {
+    pub fn render(&self) -> Vec<u8> {
+        let toc_entries = self.blocks.iter()
+            .filter(|block| matches!(block, Block::Heading { level, .. } if *level <= TOC_MAX_LEVEL))
+            .count();
+        let mut layout = Layout::new(toc_page_count(toc_entries));
+        for block in &self.blocks {
+            layout.block(block);
+        }
+        layout.table_of_contents();
+        layout.page_numbers();
+        write_pdf(&self.title, layout)
+    }
+}
+
+// Halaman daftar isi yang dibutuhkan untuk `entries` baris; judul "Contents" memakai tempat
+// dua baris di halaman pertama
+fn toc_page_count(entries: usize) -> usize {
+    if entries == 0 {
+        return 0;
+    }
+    let per_page = ((PAGE_HEIGHT - 2.0 * MARGIN) / TOC_LINE) as usize;
+    (entries + 2).div_ceil(per_page)
+}
+
+// Tautan dari daftar isi ke posisi heading
+struct PageLink {
+    rect: Rect,
+    page: usize,
+    top: f32,
+}
+
+struct PageDraft {
+    content: Content,
+    links: Vec<PageLink>,
+}
+
+impl PageDraft {
+    fn new() -> Self {
+        PageDraft { content: Content::new(), links: Vec::new() }
+    }
+}
+
+struct HeadingMark {
+    level: u8,
+    text: String,
+    page: usize,
+    top: f32,
+}
+
+struct Layout<'a> {
+    pages: Vec<PageDraft>,
+    toc_pages: usize,
+    // Posisi vertikal berikutnya di halaman terakhir, diukur dari bawah halaman
+    y: f32,
+    headings: Vec<HeadingMark>,
+    images: Vec<&'a PdfImage>,
+}
+
+// Satu baris hasil wrap: potongan (gaya, teks, posisi x relatif)
+type Line = Vec<(FontStyle, String, f32)>;
+
+impl<'a> Layout<'a> {
+    fn new(toc_pages: usize) -> Self {
+        let mut layout = Layout { pages: Vec::new(), toc_pages, y: 0.0, headings: Vec::new(), images: Vec::new() };
+        for _ in 0..toc_pages {
+            layout.pages.push(PageDraft::new());
+        }
+        layout.new_page();
+        layout
+    }
+
+    fn top() -> f32 {
+        PAGE_HEIGHT - MARGIN
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(PageDraft::new());
+        self.y = Self::top();
+    }
+
+    fn page_is_empty(&self) -> bool {
+        self.y >= Self::top()
+    }
+
+    // Pindah halaman jika `height` tidak muat lagi
+    fn ensure(&mut self, height: f32) {
+        if self.y - height < MARGIN && !self.page_is_empty() {
+            self.new_page();
+        }
+    }
+
+    fn content(&mut self) -> &mut Content {
+        &mut self.pages.last_mut().expect("layout always has a page").content
+    }
+
+    fn block(&mut self, block: &'a Block) {
+        match block {
+            Block::Heading { level, text } => self.heading(*level, text),
+            Block::Text { spans, indent, marker, quote } => self.text(spans, *indent, marker.as_deref(), *quote),
+            Block::Code(code) => self.code(code),
+            Block::Table { rows, header } => self.table(rows, *header),
+            Block::Image(image) => self.image(image),
+            Block::Rule => {
+                self.ensure(14.0);
+                let y = self.y - 7.0;
+                self.content()
+                    .set_stroke_gray(0.75)
+                    .move_to(MARGIN, y)
+                    .line_to(PAGE_WIDTH - MARGIN, y)
+                    .stroke();
+                self.y -= 14.0;
+            }
+            Block::PageBreak => {
+                if !self.page_is_empty() {
+                    self.new_page();
+                }
+            }
+        }
+    }
+
+    fn heading(&mut self, level: u8, text: &str) {
+        let size = match level {
+            1 => 20.0,
+            2 => 16.0,
+            3 => 14.0,
+            _ => 12.0,
+        };
+        if !self.page_is_empty() {
+            self.y -= size * 0.6;
+        }
+        let lines = wrap(&[Span::new(text, FontStyle::Bold)], size, CONTENT_WIDTH);
+        // Heading tidak dibiarkan sendirian di bawah halaman
+        self.ensure(lines.len() as f32 * size * LINE_HEIGHT + BODY_SIZE * 3.0);
+        self.headings.push(HeadingMark { level, text: text.to_string(), page: self.pages.len() - 1, top: self.y });
+        for line in &lines {
+            self.y -= size * LINE_HEIGHT;
+            let y = self.y + size * 0.3;
+            draw_line(self.content(), line, MARGIN, y, size);
+        }
+        self.y -= size * 0.4;
+    }
+
+    fn text(&mut self, spans: &[Span], indent: usize, marker: Option<&str>, quote: bool) {
+        let x = MARGIN + indent as f32 * INDENT;
+        let lines = wrap(spans, BODY_SIZE, CONTENT_WIDTH - indent as f32 * INDENT);
+        let line_height = BODY_SIZE * LINE_HEIGHT;
+        for (index, line) in lines.iter().enumerate() {
+            self.ensure(line_height);
+            self.y -= line_height;
+            let bottom = self.y;
+            let y = bottom + BODY_SIZE * 0.3;
+            let content = self.content();
+            if quote {
+                content.set_fill_gray(0.8).rect(x - 10.0, bottom, 2.0, line_height).fill_nonzero();
+                content.set_fill_gray(0.35);
+            }
+            if let (0, Some(marker)) = (index, marker) {
+                let width = FontStyle::Regular.width(marker, BODY_SIZE);
+                draw_line(content, &[(FontStyle::Regular, marker.to_string(), 0.0)], x - width - 5.0, y, BODY_SIZE);
+            }
+            draw_line(content, line, x, y, BODY_SIZE);
+            content.set_fill_gray(0.0);
+        }
+        self.y -= if marker.is_some() { BODY_SIZE * 0.25 } else { BODY_SIZE * 0.6 };
+    }
+
+    fn code(&mut self, code: &str) {
+        let line_height = CODE_SIZE * LINE_HEIGHT;
+        let columns = ((CONTENT_WIDTH - 12.0) / (CODE_SIZE * 0.6)) as usize;
+        let lines: Vec<String> = code.trim_end_matches('\n').lines()
+            .flat_map(|line| {
+                let chars: Vec<char> = line.chars().collect();
+                if chars.is_empty() {
+                    return vec![String::new()];
+                }
+                chars.chunks(columns.max(1)).map(|chunk| chunk.iter().collect()).collect()
+            })
+            .collect();
+        self.y -= 3.0;
+        for line in lines {
+            self.ensure(line_height);
+            self.y -= line_height;
+            let y = self.y;
+            let content = self.content();
+            content.set_fill_gray(0.95).rect(MARGIN, y, CONTENT_WIDTH, line_height).fill_nonzero();
+            content.set_fill_gray(0.0);
+            draw_line(content, &[(FontStyle::Mono, line, 0.0)], MARGIN + 6.0, y + CODE_SIZE * 0.35, CODE_SIZE);
+        }
+        self.y -= BODY_SIZE * 0.8;
+    }
+
+    fn table(&mut self, rows: &[Vec<Vec<Span>>], header: bool) {
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        if columns == 0 {
+            return;
+        }
+        let column_width = CONTENT_WIDTH / columns as f32;
+        let line_height = BODY_SIZE * LINE_HEIGHT;
+        for (row_index, row) in rows.iter().enumerate() {
+            let cells: Vec<Vec<Line>> = row.iter()
+                .map(|cell| {
+                    let spans: Vec<Span> = if header && row_index == 0 {
+                        cell.iter().map(|span| Span::new(span.text.clone(), span.style.bold())).collect()
+                    } else {
+                        cell.clone()
+                    };
+                    wrap(&spans, BODY_SIZE, column_width - 8.0)
+                })
+                .collect();
+            let row_height = cells.iter().map(Vec::len).max().unwrap_or(1).max(1) as f32 * line_height + 6.0;
+            self.ensure(row_height);
+            let top = self.y;
+            let content = &mut self.pages.last_mut().expect("layout always has a page").content;
+            content.set_stroke_gray(0.7).set_line_width(0.5);
+            for column in 0..columns {
+                let x = MARGIN + column as f32 * column_width;
+                content.rect(x, top - row_height, column_width, row_height).stroke();
+                for (index, line) in cells.get(column).into_iter().flatten().enumerate() {
+                    let y = top - 3.0 - (index + 1) as f32 * line_height + BODY_SIZE * 0.3;
+                    draw_line(content, line, x + 4.0, y, BODY_SIZE);
+                }
+            }
+            self.y -= row_height;
+        }
+        self.y -= BODY_SIZE * 0.8;
+    }
+
+    fn image(&mut self, image: &'a PdfImage) {
+        // Piksel dianggap 96 dpi, diperkecil agar muat di area halaman
+        let mut width = image.width as f32 * 0.75;
+        let mut height = image.height as f32 * 0.75;
+        let max_height = PAGE_HEIGHT - 2.0 * MARGIN - BODY_SIZE;
+        let scale = (CONTENT_WIDTH / width).min(max_height / height).min(1.0);
+        width *= scale;
+        height *= scale;
+        self.ensure(height + 8.0);
+        self.y -= height + 4.0;
+        let name = format!("Im{}", self.images.len());
+        self.images.push(image);
+        let y = self.y;
+        self.content()
+            .save_state()
+            .transform([width, 0.0, 0.0, height, MARGIN, y])
+            .x_object(Name(name.as_bytes()))
+            .restore_state();
+        self.y -= BODY_SIZE * 0.8;
+    }
+
+    // Daftar isi digambar terakhir karena nomor halaman heading baru diketahui setelah layout
+    fn table_of_contents(&mut self) {
+        if self.toc_pages == 0 {
+            return;
+        }
+        let entries: Vec<(u8, String, usize, f32)> = self.headings.iter()
+            .filter(|heading| heading.level <= TOC_MAX_LEVEL)
+            .map(|heading| (heading.level, heading.text.clone(), heading.page, heading.top))
+            .collect();
+        let mut page = 0;
+        let mut y = Self::top() - TOC_LINE;
+        draw_line(&mut self.pages[0].content, &[(FontStyle::Bold, "Contents".to_string(), 0.0)], MARGIN, y, 18.0);
+        y -= TOC_LINE;
+        for (level, text, target, top) in entries {
+            y -= TOC_LINE;
+            if y < MARGIN {
+                page += 1;
+                y = Self::top() - TOC_LINE;
+            }
+            let x = MARGIN + (level as f32 - 1.0) * 14.0;
+            let style = if level == 1 { FontStyle::Bold } else { FontStyle::Regular };
+            let number = (target + 1).to_string();
+            let number_width = FontStyle::Regular.width(&number, BODY_SIZE);
+            let title = truncate(&text, style, BODY_SIZE, PAGE_WIDTH - MARGIN - x - number_width - 16.0);
+            let draft = &mut self.pages[page];
+            draw_line(&mut draft.content, &[(style, title, 0.0)], x, y, BODY_SIZE);
+            draw_line(&mut draft.content, &[(FontStyle::Regular, number, 0.0)], PAGE_WIDTH - MARGIN - number_width, y, BODY_SIZE);
+            draft.links.push(PageLink {
+                rect: Rect::new(x, y - 4.0, PAGE_WIDTH - MARGIN, y + BODY_SIZE),
+                page: target,
+                top,
+            });
+        }
+    }
+
+    fn page_numbers(&mut self) {
+        let total = self.pages.len();
+        for (index, draft) in self.pages.iter_mut().enumerate() {
+            let label = format!("{} / {}", index + 1, total);
+            let width = FontStyle::Regular.width(&label, 9.0);
+            draft.content.set_fill_gray(0.45);
+            draw_line(&mut draft.content, &[(FontStyle::Regular, label, 0.0)], (PAGE_WIDTH - width) / 2.0, MARGIN / 2.0, 9.0);
+        }
+    }
+}
+
+fn draw_line(content: &mut Content, line: &[(FontStyle, String, f32)], x: f32, y: f32, size: f32) {
+    content.begin_text();
+    for (style, text, offset) in line {
+        content
+            .set_font(style.resource_name(), size)
+            .set_text_matrix([1.0, 0.0, 0.0, 1.0, x + offset, y])
+            .show(Str(&win_ansi(text)));
+    }
+    content.end_text();
+}
+
+// Pecah span menjadi baris selebar maksimal `max_width`; '\n' memaksa baris baru
+fn wrap(spans: &[Span], size: f32, max_width: f32) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut line: Line = Vec::new();
+    let mut x = 0.0;
+    let mut pending_space: Option<FontStyle> = None;
+
+    for span in spans {
+        for (index, part) in span.text.split('\n').enumerate() {
+            if index > 0 {
+                lines.push(std::mem::take(&mut line));
+                x = 0.0;
+                pending_space = None;
+            }
+            let mut words = part.split(' ').peekable();
+            if part.starts_with(' ') && !line.is_empty() {
+                pending_space = Some(span.style);
+            }
+            while let Some(word) = words.next() {
+                if !word.is_empty() {
+                    let mut word = word.to_string();
+                    let space = pending_space.take().filter(|_| !line.is_empty());
+                    let space_width = space.map_or(0.0, |style| style.width(" ", size));
+                    let word_width = span.style.width(&word, size);
+                    if !line.is_empty() && x + space_width + word_width > max_width {
+                        lines.push(std::mem::take(&mut line));
+                        x = 0.0;
+                    } else if let Some(style) = space {
+                        push_fragment(&mut line, style, " ", x);
+                        x += space_width;
+                    }
+                    // Kata yang lebih panjang dari satu baris dipotong per karakter
+                    while span.style.width(&word, size) > max_width {
+                        let mut cut = 0;
+                        let mut width = 0.0;
+                        for (offset, c) in word.char_indices() {
+                            width += span.style.char_width(c) as f32 * size / 1000.0;
+                            if width > max_width - x && offset > 0 {
+                                break;
+                            }
+                            cut = offset + c.len_utf8();
+                        }
+                        push_fragment(&mut line, span.style, &word[..cut], x);
+                        lines.push(std::mem::take(&mut line));
+                        x = 0.0;
+                        word = word[cut..].to_string();
+                    }
+                    push_fragment(&mut line, span.style, &word, x);
+                    x += span.style.width(&word, size);
+                }
+                if words.peek().is_some() {
+                    pending_space = Some(span.style);
+                }
+            }
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+// Gabungkan dengan potongan sebelumnya jika gayanya sama
+fn push_fragment(line: &mut Line, style: FontStyle, text: &str, x: f32) {
+    match line.last_mut() {
+        Some((last_style, last_text, _)) if *last_style == style => last_text.push_str(text),
+        _ => line.push((style, text.to_string(), x)),
+    }
+}
+
+fn truncate(text: &str, style: FontStyle, size: f32, max_width: f32) -> String {
+    if style.width(text, size) <= max_width {
+        return text.to_string();
+    }
+    let mut truncated = String::new();
+    for c in text.chars() {
+        if style.width(&truncated, size) + style.width(&format!("{}...", c), size) > max_width {
+            break;
+        }
+        truncated.push(c);
+    }
+    truncated.push_str("...");
+    truncated
+}
+
+// Encode ke WinAnsi; Latin-1 dipetakan langsung, tanda baca tipografis ke slot 0x80-0x9F
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            ' '..='~' | '\u{A0}'..='\u{FF}' => c as u8,
+            '\u{20AC}' => 0x80,
+            '\u{2026}' => 0x85,
+            '\u{2018}' => 0x91,
+            '\u{2019}' => 0x92,
+            '\u{201C}' => 0x93,
+            '\u{201D}' => 0x94,
+            '\u{2022}' => 0x95,
+            '\u{2013}' => 0x96,
+            '\u{2014}' => 0x97,
+            '\t' => b' ',
+            _ => b'?',
+        })
+        .collect()
+}
+
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    // Menulis ke Vec tidak bisa gagal
+    let _ = encoder.write_all(data);
+    encoder.finish().unwrap_or_default()
+}
+
+fn write_pdf(title: &str, layout: Layout) -> Vec<u8> {
+    let mut next_ref = Ref::new(1);
+    let catalog_id = next_ref.bump();
+    let page_tree_id = next_ref.bump();
+    let info_id = next_ref.bump();
+    let font_ids: Vec<Ref> = FontStyle::ALL.iter().map(|_| next_ref.bump()).collect();
+    let page_ids: Vec<Ref> = layout.pages.iter().map(|_| next_ref.bump()).collect();
+    let mut pdf = Pdf::new();
+
+    for (style, id) in FontStyle::ALL.iter().zip(&font_ids) {
+        pdf.type1_font(*id).base_font(style.base_font()).encoding_predefined(Name(b"WinAnsiEncoding"));
+    }
+
+    let mut image_ids = Vec::new();
+    for image in &layout.images {
+        let id = next_ref.bump();
+        let mask_id = image.alpha.as_ref().map(|_| next_ref.bump());
+        let mut xobject = pdf.image_xobject(id, &image.data);
+        xobject.filter(match image.encoding {
+            ImageEncoding::Jpeg => Filter::DctDecode,
+            ImageEncoding::Flate => Filter::FlateDecode,
+        });
+        xobject.width(image.width as i32).height(image.height as i32).bits_per_component(8);
+        if image.components == 1 {
+            xobject.color_space().device_gray();
+        } else {
+            xobject.color_space().device_rgb();
+        }
+        if let Some(mask_id) = mask_id {
+            xobject.s_mask(mask_id);
+        }
+        xobject.finish();
+        if let (Some(mask_id), Some(alpha)) = (mask_id, &image.alpha) {
+            let mut mask = pdf.image_xobject(mask_id, alpha);
+            mask.filter(Filter::FlateDecode);
+            mask.width(image.width as i32).height(image.height as i32).bits_per_component(8);
+            mask.color_space().device_gray();
+        }
+        image_ids.push(id);
+    }
+
+    for (index, draft) in layout.pages.into_iter().enumerate() {
+        let content_id = next_ref.bump();
+        let mut page = pdf.page(page_ids[index]);
+        page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT))
+            .parent(page_tree_id)
+            .contents(content_id);
+        let mut resources = page.resources();
+        let mut fonts = resources.fonts();
+        for (style, id) in FontStyle::ALL.iter().zip(&font_ids) {
+            fonts.pair(style.resource_name(), *id);
+        }
+        fonts.finish();
+        let mut xobjects = resources.x_objects();
+        for (image_index, id) in image_ids.iter().enumerate() {
+            let name = format!("Im{}", image_index);
+            xobjects.pair(Name(name.as_bytes()), *id);
+        }
+        xobjects.finish();
+        resources.finish();
+        if !draft.links.is_empty() {
+            let mut annotations = page.annotations();
+            for link in &draft.links {
+                let mut annotation = annotations.push();
+                annotation.subtype(AnnotationType::Link).rect(link.rect).border(0.0, 0.0, 0.0, None);
+                annotation.action()
+                    .action_type(ActionType::GoTo)
+                    .destination()
+                    .page(page_ids[link.page])
+                    .xyz(0.0, link.top, None);
+            }
+        }
+        page.finish();
+        pdf.stream(content_id, &deflate(&draft.content.finish())).filter(Filter::FlateDecode);
+    }
+    pdf.pages(page_tree_id).kids(page_ids.iter().copied()).count(page_ids.len() as i32);
+
+    let outline_id = write_outline(&mut pdf, &mut next_ref, &layout.headings, &page_ids);
+    let mut catalog = pdf.catalog(catalog_id);
+    catalog.pages(page_tree_id);
+    if let Some(outline_id) = outline_id {
+        catalog.outlines(outline_id);
+    }
+    catalog.finish();
+    pdf.document_info(info_id).title(TextStr(title));
+    pdf.finish()
+}
+
+// Bookmark dua tingkat: heading level 1 (judul note) dengan heading di bawahnya sebagai anak
+fn write_outline(pdf: &mut Pdf, next_ref: &mut Ref, headings: &[HeadingMark], page_ids: &[Ref]) -> Option<Ref> {
+    let marks: Vec<&HeadingMark> = headings.iter().filter(|heading| heading.level <= TOC_MAX_LEVEL).collect();
+    if marks.is_empty() {
+        return None;
+    }
+    let mut groups: Vec<(usize, Vec<usize>)> = Vec::new();
+    for (index, mark) in marks.iter().enumerate() {
+        match groups.last_mut() {
+            Some((_, children)) if mark.level > 1 => children.push(index),
+            _ => groups.push((index, Vec::new())),
+        }
+    }
+    let outline_id = next_ref.bump();
+    let ids: Vec<Ref> = marks.iter().map(|_| next_ref.bump()).collect();
+
+    let write_item = |pdf: &mut Pdf, index: usize, parent: Ref, prev: Option<usize>, next: Option<usize>, children: &[usize]| {
+        let mark = marks[index];
+        let mut item = pdf.outline_item(ids[index]);
+        item.title(TextStr(&mark.text)).parent(parent);
+        if let Some(prev) = prev {
+            item.prev(ids[prev]);
+        }
+        if let Some(next) = next {
+            item.next(ids[next]);
+        }
+        if let (Some(first), Some(last)) = (children.first(), children.last()) {
+            item.first(ids[*first]).last(ids[*last]).count(children.len() as i32);
+        }
+        item.dest().page(page_ids[mark.page]).xyz(0.0, mark.top, None);
+    };
+
+    for (group_index, (index, children)) in groups.iter().enumerate() {
+        let prev = group_index.checked_sub(1).map(|prev| groups[prev].0);
+        let next = groups.get(group_index + 1).map(|(next, _)| *next);
+        write_item(pdf, *index, outline_id, prev, next, children);
+        for (child_index, child) in children.iter().enumerate() {
+            let prev = child_index.checked_sub(1).map(|prev| children[prev]);
+            let next = children.get(child_index + 1).copied();
+            write_item(pdf, *child, ids[*index], prev, next, &[]);
+        }
+    }
+    let mut outline = pdf.outline(outline_id);
+    if let (Some((first, _)), Some((last, _))) = (groups.first(), groups.last()) {
+        outline.first(ids[*first]).last(ids[*last]);
+    }
+    outline.count(marks.len() as i32);
+    Some(outline_id)
+}
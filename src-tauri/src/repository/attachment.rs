@@ -0,0 +1,97 @@
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use crate::model::attachment::{Attachment, AttachmentOwner};
+
+const COLUMNS: &str = "id, owner_kind, owner_id, file_name, content_hash, size, mime_type, created_at";
+
+fn attachment_from_row(row: &Row) -> Result<Attachment, rusqlite::Error> {
+    let owner: String = row.get(1)?;
+    Ok(Attachment {
+        id: row.get(0)?,
+        owner: owner.parse().map_err(|e: String| rusqlite::Error::FromSqlConversionFailure(1, Type::Text, e.into()))?,
+        owner_id: row.get(2)?,
+        file_name: row.get(3)?,
+        content_hash: row.get(4)?,
+        size: row.get(5)?,
+        mime_type: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+pub trait AttachmentRepository {
+    // Field id dari `attachment` diabaikan
+    fn create(&self, conn: &Connection, attachment: &Attachment) -> Result<i32, rusqlite::Error>;
+    fn fetch_by_id(&self, conn: &Connection, id: i32) -> Result<Option<Attachment>, rusqlite::Error>;
+    // Urut dari yang paling lama dilampirkan
+    fn fetch_for_owner(&self, conn: &Connection, owner: AttachmentOwner, owner_id: i32) -> Result<Vec<Attachment>, rusqlite::Error>;
+    // Lampiran dengan isi yang sama pada pemilik yang sama, jika ada
+    fn find(&self, conn: &Connection, owner: AttachmentOwner, owner_id: i32, content_hash: &str) -> Result<Option<Attachment>, rusqlite::Error>;
+    fn fetch_all(&self, conn: &Connection) -> Result<Vec<Attachment>, rusqlite::Error>;
+    fn delete(&self, conn: &Connection, id: i32) -> Result<(), rusqlite::Error>;
+    // Jumlah lampiran yang masih memakai file dengan hash ini
+    fn count_with_hash(&self, conn: &Connection, content_hash: &str) -> Result<usize, rusqlite::Error>;
+}
+
+pub struct SqliteAttachmentRepository;
+
+impl AttachmentRepository for SqliteAttachmentRepository {
+    fn create(&self, conn: &Connection, attachment: &Attachment) -> Result<i32, rusqlite::Error> {
+        conn.execute(
+            "INSERT INTO attachments (owner_kind, owner_id, file_name, content_hash, size, mime_type, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                attachment.owner.as_str(),
+                attachment.owner_id,
+                attachment.file_name,
+                attachment.content_hash,
+                attachment.size,
+                attachment.mime_type,
+                attachment.created_at,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn fetch_by_id(&self, conn: &Connection, id: i32) -> Result<Option<Attachment>, rusqlite::Error> {
+        conn.query_row(
+            &format!("SELECT {} FROM attachments WHERE id = ?1", COLUMNS),
+            [id],
+            attachment_from_row,
+        ).optional()
+    }
+
+    fn fetch_for_owner(&self, conn: &Connection, owner: AttachmentOwner, owner_id: i32) -> Result<Vec<Attachment>, rusqlite::Error> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM attachments WHERE owner_kind = ?1 AND owner_id = ?2 ORDER BY created_at, id",
+            COLUMNS,
+        ))?;
+        let attachment_iter = stmt.query_map(params![owner.as_str(), owner_id], attachment_from_row)?;
+        Ok(attachment_iter.filter_map(Result::ok).collect())
+    }
+
+    fn find(&self, conn: &Connection, owner: AttachmentOwner, owner_id: i32, content_hash: &str) -> Result<Option<Attachment>, rusqlite::Error> {
+        conn.query_row(
+            &format!(
+                "SELECT {} FROM attachments WHERE owner_kind = ?1 AND owner_id = ?2 AND content_hash = ?3",
+                COLUMNS,
+            ),
+            params![owner.as_str(), owner_id, content_hash],
+            attachment_from_row,
+        ).optional()
+    }
+
+    fn fetch_all(&self, conn: &Connection) -> Result<Vec<Attachment>, rusqlite::Error> {
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM attachments ORDER BY id", COLUMNS))?;
+        let attachment_iter = stmt.query_map([], attachment_from_row)?;
+        Ok(attachment_iter.filter_map(Result::ok).collect())
+    }
+
+    fn delete(&self, conn: &Connection, id: i32) -> Result<(), rusqlite::Error> {
+        conn.execute("DELETE FROM attachments WHERE id = ?1", [id])?;
+        Ok(())
+    }
+
+    fn count_with_hash(&self, conn: &Connection, content_hash: &str) -> Result<usize, rusqlite::Error> {
+        conn.query_row("SELECT COUNT(*) FROM attachments WHERE content_hash = ?1", [content_hash], |row| row.get(0))
+    }
+}
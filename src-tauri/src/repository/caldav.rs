@@ -0,0 +1,91 @@
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use crate::model::caldav::{SyncKind, SyncedItem};
+
+fn item_from_row(row: &Row) -> Result<SyncedItem, rusqlite::Error> {
+    let kind: String = row.get(0)?;
+    Ok(SyncedItem {
+        kind: kind.parse().map_err(|e: String| rusqlite::Error::FromSqlConversionFailure(0, Type::Text, e.into()))?,
+        local_id: row.get(1)?,
+        calendar: row.get(2)?,
+        href: row.get(3)?,
+        uid: row.get(4)?,
+        etag: row.get(5)?,
+        local_updated_at: row.get(6)?,
+    })
+}
+
+// Status sinkronisasi CalDAV: pasangan item lokal dengan resource di server dan sync-token
+// per kalender. Token disimpan per jenis karena task dan acara disinkronkan terpisah, walaupun
+// kalendernya sama.
+pub trait CalDavRepository {
+    fn fetch_items(&self, conn: &Connection, kind: SyncKind) -> Result<Vec<SyncedItem>, rusqlite::Error>;
+    // Pasangan lama untuk item lokal yang sama, atau untuk href yang sama, diganti
+    fn save_item(&self, conn: &Connection, item: &SyncedItem) -> Result<(), rusqlite::Error>;
+    fn delete_item(&self, conn: &Connection, kind: SyncKind, local_id: i32) -> Result<(), rusqlite::Error>;
+    fn sync_token(&self, conn: &Connection, kind: SyncKind, calendar: &str) -> Result<Option<String>, rusqlite::Error>;
+    fn save_sync_token(&self, conn: &Connection, kind: SyncKind, calendar: &str, token: Option<&str>) -> Result<(), rusqlite::Error>;
+    // Lupakan pasangan dan token `kind` di semua kalender selain `keep`, mis. setelah kalender
+    // di pengaturan diganti
+    fn forget_other_calendars(&self, conn: &Connection, kind: SyncKind, keep: Option<&str>) -> Result<(), rusqlite::Error>;
+}
+
+pub struct SqliteCalDavRepository;
+
+impl CalDavRepository for SqliteCalDavRepository {
+    fn fetch_items(&self, conn: &Connection, kind: SyncKind) -> Result<Vec<SyncedItem>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT kind, local_id, calendar, href, uid, etag, local_updated_at FROM caldav_items
+            WHERE kind = ?1 ORDER BY local_id",
+        )?;
+        let items = stmt.query_map([kind.as_str()], item_from_row)?;
+        items.collect()
+    }
+
+    fn save_item(&self, conn: &Connection, item: &SyncedItem) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "INSERT OR REPLACE INTO caldav_items (kind, local_id, calendar, href, uid, etag, local_updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                item.kind.as_str(),
+                item.local_id,
+                item.calendar,
+                item.href,
+                item.uid,
+                item.etag,
+                item.local_updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn delete_item(&self, conn: &Connection, kind: SyncKind, local_id: i32) -> Result<(), rusqlite::Error> {
+        conn.execute("DELETE FROM caldav_items WHERE kind = ?1 AND local_id = ?2", params![kind.as_str(), local_id])?;
+        Ok(())
+    }
+
+    fn sync_token(&self, conn: &Connection, kind: SyncKind, calendar: &str) -> Result<Option<String>, rusqlite::Error> {
+        conn.query_row(
+            "SELECT sync_token FROM caldav_sync_tokens WHERE kind = ?1 AND calendar = ?2",
+            params![kind.as_str(), calendar],
+            |row| row.get(0),
+        )
+        .optional()
+        .map(Option::flatten)
+    }
+
+    fn save_sync_token(&self, conn: &Connection, kind: SyncKind, calendar: &str, token: Option<&str>) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "INSERT INTO caldav_sync_tokens (kind, calendar, sync_token) VALUES (?1, ?2, ?3)
+            ON CONFLICT(kind, calendar) DO UPDATE SET sync_token = excluded.sync_token",
+            params![kind.as_str(), calendar, token],
+        )?;
+        Ok(())
+    }
+
+    fn forget_other_calendars(&self, conn: &Connection, kind: SyncKind, keep: Option<&str>) -> Result<(), rusqlite::Error> {
+        conn.execute("DELETE FROM caldav_items WHERE kind = ?1 AND calendar IS NOT ?2", params![kind.as_str(), keep])?;
+        conn.execute("DELETE FROM caldav_sync_tokens WHERE kind = ?1 AND calendar IS NOT ?2", params![kind.as_str(), keep])?;
+        Ok(())
+    }
+}
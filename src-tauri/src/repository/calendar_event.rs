@@ -0,0 +1,114 @@
+use chrono::NaiveDateTime;
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use crate::model::calendar_event::CalendarEvent;
+use crate::model::recurrence::Recurrence;
+
+const COLUMNS: &str = "id, title, start_at, end_at, all_day, location, recurrence, created_at, updated_at";
+
+fn event_from_row(row: &Row) -> Result<CalendarEvent, rusqlite::Error> {
+    // Aturan pengulangan disimpan sebagai JSON
+    let recurrence: Option<String> = row.get(6)?;
+    let recurrence = recurrence
+        .map(|json| serde_json::from_str::<Recurrence>(&json))
+        .transpose()
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(6, Type::Text, e.into()))?;
+    Ok(CalendarEvent {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        start: row.get(2)?,
+        end: row.get(3)?,
+        all_day: row.get(4)?,
+        location: row.get(5)?,
+        recurrence,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+fn recurrence_json(event: &CalendarEvent) -> Result<Option<String>, rusqlite::Error> {
+    event.recurrence.as_ref()
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))
+}
+
+pub trait CalendarEventRepository {
+    // Field id dari `event` diabaikan
+    fn create(&self, conn: &Connection, event: &CalendarEvent) -> Result<i32, rusqlite::Error>;
+    fn fetch_by_id(&self, conn: &Connection, id: i32) -> Result<Option<CalendarEvent>, rusqlite::Error>;
+    // Urut dari yang paling awal
+    fn fetch_all(&self, conn: &Connection) -> Result<Vec<CalendarEvent>, rusqlite::Error>;
+    // Acara yang bersinggungan dengan `from`..`to`, ditambah semua acara berulang yang dimulai
+    // sebelum `to` (kemunculannya dihitung oleh pemanggil)
+    fn fetch_between(&self, conn: &Connection, from: NaiveDateTime, to: NaiveDateTime) -> Result<Vec<CalendarEvent>, rusqlite::Error>;
+    fn update(&self, conn: &Connection, event: &CalendarEvent) -> Result<(), rusqlite::Error>;
+    fn delete(&self, conn: &Connection, id: i32) -> Result<(), rusqlite::Error>;
+}
+
+pub struct SqliteCalendarEventRepository;
+
+impl CalendarEventRepository for SqliteCalendarEventRepository {
+    fn create(&self, conn: &Connection, event: &CalendarEvent) -> Result<i32, rusqlite::Error> {
+        conn.execute(
+            "INSERT INTO events (title, start_at, end_at, all_day, location, recurrence, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                event.title,
+                event.start,
+                event.end,
+                event.all_day,
+                event.location,
+                recurrence_json(event)?,
+                event.created_at,
+                event.updated_at,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn fetch_by_id(&self, conn: &Connection, id: i32) -> Result<Option<CalendarEvent>, rusqlite::Error> {
+        conn.query_row(&format!("SELECT {} FROM events WHERE id = ?1", COLUMNS), [id], event_from_row)
+            .optional()
+    }
+
+    fn fetch_all(&self, conn: &Connection) -> Result<Vec<CalendarEvent>, rusqlite::Error> {
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM events ORDER BY start_at, id", COLUMNS))?;
+        let event_iter = stmt.query_map([], event_from_row)?;
+        Ok(event_iter.filter_map(Result::ok).collect())
+    }
+
+    fn fetch_between(&self, conn: &Connection, from: NaiveDateTime, to: NaiveDateTime) -> Result<Vec<CalendarEvent>, rusqlite::Error> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM events
+            WHERE start_at < ?2 AND (end_at > ?1 OR start_at >= ?1 OR recurrence IS NOT NULL)
+            ORDER BY start_at, id",
+            COLUMNS
+        ))?;
+        let event_iter = stmt.query_map(params![from, to], event_from_row)?;
+        Ok(event_iter.filter_map(Result::ok).collect())
+    }
+
+    fn update(&self, conn: &Connection, event: &CalendarEvent) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "UPDATE events SET title = ?1, start_at = ?2, end_at = ?3, all_day = ?4, location = ?5,
+            recurrence = ?6, updated_at = ?7 WHERE id = ?8",
+            params![
+                event.title,
+                event.start,
+                event.end,
+                event.all_day,
+                event.location,
+                recurrence_json(event)?,
+                event.updated_at,
+                event.id,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn delete(&self, conn: &Connection, id: i32) -> Result<(), rusqlite::Error> {
+        conn.execute("DELETE FROM events WHERE id = ?1", [id])?;
+        Ok(())
+    }
+}
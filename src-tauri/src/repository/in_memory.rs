@@ -0,0 +1,664 @@
+use chrono::{NaiveDateTime, Utc};
+use rusqlite::{ffi, Connection};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use crate::model::attachment::{Attachment, AttachmentOwner};
+use crate::model::caldav::{SyncKind, SyncedItem};
+use crate::model::calendar_event::CalendarEvent;
+use crate::model::link::{title_key, LinkTarget};
+use crate::model::note::{Note, NotePayload};
+use crate::model::note_revision::NoteRevision;
+use crate::model::note_task::NoteTask;
+use crate::model::planner::ScheduleBlock;
+use crate::model::task::Task;
+use crate::model::task_history::TaskChange;
+use crate::model::time_entry::TimeEntry;
+use crate::model::trash::{TrashedNote, TrashedTask};
+use crate::repository::attachment::AttachmentRepository;
+use crate::repository::caldav::CalDavRepository;
+use crate::repository::calendar_event::CalendarEventRepository;
+use crate::repository::link::LinkRepository;
+use crate::repository::note::NoteRepository;
+use crate::repository::note_revision::NoteRevisionRepository;
+use crate::repository::note_task::NoteTaskRepository;
+use crate::repository::schedule_block::ScheduleBlockRepository;
+use crate::repository::task::TaskRepository;
+use crate::repository::task_history::TaskHistoryRepository;
+use crate::repository::time_entry::TimeEntryRepository;
+
+// Repository berbasis memori untuk unit test. Parameter `conn` diabaikan; test cukup
+// mengoper `Connection::open_in_memory()` karena signature trait tetap membutuhkannya.
+
+#[derive(Default)]
+pub struct InMemoryTaskRepository {
+    tasks: Mutex<Vec<Task>>,
+    // Task di trash beserta waktu penghapusannya
+    deleted: Mutex<HashMap<i32, NaiveDateTime>>,
+    next_id: Mutex<i32>,
+}
+
+impl InMemoryTaskRepository {
+    pub fn new() -> Self {
+        InMemoryTaskRepository::default()
+    }
+}
+
+impl TaskRepository for InMemoryTaskRepository {
+    fn create(&self, _conn: &Connection, task: &Task) -> Result<i32, rusqlite::Error> {
+        let mut next_id = self.next_id.lock().unwrap();
+        *next_id += 1;
+        let mut task = task.clone();
+        task.id = *next_id;
+        self.tasks.lock().unwrap().push(task);
+        Ok(*next_id)
+    }
+
+    fn fetch_all(&self, _conn: &Connection) -> Result<Vec<Task>, rusqlite::Error> {
+        let tasks = self.tasks.lock().unwrap();
+        let deleted = self.deleted.lock().unwrap();
+        Ok(tasks.iter().filter(|t| !deleted.contains_key(&t.id)).cloned().collect())
+    }
+
+    fn fetch_by_id(&self, conn: &Connection, id: i32) -> Result<Option<Task>, rusqlite::Error> {
+        Ok(self.fetch_all(conn)?.into_iter().find(|t| t.id == id))
+    }
+
+    fn update(&self, _conn: &Connection, task: &Task) -> Result<(), rusqlite::Error> {
+        // Sama seperti UPDATE di SQL: id yang tidak ada atau di trash diabaikan tanpa error
+        let deleted = self.deleted.lock().unwrap();
+        let mut tasks = self.tasks.lock().unwrap();
+        if let Some(existing) = tasks.iter_mut().find(|t| t.id == task.id && !deleted.contains_key(&t.id)) {
+            let created_at = existing.created_at;
+            *existing = task.clone();
+            existing.created_at = created_at;
+        }
+        Ok(())
+    }
+
+    fn delete(&self, _conn: &Connection, id: i32) -> Result<(), rusqlite::Error> {
+        self.tasks.lock().unwrap().retain(|t| t.id != id);
+        self.deleted.lock().unwrap().remove(&id);
+        Ok(())
+    }
+
+    fn soft_delete(&self, conn: &Connection, id: i32, deleted_at: NaiveDateTime) -> Result<bool, rusqlite::Error> {
+        if self.fetch_by_id(conn, id)?.is_none() {
+            return Ok(false);
+        }
+        self.deleted.lock().unwrap().insert(id, deleted_at);
+        Ok(true)
+    }
+
+    fn undelete(&self, _conn: &Connection, id: i32) -> Result<bool, rusqlite::Error> {
+        Ok(self.deleted.lock().unwrap().remove(&id).is_some())
+    }
+
+    fn fetch_deleted(&self, _conn: &Connection) -> Result<Vec<TrashedTask>, rusqlite::Error> {
+        let tasks = self.tasks.lock().unwrap();
+        let deleted = self.deleted.lock().unwrap();
+        let mut trashed: Vec<TrashedTask> = tasks
+            .iter()
+            .filter_map(|t| deleted.get(&t.id).map(|&deleted_at| TrashedTask { task: t.clone(), deleted_at }))
+            .collect();
+        trashed.sort_by_key(|t| std::cmp::Reverse(t.deleted_at));
+        Ok(trashed)
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryNoteRepository {
+    notes: Mutex<Vec<Note>>,
+    deleted: Mutex<HashMap<i32, NaiveDateTime>>,
+    next_id: Mutex<i32>,
+}
+
+impl InMemoryNoteRepository {
+    pub fn new() -> Self {
+        InMemoryNoteRepository::default()
+    }
+
+    // Tiru index UNIQUE parsial: file_path hanya unik di antara note yang tidak di trash
+    fn check_unique_path(&self, notes: &[Note], file_path: &str, except_id: i32) -> Result<(), rusqlite::Error> {
+        let deleted = self.deleted.lock().unwrap();
+        let taken = notes.iter()
+            .any(|n| n.id != except_id && n.file_path == file_path && !deleted.contains_key(&n.id));
+        if taken {
+            Err(constraint_error("notes.file_path"))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn insert(&self, mut note: Note) -> Result<i32, rusqlite::Error> {
+        let mut notes = self.notes.lock().unwrap();
+        self.check_unique_path(&notes, &note.file_path, 0)?;
+        let mut next_id = self.next_id.lock().unwrap();
+        *next_id += 1;
+        note.id = *next_id;
+        notes.push(note);
+        Ok(*next_id)
+    }
+}
+
+impl NoteRepository for InMemoryNoteRepository {
+    fn create(&self, _conn: &Connection, payload: &NotePayload) -> Result<i32, rusqlite::Error> {
+        let now = Utc::now().naive_utc();
+        self.insert(Note {
+            id: 0,
+            title: payload.title.clone(),
+            file_path: payload.file_path.clone(),
+            created_at: now,
+            updated_at: now,
+            missing: false,
+            tags: Vec::new(),
+            color: None,
+            task_ids: Vec::new(),
+        })
+    }
+
+    fn insert_with_timestamps(&self, _conn: &Connection, note: &Note) -> Result<i32, rusqlite::Error> {
+        self.insert(note.clone())
+    }
+
+    fn fetch_all(&self, _conn: &Connection) -> Result<Vec<Note>, rusqlite::Error> {
+        let notes = self.notes.lock().unwrap();
+        let deleted = self.deleted.lock().unwrap();
+        Ok(notes.iter().filter(|n| !deleted.contains_key(&n.id)).cloned().collect())
+    }
+
+    fn fetch_by_id(&self, conn: &Connection, id: i32) -> Result<Option<Note>, rusqlite::Error> {
+        Ok(self.fetch_all(conn)?.into_iter().find(|n| n.id == id))
+    }
+
+    fn update(&self, _conn: &Connection, note: &Note) -> Result<(), rusqlite::Error> {
+        let mut notes = self.notes.lock().unwrap();
+        self.check_unique_path(&notes, &note.file_path, note.id)?;
+        let deleted = self.deleted.lock().unwrap();
+        if let Some(existing) = notes.iter_mut().find(|n| n.id == note.id && !deleted.contains_key(&n.id)) {
+            existing.title = note.title.clone();
+            existing.file_path = note.file_path.clone();
+            existing.tags = note.tags.clone();
+            existing.color = note.color.clone();
+            existing.task_ids = note.task_ids.clone();
+            existing.updated_at = Utc::now().naive_utc();
+        }
+        Ok(())
+    }
+
+    fn delete(&self, _conn: &Connection, id: i32) -> Result<(), rusqlite::Error> {
+        self.notes.lock().unwrap().retain(|n| n.id != id);
+        self.deleted.lock().unwrap().remove(&id);
+        Ok(())
+    }
+
+    fn soft_delete(&self, conn: &Connection, id: i32, deleted_at: NaiveDateTime) -> Result<bool, rusqlite::Error> {
+        if self.fetch_by_id(conn, id)?.is_none() {
+            return Ok(false);
+        }
+        self.deleted.lock().unwrap().insert(id, deleted_at);
+        Ok(true)
+    }
+
+    fn undelete(&self, _conn: &Connection, id: i32) -> Result<bool, rusqlite::Error> {
+        let notes = self.notes.lock().unwrap();
+        if !self.deleted.lock().unwrap().contains_key(&id) {
+            return Ok(false);
+        }
+        if let Some(note) = notes.iter().find(|n| n.id == id) {
+            self.check_unique_path(&notes, &note.file_path, id)?;
+        }
+        self.deleted.lock().unwrap().remove(&id);
+        Ok(true)
+    }
+
+    fn fetch_deleted(&self, _conn: &Connection) -> Result<Vec<TrashedNote>, rusqlite::Error> {
+        let notes = self.notes.lock().unwrap();
+        let deleted = self.deleted.lock().unwrap();
+        let mut trashed: Vec<TrashedNote> = notes
+            .iter()
+            .filter_map(|n| deleted.get(&n.id).map(|&deleted_at| TrashedNote { note: n.clone(), deleted_at }))
+            .collect();
+        trashed.sort_by_key(|n| std::cmp::Reverse(n.deleted_at));
+        Ok(trashed)
+    }
+
+    fn get_file_path_by_id(&self, _conn: &Connection, id: i32) -> Result<Option<String>, rusqlite::Error> {
+        Ok(self.notes.lock().unwrap().iter().find(|n| n.id == id).map(|n| n.file_path.clone()))
+    }
+
+    fn update_metadata(&self, _conn: &Connection, note: &Note) -> Result<(), rusqlite::Error> {
+        if let Some(existing) = self.notes.lock().unwrap().iter_mut().find(|n| n.id == note.id) {
+            *existing = Note { file_path: existing.file_path.clone(), missing: existing.missing, ..note.clone() };
+        }
+        Ok(())
+    }
+
+    fn set_missing(&self, _conn: &Connection, id: i32, missing: bool) -> Result<(), rusqlite::Error> {
+        if let Some(note) = self.notes.lock().unwrap().iter_mut().find(|n| n.id == id) {
+            note.missing = missing;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryTaskHistoryRepository {
+    changes: Mutex<Vec<TaskChange>>,
+}
+
+impl InMemoryTaskHistoryRepository {
+    pub fn new() -> Self {
+        InMemoryTaskHistoryRepository::default()
+    }
+}
+
+impl TaskHistoryRepository for InMemoryTaskHistoryRepository {
+    fn record(&self, _conn: &Connection, change: &TaskChange) -> Result<i32, rusqlite::Error> {
+        let mut changes = self.changes.lock().unwrap();
+        let id = changes.len() as i32 + 1;
+        changes.push(TaskChange { id, ..change.clone() });
+        Ok(id)
+    }
+
+    fn fetch_for_task(&self, _conn: &Connection, task_id: i32) -> Result<Vec<TaskChange>, rusqlite::Error> {
+        // Dicatat berurutan, jadi urutan penyimpanan sudah urut waktu
+        Ok(self.changes.lock().unwrap().iter().filter(|c| c.task_id == task_id).cloned().collect())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryNoteRevisionRepository {
+    revisions: Mutex<Vec<(NoteRevision, Vec<u8>)>>,
+    next_id: Mutex<i32>,
+}
+
+impl InMemoryNoteRevisionRepository {
+    pub fn new() -> Self {
+        InMemoryNoteRevisionRepository::default()
+    }
+}
+
+impl NoteRevisionRepository for InMemoryNoteRevisionRepository {
+    fn create(&self, _conn: &Connection, revision: &NoteRevision, data: &[u8]) -> Result<i32, rusqlite::Error> {
+        let mut next_id = self.next_id.lock().unwrap();
+        *next_id += 1;
+        let revision = NoteRevision { id: *next_id, ..revision.clone() };
+        self.revisions.lock().unwrap().push((revision, data.to_vec()));
+        Ok(*next_id)
+    }
+
+    fn fetch_latest(&self, conn: &Connection, note_id: i32) -> Result<Option<NoteRevision>, rusqlite::Error> {
+        Ok(self.fetch_for_note(conn, note_id)?.into_iter().next())
+    }
+
+    fn fetch_for_note(&self, _conn: &Connection, note_id: i32) -> Result<Vec<NoteRevision>, rusqlite::Error> {
+        // Disimpan berurutan, jadi cukup dibalik untuk mendapat yang terbaru lebih dulu
+        Ok(self.revisions.lock().unwrap().iter().rev()
+            .filter(|(r, _)| r.note_id == note_id)
+            .map(|(r, _)| r.clone())
+            .collect())
+    }
+
+    fn fetch_data(&self, _conn: &Connection, note_id: i32, id: i32) -> Result<Option<Vec<u8>>, rusqlite::Error> {
+        Ok(self.revisions.lock().unwrap().iter()
+            .find(|(r, _)| r.id == id && r.note_id == note_id)
+            .map(|(_, data)| data.clone()))
+    }
+
+    fn prune(&self, _conn: &Connection, note_id: i32, keep: usize, cutoff: NaiveDateTime) -> Result<usize, rusqlite::Error> {
+        let mut revisions = self.revisions.lock().unwrap();
+        let before = revisions.len();
+        let mut kept = 0;
+        // Telusuri dari yang terbaru supaya `keep` revisi terakhir yang dipertahankan
+        let mut retained: Vec<_> = revisions.drain(..).rev()
+            .filter(|(r, _)| {
+                if r.note_id != note_id {
+                    return true;
+                }
+                kept += 1;
+                kept <= keep && r.created_at >= cutoff
+            })
+            .collect();
+        retained.reverse();
+        *revisions = retained;
+        Ok(before - revisions.len())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryLinkRepository {
+    links: Mutex<Vec<(i32, LinkTarget)>>,
+}
+
+impl InMemoryLinkRepository {
+    pub fn new() -> Self {
+        InMemoryLinkRepository::default()
+    }
+}
+
+// Judul target disimpan dalam bentuk ternormalisasi, sama seperti di SQLite
+fn stored_target(target: &LinkTarget) -> LinkTarget {
+    match target {
+        LinkTarget::Note(title) => LinkTarget::Note(title_key(title)),
+        LinkTarget::Task(task_id) => LinkTarget::Task(*task_id),
+    }
+}
+
+impl LinkRepository for InMemoryLinkRepository {
+    fn replace_for_note(&self, _conn: &Connection, note_id: i32, links: &[LinkTarget]) -> Result<(), rusqlite::Error> {
+        let mut stored = self.links.lock().unwrap();
+        stored.retain(|(source_id, _)| *source_id != note_id);
+        stored.extend(links.iter().map(|link| (note_id, stored_target(link))));
+        Ok(())
+    }
+
+    fn fetch_for_note(&self, _conn: &Connection, note_id: i32) -> Result<Vec<LinkTarget>, rusqlite::Error> {
+        Ok(self.links.lock().unwrap().iter()
+            .filter(|(source_id, _)| *source_id == note_id)
+            .map(|(_, link)| link.clone())
+            .collect())
+    }
+
+    fn fetch_sources(&self, _conn: &Connection, target: &LinkTarget) -> Result<Vec<i32>, rusqlite::Error> {
+        let target = stored_target(target);
+        let mut sources: Vec<i32> = self.links.lock().unwrap().iter()
+            .filter(|(_, link)| *link == target)
+            .map(|(source_id, _)| *source_id)
+            .collect();
+        sources.sort_unstable();
+        sources.dedup();
+        Ok(sources)
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryAttachmentRepository {
+    attachments: Mutex<Vec<Attachment>>,
+    next_id: Mutex<i32>,
+}
+
+impl InMemoryAttachmentRepository {
+    pub fn new() -> Self {
+        InMemoryAttachmentRepository::default()
+    }
+}
+
+impl AttachmentRepository for InMemoryAttachmentRepository {
+    fn create(&self, conn: &Connection, attachment: &Attachment) -> Result<i32, rusqlite::Error> {
+        // Tiru UNIQUE (owner_kind, owner_id, content_hash)
+        if self.find(conn, attachment.owner, attachment.owner_id, &attachment.content_hash)?.is_some() {
+            return Err(constraint_error("attachments.owner_kind, attachments.owner_id, attachments.content_hash"));
+        }
+        let mut next_id = self.next_id.lock().unwrap();
+        *next_id += 1;
+        self.attachments.lock().unwrap().push(Attachment { id: *next_id, ..attachment.clone() });
+        Ok(*next_id)
+    }
+
+    fn fetch_by_id(&self, _conn: &Connection, id: i32) -> Result<Option<Attachment>, rusqlite::Error> {
+        Ok(self.attachments.lock().unwrap().iter().find(|a| a.id == id).cloned())
+    }
+
+    fn fetch_for_owner(&self, _conn: &Connection, owner: AttachmentOwner, owner_id: i32) -> Result<Vec<Attachment>, rusqlite::Error> {
+        Ok(self.attachments.lock().unwrap().iter()
+            .filter(|a| a.owner == owner && a.owner_id == owner_id)
+            .cloned()
+            .collect())
+    }
+
+    fn find(&self, conn: &Connection, owner: AttachmentOwner, owner_id: i32, content_hash: &str) -> Result<Option<Attachment>, rusqlite::Error> {
+        Ok(self.fetch_for_owner(conn, owner, owner_id)?.into_iter().find(|a| a.content_hash == content_hash))
+    }
+
+    fn fetch_all(&self, _conn: &Connection) -> Result<Vec<Attachment>, rusqlite::Error> {
+        Ok(self.attachments.lock().unwrap().clone())
+    }
+
+    fn delete(&self, _conn: &Connection, id: i32) -> Result<(), rusqlite::Error> {
+        self.attachments.lock().unwrap().retain(|a| a.id != id);
+        Ok(())
+    }
+
+    fn count_with_hash(&self, _conn: &Connection, content_hash: &str) -> Result<usize, rusqlite::Error> {
+        Ok(self.attachments.lock().unwrap().iter().filter(|a| a.content_hash == content_hash).count())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryNoteTaskRepository {
+    links: Mutex<Vec<NoteTask>>,
+}
+
+impl InMemoryNoteTaskRepository {
+    pub fn new() -> Self {
+        InMemoryNoteTaskRepository::default()
+    }
+}
+
+impl NoteTaskRepository for InMemoryNoteTaskRepository {
+    fn replace_for_note(&self, _conn: &Connection, note_id: i32, links: &[NoteTask]) -> Result<(), rusqlite::Error> {
+        let mut stored = self.links.lock().unwrap();
+        // Tiru PRIMARY KEY task_id: task yang pindah note menggantikan tautan lamanya
+        stored.retain(|link| link.note_id != note_id && !links.iter().any(|new| new.task_id == link.task_id));
+        stored.extend(links.iter().map(|link| NoteTask { note_id, ..link.clone() }));
+        Ok(())
+    }
+
+    fn fetch_for_note(&self, _conn: &Connection, note_id: i32) -> Result<Vec<NoteTask>, rusqlite::Error> {
+        let mut links: Vec<NoteTask> = self.links.lock().unwrap().iter()
+            .filter(|link| link.note_id == note_id)
+            .cloned()
+            .collect();
+        links.sort_by_key(|link| (link.line, link.task_id));
+        Ok(links)
+    }
+
+    fn fetch_for_task(&self, _conn: &Connection, task_id: i32) -> Result<Option<NoteTask>, rusqlite::Error> {
+        Ok(self.links.lock().unwrap().iter().find(|link| link.task_id == task_id).cloned())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryTimeEntryRepository {
+    entries: Mutex<Vec<TimeEntry>>,
+    next_id: Mutex<i32>,
+}
+
+impl InMemoryTimeEntryRepository {
+    pub fn new() -> Self {
+        InMemoryTimeEntryRepository::default()
+    }
+
+    fn fetch_where(&self, filter: impl Fn(&TimeEntry) -> bool) -> Vec<TimeEntry> {
+        let mut entries: Vec<TimeEntry> = self.entries.lock().unwrap().iter().filter(|e| filter(e)).cloned().collect();
+        entries.sort_by_key(|e| (e.started_at, e.id));
+        entries
+    }
+}
+
+impl TimeEntryRepository for InMemoryTimeEntryRepository {
+    fn create(&self, _conn: &Connection, entry: &TimeEntry) -> Result<i32, rusqlite::Error> {
+        let mut next_id = self.next_id.lock().unwrap();
+        *next_id += 1;
+        self.entries.lock().unwrap().push(TimeEntry { id: *next_id, ..entry.clone() });
+        Ok(*next_id)
+    }
+
+    fn fetch_by_id(&self, _conn: &Connection, id: i32) -> Result<Option<TimeEntry>, rusqlite::Error> {
+        Ok(self.entries.lock().unwrap().iter().find(|e| e.id == id).cloned())
+    }
+
+    fn fetch_for_task(&self, _conn: &Connection, task_id: i32) -> Result<Vec<TimeEntry>, rusqlite::Error> {
+        Ok(self.fetch_where(|e| e.task_id == task_id))
+    }
+
+    fn fetch_between(&self, _conn: &Connection, from: NaiveDateTime, to: NaiveDateTime) -> Result<Vec<TimeEntry>, rusqlite::Error> {
+        Ok(self.fetch_where(|e| e.ended_at.is_some() && e.started_at >= from && e.started_at < to))
+    }
+
+    fn fetch_running(&self, _conn: &Connection) -> Result<Option<TimeEntry>, rusqlite::Error> {
+        let mut running = self.fetch_where(|e| e.ended_at.is_none());
+        running.sort_by_key(|e| e.id);
+        Ok(running.into_iter().next())
+    }
+
+    fn finish(&self, _conn: &Connection, id: i32, ended_at: NaiveDateTime, minutes: i32) -> Result<(), rusqlite::Error> {
+        if let Some(entry) = self.entries.lock().unwrap().iter_mut().find(|e| e.id == id) {
+            entry.ended_at = Some(ended_at);
+            entry.minutes = minutes;
+        }
+        Ok(())
+    }
+
+    fn delete(&self, _conn: &Connection, id: i32) -> Result<(), rusqlite::Error> {
+        self.entries.lock().unwrap().retain(|e| e.id != id);
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryScheduleBlockRepository {
+    blocks: Mutex<Vec<ScheduleBlock>>,
+    next_id: Mutex<i32>,
+}
+
+impl InMemoryScheduleBlockRepository {
+    pub fn new() -> Self {
+        InMemoryScheduleBlockRepository::default()
+    }
+}
+
+impl ScheduleBlockRepository for InMemoryScheduleBlockRepository {
+    fn create(&self, _conn: &Connection, block: &ScheduleBlock) -> Result<i32, rusqlite::Error> {
+        let mut next_id = self.next_id.lock().unwrap();
+        *next_id += 1;
+        self.blocks.lock().unwrap().push(ScheduleBlock { id: *next_id, ..block.clone() });
+        Ok(*next_id)
+    }
+
+    fn fetch_by_id(&self, _conn: &Connection, id: i32) -> Result<Option<ScheduleBlock>, rusqlite::Error> {
+        Ok(self.blocks.lock().unwrap().iter().find(|b| b.id == id).cloned())
+    }
+
+    fn fetch_between(&self, conn: &Connection, from: NaiveDateTime, to: NaiveDateTime) -> Result<Vec<ScheduleBlock>, rusqlite::Error> {
+        Ok(self.fetch_all(conn)?.into_iter().filter(|b| b.start < to && b.end > from).collect())
+    }
+
+    fn fetch_all(&self, _conn: &Connection) -> Result<Vec<ScheduleBlock>, rusqlite::Error> {
+        let mut blocks = self.blocks.lock().unwrap().clone();
+        blocks.sort_by_key(|b| (b.start, b.id));
+        Ok(blocks)
+    }
+
+    fn delete(&self, _conn: &Connection, id: i32) -> Result<(), rusqlite::Error> {
+        self.blocks.lock().unwrap().retain(|b| b.id != id);
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryCalendarEventRepository {
+    events: Mutex<Vec<CalendarEvent>>,
+    next_id: Mutex<i32>,
+}
+
+impl InMemoryCalendarEventRepository {
+    pub fn new() -> Self {
+        InMemoryCalendarEventRepository::default()
+    }
+}
+
+impl CalendarEventRepository for InMemoryCalendarEventRepository {
+    fn create(&self, _conn: &Connection, event: &CalendarEvent) -> Result<i32, rusqlite::Error> {
+        let mut next_id = self.next_id.lock().unwrap();
+        *next_id += 1;
+        self.events.lock().unwrap().push(CalendarEvent { id: *next_id, ..event.clone() });
+        Ok(*next_id)
+    }
+
+    fn fetch_by_id(&self, _conn: &Connection, id: i32) -> Result<Option<CalendarEvent>, rusqlite::Error> {
+        Ok(self.events.lock().unwrap().iter().find(|e| e.id == id).cloned())
+    }
+
+    fn fetch_all(&self, _conn: &Connection) -> Result<Vec<CalendarEvent>, rusqlite::Error> {
+        let mut events = self.events.lock().unwrap().clone();
+        events.sort_by_key(|e| (e.start, e.id));
+        Ok(events)
+    }
+
+    fn fetch_between(&self, conn: &Connection, from: NaiveDateTime, to: NaiveDateTime) -> Result<Vec<CalendarEvent>, rusqlite::Error> {
+        Ok(self.fetch_all(conn)?.into_iter()
+            .filter(|e| e.start < to && (e.end > from || e.start >= from || e.recurrence.is_some()))
+            .collect())
+    }
+
+    fn update(&self, _conn: &Connection, event: &CalendarEvent) -> Result<(), rusqlite::Error> {
+        // Sama seperti UPDATE di SQL: id yang tidak ada diabaikan tanpa error
+        if let Some(stored) = self.events.lock().unwrap().iter_mut().find(|e| e.id == event.id) {
+            *stored = CalendarEvent { created_at: stored.created_at, ..event.clone() };
+        }
+        Ok(())
+    }
+
+    fn delete(&self, _conn: &Connection, id: i32) -> Result<(), rusqlite::Error> {
+        self.events.lock().unwrap().retain(|e| e.id != id);
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryCalDavRepository {
+    items: Mutex<Vec<SyncedItem>>,
+    // (jenis, kalender) -> token
+    tokens: Mutex<HashMap<(SyncKind, String), Option<String>>>,
+}
+
+impl InMemoryCalDavRepository {
+    pub fn new() -> Self {
+        InMemoryCalDavRepository::default()
+    }
+}
+
+impl CalDavRepository for InMemoryCalDavRepository {
+    fn fetch_items(&self, _conn: &Connection, kind: SyncKind) -> Result<Vec<SyncedItem>, rusqlite::Error> {
+        let mut items: Vec<SyncedItem> = self.items.lock().unwrap().iter().filter(|i| i.kind == kind).cloned().collect();
+        items.sort_by_key(|i| i.local_id);
+        Ok(items)
+    }
+
+    fn save_item(&self, _conn: &Connection, item: &SyncedItem) -> Result<(), rusqlite::Error> {
+        // Sama seperti INSERT OR REPLACE: baris yang bentrok di (kind, local_id) atau href dibuang
+        let mut items = self.items.lock().unwrap();
+        items.retain(|i| !(i.kind == item.kind && i.local_id == item.local_id) && i.href != item.href);
+        items.push(item.clone());
+        Ok(())
+    }
+
+    fn delete_item(&self, _conn: &Connection, kind: SyncKind, local_id: i32) -> Result<(), rusqlite::Error> {
+        self.items.lock().unwrap().retain(|i| !(i.kind == kind && i.local_id == local_id));
+        Ok(())
+    }
+
+    fn sync_token(&self, _conn: &Connection, kind: SyncKind, calendar: &str) -> Result<Option<String>, rusqlite::Error> {
+        Ok(self.tokens.lock().unwrap().get(&(kind, calendar.to_string())).cloned().flatten())
+    }
+
+    fn save_sync_token(&self, _conn: &Connection, kind: SyncKind, calendar: &str, token: Option<&str>) -> Result<(), rusqlite::Error> {
+        self.tokens.lock().unwrap().insert((kind, calendar.to_string()), token.map(str::to_string));
+        Ok(())
+    }
+
+    fn forget_other_calendars(&self, _conn: &Connection, kind: SyncKind, keep: Option<&str>) -> Result<(), rusqlite::Error> {
+        let other = |k: SyncKind, calendar: &str| k == kind && Some(calendar) != keep;
+        self.items.lock().unwrap().retain(|i| !other(i.kind, &i.calendar));
+        self.tokens.lock().unwrap().retain(|(k, calendar), _| !other(*k, calendar));
+        Ok(())
+    }
+}
+
+fn constraint_error(column: &str) -> rusqlite::Error {
+    rusqlite::Error::SqliteFailure(
+        ffi::Error::new(ffi::SQLITE_CONSTRAINT_UNIQUE),
+        Some(format!("UNIQUE constraint failed: {}", column)),
+    )
+}
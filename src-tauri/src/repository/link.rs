@@ -0,0 +1,57 @@
+use rusqlite::{params, Connection};
+use crate::model::link::{title_key, LinkTarget};
+
+pub trait LinkRepository {
+    // Replaces every link going out of a note
+    fn replace_for_note(&self, conn: &Connection, note_id: i32, links: &[LinkTarget]) -> Result<(), rusqlite::Error>;
+    // Note targets come back in their stored, lowercased form
+    fn fetch_for_note(&self, conn: &Connection, note_id: i32) -> Result<Vec<LinkTarget>, rusqlite::Error>;
+    // Ids of the notes linking to `target`, including ones in the trash
+    fn fetch_sources(&self, conn: &Connection, target: &LinkTarget) -> Result<Vec<i32>, rusqlite::Error>;
+}
+
+pub struct SqliteLinkRepository;
+
+impl LinkRepository for SqliteLinkRepository {
+    fn replace_for_note(&self, conn: &Connection, note_id: i32, links: &[LinkTarget]) -> Result<(), rusqlite::Error> {
+        conn.execute("DELETE FROM note_links WHERE source_id = ?1", [note_id])?;
+        let mut stmt = conn.prepare(
+            "INSERT INTO note_links (source_id, target_title, target_task_id) VALUES (?1, ?2, ?3)",
+        )?;
+        for link in links {
+            match link {
+                LinkTarget::Note(title) => stmt.execute(params![note_id, title_key(title), None::<i32>])?,
+                LinkTarget::Task(task_id) => stmt.execute(params![note_id, None::<String>, task_id])?,
+            };
+        }
+        Ok(())
+    }
+
+    fn fetch_for_note(&self, conn: &Connection, note_id: i32) -> Result<Vec<LinkTarget>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT target_title, target_task_id FROM note_links WHERE source_id = ?1 ORDER BY rowid",
+        )?;
+        let link_iter = stmt.query_map([note_id], |row| {
+            let title: Option<String> = row.get(0)?;
+            let task_id: Option<i32> = row.get(1)?;
+            Ok(match (title, task_id) {
+                (_, Some(task_id)) => LinkTarget::Task(task_id),
+                (title, None) => LinkTarget::Note(title.unwrap_or_default()),
+            })
+        })?;
+        Ok(link_iter.filter_map(Result::ok).collect())
+    }
+
+    fn fetch_sources(&self, conn: &Connection, target: &LinkTarget) -> Result<Vec<i32>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT source_id FROM note_links
+            WHERE target_title = ?1 OR target_task_id = ?2 ORDER BY source_id",
+        )?;
+        let (title, task_id) = match target {
+            LinkTarget::Note(title) => (Some(title_key(title)), None),
+            LinkTarget::Task(task_id) => (None, Some(*task_id)),
+        };
+        let id_iter = stmt.query_map(params![title, task_id], |row| row.get(0))?;
+        Ok(id_iter.filter_map(Result::ok).collect())
+    }
+}
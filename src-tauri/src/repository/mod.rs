@@ -1,2 +1,13 @@
 pub mod task;
-pub mod note;
\ No newline at end of file
+pub mod note;
+pub mod note_revision;
+pub mod settings;
+pub mod task_history;
+pub mod link;
+pub mod attachment;
+pub mod note_task;
+pub mod time_entry;
+pub mod schedule_block;
+pub mod calendar_event;
+pub mod caldav;
+pub mod in_memory;
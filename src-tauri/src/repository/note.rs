@@ -1,43 +1,98 @@
-use rusqlite::{params, Connection, OptionalExtension};
+use chrono::NaiveDateTime;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use crate::model::note::{Note, NotePayload};
+use crate::model::trash::TrashedNote;
+
+fn note_from_row(row: &Row) -> Result<Note, rusqlite::Error> {
+    Ok(Note {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        file_path: row.get(2)?,
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+        missing: row.get("missing")?,
+        tags: json_column(row, "tags")?,
+        color: row.get("color")?,
+        task_ids: json_column(row, "task_ids")?,
+    })
+}
+
+// Tags and task ids are stored as JSON arrays; a malformed value reads as empty
+fn json_column<T: DeserializeOwned>(row: &Row, column: &str) -> Result<Vec<T>, rusqlite::Error> {
+    let text: String = row.get(column)?;
+    Ok(serde_json::from_str(&text).unwrap_or_default())
+}
+
+fn to_json<T: Serialize>(values: &[T]) -> String {
+    serde_json::to_string(values).unwrap_or_else(|_| "[]".to_string())
+}
 
 pub trait NoteRepository {
-    fn create(&self, conn: &Connection, payload: &NotePayload) -> Result<(), rusqlite::Error>;
+    fn create(&self, conn: &Connection, payload: &NotePayload) -> Result<i32, rusqlite::Error>;
+    fn insert_with_timestamps(&self, conn: &Connection, note: &Note) -> Result<i32, rusqlite::Error>;
+    // fetch_all and fetch_by_id only return notes that are not in the trash
     fn fetch_all(&self, conn: &Connection) -> Result<Vec<Note>, rusqlite::Error>;
+    fn fetch_by_id(&self, conn: &Connection, id: i32) -> Result<Option<Note>, rusqlite::Error>;
     fn update(&self, conn: &Connection, note: &Note) -> Result<(), rusqlite::Error>;
+    // Permanently removes the row
     fn delete(&self, conn: &Connection, id: i32) -> Result<(), rusqlite::Error>;
+    // Moves the note to the trash; `false` if it doesn't exist or is already trashed
+    fn soft_delete(&self, conn: &Connection, id: i32, deleted_at: NaiveDateTime) -> Result<bool, rusqlite::Error>;
+    // Takes the note out of the trash; `false` if it isn't in the trash
+    fn undelete(&self, conn: &Connection, id: i32) -> Result<bool, rusqlite::Error>;
+    fn fetch_deleted(&self, conn: &Connection) -> Result<Vec<TrashedNote>, rusqlite::Error>;
     fn get_file_path_by_id(&self, conn: &Connection, id: i32) -> Result<Option<String>, rusqlite::Error>;
+    // Used when syncing with the vault: stores the metadata read from the file, including
+    // its timestamps, as they are
+    fn update_metadata(&self, conn: &Connection, note: &Note) -> Result<(), rusqlite::Error>;
+    fn set_missing(&self, conn: &Connection, id: i32, missing: bool) -> Result<(), rusqlite::Error>;
 }
 
 pub struct SqliteNoteRepository;
 
 impl NoteRepository for SqliteNoteRepository {
-    fn create(&self, conn: &Connection, payload: &NotePayload) -> Result<(), rusqlite::Error> {
+    fn create(&self, conn: &Connection, payload: &NotePayload) -> Result<i32, rusqlite::Error> {
         conn.execute(
             "INSERT INTO notes (title, file_path) VALUES (?1, ?2)",
             params![payload.title, payload.file_path],
         )?;
-        Ok(())
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn insert_with_timestamps(&self, conn: &Connection, note: &Note) -> Result<i32, rusqlite::Error> {
+        conn.execute(
+            "INSERT INTO notes (title, file_path, created_at, updated_at, tags, color, task_ids)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                note.title,
+                note.file_path,
+                note.created_at,
+                note.updated_at,
+                to_json(&note.tags),
+                note.color,
+                to_json(&note.task_ids),
+            ],
+        )?;
+        Ok(conn.last_insert_rowid() as i32)
     }
 
     fn fetch_all(&self, conn: &Connection) -> Result<Vec<Note>, rusqlite::Error> {
-        let mut stmt = conn.prepare("SELECT * FROM notes")?;
-        let note_iter = stmt.query_map([], |row| {
-            Ok(Note {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                file_path: row.get(2)?,
-                created_at: row.get(3)?,
-                updated_at: row.get(4)?,
-            })
-        })?;
+        let mut stmt = conn.prepare("SELECT * FROM notes WHERE deleted_at IS NULL")?;
+        let note_iter = stmt.query_map([], note_from_row)?;
         Ok(note_iter.filter_map(Result::ok).collect())
     }
 
+    fn fetch_by_id(&self, conn: &Connection, id: i32) -> Result<Option<Note>, rusqlite::Error> {
+        conn.query_row("SELECT * FROM notes WHERE id = ?1 AND deleted_at IS NULL", [id], note_from_row).optional()
+    }
+
     fn update(&self, conn: &Connection, note: &Note) -> Result<(), rusqlite::Error> {
         conn.execute(
-            "UPDATE notes SET title = ?1, file_path = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?3",
-            params![note.title, note.file_path, note.id],
+            "UPDATE notes SET title = ?1, file_path = ?2, tags = ?3, color = ?4, task_ids = ?5,
+             updated_at = CURRENT_TIMESTAMP WHERE id = ?6 AND deleted_at IS NULL",
+            params![note.title, note.file_path, to_json(&note.tags), note.color, to_json(&note.task_ids), note.id],
         )?;
         Ok(())
     }
@@ -47,9 +102,55 @@ impl NoteRepository for SqliteNoteRepository {
         Ok(())
     }
 
+    fn soft_delete(&self, conn: &Connection, id: i32, deleted_at: NaiveDateTime) -> Result<bool, rusqlite::Error> {
+        let changed = conn.execute(
+            "UPDATE notes SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+            params![deleted_at, id],
+        )?;
+        Ok(changed > 0)
+    }
+
+    fn undelete(&self, conn: &Connection, id: i32) -> Result<bool, rusqlite::Error> {
+        let changed = conn.execute(
+            "UPDATE notes SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+            [id],
+        )?;
+        Ok(changed > 0)
+    }
+
+    fn fetch_deleted(&self, conn: &Connection) -> Result<Vec<TrashedNote>, rusqlite::Error> {
+        let mut stmt = conn.prepare("SELECT * FROM notes WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC")?;
+        let note_iter = stmt.query_map([], |row| {
+            Ok(TrashedNote { note: note_from_row(row)?, deleted_at: row.get("deleted_at")? })
+        })?;
+        Ok(note_iter.filter_map(Result::ok).collect())
+    }
+
     fn get_file_path_by_id(&self, conn: &Connection, id: i32) -> Result<Option<String>, rusqlite::Error> {
         let mut stmt = conn.prepare("SELECT file_path FROM notes WHERE id = ?1")?;
         let result = stmt.query_row([id], |row| row.get(0)).optional()?;
         Ok(result)
     }
+
+    fn update_metadata(&self, conn: &Connection, note: &Note) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "UPDATE notes SET title = ?1, tags = ?2, color = ?3, task_ids = ?4, created_at = ?5, updated_at = ?6
+             WHERE id = ?7",
+            params![
+                note.title,
+                to_json(&note.tags),
+                note.color,
+                to_json(&note.task_ids),
+                note.created_at,
+                note.updated_at,
+                note.id,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn set_missing(&self, conn: &Connection, id: i32, missing: bool) -> Result<(), rusqlite::Error> {
+        conn.execute("UPDATE notes SET missing = ?1 WHERE id = ?2", params![missing, id])?;
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -0,0 +1,75 @@
+use chrono::NaiveDateTime;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use crate::model::note_revision::NoteRevision;
+
+fn revision_from_row(row: &Row) -> Result<NoteRevision, rusqlite::Error> {
+    Ok(NoteRevision {
+        id: row.get(0)?,
+        note_id: row.get(1)?,
+        content_hash: row.get(2)?,
+        size: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+pub trait NoteRevisionRepository {
+    // `data` is the compressed content; the id field of `revision` is ignored
+    fn create(&self, conn: &Connection, revision: &NoteRevision, data: &[u8]) -> Result<i32, rusqlite::Error>;
+    fn fetch_latest(&self, conn: &Connection, note_id: i32) -> Result<Option<NoteRevision>, rusqlite::Error>;
+    // Newest first
+    fn fetch_for_note(&self, conn: &Connection, note_id: i32) -> Result<Vec<NoteRevision>, rusqlite::Error>;
+    fn fetch_data(&self, conn: &Connection, note_id: i32, id: i32) -> Result<Option<Vec<u8>>, rusqlite::Error>;
+    // Keeps the newest `keep` revisions of a note and drops anything created before `cutoff`
+    fn prune(&self, conn: &Connection, note_id: i32, keep: usize, cutoff: NaiveDateTime) -> Result<usize, rusqlite::Error>;
+}
+
+pub struct SqliteNoteRevisionRepository;
+
+impl NoteRevisionRepository for SqliteNoteRevisionRepository {
+    fn create(&self, conn: &Connection, revision: &NoteRevision, data: &[u8]) -> Result<i32, rusqlite::Error> {
+        conn.execute(
+            "INSERT INTO note_revisions (note_id, content_hash, size, created_at, data)
+            VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![revision.note_id, revision.content_hash, revision.size, revision.created_at, data],
+        )?;
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn fetch_latest(&self, conn: &Connection, note_id: i32) -> Result<Option<NoteRevision>, rusqlite::Error> {
+        conn.query_row(
+            "SELECT id, note_id, content_hash, size, created_at FROM note_revisions
+            WHERE note_id = ?1 ORDER BY created_at DESC, id DESC LIMIT 1",
+            [note_id],
+            revision_from_row,
+        ).optional()
+    }
+
+    fn fetch_for_note(&self, conn: &Connection, note_id: i32) -> Result<Vec<NoteRevision>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT id, note_id, content_hash, size, created_at FROM note_revisions
+            WHERE note_id = ?1 ORDER BY created_at DESC, id DESC",
+        )?;
+        let revision_iter = stmt.query_map([note_id], revision_from_row)?;
+        Ok(revision_iter.filter_map(Result::ok).collect())
+    }
+
+    fn fetch_data(&self, conn: &Connection, note_id: i32, id: i32) -> Result<Option<Vec<u8>>, rusqlite::Error> {
+        conn.query_row(
+            "SELECT data FROM note_revisions WHERE id = ?1 AND note_id = ?2",
+            [id, note_id],
+            |row| row.get(0),
+        ).optional()
+    }
+
+    fn prune(&self, conn: &Connection, note_id: i32, keep: usize, cutoff: NaiveDateTime) -> Result<usize, rusqlite::Error> {
+        conn.execute(
+            "DELETE FROM note_revisions WHERE note_id = ?1 AND (
+                created_at < ?2 OR id NOT IN (
+                    SELECT id FROM note_revisions WHERE note_id = ?1
+                    ORDER BY created_at DESC, id DESC LIMIT ?3
+                )
+            )",
+            params![note_id, cutoff, keep as i64],
+        )
+    }
+}
@@ -0,0 +1,51 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use crate::model::note_task::NoteTask;
+
+fn note_task_from_row(row: &Row) -> Result<NoteTask, rusqlite::Error> {
+    Ok(NoteTask {
+        task_id: row.get(0)?,
+        note_id: row.get(1)?,
+        line: row.get::<_, i64>(2)? as usize,
+        text: row.get(3)?,
+    })
+}
+
+pub trait NoteTaskRepository {
+    // Replaces every task linked to a note's checkboxes
+    fn replace_for_note(&self, conn: &Connection, note_id: i32, links: &[NoteTask]) -> Result<(), rusqlite::Error>;
+    // Ordered by line
+    fn fetch_for_note(&self, conn: &Connection, note_id: i32) -> Result<Vec<NoteTask>, rusqlite::Error>;
+    fn fetch_for_task(&self, conn: &Connection, task_id: i32) -> Result<Option<NoteTask>, rusqlite::Error>;
+}
+
+pub struct SqliteNoteTaskRepository;
+
+impl NoteTaskRepository for SqliteNoteTaskRepository {
+    fn replace_for_note(&self, conn: &Connection, note_id: i32, links: &[NoteTask]) -> Result<(), rusqlite::Error> {
+        conn.execute("DELETE FROM note_tasks WHERE note_id = ?1", [note_id])?;
+        let mut stmt = conn.prepare(
+            "INSERT OR REPLACE INTO note_tasks (task_id, note_id, line, text) VALUES (?1, ?2, ?3, ?4)",
+        )?;
+        for link in links {
+            stmt.execute(params![link.task_id, note_id, link.line as i64, link.text])?;
+        }
+        Ok(())
+    }
+
+    fn fetch_for_note(&self, conn: &Connection, note_id: i32) -> Result<Vec<NoteTask>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT task_id, note_id, line, text FROM note_tasks WHERE note_id = ?1 ORDER BY line, task_id",
+        )?;
+        let link_iter = stmt.query_map([note_id], note_task_from_row)?;
+        Ok(link_iter.filter_map(Result::ok).collect())
+    }
+
+    fn fetch_for_task(&self, conn: &Connection, task_id: i32) -> Result<Option<NoteTask>, rusqlite::Error> {
+        conn.query_row(
+            "SELECT task_id, note_id, line, text FROM note_tasks WHERE task_id = ?1",
+            [task_id],
+            note_task_from_row,
+        )
+        .optional()
+    }
+}
@@ -0,0 +1,67 @@
+use chrono::NaiveDateTime;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use crate::model::planner::ScheduleBlock;
+
+const COLUMNS: &str = "id, task_id, title, start_at, end_at";
+
+fn schedule_block_from_row(row: &Row) -> Result<ScheduleBlock, rusqlite::Error> {
+    Ok(ScheduleBlock {
+        id: row.get(0)?,
+        task_id: row.get(1)?,
+        title: row.get(2)?,
+        start: row.get(3)?,
+        end: row.get(4)?,
+    })
+}
+
+pub trait ScheduleBlockRepository {
+    // Field id dari `block` diabaikan
+    fn create(&self, conn: &Connection, block: &ScheduleBlock) -> Result<i32, rusqlite::Error>;
+    fn fetch_by_id(&self, conn: &Connection, id: i32) -> Result<Option<ScheduleBlock>, rusqlite::Error>;
+    // Blok yang bersinggungan dengan `from`..`to`, urut dari yang paling awal
+    fn fetch_between(&self, conn: &Connection, from: NaiveDateTime, to: NaiveDateTime) -> Result<Vec<ScheduleBlock>, rusqlite::Error>;
+    // Urut dari yang paling awal
+    fn fetch_all(&self, conn: &Connection) -> Result<Vec<ScheduleBlock>, rusqlite::Error>;
+    fn delete(&self, conn: &Connection, id: i32) -> Result<(), rusqlite::Error>;
+}
+
+pub struct SqliteScheduleBlockRepository;
+
+impl ScheduleBlockRepository for SqliteScheduleBlockRepository {
+    fn create(&self, conn: &Connection, block: &ScheduleBlock) -> Result<i32, rusqlite::Error> {
+        conn.execute(
+            "INSERT INTO schedule_blocks (task_id, title, start_at, end_at) VALUES (?1, ?2, ?3, ?4)",
+            params![block.task_id, block.title, block.start, block.end],
+        )?;
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn fetch_by_id(&self, conn: &Connection, id: i32) -> Result<Option<ScheduleBlock>, rusqlite::Error> {
+        conn.query_row(
+            &format!("SELECT {} FROM schedule_blocks WHERE id = ?1", COLUMNS),
+            [id],
+            schedule_block_from_row,
+        )
+        .optional()
+    }
+
+    fn fetch_between(&self, conn: &Connection, from: NaiveDateTime, to: NaiveDateTime) -> Result<Vec<ScheduleBlock>, rusqlite::Error> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM schedule_blocks WHERE start_at < ?2 AND end_at > ?1 ORDER BY start_at, id",
+            COLUMNS
+        ))?;
+        let block_iter = stmt.query_map(params![from, to], schedule_block_from_row)?;
+        Ok(block_iter.filter_map(Result::ok).collect())
+    }
+
+    fn fetch_all(&self, conn: &Connection) -> Result<Vec<ScheduleBlock>, rusqlite::Error> {
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM schedule_blocks ORDER BY start_at, id", COLUMNS))?;
+        let block_iter = stmt.query_map([], schedule_block_from_row)?;
+        Ok(block_iter.filter_map(Result::ok).collect())
+    }
+
+    fn delete(&self, conn: &Connection, id: i32) -> Result<(), rusqlite::Error> {
+        conn.execute("DELETE FROM schedule_blocks WHERE id = ?1", [id])?;
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use rusqlite::{params, Connection, OptionalExtension};
+
+// Penyimpanan key-value sederhana untuk pengaturan aplikasi (nilai disimpan sebagai JSON)
+pub trait SettingsRepository {
+    fn get(&self, conn: &Connection, key: &str) -> Result<Option<String>, rusqlite::Error>;
+    fn set(&self, conn: &Connection, key: &str, value: &str) -> Result<(), rusqlite::Error>;
+}
+
+pub struct SqliteSettingsRepository;
+
+impl SettingsRepository for SqliteSettingsRepository {
+    fn get(&self, conn: &Connection, key: &str) -> Result<Option<String>, rusqlite::Error> {
+        conn.query_row("SELECT value FROM settings WHERE key = ?1", [key], |row| row.get(0))
+            .optional()
+    }
+
+    fn set(&self, conn: &Connection, key: &str, value: &str) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+            ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+}
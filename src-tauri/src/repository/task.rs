@@ -1,20 +1,48 @@
-use rusqlite::{params, Connection};
-use crate::model::task::{Task, Category, Priority};
+use chrono::NaiveDateTime;
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use crate::model::task::Task;
+use crate::model::trash::TrashedTask;
+
+// Kategori atau prioritas yang tidak dikenal dilaporkan sebagai error, bukan diganti default
+fn task_from_row(row: &Row) -> Result<Task, rusqlite::Error> {
+    let category_str: String = row.get(2)?;
+    let priority_str: String = row.get(3)?;
+    Ok(Task {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        category: category_str.parse().map_err(|e: String| rusqlite::Error::FromSqlConversionFailure(2, Type::Text, e.into()))?,
+        priority: priority_str.parse().map_err(|e: String| rusqlite::Error::FromSqlConversionFailure(3, Type::Text, e.into()))?,
+        deadline: row.get(4)?,
+        completed: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+        estimated_minutes: row.get("estimated_minutes")?,
+    })
+}
 
 pub trait TaskRepository {
-    fn create(&self, conn: &Connection, task: &Task) -> Result<(), rusqlite::Error>;
+    fn create(&self, conn: &Connection, task: &Task) -> Result<i32, rusqlite::Error>;
+    // fetch_all dan fetch_by_id hanya mengembalikan task yang tidak ada di trash
     fn fetch_all(&self, conn: &Connection) -> Result<Vec<Task>, rusqlite::Error>;
+    fn fetch_by_id(&self, conn: &Connection, id: i32) -> Result<Option<Task>, rusqlite::Error>;
     fn update(&self, conn: &Connection, task: &Task) -> Result<(), rusqlite::Error>;
+    // Hapus permanen
     fn delete(&self, conn: &Connection, id: i32) -> Result<(), rusqlite::Error>;
+    // Pindahkan ke trash; `false` jika task tidak ada atau sudah di trash
+    fn soft_delete(&self, conn: &Connection, id: i32, deleted_at: NaiveDateTime) -> Result<bool, rusqlite::Error>;
+    // Keluarkan dari trash; `false` jika task tidak ada di trash
+    fn undelete(&self, conn: &Connection, id: i32) -> Result<bool, rusqlite::Error>;
+    fn fetch_deleted(&self, conn: &Connection) -> Result<Vec<TrashedTask>, rusqlite::Error>;
 }
 
 pub struct SqliteTaskRepository;
 
 impl TaskRepository for SqliteTaskRepository {
-    fn create(&self, conn: &Connection, task: &Task) -> Result<(), rusqlite::Error> {
+    fn create(&self, conn: &Connection, task: &Task) -> Result<i32, rusqlite::Error> {
         conn.execute(
-            "INSERT INTO tasks (title, category, priority, deadline, completed, created_at, updated_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO tasks (title, category, priority, deadline, completed, created_at, updated_at, estimated_minutes)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
             params![
                 task.title,
                 task.category.to_string(),
@@ -23,32 +51,26 @@ impl TaskRepository for SqliteTaskRepository {
                 task.completed,
                 task.created_at,
                 task.updated_at,
+                task.estimated_minutes,
             ],
         )?;
-        Ok(())
+        Ok(conn.last_insert_rowid() as i32)
     }
 
     fn fetch_all(&self, conn: &Connection) -> Result<Vec<Task>, rusqlite::Error> {
-        let mut stmt = conn.prepare("SELECT * FROM tasks")?;
-        let task_iter = stmt.query_map([], |row| {
-            let category_str: String = row.get(2)?;
-            let priority_str: String = row.get(3)?;
-            Ok(Task {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                category: category_str.parse().unwrap_or(Category::Personal),
-                priority: priority_str.parse().unwrap_or(Priority::Low),
-                deadline: row.get(4)?,
-                completed: row.get(5)?,
-                created_at: row.get(6)?,
-                updated_at: row.get(7)?,
-            })
-        })?;
-        Ok(task_iter.filter_map(Result::ok).collect())
+        let mut stmt = conn.prepare("SELECT * FROM tasks WHERE deleted_at IS NULL")?;
+        let task_iter = stmt.query_map([], task_from_row)?;
+        task_iter.collect()
     }
+
+    fn fetch_by_id(&self, conn: &Connection, id: i32) -> Result<Option<Task>, rusqlite::Error> {
+        conn.query_row("SELECT * FROM tasks WHERE id = ?1 AND deleted_at IS NULL", [id], task_from_row).optional()
+    }
+
     fn update(&self, conn: &Connection, task: &Task) -> Result<(), rusqlite::Error> {
         conn.execute(
-            "UPDATE tasks SET title = ?1, category = ?2, priority = ?3, deadline = ?4, completed = ?5, updated_at = ?6 WHERE id = ?7",
+            "UPDATE tasks SET title = ?1, category = ?2, priority = ?3, deadline = ?4, completed = ?5, updated_at = ?6,
+            estimated_minutes = ?7 WHERE id = ?8 AND deleted_at IS NULL",
             params![
                 task.title,
                 task.category.to_string(),
@@ -56,6 +78,7 @@ impl TaskRepository for SqliteTaskRepository {
                 task.deadline,
                 task.completed,
                 task.updated_at,
+                task.estimated_minutes,
                 task.id,
             ],
         )?;
@@ -64,5 +87,29 @@ impl TaskRepository for SqliteTaskRepository {
     fn delete(&self, conn: &Connection, id: i32) -> Result<(), rusqlite::Error> {
         conn.execute("DELETE FROM tasks WHERE id = ?1", [id])?;
         Ok(())
-    }    
+    }
+
+    fn soft_delete(&self, conn: &Connection, id: i32, deleted_at: NaiveDateTime) -> Result<bool, rusqlite::Error> {
+        let changed = conn.execute(
+            "UPDATE tasks SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+            params![deleted_at, id],
+        )?;
+        Ok(changed > 0)
+    }
+
+    fn undelete(&self, conn: &Connection, id: i32) -> Result<bool, rusqlite::Error> {
+        let changed = conn.execute(
+            "UPDATE tasks SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+            [id],
+        )?;
+        Ok(changed > 0)
+    }
+
+    fn fetch_deleted(&self, conn: &Connection) -> Result<Vec<TrashedTask>, rusqlite::Error> {
+        let mut stmt = conn.prepare("SELECT * FROM tasks WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC")?;
+        let task_iter = stmt.query_map([], |row| {
+            Ok(TrashedTask { task: task_from_row(row)?, deleted_at: row.get("deleted_at")? })
+        })?;
+        task_iter.collect()
+    }
 }
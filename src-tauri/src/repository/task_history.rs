@@ -0,0 +1,58 @@
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, Row};
+use crate::model::task_csv::TaskColumn;
+use crate::model::task_history::TaskChange;
+
+pub trait TaskHistoryRepository {
+    fn record(&self, conn: &Connection, change: &TaskChange) -> Result<i32, rusqlite::Error>;
+    // Riwayat satu task, urut dari perubahan terlama
+    fn fetch_for_task(&self, conn: &Connection, task_id: i32) -> Result<Vec<TaskChange>, rusqlite::Error>;
+}
+
+fn change_from_row(row: &Row) -> Result<TaskChange, rusqlite::Error> {
+    let kind: String = row.get(2)?;
+    let field: Option<String> = row.get(3)?;
+    // Kolom tidak dikenal hanya mungkin jika database diubah manual; jangan ditebak
+    let field = field
+        .map(|field| TaskColumn::from_header(&field).ok_or_else(|| format!("Unknown task field '{}'", field)))
+        .transpose()
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(3, Type::Text, e.into()))?;
+    Ok(TaskChange {
+        id: row.get(0)?,
+        task_id: row.get(1)?,
+        kind: kind.parse().map_err(|e: String| rusqlite::Error::FromSqlConversionFailure(2, Type::Text, e.into()))?,
+        field,
+        old_value: row.get(4)?,
+        new_value: row.get(5)?,
+        changed_at: row.get(6)?,
+    })
+}
+
+pub struct SqliteTaskHistoryRepository;
+
+impl TaskHistoryRepository for SqliteTaskHistoryRepository {
+    fn record(&self, conn: &Connection, change: &TaskChange) -> Result<i32, rusqlite::Error> {
+        conn.execute(
+            "INSERT INTO task_history (task_id, kind, field, old_value, new_value, changed_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                change.task_id,
+                change.kind.as_str(),
+                change.field.map(|field| field.header()),
+                change.old_value,
+                change.new_value,
+                change.changed_at,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn fetch_for_task(&self, conn: &Connection, task_id: i32) -> Result<Vec<TaskChange>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT id, task_id, kind, field, old_value, new_value, changed_at FROM task_history
+            WHERE task_id = ?1 ORDER BY changed_at, id",
+        )?;
+        let change_iter = stmt.query_map([task_id], change_from_row)?;
+        change_iter.collect()
+    }
+}
@@ -0,0 +1,105 @@
+use chrono::NaiveDateTime;
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use crate::model::time_entry::TimeEntry;
+
+const COLUMNS: &str = "id, task_id, source, started_at, ended_at, minutes, note";
+
+fn time_entry_from_row(row: &Row) -> Result<TimeEntry, rusqlite::Error> {
+    let source: String = row.get(2)?;
+    Ok(TimeEntry {
+        id: row.get(0)?,
+        task_id: row.get(1)?,
+        source: source.parse().map_err(|e: String| rusqlite::Error::FromSqlConversionFailure(2, Type::Text, e.into()))?,
+        started_at: row.get(3)?,
+        ended_at: row.get(4)?,
+        minutes: row.get(5)?,
+        note: row.get(6)?,
+    })
+}
+
+pub trait TimeEntryRepository {
+    // Field id dari `entry` diabaikan
+    fn create(&self, conn: &Connection, entry: &TimeEntry) -> Result<i32, rusqlite::Error>;
+    fn fetch_by_id(&self, conn: &Connection, id: i32) -> Result<Option<TimeEntry>, rusqlite::Error>;
+    // Urut dari yang paling awal dimulai
+    fn fetch_for_task(&self, conn: &Connection, task_id: i32) -> Result<Vec<TimeEntry>, rusqlite::Error>;
+    // Catatan yang sudah selesai dan dimulai di antara `from` (inklusif) dan `to` (eksklusif)
+    fn fetch_between(&self, conn: &Connection, from: NaiveDateTime, to: NaiveDateTime) -> Result<Vec<TimeEntry>, rusqlite::Error>;
+    // Timer yang belum dihentikan, jika ada
+    fn fetch_running(&self, conn: &Connection) -> Result<Option<TimeEntry>, rusqlite::Error>;
+    // Simpan waktu selesai dan durasi
+    fn finish(&self, conn: &Connection, id: i32, ended_at: NaiveDateTime, minutes: i32) -> Result<(), rusqlite::Error>;
+    fn delete(&self, conn: &Connection, id: i32) -> Result<(), rusqlite::Error>;
+}
+
+pub struct SqliteTimeEntryRepository;
+
+impl TimeEntryRepository for SqliteTimeEntryRepository {
+    fn create(&self, conn: &Connection, entry: &TimeEntry) -> Result<i32, rusqlite::Error> {
+        conn.execute(
+            "INSERT INTO time_entries (task_id, source, started_at, ended_at, minutes, note)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                entry.task_id,
+                entry.source.as_str(),
+                entry.started_at,
+                entry.ended_at,
+                entry.minutes,
+                entry.note,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn fetch_by_id(&self, conn: &Connection, id: i32) -> Result<Option<TimeEntry>, rusqlite::Error> {
+        conn.query_row(
+            &format!("SELECT {} FROM time_entries WHERE id = ?1", COLUMNS),
+            [id],
+            time_entry_from_row,
+        )
+        .optional()
+    }
+
+    fn fetch_for_task(&self, conn: &Connection, task_id: i32) -> Result<Vec<TimeEntry>, rusqlite::Error> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM time_entries WHERE task_id = ?1 ORDER BY started_at, id",
+            COLUMNS
+        ))?;
+        let entry_iter = stmt.query_map([task_id], time_entry_from_row)?;
+        Ok(entry_iter.filter_map(Result::ok).collect())
+    }
+
+    fn fetch_between(&self, conn: &Connection, from: NaiveDateTime, to: NaiveDateTime) -> Result<Vec<TimeEntry>, rusqlite::Error> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM time_entries
+            WHERE ended_at IS NOT NULL AND started_at >= ?1 AND started_at < ?2
+            ORDER BY started_at, id",
+            COLUMNS
+        ))?;
+        let entry_iter = stmt.query_map(params![from, to], time_entry_from_row)?;
+        Ok(entry_iter.filter_map(Result::ok).collect())
+    }
+
+    fn fetch_running(&self, conn: &Connection) -> Result<Option<TimeEntry>, rusqlite::Error> {
+        conn.query_row(
+            &format!("SELECT {} FROM time_entries WHERE ended_at IS NULL ORDER BY id LIMIT 1", COLUMNS),
+            [],
+            time_entry_from_row,
+        )
+        .optional()
+    }
+
+    fn finish(&self, conn: &Connection, id: i32, ended_at: NaiveDateTime, minutes: i32) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "UPDATE time_entries SET ended_at = ?1, minutes = ?2 WHERE id = ?3",
+            params![ended_at, minutes, id],
+        )?;
+        Ok(())
+    }
+
+    fn delete(&self, conn: &Connection, id: i32) -> Result<(), rusqlite::Error> {
+        conn.execute("DELETE FROM time_entries WHERE id = ?1", [id])?;
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+use crate::events::{DomainEvent, EventBus};
+use crate::fs::FileSystem;
+use crate::journal::Journal;
+use crate::model::archive::{
+    ArchiveManifest, ArchivedNote, ExportSummary, IdMapping, ImportSummary, ARCHIVE_SCHEMA_VERSION,
+};
+use crate::model::note::Note;
+use crate::model::task::Task;
+use crate::repository::note::NoteRepository;
+use crate::repository::task::TaskRepository;
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+pub trait ArchiveService {
+    fn export_archive(&self, conn: &Connection, dest: &Path) -> Result<ExportSummary, String>;
+    fn import_archive(&self, conn: &Connection, src: &Path, vault_dir: &Path) -> Result<ImportSummary, String>;
+}
+
+pub struct ArchiveServiceImpl<T: TaskRepository, N: NoteRepository, F: FileSystem> {
+    pub task_repository: T,
+    pub note_repository: N,
+    pub fs: F,
+    // Task dan note hasil impor dipublikasikan seperti item baru lainnya, supaya view yang terbuka ikut diperbarui
+    pub events: EventBus,
+    // Dikosongkan setelah impor, karena impor sendiri tidak bisa di-undo
+    pub journal: Journal,
+}
+
+impl<T: TaskRepository, N: NoteRepository, F: FileSystem> ArchiveService for ArchiveServiceImpl<T, N, F> {
+    fn export_archive(&self, conn: &Connection, dest: &Path) -> Result<ExportSummary, String> {
+        let tasks = self.task_repository.fetch_all(conn).map_err(|e| e.to_string())?;
+        let notes = self.note_repository.fetch_all(conn).map_err(|e| e.to_string())?;
+
+        let file = File::create(dest).map_err(|e| format!("Failed to create archive: {}", e))?;
+        let mut zip = ZipWriter::new(file);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        let mut archived_notes = Vec::with_capacity(notes.len());
+        for note in &notes {
+            // Note yang file markdown-nya hilang tetap diekspor dengan isi kosong
+            let body = self.fs.read(Path::new(&note.file_path)).unwrap_or_default();
+            let body_file = format!("notes/{}.md", note.id);
+            zip.start_file(body_file.as_str(), options)
+                .and_then(|_| zip.write_all(&body).map_err(Into::into))
+                .map_err(|e| format!("Failed to write note body to archive: {}", e))?;
+            archived_notes.push(ArchivedNote {
+                id: note.id,
+                title: note.title.clone(),
+                body_file,
+                created_at: note.created_at,
+                updated_at: note.updated_at,
+            });
+        }
+
+        let manifest = ArchiveManifest {
+            schema_version: ARCHIVE_SCHEMA_VERSION,
+            exported_at: Utc::now().naive_utc(),
+            tasks,
+            notes: archived_notes,
+        };
+        let json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+        zip.start_file(MANIFEST_FILE, options)
+            .and_then(|_| zip.write_all(&json).map_err(Into::into))
+            .map_err(|e| format!("Failed to write archive manifest: {}", e))?;
+        zip.finish().map_err(|e| format!("Failed to finish archive: {}", e))?;
+
+        Ok(ExportSummary {
+            tasks_exported: manifest.tasks.len(),
+            notes_exported: manifest.notes.len(),
+        })
+    }
+
+    fn import_archive(&self, conn: &Connection, src: &Path, vault_dir: &Path) -> Result<ImportSummary, String> {
+        let file = File::open(src).map_err(|e| format!("Failed to open archive: {}", e))?;
+        let mut zip = ZipArchive::new(file).map_err(|e| format!("Invalid archive: {}", e))?;
+
+        let manifest: ArchiveManifest = {
+            let mut entry = zip.by_name(MANIFEST_FILE)
+                .map_err(|_| "Archive has no manifest".to_string())?;
+            let mut json = String::new();
+            entry.read_to_string(&mut json).map_err(|e| e.to_string())?;
+            serde_json::from_str(&json).map_err(|e| format!("Invalid archive manifest: {}", e))?
+        };
+        if manifest.schema_version != ARCHIVE_SCHEMA_VERSION {
+            return Err(format!(
+                "Unsupported archive version {} (expected {})",
+                manifest.schema_version, ARCHIVE_SCHEMA_VERSION
+            ));
+        }
+
+        // Semua isi dibaca lebih dulu, supaya arsip yang terpotong gagal sebelum ada yang ditulis
+        let mut bodies = Vec::with_capacity(manifest.notes.len());
+        for note in &manifest.notes {
+            let mut entry = zip.by_name(&note.body_file)
+                .map_err(|_| format!("Archive is missing {}", note.body_file))?;
+            let mut body = Vec::new();
+            entry.read_to_end(&mut body).map_err(|e| e.to_string())?;
+            bodies.push(body);
+        }
+
+        self.fs.create_dir_all(vault_dir)
+            .map_err(|e| format!("Failed to create notes folder: {}", e))?;
+
+        let mut written: Vec<PathBuf> = Vec::new();
+        let result = self.restore(conn, &manifest, &bodies, vault_dir, &mut written);
+        if result.is_err() {
+            for path in &written {
+                self.fs.remove_file(path).ok();
+            }
+        }
+        result
+    }
+}
+
+impl<T: TaskRepository, N: NoteRepository, F: FileSystem> ArchiveServiceImpl<T, N, F> {
+    fn restore(
+        &self,
+        conn: &Connection,
+        manifest: &ArchiveManifest,
+        bodies: &[Vec<u8>],
+        vault_dir: &Path,
+        written: &mut Vec<PathBuf>,
+    ) -> Result<ImportSummary, String> {
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        let mut summary = ImportSummary { tasks: Vec::new(), notes: Vec::new() };
+        let mut created = Vec::new();
+
+        for task in &manifest.tasks {
+            let new_id = self.task_repository.create(&tx, task).map_err(|e| e.to_string())?;
+            summary.tasks.push(IdMapping { old_id: task.id, new_id });
+            created.push(DomainEvent::TaskCreated(Task { id: new_id, ..task.clone() }));
+        }
+
+        for (archived, body) in manifest.notes.iter().zip(bodies) {
+            let path = unique_note_path(&self.fs, vault_dir, &archived.title);
+            self.fs.write(&path, body)
+                .map_err(|e| format!("Failed to write markdown file: {}", e))?;
+            written.push(path.clone());
+
+            let note = Note {
+                id: 0,
+                title: archived.title.clone(),
+                file_path: path.to_string_lossy().into_owned(),
+                created_at: archived.created_at,
+                updated_at: archived.updated_at,
+                missing: false,
+                tags: Vec::new(),
+                color: None,
+                task_ids: Vec::new(),
+            };
+            let new_id = self.note_repository.insert_with_timestamps(&tx, &note).map_err(|e| e.to_string())?;
+            summary.notes.push(IdMapping { old_id: archived.id, new_id });
+            created.push(DomainEvent::NoteCreated(Note { id: new_id, ..note }));
+        }
+
+        tx.commit().map_err(|e| e.to_string())?;
+        self.journal.clear();
+        for event in created {
+            self.events.publish(event);
+        }
+        self.events.publish(DomainEvent::DataReloaded);
+        Ok(summary)
+    }
+}
+
+// Nama file di vault dari judul note yang tidak bentrok dengan file yang sudah ada
+pub fn unique_note_path(fs: &impl FileSystem, vault_dir: &Path, title: &str) -> PathBuf {
+    let slug: String = title
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let stem = if slug.trim_matches('_').is_empty() { "note".to_string() } else { slug };
+
+    let mut candidate = vault_dir.join(format!("{}.md", stem));
+    let mut counter = 2;
+    while fs.exists(&candidate) {
+        candidate = vault_dir.join(format!("{}-{}.md", stem, counter));
+        counter += 1;
+    }
+    candidate
+}
@@ -0,0 +1,212 @@
+use chrono::{Duration, Utc};
+use rusqlite::Connection;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use crate::fs::FileSystem;
+use crate::model::attachment::{Attachment, AttachmentGcSummary, AttachmentOwner};
+use crate::repository::attachment::AttachmentRepository;
+use crate::repository::note::NoteRepository;
+use crate::repository::task::TaskRepository;
+
+// File yang baru ditulis belum tentu sudah punya baris (add_attachment menulis file dulu),
+// jadi file semuda ini tidak disentuh garbage collector
+const GC_GRACE_MINUTES: i64 = 10;
+
+pub trait AttachmentService {
+    // Salin file `source` ke folder lampiran lalu lampirkan ke task/note. Isi yang sama hanya
+    // disimpan sekali; melampirkannya lagi ke pemilik yang sama mengembalikan lampiran yang ada.
+    fn add_attachment(&self, conn: &Connection, owner: AttachmentOwner, owner_id: i32, source: &Path) -> Result<Attachment, String>;
+    fn list_attachments(&self, conn: &Connection, owner: AttachmentOwner, owner_id: i32) -> Result<Vec<Attachment>, String>;
+    // Metadata dan isi file sebuah lampiran
+    fn read_attachment(&self, conn: &Connection, id: i32) -> Result<(Attachment, Vec<u8>), String>;
+    // Hapus lampiran; filenya ikut dihapus jika tidak dipakai lampiran lain
+    fn remove_attachment(&self, conn: &Connection, id: i32) -> Result<(), String>;
+    // Hapus lampiran yang task/note-nya sudah dihapus permanen dan file yang tidak dipakai lagi
+    fn collect_garbage(&self, conn: &Connection) -> Result<AttachmentGcSummary, String>;
+}
+
+pub struct AttachmentServiceImpl<A, T, N, F>
+where
+    A: AttachmentRepository,
+    T: TaskRepository,
+    N: NoteRepository,
+    F: FileSystem,
+{
+    pub repository: A,
+    pub task_repository: T,
+    pub note_repository: N,
+    pub fs: F,
+    // Folder `.attachments` di vault; file disimpan di `<2 huruf awal hash>/<hash>`
+    pub attachments_dir: PathBuf,
+}
+
+impl<A, T, N, F> AttachmentService for AttachmentServiceImpl<A, T, N, F>
+where
+    A: AttachmentRepository,
+    T: TaskRepository,
+    N: NoteRepository,
+    F: FileSystem,
+{
+    fn add_attachment(&self, conn: &Connection, owner: AttachmentOwner, owner_id: i32, source: &Path) -> Result<Attachment, String> {
+        self.check_owner(conn, owner, owner_id)?;
+        let file_name = source.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or_else(|| format!("Invalid file path: {}", source.display()))?;
+        let data = self.fs.read(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+        let content_hash = format!("{:x}", Sha256::digest(&data));
+
+        // File disimpan sebelum barisnya supaya lampiran tidak pernah menunjuk file yang belum ada
+        let path = self.blob_path(&content_hash);
+        if !self.fs.exists(&path) {
+            if let Some(parent) = path.parent() {
+                self.fs.create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create attachments folder: {}", e))?;
+            }
+            self.fs.write(&path, &data).map_err(|e| format!("Failed to store attachment: {}", e))?;
+        }
+
+        if let Some(existing) = self.repository.find(conn, owner, owner_id, &content_hash).map_err(|e| e.to_string())? {
+            return Ok(existing);
+        }
+        let attachment = Attachment {
+            id: 0,
+            owner,
+            owner_id,
+            file_name,
+            content_hash,
+            size: data.len() as i64,
+            mime_type: mime_guess::from_path(source).first_or_octet_stream().essence_str().to_string(),
+            created_at: Utc::now().naive_utc(),
+        };
+        let id = self.repository.create(conn, &attachment).map_err(|e| e.to_string())?;
+        Ok(Attachment { id, ..attachment })
+    }
+
+    fn list_attachments(&self, conn: &Connection, owner: AttachmentOwner, owner_id: i32) -> Result<Vec<Attachment>, String> {
+        self.repository.fetch_for_owner(conn, owner, owner_id).map_err(|e| e.to_string())
+    }
+
+    fn read_attachment(&self, conn: &Connection, id: i32) -> Result<(Attachment, Vec<u8>), String> {
+        let attachment = self.fetch_attachment(conn, id)?;
+        let data = self.fs.read(&self.blob_path(&attachment.content_hash))
+            .map_err(|e| format!("Failed to read attachment {}: {}", id, e))?;
+        Ok((attachment, data))
+    }
+
+    fn remove_attachment(&self, conn: &Connection, id: i32) -> Result<(), String> {
+        let attachment = self.fetch_attachment(conn, id)?;
+        self.repository.delete(conn, id).map_err(|e| e.to_string())?;
+        let still_used = self.repository.count_with_hash(conn, &attachment.content_hash).map_err(|e| e.to_string())?;
+        if still_used == 0 {
+            // File yang gagal dihapus akan dibersihkan garbage collector
+            let _ = self.fs.remove_file(&self.blob_path(&attachment.content_hash));
+        }
+        Ok(())
+    }
+
+    fn collect_garbage(&self, conn: &Connection) -> Result<AttachmentGcSummary, String> {
+        let mut summary = AttachmentGcSummary::default();
+
+        // Task/note di trash masih bisa di-restore, jadi lampirannya dipertahankan
+        let mut tasks: HashSet<i32> = self.task_repository.fetch_all(conn).map_err(|e| e.to_string())?
+            .into_iter().map(|task| task.id).collect();
+        tasks.extend(self.task_repository.fetch_deleted(conn).map_err(|e| e.to_string())?
+            .into_iter().map(|trashed| trashed.task.id));
+        let mut notes: HashSet<i32> = self.note_repository.fetch_all(conn).map_err(|e| e.to_string())?
+            .into_iter().map(|note| note.id).collect();
+        notes.extend(self.note_repository.fetch_deleted(conn).map_err(|e| e.to_string())?
+            .into_iter().map(|trashed| trashed.note.id));
+
+        let mut used = HashSet::new();
+        for attachment in self.repository.fetch_all(conn).map_err(|e| e.to_string())? {
+            let owners = match attachment.owner {
+                AttachmentOwner::Task => &tasks,
+                AttachmentOwner::Note => &notes,
+            };
+            if owners.contains(&attachment.owner_id) {
+                used.insert(attachment.content_hash);
+            } else {
+                self.repository.delete(conn, attachment.id).map_err(|e| e.to_string())?;
+                summary.rows_removed += 1;
+            }
+        }
+
+        let cutoff = Utc::now().naive_utc() - Duration::minutes(GC_GRACE_MINUTES);
+        for path in self.stored_files()? {
+            let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            if used.contains(&name) {
+                continue;
+            }
+            let modified = self.fs.modified(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            if chrono::DateTime::<Utc>::from(modified).naive_utc() > cutoff {
+                continue;
+            }
+            let size = self.fs.read(&path).map(|data| data.len() as u64).unwrap_or(0);
+            self.fs.remove_file(&path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+            summary.files_removed += 1;
+            summary.bytes_freed += size;
+        }
+        Ok(summary)
+    }
+}
+
+impl<A, T, N, F> AttachmentServiceImpl<A, T, N, F>
+where
+    A: AttachmentRepository,
+    T: TaskRepository,
+    N: NoteRepository,
+    F: FileSystem,
+{
+    fn blob_path(&self, content_hash: &str) -> PathBuf {
+        blob_path(&self.attachments_dir, content_hash)
+    }
+
+    fn fetch_attachment(&self, conn: &Connection, id: i32) -> Result<Attachment, String> {
+        self.repository.fetch_by_id(conn, id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Attachment {} not found", id))
+    }
+
+    // Lampiran hanya bisa ditambahkan ke task/note yang ada dan tidak di trash
+    fn check_owner(&self, conn: &Connection, owner: AttachmentOwner, owner_id: i32) -> Result<(), String> {
+        let exists = match owner {
+            AttachmentOwner::Task => self.task_repository.fetch_by_id(conn, owner_id).map_err(|e| e.to_string())?.is_some(),
+            AttachmentOwner::Note => self.note_repository.fetch_by_id(conn, owner_id).map_err(|e| e.to_string())?.is_some(),
+        };
+        if !exists {
+            let kind = match owner {
+                AttachmentOwner::Task => "Task",
+                AttachmentOwner::Note => "Note",
+            };
+            return Err(format!("{} {} not found", kind, owner_id));
+        }
+        Ok(())
+    }
+
+    // Semua file di folder lampiran, termasuk yang tidak berada di subfolder hash
+    fn stored_files(&self) -> Result<Vec<PathBuf>, String> {
+        let mut files = Vec::new();
+        let mut pending = vec![self.attachments_dir.clone()];
+        while let Some(dir) = pending.pop() {
+            if !self.fs.is_dir(&dir) {
+                continue;
+            }
+            let entries = self.fs.read_dir(&dir)
+                .map_err(|e| format!("Failed to read folder {}: {}", dir.display(), e))?;
+            for entry in entries {
+                if self.fs.is_dir(&entry) {
+                    pending.push(entry);
+                } else {
+                    files.push(entry);
+                }
+            }
+        }
+        Ok(files)
+    }
+}
+
+// Lokasi file dengan hash `content_hash` di folder lampiran
+pub fn blob_path(attachments_dir: &Path, content_hash: &str) -> PathBuf {
+    attachments_dir.join(&content_hash[..2]).join(content_hash)
+}
@@ -0,0 +1,202 @@
+use chrono::{NaiveDateTime, Utc};
+use rusqlite::{Connection, DatabaseName, OpenFlags};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use crate::db::Database;
+use crate::events::{DomainEvent, EventBus};
+use crate::journal::Journal;
+use crate::model::backup::{BackupConfig, BackupInfo, BACKUP_SETTINGS_KEY};
+use crate::repository::settings::SettingsRepository;
+use crate::services::AppServices;
+
+const BACKUP_PREFIX: &str = "nocture-";
+const BACKUP_EXTENSION: &str = ".db";
+// Milidetik ikut disimpan agar dua backup dalam detik yang sama tidak bertabrakan
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S%3f";
+
+pub trait BackupService {
+    fn load_config(&self, conn: &Connection) -> Result<BackupConfig, String>;
+    fn save_config(&self, conn: &Connection, config: &BackupConfig) -> Result<(), String>;
+    fn backup_now(&self, conn: &Connection, backup_dir: &Path) -> Result<BackupInfo, String>;
+    fn backup_if_due(&self, conn: &Connection, backup_dir: &Path) -> Result<Option<BackupInfo>, String>;
+    fn list_backups(&self, backup_dir: &Path) -> Result<Vec<BackupInfo>, String>;
+    fn restore_backup(&self, db: &Database, backup_dir: &Path, file_name: &str) -> Result<(), String>;
+}
+
+pub struct BackupServiceImpl<S: SettingsRepository> {
+    pub settings: S,
+    // Setelah restore, riwayat undo dikosongkan dan window diminta memuat ulang
+    pub events: EventBus,
+    pub journal: Journal,
+}
+
+impl<S: SettingsRepository> BackupService for BackupServiceImpl<S> {
+    fn load_config(&self, conn: &Connection) -> Result<BackupConfig, String> {
+        let value = self.settings.get(conn, BACKUP_SETTINGS_KEY).map_err(|e| e.to_string())?;
+        match value {
+            Some(json) => serde_json::from_str(&json).map_err(|e| format!("Invalid backup settings: {}", e)),
+            None => Ok(BackupConfig::default()),
+        }
+    }
+
+    fn save_config(&self, conn: &Connection, config: &BackupConfig) -> Result<(), String> {
+        if config.keep == 0 {
+            return Err("At least one backup must be kept".to_string());
+        }
+        if config.interval_minutes == 0 {
+            return Err("Backup interval must be at least one minute".to_string());
+        }
+        let json = serde_json::to_string(config).map_err(|e| e.to_string())?;
+        self.settings.set(conn, BACKUP_SETTINGS_KEY, &json).map_err(|e| e.to_string())
+    }
+
+    fn backup_now(&self, conn: &Connection, backup_dir: &Path) -> Result<BackupInfo, String> {
+        self.take_backup(conn, backup_dir, None)
+    }
+
+    fn backup_if_due(&self, conn: &Connection, backup_dir: &Path) -> Result<Option<BackupInfo>, String> {
+        let config = self.load_config(conn)?;
+        if !config.enabled {
+            return Ok(None);
+        }
+        let latest = self.list_backups(backup_dir)?.into_iter().next();
+        let due = match latest {
+            Some(backup) => {
+                let elapsed = Utc::now().naive_utc() - backup.created_at;
+                elapsed.num_minutes() >= config.interval_minutes as i64
+            }
+            None => true,
+        };
+        if due {
+            self.backup_now(conn, backup_dir).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn list_backups(&self, backup_dir: &Path) -> Result<Vec<BackupInfo>, String> {
+        if !backup_dir.exists() {
+            return Ok(Vec::new());
+        }
+        let entries = std::fs::read_dir(backup_dir)
+            .map_err(|e| format!("Failed to read backup folder: {}", e))?;
+
+        let mut backups: Vec<BackupInfo> = entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let file_name = entry.file_name().to_string_lossy().into_owned();
+                let created_at = parse_backup_timestamp(&file_name)?;
+                let size_bytes = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                Some(BackupInfo { file_name, created_at, size_bytes })
+            })
+            .collect();
+        // Terbaru lebih dulu
+        backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+        Ok(backups)
+    }
+
+    fn restore_backup(&self, db: &Database, backup_dir: &Path, file_name: &str) -> Result<(), String> {
+        // Hanya nama file buatan `backup_now` yang diterima, sekaligus mencegah path keluar dari folder backup
+        if parse_backup_timestamp(file_name).is_none() {
+            return Err(format!("'{}' is not a Nocture backup", file_name));
+        }
+        let path = backup_dir.join(file_name);
+        if !path.exists() {
+            return Err(format!("Backup '{}' not found", file_name));
+        }
+        verify_integrity(&path)?;
+
+        // Snapshot kondisi sekarang dulu supaya restore ini sendiri masih bisa dibatalkan. Rotasi
+        // tidak boleh menghapus file yang akan di-restore, walaupun itu backup paling lama
+        {
+            let conn = db.get()?;
+            self.take_backup(&conn, backup_dir, Some(file_name))?;
+        }
+        db.restore_from(&path)?;
+        self.journal.clear();
+        self.events.publish(DomainEvent::DataReloaded);
+        Ok(())
+    }
+}
+
+impl<S: SettingsRepository> BackupServiceImpl<S> {
+    // Buat backup baru lalu hapus backup lama di luar jumlah `keep`. `keep_file` tidak ikut
+    // dihapus walaupun sudah di luar rotasi, mis. backup yang sedang di-restore
+    fn take_backup(&self, conn: &Connection, backup_dir: &Path, keep_file: Option<&str>) -> Result<BackupInfo, String> {
+        let config = self.load_config(conn)?;
+        std::fs::create_dir_all(backup_dir)
+            .map_err(|e| format!("Failed to create backup folder: {}", e))?;
+
+        let created_at = Utc::now().naive_utc();
+        let file_name = format!(
+            "{}{}{}",
+            BACKUP_PREFIX,
+            created_at.format(BACKUP_TIMESTAMP_FORMAT),
+            BACKUP_EXTENSION
+        );
+        let path = backup_dir.join(&file_name);
+        conn.backup(DatabaseName::Main, &path, None)
+            .map_err(|e| format!("Failed to back up database: {}", e))?;
+
+        // Backup yang gagal integrity check lebih berbahaya daripada tidak ada, jadi langsung dihapus
+        if let Err(e) = verify_integrity(&path) {
+            std::fs::remove_file(&path).ok();
+            return Err(e);
+        }
+
+        let backups = self.list_backups(backup_dir)?;
+        for stale in backups.iter().skip(config.keep).filter(|b| Some(b.file_name.as_str()) != keep_file) {
+            std::fs::remove_file(backup_dir.join(&stale.file_name)).ok();
+        }
+
+        let size_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        Ok(BackupInfo { file_name, created_at, size_bytes })
+    }
+}
+
+// Seberapa sering thread scheduler mengecek apakah backup sudah jatuh tempo
+const SCHEDULER_TICK: Duration = Duration::from_secs(60);
+
+// Backup saat startup, lalu cek setiap menit apakah interval backup sudah lewat
+pub fn start_backup_scheduler(db: Arc<Database>, services: Arc<AppServices>, backup_dir: PathBuf) {
+    std::thread::spawn(move || {
+        let service = &services.backup;
+        let startup = db.get().and_then(|conn| {
+            if service.load_config(&conn)?.enabled {
+                service.backup_now(&conn, &backup_dir).map(|_| ())
+            } else {
+                Ok(())
+            }
+        });
+        if let Err(e) = startup {
+            eprintln!("Backup saat startup gagal: {}", e);
+        }
+
+        loop {
+            std::thread::sleep(SCHEDULER_TICK);
+            if let Err(e) = db.get().and_then(|conn| service.backup_if_due(&conn, &backup_dir)) {
+                eprintln!("Backup terjadwal gagal: {}", e);
+            }
+        }
+    });
+}
+
+fn parse_backup_timestamp(file_name: &str) -> Option<NaiveDateTime> {
+    let stamp = file_name.strip_prefix(BACKUP_PREFIX)?.strip_suffix(BACKUP_EXTENSION)?;
+    NaiveDateTime::parse_from_str(stamp, BACKUP_TIMESTAMP_FORMAT).ok()
+}
+
+// Jalankan `PRAGMA integrity_check` pada file backup tanpa mengubahnya
+pub fn verify_integrity(path: &Path) -> Result<(), String> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Failed to open backup: {}", e))?;
+    let result: String = conn
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to verify backup: {}", e))?;
+    if result == "ok" {
+        Ok(())
+    } else {
+        Err(format!("Backup failed integrity check: {}", result))
+    }
+}
@@ -0,0 +1,511 @@
+use chrono::NaiveDateTime;
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+use crate::caldav::{parse_server_url, CalDavClient, CalDavError, RemoteItem};
+use crate::db::in_transaction;
+use crate::events::EventBus;
+use crate::ical::{event_to_ical, parse_ical, task_to_ical, ICalComponent};
+use crate::journal::{Changes, Journal};
+use crate::model::caldav::{
+    CalDavConfig, ConflictWinner, RemoteCalendar, SyncConflict, SyncKind, SyncSummary, SyncedItem, CALDAV_SETTINGS_KEY,
+};
+use crate::model::calendar_event::CalendarEvent;
+use crate::model::task::Task;
+use crate::repository::caldav::CalDavRepository;
+use crate::repository::calendar_event::CalendarEventRepository;
+use crate::repository::settings::SettingsRepository;
+use crate::repository::task::TaskRepository;
+use crate::repository::task_history::TaskHistoryRepository;
+use crate::services::task::TaskService;
+use crate::services::trash::restore_task;
+
+pub trait CalDavService {
+    fn load_config(&self, conn: &Connection) -> Result<CalDavConfig, String>;
+    fn save_config(&self, conn: &Connection, config: &CalDavConfig) -> Result<(), String>;
+    // Kalender di server yang bisa dipilih untuk task dan acara
+    fn discover_calendars(&self, client: &dyn CalDavClient) -> Result<Vec<RemoteCalendar>, String>;
+    // Tarik perubahan dari server lalu dorong perubahan lokal, untuk kalender task dan kalender
+    // acara yang dipilih di pengaturan. `now` (UTC) dipakai untuk item yang dibuat atau diubah
+    // dari server.
+    //
+    // Jika item berubah di kedua sisi sejak sinkronisasi terakhir, yang terakhir diubah menang:
+    // `updated_at` lokal dibandingkan dengan LAST-MODIFIED di server. Jika sama, atau server tidak
+    // mengirim LAST-MODIFIED, isi server yang dipakai. Item yang diubah di satu sisi tapi dihapus
+    // di sisi lain selalu dipertahankan.
+    fn sync(&self, conn: &Connection, client: &dyn CalDavClient, now: NaiveDateTime) -> Result<SyncSummary, String>;
+}
+
+pub struct CalDavServiceImpl<T, H, E, R, S, K>
+where
+    T: TaskRepository,
+    H: TaskHistoryRepository,
+    E: CalendarEventRepository,
+    R: CalDavRepository,
+    S: SettingsRepository,
+    K: TaskService,
+{
+    pub task_repository: T,
+    // Task di trash yang diubah di server dipulihkan; pemulihannya dicatat di riwayat task
+    pub task_history_repository: H,
+    pub event_repository: E,
+    pub repository: R,
+    pub settings: S,
+    // Task dari server disimpan lewat TaskService seperti edit biasa: riwayat, journal, event,
+    // checkbox note, dan reflow jadwal ikut berjalan
+    pub tasks: K,
+    pub events: EventBus,
+    pub journal: Journal,
+}
+
+// Task atau acara lokal yang disinkronkan
+#[derive(Debug, Clone)]
+enum LocalItem {
+    Task(Task),
+    Event(CalendarEvent),
+}
+
+impl LocalItem {
+    fn id(&self) -> i32 {
+        match self {
+            LocalItem::Task(task) => task.id,
+            LocalItem::Event(event) => event.id,
+        }
+    }
+
+    fn title(&self) -> &str {
+        match self {
+            LocalItem::Task(task) => &task.title,
+            LocalItem::Event(event) => &event.title,
+        }
+    }
+
+    fn updated_at(&self) -> NaiveDateTime {
+        match self {
+            LocalItem::Task(task) => task.updated_at,
+            LocalItem::Event(event) => event.updated_at,
+        }
+    }
+
+    // UID tetap per item, jadi resource yang pernah dibuat dikenali lagi walaupun status
+    // sinkronnya hilang
+    fn uid(&self) -> String {
+        let (kind, id, created_at) = match self {
+            LocalItem::Task(task) => ("task", task.id, task.created_at),
+            LocalItem::Event(event) => ("event", event.id, event.created_at),
+        };
+        format!("nocture-{}-{}-{}", kind, id, created_at.format("%Y%m%dT%H%M%S"))
+    }
+
+    fn to_ical(&self, uid: &str) -> String {
+        match self {
+            LocalItem::Task(task) => task_to_ical(task, uid),
+            LocalItem::Event(event) => event_to_ical(event, uid),
+        }
+    }
+}
+
+// Satu putaran sinkronisasi: satu jenis item di satu kalender
+struct SyncPass<'a> {
+    conn: &'a Connection,
+    client: &'a dyn CalDavClient,
+    kind: SyncKind,
+    calendar: &'a str,
+    now: NaiveDateTime,
+}
+
+impl<T, H, E, R, S, K> CalDavService for CalDavServiceImpl<T, H, E, R, S, K>
+where
+    T: TaskRepository,
+    H: TaskHistoryRepository,
+    E: CalendarEventRepository,
+    R: CalDavRepository,
+    S: SettingsRepository,
+    K: TaskService,
+{
+    fn load_config(&self, conn: &Connection) -> Result<CalDavConfig, String> {
+        let value = self.settings.get(conn, CALDAV_SETTINGS_KEY).map_err(|e| e.to_string())?;
+        match value {
+            Some(json) => serde_json::from_str(&json).map_err(|e| format!("Invalid CalDAV settings: {}", e)),
+            None => Ok(CalDavConfig::default()),
+        }
+    }
+
+    fn save_config(&self, conn: &Connection, config: &CalDavConfig) -> Result<(), String> {
+        parse_server_url(&config.server_url)?;
+        let calendar = |href: &Option<String>| href.as_ref().map(|h| h.trim().to_string()).filter(|h| !h.is_empty());
+        let config = CalDavConfig {
+            server_url: config.server_url.trim().to_string(),
+            username: config.username.trim().to_string(),
+            password: config.password.clone(),
+            task_calendar: calendar(&config.task_calendar),
+            event_calendar: calendar(&config.event_calendar),
+        };
+        let json = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+        self.settings.set(conn, CALDAV_SETTINGS_KEY, &json).map_err(|e| e.to_string())
+    }
+
+    fn discover_calendars(&self, client: &dyn CalDavClient) -> Result<Vec<RemoteCalendar>, String> {
+        client.discover().map_err(|e| e.to_string())
+    }
+
+    fn sync(&self, conn: &Connection, client: &dyn CalDavClient, now: NaiveDateTime) -> Result<SyncSummary, String> {
+        let config = self.load_config(conn)?;
+        if config.task_calendar.is_none() && config.event_calendar.is_none() {
+            return Err("Pick a calendar for tasks or events before syncing".to_string());
+        }
+        let mut summary = SyncSummary::default();
+        for (kind, calendar) in [(SyncKind::Task, &config.task_calendar), (SyncKind::Event, &config.event_calendar)] {
+            // Status dari kalender yang sudah tidak dipilih dibuang; item lokal akan dibuat di kalender yang baru
+            self.repository.forget_other_calendars(conn, kind, calendar.as_deref()).map_err(|e| e.to_string())?;
+            if let Some(calendar) = calendar {
+                let pass = SyncPass { conn, client, kind, calendar, now };
+                self.sync_pass(&pass, &mut summary)?;
+            }
+        }
+        Ok(summary)
+    }
+}
+
+impl<T, H, E, R, S, K> CalDavServiceImpl<T, H, E, R, S, K>
+where
+    T: TaskRepository,
+    H: TaskHistoryRepository,
+    E: CalendarEventRepository,
+    R: CalDavRepository,
+    S: SettingsRepository,
+    K: TaskService,
+{
+    fn sync_pass(&self, pass: &SyncPass, summary: &mut SyncSummary) -> Result<(), String> {
+        let (conn, kind) = (pass.conn, pass.kind);
+        let token = self.repository.sync_token(conn, kind, pass.calendar).map_err(|e| e.to_string())?;
+        let changes = pass.client.changes(pass.calendar, token.as_deref()).map_err(|e| e.to_string())?;
+        let synced: HashMap<String, SyncedItem> = self.repository.fetch_items(conn, kind)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|item| (item.href.clone(), item))
+            .collect();
+
+        // Tarik: resource yang ETag-nya sama dengan yang terakhir kita lihat tidak perlu dibaca ulang
+        let mut to_fetch: Vec<String> = changes.changed.iter()
+            .filter(|remote| {
+                let known = synced.get(&remote.href).and_then(|item| item.etag.as_ref());
+                known.is_none() || known != remote.etag.as_ref()
+            })
+            .map(|remote| remote.href.clone())
+            .collect();
+        to_fetch.sort();
+        let mut removed: Vec<String> = if changes.complete {
+            let listed: HashSet<&str> = changes.changed.iter().map(|remote| remote.href.as_str()).collect();
+            synced.keys().filter(|href| !listed.contains(href.as_str())).cloned().collect()
+        } else {
+            changes.removed.clone()
+        };
+        removed.sort();
+
+        // Item lokal tanpa status sinkron, dikenali dari UID-nya jika resource-nya sudah ada di server
+        let mapped: HashSet<i32> = synced.values().map(|item| item.local_id).collect();
+        let unmapped: HashMap<String, LocalItem> = self.fetch_locals(conn, kind)?
+            .into_iter()
+            .filter(|local| !mapped.contains(&local.id()))
+            .map(|local| (local.uid(), local))
+            .collect();
+
+        for remote in pass.client.fetch(pass.calendar, &to_fetch).map_err(|e| e.to_string())? {
+            let component = match parse_ical(&remote.data, pass.now) {
+                Ok(Some(component)) if component_kind(&component) == kind => component,
+                // Komponen jenis lain (mis. VEVENT di kalender task) atau isi yang tidak bisa dibaca dilewati
+                _ => continue,
+            };
+            match synced.get(&remote.href) {
+                Some(item) => {
+                    let local = self.fetch_local(conn, kind, item.local_id)?;
+                    match local {
+                        Some(local) if local.updated_at() != item.local_updated_at => {
+                            self.resolve_conflict(pass, &local, remote, component, summary)?;
+                        }
+                        Some(local) => {
+                            let local = self.apply_remote(pass, component, Some(&local))?;
+                            self.remember(pass, &local, &remote.href, &item.uid, remote.etag)?;
+                            summary.pulled += 1;
+                        }
+                        // Dihapus lokal tapi diubah di server: isi server dipulihkan. Task di trash
+                        // dikeluarkan dari trash dan ditimpa, bukan dibuat ulang sebagai task baru.
+                        None => {
+                            let trashed = self.fetch_trashed(conn, kind, item.local_id)?;
+                            let local = self.apply_remote(pass, component, trashed.as_ref())?;
+                            self.remember(pass, &local, &remote.href, &item.uid, remote.etag)?;
+                            summary.pulled += 1;
+                            record_conflict(summary, kind, &local, ConflictWinner::Remote);
+                        }
+                    }
+                }
+                None => match unmapped.get(component.uid()) {
+                    Some(local) => self.resolve_conflict(pass, local, remote, component, summary)?,
+                    None => {
+                        let uid = component.uid().to_string();
+                        let local = self.apply_remote(pass, component, None)?;
+                        self.remember(pass, &local, &remote.href, &uid, remote.etag)?;
+                        summary.pulled += 1;
+                    }
+                },
+            }
+        }
+
+        for href in removed {
+            let Some(item) = synced.get(&href) else {
+                continue;
+            };
+            match self.fetch_local(conn, kind, item.local_id)? {
+                // Diubah lokal tapi dihapus di server: dibuat ulang di server pada tahap dorong
+                Some(local) if local.updated_at() != item.local_updated_at => {
+                    record_conflict(summary, kind, &local, ConflictWinner::Local);
+                }
+                Some(local) => {
+                    self.delete_local(pass, &local)?;
+                    summary.deleted_locally += 1;
+                }
+                None => {}
+            }
+            self.repository.delete_item(conn, kind, item.local_id).map_err(|e| e.to_string())?;
+        }
+
+        // Dorong: item baru dan item yang berubah sejak sinkronisasi terakhir
+        let mut synced: HashMap<i32, SyncedItem> = self.repository.fetch_items(conn, kind)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|item| (item.local_id, item))
+            .collect();
+        for local in self.fetch_locals(conn, kind)? {
+            match synced.remove(&local.id()) {
+                None => {
+                    let uid = local.uid();
+                    let href = format!("{}/{}.ics", pass.calendar.trim_end_matches('/'), uid);
+                    match pass.client.create(&href, &local.to_ical(&uid)) {
+                        Ok(etag) => {
+                            self.remember(pass, &local, &href, &uid, etag)?;
+                            summary.pushed += 1;
+                        }
+                        Err(CalDavError::Status(412)) => self.resolve_rejected(pass, Some(&local), &href, &uid, summary)?,
+                        Err(e) => return Err(e.to_string()),
+                    }
+                }
+                Some(item) if local.updated_at() != item.local_updated_at => {
+                    match pass.client.update(&item.href, &local.to_ical(&item.uid), item.etag.as_deref()) {
+                        Ok(etag) => {
+                            self.remember(pass, &local, &item.href, &item.uid, etag)?;
+                            summary.pushed += 1;
+                        }
+                        Err(CalDavError::Status(412)) => self.resolve_rejected(pass, Some(&local), &item.href, &item.uid, summary)?,
+                        Err(e) => return Err(e.to_string()),
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+
+        // Item lokal yang sudah dihapus (termasuk task di trash) dihapus juga di server
+        let mut deleted: Vec<SyncedItem> = synced.into_values().collect();
+        deleted.sort_by_key(|item| item.local_id);
+        for item in deleted {
+            match pass.client.delete(&item.href, item.etag.as_deref()) {
+                Ok(()) => {
+                    self.repository.delete_item(conn, kind, item.local_id).map_err(|e| e.to_string())?;
+                    summary.deleted_remotely += 1;
+                }
+                Err(CalDavError::Status(412)) => self.resolve_rejected(pass, None, &item.href, &item.uid, summary)?,
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+
+        // Token dari awal putaran: perubahan yang baru kita dorong akan muncul lagi berikutnya,
+        // tapi dilewati karena ETag-nya sudah tercatat
+        self.repository.save_sync_token(conn, kind, pass.calendar, changes.sync_token.as_deref())
+            .map_err(|e| e.to_string())
+    }
+
+    // Item berubah di kedua sisi: yang terakhir diubah menang, seri dimenangkan server
+    fn resolve_conflict(
+        &self,
+        pass: &SyncPass,
+        local: &LocalItem,
+        remote: RemoteItem,
+        component: ICalComponent,
+        summary: &mut SyncSummary,
+    ) -> Result<(), String> {
+        let uid = component.uid().to_string();
+        let local_is_newer = component.last_modified().is_some_and(|remote_time| local.updated_at() > remote_time);
+        if local_is_newer {
+            let etag = pass.client.update(&remote.href, &local.to_ical(&uid), remote.etag.as_deref())
+                .map_err(|e| e.to_string())?;
+            self.remember(pass, local, &remote.href, &uid, etag)?;
+            summary.pushed += 1;
+            record_conflict(summary, pass.kind, local, ConflictWinner::Local);
+        } else {
+            let local = self.apply_remote(pass, component, Some(local))?;
+            self.remember(pass, &local, &remote.href, &uid, remote.etag)?;
+            summary.pulled += 1;
+            record_conflict(summary, pass.kind, &local, ConflictWinner::Remote);
+        }
+        Ok(())
+    }
+
+    // Server menolak perubahan (412) karena resource-nya berbeda dari yang terakhir kita lihat:
+    // baca ulang lalu selesaikan sebagai konflik. `local` kosong berarti item lokalnya sudah dihapus.
+    fn resolve_rejected(
+        &self,
+        pass: &SyncPass,
+        local: Option<&LocalItem>,
+        href: &str,
+        uid: &str,
+        summary: &mut SyncSummary,
+    ) -> Result<(), String> {
+        let remote = pass.client.fetch(pass.calendar, &[href.to_string()])
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .next();
+        let Some(remote) = remote else {
+            // Resource sudah hilang dari server
+            if let Some(local) = local {
+                let etag = pass.client.create(href, &local.to_ical(uid)).map_err(|e| e.to_string())?;
+                self.remember(pass, local, href, uid, etag)?;
+                summary.pushed += 1;
+            }
+            return Ok(());
+        };
+        let component = match parse_ical(&remote.data, pass.now) {
+            Ok(Some(component)) if component_kind(&component) == pass.kind => component,
+            _ => return Err(format!("Could not read {} from the CalDAV server", href)),
+        };
+        match local {
+            Some(local) => self.resolve_conflict(pass, local, remote, component, summary),
+            // Dihapus lokal tapi diubah di server: isi server dipulihkan
+            None => {
+                let local = self.apply_remote(pass, component, None)?;
+                self.remember(pass, &local, &remote.href, uid, remote.etag)?;
+                summary.pulled += 1;
+                record_conflict(summary, pass.kind, &local, ConflictWinner::Remote);
+                Ok(())
+            }
+        }
+    }
+
+    fn remember(&self, pass: &SyncPass, local: &LocalItem, href: &str, uid: &str, etag: Option<String>) -> Result<(), String> {
+        let item = SyncedItem {
+            kind: pass.kind,
+            local_id: local.id(),
+            calendar: pass.calendar.to_string(),
+            href: href.to_string(),
+            uid: uid.to_string(),
+            etag,
+            local_updated_at: local.updated_at(),
+        };
+        self.repository.save_item(pass.conn, &item).map_err(|e| e.to_string())
+    }
+
+    fn fetch_local(&self, conn: &Connection, kind: SyncKind, id: i32) -> Result<Option<LocalItem>, String> {
+        match kind {
+            SyncKind::Task => self.task_repository.fetch_by_id(conn, id).map(|task| task.map(LocalItem::Task)),
+            SyncKind::Event => self.event_repository.fetch_by_id(conn, id).map(|event| event.map(LocalItem::Event)),
+        }
+        .map_err(|e| e.to_string())
+    }
+
+    // Task di trash dengan id `id`; acara tidak punya trash
+    fn fetch_trashed(&self, conn: &Connection, kind: SyncKind, id: i32) -> Result<Option<LocalItem>, String> {
+        if kind != SyncKind::Task {
+            return Ok(None);
+        }
+        Ok(self.task_repository.fetch_deleted(conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .find(|trashed| trashed.task.id == id)
+            .map(|trashed| LocalItem::Task(trashed.task)))
+    }
+
+    // Task di trash tidak ikut, sehingga dianggap sudah dihapus
+    fn fetch_locals(&self, conn: &Connection, kind: SyncKind) -> Result<Vec<LocalItem>, String> {
+        let mut locals: Vec<LocalItem> = match kind {
+            SyncKind::Task => self.task_repository.fetch_all(conn).map(|tasks| tasks.into_iter().map(LocalItem::Task).collect()),
+            SyncKind::Event => self.event_repository.fetch_all(conn).map(|events| events.into_iter().map(LocalItem::Event).collect()),
+        }
+        .map_err(|e| e.to_string())?;
+        locals.sort_by_key(LocalItem::id);
+        Ok(locals)
+    }
+
+    // Simpan isi dari server sebagai item baru, atau timpa `existing`. Id, `created_at`, dan
+    // perkiraan waktu task tetap dari item lokal karena tidak ada padanannya di iCalendar.
+    fn apply_remote(&self, pass: &SyncPass, component: ICalComponent, existing: Option<&LocalItem>) -> Result<LocalItem, String> {
+        let conn = pass.conn;
+        let local = match (component, existing) {
+            (ICalComponent::Todo { task, .. }, Some(LocalItem::Task(current))) => {
+                let task = Task {
+                    id: current.id,
+                    created_at: current.created_at,
+                    updated_at: pass.now,
+                    estimated_minutes: current.estimated_minutes,
+                    ..task
+                };
+                self.commit_task_changes(conn, |tx, changes| {
+                    // Tidak berpengaruh jika task tidak ada di trash
+                    restore_task(&self.task_repository, &self.task_history_repository, changes, tx, task.id)?;
+                    self.tasks.save_task(tx, task.clone(), changes)?;
+                    Ok(())
+                })?;
+                LocalItem::Task(task)
+            }
+            (ICalComponent::Todo { task, .. }, _) => {
+                let task = Task { created_at: pass.now, updated_at: pass.now, ..task };
+                LocalItem::Task(self.commit_task_changes(conn, |tx, changes| self.tasks.insert_task(tx, task, changes))?)
+            }
+            (ICalComponent::Event { event, .. }, Some(LocalItem::Event(current))) => {
+                let event = CalendarEvent { id: current.id, created_at: current.created_at, updated_at: pass.now, ..event };
+                self.event_repository.update(conn, &event).map_err(|e| e.to_string())?;
+                LocalItem::Event(event)
+            }
+            (ICalComponent::Event { event, .. }, _) => {
+                let mut event = CalendarEvent { created_at: pass.now, updated_at: pass.now, ..event };
+                event.id = self.event_repository.create(conn, &event).map_err(|e| e.to_string())?;
+                LocalItem::Event(event)
+            }
+        };
+        Ok(local)
+    }
+
+    // Task dipindahkan ke trash supaya masih bisa dipulihkan; acara dihapus permanen
+    fn delete_local(&self, pass: &SyncPass, local: &LocalItem) -> Result<(), String> {
+        match local {
+            LocalItem::Task(task) => self.tasks.delete_task(pass.conn, task.id),
+            LocalItem::Event(event) => self.event_repository.delete(pass.conn, event.id).map_err(|e| e.to_string()),
+        }
+    }
+
+    // Satu task dari server disimpan dalam transaksinya sendiri; entri journal dan event dicatat
+    // setelah commit
+    fn commit_task_changes<V>(
+        &self,
+        conn: &Connection,
+        f: impl FnOnce(&Connection, &mut Changes) -> Result<V, String>,
+    ) -> Result<V, String> {
+        let mut changes = Changes::new();
+        let value = in_transaction(conn, |tx| f(tx, &mut changes))?;
+        changes.commit(&self.journal, &self.events);
+        Ok(value)
+    }
+}
+
+fn component_kind(component: &ICalComponent) -> SyncKind {
+    match component {
+        ICalComponent::Todo { .. } => SyncKind::Task,
+        ICalComponent::Event { .. } => SyncKind::Event,
+    }
+}
+
+fn record_conflict(summary: &mut SyncSummary, kind: SyncKind, local: &LocalItem, winner: ConflictWinner) {
+    summary.conflicts.push(SyncConflict {
+        kind,
+        local_id: local.id(),
+        title: local.title().to_string(),
+        winner,
+    });
+}
@@ -0,0 +1,145 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use rusqlite::Connection;
+use crate::model::calendar_event::{CalendarEvent, CalendarEventPayload, CalendarItem, CalendarItemKind};
+use crate::model::quick_add::{DATE_FORMAT, DATE_TIME_FORMAT};
+use crate::repository::calendar_event::CalendarEventRepository;
+use crate::repository::task::TaskRepository;
+
+pub trait CalendarService {
+    fn create_event(&self, conn: &Connection, payload: CalendarEventPayload) -> Result<CalendarEvent, String>;
+    fn fetch_events(&self, conn: &Connection) -> Result<Vec<CalendarEvent>, String>;
+    fn update_event(&self, conn: &Connection, id: i32, payload: CalendarEventPayload) -> Result<CalendarEvent, String>;
+    fn delete_event(&self, conn: &Connection, id: i32) -> Result<(), String>;
+    // Kemunculan acara (termasuk pengulangannya) dan deadline task antara `from` dan `to`
+    // (keduanya inklusif), urut berdasarkan waktu mulai
+    fn calendar_range(&self, conn: &Connection, from: NaiveDate, to: NaiveDate) -> Result<Vec<CalendarItem>, String>;
+}
+
+pub struct CalendarServiceImpl<E: CalendarEventRepository, T: TaskRepository> {
+    pub repository: E,
+    pub task_repository: T,
+}
+
+impl<E: CalendarEventRepository, T: TaskRepository> CalendarService for CalendarServiceImpl<E, T> {
+    fn create_event(&self, conn: &Connection, payload: CalendarEventPayload) -> Result<CalendarEvent, String> {
+        let now = Utc::now().naive_utc();
+        let mut event = event_from_payload(payload, now)?;
+        event.id = self.repository.create(conn, &event).map_err(|e| e.to_string())?;
+        Ok(event)
+    }
+
+    fn fetch_events(&self, conn: &Connection) -> Result<Vec<CalendarEvent>, String> {
+        self.repository.fetch_all(conn).map_err(|e| e.to_string())
+    }
+
+    fn update_event(&self, conn: &Connection, id: i32, payload: CalendarEventPayload) -> Result<CalendarEvent, String> {
+        let existing = self.repository.fetch_by_id(conn, id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Event {} not found", id))?;
+        let event = CalendarEvent {
+            id,
+            created_at: existing.created_at,
+            ..event_from_payload(payload, Utc::now().naive_utc())?
+        };
+        self.repository.update(conn, &event).map_err(|e| e.to_string())?;
+        Ok(event)
+    }
+
+    fn delete_event(&self, conn: &Connection, id: i32) -> Result<(), String> {
+        self.repository.delete(conn, id).map_err(|e| e.to_string())
+    }
+
+    fn calendar_range(&self, conn: &Connection, from: NaiveDate, to: NaiveDate) -> Result<Vec<CalendarItem>, String> {
+        if to < from {
+            return Err("Calendar end date is before its start date".to_string());
+        }
+        let range_start = from.and_time(NaiveTime::MIN);
+        let range_end = to.succ_opt().ok_or("Calendar end date is out of range")?.and_time(NaiveTime::MIN);
+
+        let mut items = Vec::new();
+        for event in self.repository.fetch_between(conn, range_start, range_end).map_err(|e| e.to_string())? {
+            for (start, end) in event.occurrences(range_start, range_end) {
+                items.push(CalendarItem {
+                    kind: CalendarItemKind::Event,
+                    id: event.id,
+                    title: event.title.clone(),
+                    start,
+                    end,
+                    all_day: event.all_day,
+                    location: event.location.clone(),
+                    completed: false,
+                    recurring: event.recurrence.is_some(),
+                });
+            }
+        }
+        for task in self.task_repository.fetch_all(conn).map_err(|e| e.to_string())? {
+            let Some(deadline) = task.deadline.filter(|deadline| range_start <= *deadline && *deadline < range_end) else {
+                continue;
+            };
+            // Deadline tanpa jam tampil sebagai entri seharian
+            let all_day = deadline.time() == NaiveTime::MIN;
+            items.push(CalendarItem {
+                kind: CalendarItemKind::Task,
+                id: task.id,
+                title: task.title,
+                start: deadline,
+                end: if all_day { deadline + Duration::days(1) } else { deadline },
+                all_day,
+                location: None,
+                completed: task.completed,
+                recurring: false,
+            });
+        }
+        // Entri seharian di atas entri berjam pada hari yang sama
+        items.sort_by(|a, b| {
+            (a.start, !a.all_day, &a.title, a.id).cmp(&(b.start, !b.all_day, &b.title, b.id))
+        });
+        Ok(items)
+    }
+}
+
+fn event_from_payload(payload: CalendarEventPayload, now: NaiveDateTime) -> Result<CalendarEvent, String> {
+    let title = payload.title.trim().to_string();
+    if title.is_empty() {
+        return Err("Event title is empty".to_string());
+    }
+    let (start, end) = if payload.all_day {
+        let parse = |value: &str| {
+            NaiveDate::parse_from_str(value, DATE_FORMAT).map_err(|_| format!("Invalid date '{}'", value))
+        };
+        let (first_day, last_day) = (parse(&payload.start)?, parse(&payload.end)?);
+        if last_day < first_day {
+            return Err("Event must end on or after the day it starts".to_string());
+        }
+        let after_last = last_day.succ_opt().ok_or("Event end date is out of range")?;
+        (first_day.and_time(NaiveTime::MIN), after_last.and_time(NaiveTime::MIN))
+    } else {
+        let parse = |value: &str| {
+            NaiveDateTime::parse_from_str(value, DATE_TIME_FORMAT).map_err(|_| format!("Invalid date and time '{}'", value))
+        };
+        let (start, end) = (parse(&payload.start)?, parse(&payload.end)?);
+        if end <= start {
+            return Err("Event must end after it starts".to_string());
+        }
+        (start, end)
+    };
+    if let Some(recurrence) = &payload.recurrence {
+        if recurrence.interval == 0 {
+            return Err("Recurrence interval must be at least 1".to_string());
+        }
+        if recurrence.until.is_some_and(|until| until < start.date()) {
+            return Err("Recurrence ends before the event starts".to_string());
+        }
+    }
+    Ok(CalendarEvent {
+        id: 0,
+        title,
+        start,
+        end,
+        all_day: payload.all_day,
+        location: payload.location.map(|location| location.trim().to_string()).filter(|location| !location.is_empty()),
+        recurrence: payload.recurrence,
+        created_at: now,
+        updated_at: now,
+    })
+}
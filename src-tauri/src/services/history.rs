@@ -0,0 +1,177 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+use crate::db::in_transaction;
+use crate::events::{DomainEvent, EventBus};
+use crate::fs::FileSystem;
+use crate::journal::{Changes, Journal};
+use crate::model::front_matter::FrontMatter;
+use crate::model::journal::{HistoryStatus, Operation};
+use crate::model::note_revision::RevisionPolicy;
+use crate::model::task::Task;
+use crate::repository::link::LinkRepository;
+use crate::repository::note::NoteRepository;
+use crate::repository::note_revision::NoteRevisionRepository;
+use crate::repository::task::TaskRepository;
+use crate::repository::task_history::TaskHistoryRepository;
+use crate::services::note::{index_links, move_note_file, save_revision};
+use crate::services::task::TaskService;
+use crate::services::trash::{restore_note, restore_task, trash_note, trash_task};
+
+pub trait HistoryService {
+    fn undo(&self, conn: &Connection) -> Result<HistoryStatus, String>;
+    fn redo(&self, conn: &Connection) -> Result<HistoryStatus, String>;
+    fn status(&self) -> HistoryStatus;
+}
+
+pub struct HistoryServiceImpl<T, H, N, V, L, F, S>
+where
+    T: TaskRepository,
+    H: TaskHistoryRepository,
+    N: NoteRepository,
+    V: NoteRevisionRepository,
+    L: LinkRepository,
+    F: FileSystem,
+    S: TaskService,
+{
+    pub task_repository: T,
+    pub task_history_repository: H,
+    // Edit task dikembalikan lewat TaskService supaya riwayat dan checkbox note-nya ikut
+    pub tasks: S,
+    pub note_repository: N,
+    // Konten note yang ditimpa undo/redo tetap disimpan sebagai revisi
+    pub revision_repository: V,
+    pub revision_policy: RevisionPolicy,
+    // Link note diindeks ulang setelah isinya dikembalikan
+    pub link_repository: L,
+    pub fs: F,
+    pub events: EventBus,
+    pub journal: Journal,
+    pub trash_dir: PathBuf,
+}
+
+impl<T, H, N, V, L, F, S> HistoryService for HistoryServiceImpl<T, H, N, V, L, F, S>
+where
+    T: TaskRepository,
+    H: TaskHistoryRepository,
+    N: NoteRepository,
+    V: NoteRevisionRepository,
+    L: LinkRepository,
+    F: FileSystem,
+    S: TaskService,
+{
+    fn undo(&self, conn: &Connection) -> Result<HistoryStatus, String> {
+        let operation = self.journal.pop_undo().ok_or("Nothing to undo")?;
+        let mut changes = Changes::new();
+        match self.apply(conn, &operation, true, &mut changes) {
+            Ok(()) => {
+                self.journal.push_redo(operation);
+                changes.publish_to(&self.events);
+            }
+            Err(e) => {
+                // Gagal dibalik: biarkan tetap di riwayat supaya bisa dicoba lagi
+                self.journal.push_undo(operation);
+                return Err(e);
+            }
+        }
+        Ok(self.journal.status())
+    }
+
+    fn redo(&self, conn: &Connection) -> Result<HistoryStatus, String> {
+        let operation = self.journal.pop_redo().ok_or("Nothing to redo")?;
+        let mut changes = Changes::new();
+        match self.apply(conn, &operation, false, &mut changes) {
+            Ok(()) => {
+                self.journal.push_undo(operation);
+                changes.publish_to(&self.events);
+            }
+            Err(e) => {
+                self.journal.push_redo(operation);
+                return Err(e);
+            }
+        }
+        Ok(self.journal.status())
+    }
+
+    fn status(&self) -> HistoryStatus {
+        self.journal.status()
+    }
+}
+
+impl<T, H, N, V, L, F, S> HistoryServiceImpl<T, H, N, V, L, F, S>
+where
+    T: TaskRepository,
+    H: TaskHistoryRepository,
+    N: NoteRepository,
+    V: NoteRevisionRepository,
+    L: LinkRepository,
+    F: FileSystem,
+    S: TaskService,
+{
+    // Jalankan kebalikan `operation` (undo) atau ulangi operasinya (redo).
+    // Create/delete dibalik lewat trash. Item yang sudah di-restore atau di-purge dari trash
+    // dilewati saja, supaya entri seperti itu tidak membuat undo macet. Event dikumpulkan di
+    // `changes` dan baru dipublikasikan pemanggil setelah semuanya ter-commit; entri journal dari
+    // service lain di dalamnya dibuang karena operasi ini sendiri sudah ada di journal.
+    fn apply(&self, conn: &Connection, operation: &Operation, undo: bool, changes: &mut Changes) -> Result<(), String> {
+        match operation {
+            Operation::TaskCreated { task } | Operation::TaskDeleted { task } => {
+                // Undo pembuatan == redo penghapusan, dan sebaliknya
+                if undo == matches!(operation, Operation::TaskCreated { .. }) {
+                    trash_task(&self.task_repository, &self.task_history_repository, changes, conn, task.id)?;
+                } else {
+                    restore_task(&self.task_repository, &self.task_history_repository, changes, conn, task.id)?;
+                }
+            }
+            Operation::TaskUpdated { before, after } => {
+                let task = if undo { before } else { after };
+                // Undo/redo juga edit baru, jadi dicap dengan waktu sekarang supaya ikut tersinkron
+                let task = Task { updated_at: Utc::now().naive_utc(), ..task.clone() };
+                in_transaction(conn, |tx| self.tasks.save_task(tx, task, changes))?;
+            }
+            Operation::NoteCreated { note } | Operation::NoteDeleted { note } => {
+                if undo == matches!(operation, Operation::NoteCreated { .. }) {
+                    trash_note(&self.note_repository, &self.fs, &self.trash_dir, changes, conn, note.id)?;
+                } else {
+                    restore_note(&self.note_repository, &self.fs, &self.trash_dir, changes, conn, note.id)?;
+                }
+            }
+            Operation::NoteUpdated { before, after, before_content, after_content } => {
+                let (note, content) = if undo { (before, before_content) } else { (after, after_content) };
+                in_transaction(conn, |tx| {
+                    let stored = self.note_repository.fetch_by_id(tx, note.id)
+                        .map_err(|e| e.to_string())?
+                        .ok_or_else(|| format!("Note {} not found", note.id))?;
+                    let current = self.fs.read(Path::new(&stored.file_path)).unwrap_or_default();
+                    let current = String::from_utf8_lossy(&current);
+                    let current_body = FrontMatter::body(&current);
+                    if current_body != FrontMatter::body(&String::from_utf8_lossy(content)) {
+                        save_revision(&self.revision_repository, tx, note.id, current_body.as_bytes(), self.revision_policy)?;
+                    }
+                    self.note_repository.update(tx, note).map_err(|e| e.to_string())?;
+                    // Edit yang memindahkan file note juga dibalik: file ikut kembali ke path lamanya
+                    move_note_file(&self.fs, &stored.file_path, &note.file_path)?;
+                    self.fs.write(Path::new(&note.file_path), content)
+                        .map_err(|e| format!("Failed to write note file: {}", e))?;
+                    // Link yang ditulis ulang saat rename ada di Batch yang sama, jadi ikut dibalik sendiri
+                    index_links(&self.link_repository, tx, note.id, FrontMatter::body(&String::from_utf8_lossy(content)))?;
+                    Ok(())
+                })?;
+                if let Some(note) = self.note_repository.fetch_by_id(conn, note.id).map_err(|e| e.to_string())? {
+                    changes.publish(DomainEvent::NoteUpdated(note));
+                }
+            }
+            Operation::Batch { operations, .. } => {
+                // Undo membalik dari operasi terakhir
+                in_transaction(conn, |tx| {
+                    if undo {
+                        operations.iter().rev().try_for_each(|operation| self.apply(tx, operation, true, changes))
+                    } else {
+                        operations.iter().try_for_each(|operation| self.apply(tx, operation, false, changes))
+                    }
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,2 +1,274 @@
 pub mod task;
 pub mod note;
+pub mod archive;
+pub mod task_csv;
+pub mod backup;
+pub mod history;
+pub mod trash;
+pub mod vault;
+pub mod attachment;
+pub mod render;
+pub mod note_export;
+pub mod note_task;
+pub mod scoring;
+pub mod time_tracking;
+pub mod planner;
+pub mod calendar;
+pub mod caldav;
+
+use crate::events::EventBus;
+use crate::fs::StdFileSystem;
+use crate::journal::Journal;
+use crate::model::note_revision::RevisionPolicy;
+use crate::repository::attachment::SqliteAttachmentRepository;
+use crate::repository::caldav::SqliteCalDavRepository;
+use crate::repository::calendar_event::SqliteCalendarEventRepository;
+use crate::repository::link::SqliteLinkRepository;
+use crate::repository::note::SqliteNoteRepository;
+use crate::repository::note_revision::SqliteNoteRevisionRepository;
+use crate::repository::note_task::SqliteNoteTaskRepository;
+use crate::repository::schedule_block::SqliteScheduleBlockRepository;
+use crate::repository::settings::SqliteSettingsRepository;
+use crate::repository::task::SqliteTaskRepository;
+use crate::repository::task_history::SqliteTaskHistoryRepository;
+use crate::repository::time_entry::SqliteTimeEntryRepository;
+use std::path::PathBuf;
+use archive::ArchiveServiceImpl;
+use attachment::AttachmentServiceImpl;
+use backup::BackupServiceImpl;
+use caldav::CalDavServiceImpl;
+use calendar::CalendarServiceImpl;
+use history::HistoryServiceImpl;
+use note::NoteServiceImpl;
+use note_export::NoteExportServiceImpl;
+use note_task::{CheckboxWriter, NoteTaskServiceImpl};
+use planner::PlannerServiceImpl;
+use render::{RenderCache, RenderServiceImpl};
+use scoring::ScoringServiceImpl;
+use task::TaskServiceImpl;
+use task_csv::TaskCsvServiceImpl;
+use time_tracking::TimeTrackingServiceImpl;
+use trash::TrashServiceImpl;
+use vault::VaultServiceImpl;
+
+// Folder di dalam vault tempat file note yang dihapus disimpan
+pub const TRASH_FOLDER: &str = ".trash";
+// Folder di dalam vault tempat file lampiran task dan note disimpan
+pub const ATTACHMENTS_FOLDER: &str = ".attachments";
+
+// TaskService yang ikut memperbarui checkbox asal task di note dan menyusun ulang jadwal
+type AppTaskService = TaskServiceImpl<
+    SqliteTaskRepository,
+    SqliteTaskHistoryRepository,
+    CheckboxWriter<SqliteNoteRepository, SqliteNoteTaskRepository, SqliteNoteRevisionRepository, StdFileSystem>,
+    AppPlannerService,
+>;
+type AppPlannerService = PlannerServiceImpl<
+    SqliteTaskRepository,
+    SqliteTimeEntryRepository,
+    SqliteScheduleBlockRepository,
+    SqliteCalendarEventRepository,
+    SqliteSettingsRepository,
+>;
+type AppNoteTaskService = NoteTaskServiceImpl<
+    SqliteNoteRepository,
+    SqliteTaskRepository,
+    SqliteNoteTaskRepository,
+    StdFileSystem,
+    AppTaskService,
+>;
+
+// Semua service yang dipakai command, dibuat sekali saat startup dan disimpan di AppState
+pub struct AppServices {
+    pub task: AppTaskService,
+    pub note: NoteServiceImpl<
+        SqliteNoteRepository,
+        SqliteNoteRevisionRepository,
+        SqliteLinkRepository,
+        StdFileSystem,
+        AppNoteTaskService,
+    >,
+    pub archive: ArchiveServiceImpl<SqliteTaskRepository, SqliteNoteRepository, StdFileSystem>,
+    pub task_csv: TaskCsvServiceImpl<SqliteTaskRepository, AppTaskService>,
+    pub backup: BackupServiceImpl<SqliteSettingsRepository>,
+    pub history: HistoryServiceImpl<
+        SqliteTaskRepository,
+        SqliteTaskHistoryRepository,
+        SqliteNoteRepository,
+        SqliteNoteRevisionRepository,
+        SqliteLinkRepository,
+        StdFileSystem,
+        AppTaskService,
+    >,
+    pub trash: TrashServiceImpl<SqliteTaskRepository, SqliteTaskHistoryRepository, SqliteNoteRepository, StdFileSystem, SqliteSettingsRepository>,
+    pub vault: VaultServiceImpl<SqliteNoteRepository, SqliteLinkRepository, StdFileSystem>,
+    pub attachment: AttachmentServiceImpl<SqliteAttachmentRepository, SqliteTaskRepository, SqliteNoteRepository, StdFileSystem>,
+    pub render: RenderServiceImpl<SqliteNoteRepository, StdFileSystem>,
+    pub note_export: NoteExportServiceImpl<SqliteNoteRepository, SqliteAttachmentRepository, StdFileSystem>,
+    pub note_task: AppNoteTaskService,
+    pub scoring: ScoringServiceImpl<SqliteTaskRepository, SqliteSettingsRepository>,
+    pub time_tracking: TimeTrackingServiceImpl<SqliteTaskRepository, SqliteTimeEntryRepository>,
+    pub planner: AppPlannerService,
+    pub calendar: CalendarServiceImpl<SqliteCalendarEventRepository, SqliteTaskRepository>,
+    pub caldav: CalDavServiceImpl<
+        SqliteTaskRepository,
+        SqliteTaskHistoryRepository,
+        SqliteCalendarEventRepository,
+        SqliteCalDavRepository,
+        SqliteSettingsRepository,
+        AppTaskService,
+    >,
+}
+
+impl AppServices {
+    // Rangkai service dengan repository SQLite dan filesystem asli.
+    // Semua service berbagi satu bus event; subscribe lewat `events` untuk menerima perubahan.
+    // Task dan note mencatat mutasinya ke `journal` yang sama dengan service history.
+    // File markdown note ada di `vault_dir`; yang dihapus dipindahkan ke `.trash` di dalamnya.
+    // Lampiran disimpan di `.attachments` di vault yang sama.
+    // Checkbox di note bisa dijadikan task lewat `note_task`.
+    // `scoring` mengurutkan task terbuka untuk "kerjakan apa berikutnya".
+    pub fn new(events: EventBus, journal: Journal, vault_dir: PathBuf) -> Self {
+        let trash_dir = vault_dir.join(TRASH_FOLDER);
+        let attachments_dir = vault_dir.join(ATTACHMENTS_FOLDER);
+        AppServices {
+            task: task_service(&events, &journal),
+            note: NoteServiceImpl {
+                repository: SqliteNoteRepository,
+                revision_repository: SqliteNoteRevisionRepository,
+                revision_policy: RevisionPolicy::default(),
+                link_repository: SqliteLinkRepository,
+                fs: StdFileSystem,
+                events: events.clone(),
+                journal: journal.clone(),
+                vault_dir: vault_dir.clone(),
+                trash_dir: trash_dir.clone(),
+                note_tasks: note_task_service(&events, &journal),
+            },
+            archive: ArchiveServiceImpl {
+                task_repository: SqliteTaskRepository,
+                note_repository: SqliteNoteRepository,
+                fs: StdFileSystem,
+                events: events.clone(),
+                journal: journal.clone(),
+            },
+            task_csv: TaskCsvServiceImpl {
+                repository: SqliteTaskRepository,
+                tasks: task_service(&events, &journal),
+                events: events.clone(),
+                journal: journal.clone(),
+            },
+            backup: BackupServiceImpl { settings: SqliteSettingsRepository, events: events.clone(), journal: journal.clone() },
+            history: HistoryServiceImpl {
+                task_repository: SqliteTaskRepository,
+                task_history_repository: SqliteTaskHistoryRepository,
+                tasks: task_service(&events, &journal),
+                note_repository: SqliteNoteRepository,
+                revision_repository: SqliteNoteRevisionRepository,
+                revision_policy: RevisionPolicy::default(),
+                link_repository: SqliteLinkRepository,
+                fs: StdFileSystem,
+                events: events.clone(),
+                journal: journal.clone(),
+                trash_dir: trash_dir.clone(),
+            },
+            trash: TrashServiceImpl {
+                task_repository: SqliteTaskRepository,
+                task_history_repository: SqliteTaskHistoryRepository,
+                note_repository: SqliteNoteRepository,
+                fs: StdFileSystem,
+                settings: SqliteSettingsRepository,
+                events: events.clone(),
+                trash_dir,
+            },
+            vault: VaultServiceImpl {
+                repository: SqliteNoteRepository,
+                link_repository: SqliteLinkRepository,
+                fs: StdFileSystem,
+                events: events.clone(),
+                vault_dir: vault_dir.clone(),
+            },
+            attachment: AttachmentServiceImpl {
+                repository: SqliteAttachmentRepository,
+                task_repository: SqliteTaskRepository,
+                note_repository: SqliteNoteRepository,
+                fs: StdFileSystem,
+                attachments_dir: attachments_dir.clone(),
+            },
+            render: RenderServiceImpl {
+                repository: SqliteNoteRepository,
+                fs: StdFileSystem,
+                vault_dir: vault_dir.clone(),
+                cache: RenderCache::default(),
+            },
+            note_export: NoteExportServiceImpl {
+                repository: SqliteNoteRepository,
+                attachment_repository: SqliteAttachmentRepository,
+                fs: StdFileSystem,
+                vault_dir,
+                attachments_dir,
+            },
+            note_task: note_task_service(&events, &journal),
+            scoring: ScoringServiceImpl {
+                task_repository: SqliteTaskRepository,
+                settings: SqliteSettingsRepository,
+            },
+            time_tracking: TimeTrackingServiceImpl {
+                task_repository: SqliteTaskRepository,
+                repository: SqliteTimeEntryRepository,
+            },
+            planner: planner_service(),
+            calendar: CalendarServiceImpl {
+                repository: SqliteCalendarEventRepository,
+                task_repository: SqliteTaskRepository,
+            },
+            caldav: CalDavServiceImpl {
+                task_repository: SqliteTaskRepository,
+                task_history_repository: SqliteTaskHistoryRepository,
+                event_repository: SqliteCalendarEventRepository,
+                repository: SqliteCalDavRepository,
+                settings: SqliteSettingsRepository,
+                tasks: task_service(&events, &journal),
+                events: events.clone(),
+                journal: journal.clone(),
+            },
+        }
+    }
+}
+
+fn task_service(events: &EventBus, journal: &Journal) -> AppTaskService {
+    TaskServiceImpl {
+        repository: SqliteTaskRepository,
+        history_repository: SqliteTaskHistoryRepository,
+        events: events.clone(),
+        journal: journal.clone(),
+        checkboxes: CheckboxWriter {
+            note_repository: SqliteNoteRepository,
+            repository: SqliteNoteTaskRepository,
+            revision_repository: SqliteNoteRevisionRepository,
+            revision_policy: RevisionPolicy::default(),
+            fs: StdFileSystem,
+        },
+        planner: planner_service(),
+    }
+}
+
+fn planner_service() -> AppPlannerService {
+    PlannerServiceImpl {
+        task_repository: SqliteTaskRepository,
+        time_entry_repository: SqliteTimeEntryRepository,
+        repository: SqliteScheduleBlockRepository,
+        event_repository: SqliteCalendarEventRepository,
+        settings: SqliteSettingsRepository,
+    }
+}
+
+fn note_task_service(events: &EventBus, journal: &Journal) -> AppNoteTaskService {
+    NoteTaskServiceImpl {
+        note_repository: SqliteNoteRepository,
+        task_repository: SqliteTaskRepository,
+        repository: SqliteNoteTaskRepository,
+        fs: StdFileSystem,
+        tasks: task_service(events, journal),
+    }
+}
@@ -1,36 +1,103 @@
+use chrono::{Duration, Utc};
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
 use rusqlite::Connection;
-use std::fs::OpenOptions;
-use std::io::Write;
+use sha2::{Digest, Sha256};
+use similar::{ChangeTag, TextDiff};
+use crate::db::in_transaction;
+use crate::events::{DomainEvent, EventBus};
+use crate::fs::FileSystem;
+use crate::journal::{Changes, Journal};
+use crate::model::front_matter::FrontMatter;
+use crate::model::journal::Operation;
+use crate::model::link::{rename_note_links, title_key, LinkTarget};
 use crate::model::note::{Note, NotePayload};
-use crate::repository::note::{NoteRepository, SqliteNoteRepository};
-use std::path::Path;
+use crate::model::note_revision::{DiffLine, DiffTag, NoteDiff, NoteRevision, RevisionPolicy};
+use crate::model::note_task::NoteTaskSyncSummary;
+use crate::repository::link::LinkRepository;
+use crate::repository::note::NoteRepository;
+use crate::repository::note_revision::NoteRevisionRepository;
+use crate::services::note_task::NoteTaskService;
+use crate::services::trash::trash_note;
+use crate::services::vault::{absolute_path, vault_path};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
 
 pub trait NoteService {
     fn create_note(&self, conn: &Connection, payload: NotePayload) -> Result<(), String>;
     fn fetch_notes(&self, conn: &Connection) -> Result<Vec<Note>, String>;
+    // The markdown body of a note, without its front-matter
+    fn read_note(&self, conn: &Connection, id: i32) -> Result<String, String>;
+    // Saves the note's metadata and body. The metadata is written to the file's front-matter,
+    // so any front-matter in `new_content` is replaced.
     fn update_note(&self, conn: &Connection, note: Note, new_content: &str) -> Result<(), String>;
+    // Like update_note, and turns the body's `- [ ]` checkboxes into linked tasks in the same
+    // transaction. The note and task changes are undone together.
+    fn update_note_with_tasks(&self, conn: &Connection, note: Note, new_content: &str) -> Result<NoteTaskSyncSummary, String>;
     fn delete_note(&self, conn: &Connection, id: i32) -> Result<(), String>;
+    // Earlier versions of a note, newest first
+    fn list_revisions(&self, conn: &Connection, note_id: i32) -> Result<Vec<NoteRevision>, String>;
+    // Line diff from revision `from` to revision `to`, or to the current content when `to` is None
+    fn diff_revisions(&self, conn: &Connection, note_id: i32, from: i32, to: Option<i32>) -> Result<NoteDiff, String>;
+    // Makes a revision the current content; the content it replaces is kept as a new revision
+    fn restore_revision(&self, conn: &Connection, note_id: i32, revision_id: i32) -> Result<(), String>;
+    // Notes with a `[[Title]]` link to this note
+    fn fetch_backlinks(&self, conn: &Connection, note_id: i32) -> Result<Vec<Note>, String>;
+    // Notes with a `[[task:id]]` link to this task
+    fn fetch_task_backlinks(&self, conn: &Connection, task_id: i32) -> Result<Vec<Note>, String>;
 }
 
-pub struct NoteServiceImpl {
-    pub repository: SqliteNoteRepository,
+pub struct NoteServiceImpl<R: NoteRepository, V: NoteRevisionRepository, L: LinkRepository, F: FileSystem, T: NoteTaskService = ()> {
+    pub repository: R,
+    // Content overwritten by a save is kept here so it can be diffed and restored
+    pub revision_repository: V,
+    pub revision_policy: RevisionPolicy,
+    // `[[...]]` links found in each note's body, re-indexed on every save
+    pub link_repository: L,
+    pub fs: F,
+    pub events: EventBus,
+    pub journal: Journal,
+    // Note files live in this folder; paths outside it are rejected
+    pub vault_dir: PathBuf,
+    // Deleted notes keep their markdown file here until the trash is purged
+    pub trash_dir: PathBuf,
+    // Checkboxes become tasks through this when a note is saved with update_note_with_tasks
+    pub note_tasks: T,
 }
 
-impl NoteService for NoteServiceImpl {
+impl<R, V, L, F, T> NoteService for NoteServiceImpl<R, V, L, F, T>
+where
+    R: NoteRepository,
+    V: NoteRevisionRepository,
+    L: LinkRepository,
+    F: FileSystem,
+    T: NoteTaskService,
+{
     fn create_note(&self, conn: &Connection, payload: NotePayload) -> Result<(), String> {
+        let payload = NotePayload { file_path: vault_path(&self.vault_dir, Path::new(&payload.file_path))?, ..payload };
         let file_path = Path::new(&payload.file_path);
         if let Some(parent) = file_path.parent() {
-            if !parent.exists() {
-                std::fs::create_dir_all(parent)
+            if !self.fs.exists(parent) {
+                self.fs.create_dir_all(parent)
                     .map_err(|e| format!("Failed to create notes folder: {}", e))?;
             }
         }
-        OpenOptions::new()
-            .create(true)
-            .write(true)
-            .open(&file_path)
+        self.fs.create_file(file_path)
             .map_err(|e| format!("Failed to create markdown file: {}", e))?;
-        self.repository.create(conn, &payload).map_err(|e| e.to_string())
+        let id = self.repository.create(conn, &payload).map_err(|e| e.to_string())?;
+
+        if let Some(note) = self.fetch_note(conn, id)? {
+            // An existing file keeps its body; the front-matter is (re)written from the new note
+            let content = self.fs.read(file_path).map_err(|e| format!("Failed to read note file: {}", e))?;
+            let content = String::from_utf8_lossy(&content);
+            let body = FrontMatter::body(&content);
+            self.write_note_file(&note, body)?;
+            index_links(&self.link_repository, conn, id, body)?;
+            self.journal.record(Operation::NoteCreated { note: note.clone() });
+            self.events.publish(DomainEvent::NoteCreated(note));
+        }
+        Ok(())
     }
 
     fn fetch_notes(&self, conn: &Connection) -> Result<Vec<Note>, String> {
@@ -38,32 +105,301 @@ impl NoteService for NoteServiceImpl {
     }
 
     fn update_note(&self, conn: &Connection, note: Note, new_content: &str) -> Result<(), String> {
-        // Update title in DB
-        self.repository.update(conn, &note).map_err(|e| e.to_string())?;
-    
-        // Overwrite file content
-        let file_path = &note.file_path;
-        let mut file = OpenOptions::new().write(true).truncate(true).open(file_path)
-            .map_err(|e| format!("Failed to open note file: {}", e))?;
-        file.write_all(new_content.as_bytes())
-            .map_err(|e| format!("Failed to write note file: {}", e))?;
-    
+        let mut changes = Changes::new();
+        in_transaction(conn, |tx| self.save_note(tx, note, new_content, &mut changes))?;
+        changes.commit(&self.journal, &self.events);
         Ok(())
     }
 
+    fn update_note_with_tasks(&self, conn: &Connection, note: Note, new_content: &str) -> Result<NoteTaskSyncSummary, String> {
+        let id = note.id;
+        let mut note_changes = Changes::new();
+        let mut changes = Changes::new();
+        let summary = in_transaction(conn, |tx| {
+            self.save_note(tx, note, new_content, &mut note_changes)?;
+            self.note_tasks.sync_note(tx, id, &mut changes)
+        })?;
+        // Undo works backwards, so the note is restored first and its checkboxes already
+        // match when the tasks are restored
+        changes.extend(note_changes);
+        changes.commit(&self.journal, &self.events);
+        Ok(summary)
+    }
+
+    fn read_note(&self, conn: &Connection, id: i32) -> Result<String, String> {
+        let note = self.fetch_existing_note(conn, id)?;
+        let content = self.fs.read(Path::new(&note.file_path))
+            .map_err(|e| format!("Failed to read note file: {}", e))?;
+        Ok(FrontMatter::body(&String::from_utf8_lossy(&content)).to_string())
+    }
+
     fn delete_note(&self, conn: &Connection, id: i32) -> Result<(), String> {
-        let file_path = self.repository.get_file_path_by_id(conn, id)
-        .map_err(|e| format!("Failed to get file_path: {}", e))?;
-
-        // Delete the file if it exists
-        if let Some(path) = file_path {
-            let path = std::path::Path::new(&path);
-            if path.exists() {
-                std::fs::remove_file(path).ok();
+        let note = self.fetch_note(conn, id)
+            .map_err(|e| format!("Failed to get note: {}", e))?;
+
+        // Soft delete: the row is flagged and the file moves into the vault's trash folder,
+        // so the note can be restored until the trash is purged.
+        let mut changes = Changes::new();
+        if trash_note(&self.repository, &self.fs, &self.trash_dir, &mut changes, conn, id)? {
+            if let Some(note) = note {
+                changes.record(Operation::NoteDeleted { note });
             }
         }
+        changes.commit(&self.journal, &self.events);
+        Ok(())
+    }
+
+    fn list_revisions(&self, conn: &Connection, note_id: i32) -> Result<Vec<NoteRevision>, String> {
+        self.revision_repository.fetch_for_note(conn, note_id).map_err(|e| e.to_string())
+    }
+
+    fn diff_revisions(&self, conn: &Connection, note_id: i32, from: i32, to: Option<i32>) -> Result<NoteDiff, String> {
+        let old = self.revision_content(conn, note_id, from)?;
+        let new = match to {
+            Some(to) => self.revision_content(conn, note_id, to)?,
+            None => {
+                let note = self.fetch_existing_note(conn, note_id)?;
+                let content = self.fs.read(Path::new(&note.file_path))
+                    .map_err(|e| format!("Failed to read note file: {}", e))?;
+                FrontMatter::body(&String::from_utf8_lossy(&content)).to_string()
+            }
+        };
+        Ok(line_diff(&old, &new))
+    }
+
+    fn restore_revision(&self, conn: &Connection, note_id: i32, revision_id: i32) -> Result<(), String> {
+        let note = self.fetch_existing_note(conn, note_id)?;
+        let content = self.revision_content(conn, note_id, revision_id)?;
+        // Goes through a normal save so the restore is journaled and can be undone
+        self.update_note(conn, note, &content)
+    }
 
-        self.repository.delete(conn, id).map_err(|e| e.to_string())
+    fn fetch_backlinks(&self, conn: &Connection, note_id: i32) -> Result<Vec<Note>, String> {
+        let note = self.fetch_existing_note(conn, note_id)?;
+        let sources = self.link_sources(conn, &LinkTarget::Note(note.title))?;
+        Ok(sources.into_iter().filter(|source| source.id != note_id).collect())
     }
 
-}
\ No newline at end of file
+    fn fetch_task_backlinks(&self, conn: &Connection, task_id: i32) -> Result<Vec<Note>, String> {
+        self.link_sources(conn, &LinkTarget::Task(task_id))
+    }
+}
+
+impl<R, V, L, F, T> NoteServiceImpl<R, V, L, F, T>
+where
+    R: NoteRepository,
+    V: NoteRevisionRepository,
+    L: LinkRepository,
+    F: FileSystem,
+    T: NoteTaskService,
+{
+    fn fetch_note(&self, conn: &Connection, id: i32) -> Result<Option<Note>, String> {
+        self.repository.fetch_by_id(conn, id).map_err(|e| e.to_string())
+    }
+
+    // Saves metadata and body inside the caller's transaction
+    fn save_note(&self, conn: &Connection, note: Note, new_content: &str, changes: &mut Changes) -> Result<(), String> {
+        // Keep the previous metadata and file so the edit can be undone. Notes in the trash can't be edited.
+        let before = self.fetch_note(conn, note.id)?.ok_or_else(|| format!("Note {} not found", note.id))?;
+        // A new path is stored in the same form as a new note's
+        let note = if note.file_path == before.file_path {
+            note
+        } else {
+            Note { file_path: vault_path(&self.vault_dir, Path::new(&note.file_path))?, ..note }
+        };
+        let before_content = self.fs.read(Path::new(&before.file_path)).unwrap_or_default();
+        let body = FrontMatter::body(new_content);
+        // Revisions keep bodies only; metadata edits are tracked in the journal
+        let before_text = String::from_utf8_lossy(&before_content);
+        let before_body = FrontMatter::body(&before_text);
+        if before_body != body {
+            save_revision(&self.revision_repository, conn, note.id, before_body.as_bytes(), self.revision_policy)?;
+        }
+
+        // Update metadata in DB
+        self.repository.update(conn, &note).map_err(|e| e.to_string())?;
+        // A new path moves the file, so the old one isn't picked up again by the vault watcher
+        move_note_file(&self.fs, &before.file_path, &note.file_path)?;
+
+        // Re-read so the file and listeners get the timestamps set by the database
+        let after = self.fetch_note(conn, note.id)?.ok_or_else(|| format!("Note {} not found", note.id))?;
+        let after_content = self.write_note_file(&after, body)?;
+        index_links(&self.link_repository, conn, note.id, body)?;
+        let old_title = Some(before.title.clone()).filter(|title| *title != after.title);
+        changes.record(Operation::NoteUpdated { before, after: after.clone(), before_content, after_content });
+        changes.publish(DomainEvent::NoteUpdated(after.clone()));
+        // Recorded after the rename itself, so the undo step is labelled with this note
+        if let Some(old_title) = old_title {
+            self.rename_links(conn, &after, &old_title, changes)?;
+        }
+        Ok(())
+    }
+
+    // After `note` was renamed from `old_title`, rewrites `[[old_title]]` links in other notes'
+    // files so they keep pointing at it. Skipped while another note still has the old title, since
+    // those links may be meant for that note. Each rewrite is saved like an edit: the old body is
+    // kept as a revision, `updated_at` moves forward, links are re-indexed, and the edit is added to
+    // `changes` so it is undone together with the rename.
+    fn rename_links(&self, conn: &Connection, note: &Note, old_title: &str, changes: &mut Changes) -> Result<(), String> {
+        let notes = self.repository.fetch_all(conn).map_err(|e| e.to_string())?;
+        if notes.iter().any(|other| other.id != note.id && title_key(&other.title) == title_key(old_title)) {
+            return Ok(());
+        }
+        let sources = self.link_repository
+            .fetch_sources(conn, &LinkTarget::Note(old_title.to_string()))
+            .map_err(|e| e.to_string())?;
+        for source in notes.into_iter().filter(|source| sources.contains(&source.id)) {
+            let path = Path::new(&source.file_path);
+            // A note whose file is gone keeps its stale links until the file is back
+            let Ok(content) = self.fs.read(path) else {
+                continue;
+            };
+            let text = String::from_utf8_lossy(&content);
+            let body = FrontMatter::body(&text);
+            let Some(renamed) = rename_note_links(body, old_title, &note.title) else {
+                continue;
+            };
+            save_revision(&self.revision_repository, conn, source.id, body.as_bytes(), self.revision_policy)?;
+            self.repository.update(conn, &source).map_err(|e| e.to_string())?;
+            let after = self.repository.fetch_by_id(conn, source.id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Note {} not found", source.id))?;
+            let after_content = self.write_note_file(&after, &renamed)?;
+            index_links(&self.link_repository, conn, source.id, &renamed)?;
+            changes.record(Operation::NoteUpdated { before: source, after: after.clone(), before_content: content, after_content });
+            changes.publish(DomainEvent::NoteUpdated(after));
+        }
+        Ok(())
+    }
+
+    fn write_note_file(&self, note: &Note, body: &str) -> Result<Vec<u8>, String> {
+        write_note_file(&self.fs, note, body)
+    }
+
+    // Notes linking to `target`; notes in the trash are left out
+    fn link_sources(&self, conn: &Connection, target: &LinkTarget) -> Result<Vec<Note>, String> {
+        let ids = self.link_repository.fetch_sources(conn, target).map_err(|e| e.to_string())?;
+        let mut notes = Vec::new();
+        for id in ids {
+            notes.extend(self.fetch_note(conn, id)?);
+        }
+        Ok(notes)
+    }
+
+    fn fetch_existing_note(&self, conn: &Connection, id: i32) -> Result<Note, String> {
+        self.fetch_note(conn, id)?.ok_or_else(|| format!("Note {} not found", id))
+    }
+
+    fn revision_content(&self, conn: &Connection, note_id: i32, revision_id: i32) -> Result<String, String> {
+        let data = self.revision_repository.fetch_data(conn, note_id, revision_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Revision {} not found", revision_id))?;
+        let content = decompress(&data)?;
+        Ok(String::from_utf8_lossy(&content).into_owned())
+    }
+}
+
+// Stores `content` as the newest revision of a note unless it is identical to the
+// latest one, then applies the retention policy. Empty content is not worth keeping.
+pub fn save_revision(
+    repository: &impl NoteRevisionRepository,
+    conn: &Connection,
+    note_id: i32,
+    content: &[u8],
+    policy: RevisionPolicy,
+) -> Result<(), String> {
+    if content.is_empty() {
+        return Ok(());
+    }
+    let content_hash = format!("{:x}", Sha256::digest(content));
+    let latest = repository.fetch_latest(conn, note_id).map_err(|e| e.to_string())?;
+    if latest.is_some_and(|latest| latest.content_hash == content_hash) {
+        return Ok(());
+    }
+
+    let now = Utc::now().naive_utc();
+    let revision = NoteRevision {
+        id: 0,
+        note_id,
+        content_hash,
+        size: content.len() as i64,
+        created_at: now,
+    };
+    repository.create(conn, &revision, &compress(content)?).map_err(|e| e.to_string())?;
+    let cutoff = now - Duration::days(i64::from(policy.max_age_days));
+    repository.prune(conn, note_id, policy.max_count, cutoff).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// Writes `body` to the note's file under a front-matter built from `note`, keeping keys
+// that other tools added to the existing front-matter. Returns what was written.
+pub fn write_note_file(fs: &impl FileSystem, note: &Note, body: &str) -> Result<Vec<u8>, String> {
+    let path = Path::new(&note.file_path);
+    let existing = fs.read(path).ok().and_then(|content| FrontMatter::split(&String::from_utf8_lossy(&content)).0);
+    let content = FrontMatter::from_note(note, existing).render(body)?;
+    fs.write(path, content.as_bytes())
+        .map_err(|e| format!("Failed to write note file: {}", e))?;
+    Ok(content.into_bytes())
+}
+
+// Moves a note's markdown file when its path changes. Fails rather than overwrite a file that
+// is already at `to`; a note whose file is gone simply gets a new one when it is written.
+pub fn move_note_file(fs: &impl FileSystem, from: &str, to: &str) -> Result<(), String> {
+    let (from, to) = (Path::new(from), Path::new(to));
+    if absolute_path(from) == absolute_path(to) {
+        return Ok(());
+    }
+    if fs.exists(to) {
+        return Err(format!("A file already exists at {}", to.display()));
+    }
+    if let Some(parent) = to.parent().filter(|parent| !fs.exists(parent)) {
+        fs.create_dir_all(parent).map_err(|e| format!("Failed to create notes folder: {}", e))?;
+    }
+    if fs.exists(from) {
+        fs.rename(from, to).map_err(|e| format!("Failed to move note file: {}", e))?;
+    }
+    Ok(())
+}
+
+// Replaces the stored links of a note with the ones in its markdown body
+pub fn index_links(repository: &impl LinkRepository, conn: &Connection, note_id: i32, body: &str) -> Result<(), String> {
+    repository.replace_for_note(conn, note_id, &LinkTarget::extract(body)).map_err(|e| e.to_string())
+}
+
+fn compress(content: &[u8]) -> Result<Vec<u8>, String> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(content).map_err(|e| format!("Failed to compress revision: {}", e))?;
+    encoder.finish().map_err(|e| format!("Failed to compress revision: {}", e))
+}
+
+fn decompress(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut content = Vec::new();
+    ZlibDecoder::new(data)
+        .read_to_end(&mut content)
+        .map_err(|e| format!("Failed to read revision: {}", e))?;
+    Ok(content)
+}
+
+fn line_diff(old: &str, new: &str) -> NoteDiff {
+    let mut diff = NoteDiff { lines: Vec::new(), insertions: 0, deletions: 0 };
+    for change in TextDiff::from_lines(old, new).iter_all_changes() {
+        let tag = match change.tag() {
+            ChangeTag::Equal => DiffTag::Equal,
+            ChangeTag::Insert => {
+                diff.insertions += 1;
+                DiffTag::Insert
+            }
+            ChangeTag::Delete => {
+                diff.deletions += 1;
+                DiffTag::Delete
+            }
+        };
+        diff.lines.push(DiffLine {
+            tag,
+            old_line: change.old_index().map(|i| i + 1),
+            new_line: change.new_index().map(|i| i + 1),
+            text: change.value().trim_end_matches(['\r', '\n']).to_string(),
+        });
+    }
+    diff
+}
@@ -0,0 +1,371 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use pulldown_cmark::{html, Event, HeadingLevel, Tag, TagEnd};
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+use crate::fs::FileSystem;
+use crate::model::front_matter::FrontMatter;
+use crate::model::note::Note;
+use crate::model::note_export::{NoteExportFormat, NoteExportSummary};
+use crate::pdf::{Block, FontStyle, PdfDocument, PdfImage, Span};
+use crate::repository::attachment::AttachmentRepository;
+use crate::repository::note::NoteRepository;
+use crate::services::attachment::blob_path;
+use crate::services::render::{classify_url, note_folder, parse_markdown, sanitize, vault_file, UrlTarget, ID_PREFIX};
+
+// Headings up to this level (the note title being level 1) are listed in the table of contents
+const TOC_MAX_LEVEL: usize = 3;
+
+const STYLESHEET: &str = "
+body { font-family: -apple-system, 'Segoe UI', Helvetica, Arial, sans-serif; line-height: 1.6; color: #222; max-width: 48rem; margin: 2rem auto; padding: 0 1rem; }
+nav.toc ul { list-style: none; padding-left: 0; }
+nav.toc li.level-2 { padding-left: 1.2rem; }
+nav.toc li.level-3 { padding-left: 2.4rem; }
+article { border-top: 1px solid #ddd; margin-top: 2.5rem; }
+img { max-width: 100%; }
+pre { background: #f5f5f5; padding: 0.8rem; overflow-x: auto; }
+code { font-family: 'SFMono-Regular', Consolas, monospace; font-size: 0.9em; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 0.3rem 0.6rem; }
+blockquote { border-left: 3px solid #ccc; margin-left: 0; padding-left: 1rem; color: #555; }
+.footnote-definition { font-size: 0.9em; }
+@media print { article { page-break-before: always; border-top: none; } }
+";
+
+pub trait NoteExportService {
+    // Writes the notes, in the given order, to one standalone file at `dest`
+    fn export_notes(&self, conn: &Connection, note_ids: &[i32], format: NoteExportFormat, dest: &Path) -> Result<NoteExportSummary, String>;
+}
+
+pub struct NoteExportServiceImpl<N: NoteRepository, A: AttachmentRepository, F: FileSystem> {
+    pub repository: N,
+    // `attachment:<id>` images are read from the attachments folder
+    pub attachment_repository: A,
+    pub fs: F,
+    pub vault_dir: PathBuf,
+    pub attachments_dir: PathBuf,
+}
+
+// A note ready for export: its row, markdown body and folder inside the vault
+struct ExportedNote {
+    note: Note,
+    body: String,
+    folder: PathBuf,
+}
+
+impl<N: NoteRepository, A: AttachmentRepository, F: FileSystem> NoteExportService for NoteExportServiceImpl<N, A, F> {
+    fn export_notes(&self, conn: &Connection, note_ids: &[i32], format: NoteExportFormat, dest: &Path) -> Result<NoteExportSummary, String> {
+        if note_ids.is_empty() {
+            return Err("No notes to export".to_string());
+        }
+        let mut notes = Vec::with_capacity(note_ids.len());
+        for id in note_ids {
+            let note = self.repository.fetch_by_id(conn, *id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Note {} not found", id))?;
+            let content = self.fs.read(Path::new(&note.file_path))
+                .map_err(|e| format!("Failed to read note file: {}", e))?;
+            let body = FrontMatter::body(&String::from_utf8_lossy(&content)).to_string();
+            let folder = note_folder(&self.vault_dir, Path::new(&note.file_path));
+            notes.push(ExportedNote { note, body, folder });
+        }
+        let title = match notes.as_slice() {
+            [single] => single.note.title.clone(),
+            _ => "Notes".to_string(),
+        };
+
+        let mut summary = NoteExportSummary { notes_exported: notes.len(), ..Default::default() };
+        let bytes = match format {
+            NoteExportFormat::Html => self.html_document(conn, &title, &notes, &mut summary).into_bytes(),
+            NoteExportFormat::Pdf => self.pdf_document(conn, title, &notes, &mut summary).render(),
+        };
+        self.fs.write(dest, &bytes).map_err(|e| format!("Failed to write export: {}", e))?;
+        Ok(summary)
+    }
+}
+
+impl<N: NoteRepository, A: AttachmentRepository, F: FileSystem> NoteExportServiceImpl<N, A, F> {
+    // Mime type and contents of an image in the vault or the attachments folder
+    fn load_image(&self, conn: &Connection, url: &str, folder: &Path) -> Option<(String, Vec<u8>)> {
+        match classify_url(url, folder) {
+            UrlTarget::Vault(segments) => {
+                let path = vault_file(&self.vault_dir, &segments)?;
+                let data = self.fs.read(&path).ok()?;
+                Some((mime_guess::from_path(&path).first_or_octet_stream().essence_str().to_string(), data))
+            }
+            UrlTarget::Attachment(id) => {
+                let attachment = self.attachment_repository.fetch_by_id(conn, id.parse().ok()?).ok()??;
+                let data = self.fs.read(&blob_path(&self.attachments_dir, &attachment.content_hash)).ok()?;
+                Some((attachment.mime_type, data))
+            }
+            _ => None,
+        }
+    }
+
+    fn html_document(&self, conn: &Connection, title: &str, notes: &[ExportedNote], summary: &mut NoteExportSummary) -> String {
+        let mut toc = String::new();
+        let mut articles = String::new();
+        for (index, exported) in notes.iter().enumerate() {
+            let prefix = format!("{}{}-", ID_PREFIX, index + 1);
+            let title_id = format!("{}title", prefix);
+            toc.push_str(&format!("<li class=\"level-1\"><a href=\"#{}\">{}</a></li>\n", title_id, escape_html(&exported.note.title)));
+
+            let mut events: Vec<Event> = parse_markdown(&exported.body, &prefix).collect();
+            let mut headings = 0;
+            let mut heading = None;
+            let mut heading_text = String::new();
+            for event in events.iter_mut() {
+                match event {
+                    // Body headings go one level down, under the note's title
+                    Event::Start(Tag::Heading { level, id, .. }) => {
+                        headings += 1;
+                        let anchor = format!("{}h{}", prefix, headings);
+                        *id = Some(anchor.clone().into());
+                        *level = demote(*level);
+                        heading = Some((*level as usize, anchor));
+                        heading_text.clear();
+                    }
+                    Event::Text(text) | Event::Code(text) if heading.is_some() => heading_text.push_str(text),
+                    Event::End(TagEnd::Heading(level)) => {
+                        *level = demote(*level);
+                        if let Some((level, anchor)) = heading.take().filter(|(level, _)| *level <= TOC_MAX_LEVEL) {
+                            toc.push_str(&format!(
+                                "<li class=\"level-{}\"><a href=\"#{}\">{}</a></li>\n",
+                                level, anchor, escape_html(&heading_text),
+                            ));
+                        }
+                    }
+                    Event::Start(Tag::Link { dest_url, .. }) => {
+                        *dest_url = match classify_url(dest_url, &exported.folder) {
+                            UrlTarget::Fragment(fragment) => format!("#{}{}", prefix, fragment).into(),
+                            UrlTarget::External(url) => url.into(),
+                            // Vault files and attachments can't be followed from an exported file
+                            _ => "".into(),
+                        };
+                    }
+                    Event::Start(Tag::Image { dest_url, .. }) => {
+                        *dest_url = match classify_url(dest_url, &exported.folder) {
+                            UrlTarget::External(url) => url.into(),
+                            _ => match self.load_image(conn, dest_url, &exported.folder) {
+                                Some((mime, data)) => {
+                                    summary.images_embedded += 1;
+                                    format!("data:{};base64,{}", mime, STANDARD.encode(data)).into()
+                                }
+                                None => {
+                                    summary.images_skipped += 1;
+                                    "".into()
+                                }
+                            },
+                        };
+                    }
+                    _ => {}
+                }
+            }
+            let mut body = String::new();
+            html::push_html(&mut body, events.into_iter());
+            articles.push_str(&format!(
+                "<article>\n<h1 id=\"{}\">{}</h1>\n{}</article>\n",
+                title_id, escape_html(&exported.note.title), sanitize(&body, true),
+            ));
+        }
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n\
+             <nav class=\"toc\">\n<h2>Contents</h2>\n<ul>\n{}</ul>\n</nav>\n{}</body>\n</html>\n",
+            escape_html(title), STYLESHEET, toc, articles,
+        )
+    }
+
+    fn pdf_document(&self, conn: &Connection, title: String, notes: &[ExportedNote], summary: &mut NoteExportSummary) -> PdfDocument {
+        let mut builder = BlockBuilder::default();
+        for exported in notes {
+            builder.blocks.push(Block::PageBreak);
+            builder.blocks.push(Block::Heading { level: 1, text: exported.note.title.clone() });
+            for event in parse_markdown(&exported.body, "") {
+                if let Event::End(TagEnd::Image) = event {
+                    let url = builder.image.take().unwrap_or_default();
+                    match self.load_image(conn, &url, &exported.folder).and_then(|(_, data)| PdfImage::decode(&data)) {
+                        Some(image) => {
+                            summary.images_embedded += 1;
+                            builder.blocks.push(Block::Image(image));
+                        }
+                        None => summary.images_skipped += 1,
+                    }
+                    continue;
+                }
+                builder.event(event);
+            }
+            builder.flush();
+        }
+        PdfDocument { title, blocks: builder.blocks }
+    }
+}
+
+// Turns markdown events into PDF blocks. Raw HTML is left out.
+#[derive(Default)]
+struct BlockBuilder {
+    blocks: Vec<Block>,
+    spans: Vec<Span>,
+    bold: usize,
+    italic: usize,
+    // Next number of each open list, None for bullet lists
+    lists: Vec<Option<u64>>,
+    quote: usize,
+    marker: Option<String>,
+    heading: Option<u8>,
+    code: Option<String>,
+    // Url of the image being read; its alt text is skipped
+    image: Option<String>,
+    table: Option<Vec<Vec<Vec<Span>>>>,
+    row: Vec<Vec<Span>>,
+}
+
+impl BlockBuilder {
+    fn style(&self) -> FontStyle {
+        match (self.bold > 0, self.italic > 0) {
+            (true, true) => FontStyle::BoldItalic,
+            (true, false) => FontStyle::Bold,
+            (false, true) => FontStyle::Italic,
+            (false, false) => FontStyle::Regular,
+        }
+    }
+
+    fn push_text(&mut self, text: &str, style: FontStyle) {
+        if self.image.is_some() {
+            return;
+        }
+        match self.code.as_mut() {
+            Some(code) => code.push_str(text),
+            None => self.spans.push(Span::new(text, style)),
+        }
+    }
+
+    // Ends the current paragraph, if it has any text
+    fn flush(&mut self) {
+        if self.spans.iter().all(|span| span.text.trim().is_empty()) {
+            self.spans.clear();
+            return;
+        }
+        self.blocks.push(Block::Text {
+            spans: std::mem::take(&mut self.spans),
+            indent: self.lists.len() + self.quote,
+            marker: self.marker.take(),
+            quote: self.quote > 0,
+        });
+    }
+
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                self.flush();
+                self.heading = Some(level as u8 + 1);
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                let text: String = self.spans.drain(..).map(|span| span.text).collect();
+                let level = self.heading.take().unwrap_or(2);
+                self.blocks.push(Block::Heading { level, text });
+            }
+            Event::End(TagEnd::Paragraph) => self.flush(),
+            Event::Start(Tag::List(start)) => {
+                self.flush();
+                self.lists.push(start);
+            }
+            Event::End(TagEnd::List(_)) => {
+                self.flush();
+                self.lists.pop();
+            }
+            Event::Start(Tag::Item) => {
+                self.flush();
+                self.marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        Some(format!("{}.", *number - 1))
+                    }
+                    _ => Some("\u{2022}".to_string()),
+                };
+            }
+            Event::End(TagEnd::Item) => self.flush(),
+            Event::TaskListMarker(checked) => {
+                self.marker = Some(if checked { "[x]" } else { "[ ]" }.to_string());
+            }
+            Event::Start(Tag::BlockQuote(_)) => {
+                self.flush();
+                self.quote += 1;
+            }
+            Event::End(TagEnd::BlockQuote(_)) => {
+                self.flush();
+                self.quote = self.quote.saturating_sub(1);
+            }
+            Event::Start(Tag::FootnoteDefinition(name)) => {
+                self.flush();
+                self.marker = Some(format!("[{}]", name));
+                self.quote += 1;
+            }
+            Event::End(TagEnd::FootnoteDefinition) => {
+                self.flush();
+                self.quote = self.quote.saturating_sub(1);
+            }
+            Event::Start(Tag::CodeBlock(_)) => {
+                self.flush();
+                self.code = Some(String::new());
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                let code = self.code.take().unwrap_or_default();
+                self.blocks.push(Block::Code(code));
+            }
+            Event::Start(Tag::Emphasis) => self.italic += 1,
+            Event::End(TagEnd::Emphasis) => self.italic = self.italic.saturating_sub(1),
+            Event::Start(Tag::Strong) => self.bold += 1,
+            Event::End(TagEnd::Strong) => self.bold = self.bold.saturating_sub(1),
+            Event::Start(Tag::Image { dest_url, .. }) => {
+                self.flush();
+                self.image = Some(dest_url.to_string());
+            }
+            Event::Start(Tag::Table(_)) => {
+                self.flush();
+                self.table = Some(Vec::new());
+            }
+            Event::End(TagEnd::TableCell) => {
+                let cell = std::mem::take(&mut self.spans);
+                self.row.push(cell);
+            }
+            Event::End(TagEnd::TableHead | TagEnd::TableRow) => {
+                let row = std::mem::take(&mut self.row);
+                if let Some(table) = self.table.as_mut() {
+                    table.push(row);
+                }
+            }
+            Event::End(TagEnd::Table) => {
+                let rows = self.table.take().unwrap_or_default();
+                self.blocks.push(Block::Table { rows, header: true });
+            }
+            Event::Rule => {
+                self.flush();
+                self.blocks.push(Block::Rule);
+            }
+            Event::Text(text) => {
+                let style = self.style();
+                self.push_text(&text, style);
+            }
+            Event::Code(text) => self.push_text(&text, FontStyle::Mono),
+            Event::FootnoteReference(name) => {
+                let style = self.style();
+                self.push_text(&format!("[{}]", name), style);
+            }
+            Event::SoftBreak => self.push_text(" ", FontStyle::Regular),
+            Event::HardBreak => self.push_text("\n", FontStyle::Regular),
+            _ => {}
+        }
+    }
+}
+
+fn demote(level: HeadingLevel) -> HeadingLevel {
+    HeadingLevel::try_from(level as usize + 1).unwrap_or(HeadingLevel::H6)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
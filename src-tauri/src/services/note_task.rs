@@ -0,0 +1,217 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use std::path::Path;
+use crate::events::DomainEvent;
+use crate::fs::FileSystem;
+use crate::journal::Changes;
+use crate::model::front_matter::FrontMatter;
+use crate::model::journal::Operation;
+use crate::model::note_revision::RevisionPolicy;
+use crate::model::note_task::{set_checkbox, Checkbox, NoteTask, NoteTaskSyncSummary};
+use crate::model::task::{Category, Priority, Task};
+use crate::repository::note::NoteRepository;
+use crate::repository::note_revision::NoteRevisionRepository;
+use crate::repository::note_task::NoteTaskRepository;
+use crate::repository::task::TaskRepository;
+use crate::services::note::{save_revision, write_note_file};
+use crate::services::task::TaskService;
+
+pub trait NoteTaskService {
+    // Makes every `- [ ]` checkbox in the note's body a task. Checkboxes already linked to a
+    // task update its title and completion instead, so saving again creates no duplicates.
+    // Runs inside the caller's transaction (NoteService saving the note); the task changes are
+    // added to `changes`.
+    fn sync_note(&self, conn: &Connection, note_id: i32, changes: &mut Changes) -> Result<NoteTaskSyncSummary, String>;
+    // The note and line a task was created from
+    fn fetch_task_source(&self, conn: &Connection, task_id: i32) -> Result<Option<NoteTask>, String>;
+}
+
+// Notes without checkbox extraction
+impl NoteTaskService for () {
+    fn sync_note(&self, _conn: &Connection, _note_id: i32, _changes: &mut Changes) -> Result<NoteTaskSyncSummary, String> {
+        Err("Turning checkboxes into tasks is not available".to_string())
+    }
+
+    fn fetch_task_source(&self, _conn: &Connection, _task_id: i32) -> Result<Option<NoteTask>, String> {
+        Ok(None)
+    }
+}
+
+pub struct NoteTaskServiceImpl<N, T, K, F, S>
+where
+    N: NoteRepository,
+    T: TaskRepository,
+    K: NoteTaskRepository,
+    F: FileSystem,
+    S: TaskService,
+{
+    pub note_repository: N,
+    // Linked tasks are read here, including the ones in the trash
+    pub task_repository: T,
+    pub repository: K,
+    pub fs: F,
+    // Tasks are created and updated through TaskService, so they get history, journal
+    // entries and events like any other edit
+    pub tasks: S,
+}
+
+impl<N, T, K, F, S> NoteTaskService for NoteTaskServiceImpl<N, T, K, F, S>
+where
+    N: NoteRepository,
+    T: TaskRepository,
+    K: NoteTaskRepository,
+    F: FileSystem,
+    S: TaskService,
+{
+    fn sync_note(&self, conn: &Connection, note_id: i32, changes: &mut Changes) -> Result<NoteTaskSyncSummary, String> {
+        let note = self.note_repository.fetch_by_id(conn, note_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Note {} not found", note_id))?;
+        let content = self.fs.read(Path::new(&note.file_path))
+            .map_err(|e| format!("Failed to read note file: {}", e))?;
+        let checkboxes = Checkbox::extract(FrontMatter::body(&String::from_utf8_lossy(&content)));
+        let existing = self.repository.fetch_for_note(conn, note_id).map_err(|e| e.to_string())?;
+
+        // Match on the text first, so moved lines keep their task, then on the line, for
+        // checkboxes whose text was edited in place
+        let mut used = vec![false; existing.len()];
+        let mut matches: Vec<Option<usize>> = vec![None; checkboxes.len()];
+        for same_text in [true, false] {
+            for (checkbox, matched) in checkboxes.iter().zip(matches.iter_mut()).filter(|(_, m)| m.is_none()) {
+                *matched = (0..existing.len()).find(|&i| {
+                    !used[i] && if same_text { existing[i].text == checkbox.text } else { existing[i].line == checkbox.line }
+                });
+                if let Some(i) = *matched {
+                    used[i] = true;
+                }
+            }
+        }
+
+        let now = Utc::now().naive_utc();
+        let mut summary = NoteTaskSyncSummary::default();
+        let mut links = Vec::with_capacity(checkboxes.len());
+        for (checkbox, matched) in checkboxes.into_iter().zip(matches) {
+            let task_id = match matched.map(|i| existing[i].task_id) {
+                Some(task_id) => {
+                    // A task in the trash stays linked, so its checkbox doesn't bring it back as a new task
+                    let before = self.task_repository.fetch_by_id(conn, task_id).map_err(|e| e.to_string())?;
+                    if let Some(before) = before.filter(|t| t.title != checkbox.text || t.completed != checkbox.checked) {
+                        let after = Task { title: checkbox.text.clone(), completed: checkbox.checked, updated_at: now, ..before };
+                        self.tasks.save_task(conn, after, changes)?;
+                        summary.tasks_updated += 1;
+                    }
+                    task_id
+                }
+                None => {
+                    let task = Task {
+                        id: 0,
+                        title: checkbox.text.clone(),
+                        category: Category::Study,
+                        priority: Priority::Medium,
+                        deadline: None,
+                        completed: checkbox.checked,
+                        created_at: now,
+                        updated_at: now,
+                        estimated_minutes: None,
+                    };
+                    let task = self.tasks.insert_task(conn, task, changes)?;
+                    summary.tasks_created += 1;
+                    task.id
+                }
+            };
+            links.push(NoteTask { task_id, note_id, line: checkbox.line, text: checkbox.text });
+        }
+        summary.tasks_unlinked = used.iter().filter(|used| !**used).count();
+        self.repository.replace_for_note(conn, note_id, &links).map_err(|e| e.to_string())?;
+        Ok(summary)
+    }
+
+    fn fetch_task_source(&self, conn: &Connection, task_id: i32) -> Result<Option<NoteTask>, String> {
+        self.repository.fetch_for_task(conn, task_id).map_err(|e| e.to_string())
+    }
+}
+
+pub trait TaskCheckboxSync {
+    // Ticks or clears the checkbox a task was created from so it matches the task's
+    // completion. Tasks that didn't come from a checkbox are left alone. Runs inside
+    // TaskService's transaction; the note edit is added to `changes`.
+    fn sync_checkbox(&self, conn: &Connection, task: &Task, changes: &mut Changes) -> Result<(), String>;
+}
+
+// Tasks without notes to write back to
+impl TaskCheckboxSync for () {
+    fn sync_checkbox(&self, _conn: &Connection, _task: &Task, _changes: &mut Changes) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+// Writes task completion back to the note, saved like any other note edit: the previous body
+// is kept as a revision and the note's `updated_at` moves forward
+pub struct CheckboxWriter<N, K, V, F>
+where
+    N: NoteRepository,
+    K: NoteTaskRepository,
+    V: NoteRevisionRepository,
+    F: FileSystem,
+{
+    pub note_repository: N,
+    pub repository: K,
+    pub revision_repository: V,
+    pub revision_policy: RevisionPolicy,
+    pub fs: F,
+}
+
+impl<N, K, V, F> TaskCheckboxSync for CheckboxWriter<N, K, V, F>
+where
+    N: NoteRepository,
+    K: NoteTaskRepository,
+    V: NoteRevisionRepository,
+    F: FileSystem,
+{
+    fn sync_checkbox(&self, conn: &Connection, task: &Task, changes: &mut Changes) -> Result<(), String> {
+        let Some(link) = self.repository.fetch_for_task(conn, task.id).map_err(|e| e.to_string())? else {
+            return Ok(());
+        };
+        // Notes in the trash aren't edited
+        let Some(note) = self.note_repository.fetch_by_id(conn, link.note_id).map_err(|e| e.to_string())? else {
+            return Ok(());
+        };
+        let path = Path::new(&note.file_path);
+        let before_content = self.fs.read(path).map_err(|e| format!("Failed to read note file: {}", e))?;
+        let content = String::from_utf8_lossy(&before_content);
+        let body = FrontMatter::body(&content);
+
+        // The note may have been edited without a sync since; look for the text if the line moved
+        let checkboxes = Checkbox::extract(body);
+        let Some(checkbox) = checkboxes.iter()
+            .find(|checkbox| checkbox.line == link.line && checkbox.text == link.text)
+            .or_else(|| checkboxes.iter().find(|checkbox| checkbox.text == link.text))
+        else {
+            return Ok(());
+        };
+        if checkbox.checked == task.completed {
+            return Ok(());
+        }
+        let Some(new_body) = set_checkbox(body, checkbox.line, task.completed) else {
+            return Ok(());
+        };
+
+        save_revision(&self.revision_repository, conn, note.id, body.as_bytes(), self.revision_policy)?;
+        self.note_repository.update(conn, &note).map_err(|e| e.to_string())?;
+        let after = self.note_repository.fetch_by_id(conn, note.id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Note {} not found", note.id))?;
+        let after_content = write_note_file(&self.fs, &after, &new_body)?;
+
+        if checkbox.line != link.line {
+            let mut links = self.repository.fetch_for_note(conn, note.id).map_err(|e| e.to_string())?;
+            for other in links.iter_mut().filter(|other| other.task_id == task.id) {
+                other.line = checkbox.line;
+            }
+            self.repository.replace_for_note(conn, note.id, &links).map_err(|e| e.to_string())?;
+        }
+        changes.record(Operation::NoteUpdated { before: note, after: after.clone(), before_content, after_content });
+        changes.publish(DomainEvent::NoteUpdated(after));
+        Ok(())
+    }
+}
@@ -0,0 +1,390 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use rusqlite::Connection;
+use crate::db::in_transaction;
+use crate::model::calendar_event::CalendarEvent;
+use crate::model::planner::{PlanResult, PlannerConfig, ScheduleBlock, PLANNER_SETTINGS_KEY};
+use crate::model::task::Task;
+use crate::repository::calendar_event::CalendarEventRepository;
+use crate::repository::schedule_block::ScheduleBlockRepository;
+use crate::repository::settings::SettingsRepository;
+use crate::repository::task::TaskRepository;
+use crate::repository::time_entry::TimeEntryRepository;
+use crate::services::scoring::{load_scoring_weights, rank_tasks};
+
+// Usulan jadwal paling panjang sebulan
+const MAX_PLAN_DAYS: u32 = 31;
+// Reflow boleh mendorong blok sampai seminggu setelah blok terakhir
+const REFLOW_EXTRA_DAYS: i64 = 7;
+
+pub trait PlannerService {
+    // Usulan jadwal untuk `days` hari mulai `from`. Task yang belum selesai diurutkan dengan skor
+    // prioritas lalu diisi ke jam kerja yang kosong. Usulan belum disimpan sampai diterima lewat save_blocks.
+    fn propose(&self, conn: &Connection, from: NaiveDate, days: u32, now: NaiveDateTime) -> Result<PlanResult, String>;
+    // Simpan blok task yang diterima; blok tidak boleh bertumpuk dengan blok lain atau acara berjam
+    fn save_blocks(&self, conn: &Connection, blocks: Vec<ScheduleBlock>) -> Result<Vec<ScheduleBlock>, String>;
+    // Blok yang bersinggungan dengan `from`..=`to`
+    fn fetch_schedule(&self, conn: &Connection, from: NaiveDate, to: NaiveDate) -> Result<Vec<ScheduleBlock>, String>;
+    fn delete_block(&self, conn: &Connection, id: i32) -> Result<(), String>;
+    // Susun ulang blok task yang belum dimulai mulai `now`: blok task yang sudah selesai dilepas,
+    // task yang semua bloknya sudah lewat tapi belum selesai dijadwalkan lagi lebih dulu.
+    fn reflow(&self, conn: &Connection, now: NaiveDateTime) -> Result<PlanResult, String>;
+    fn load_config(&self, conn: &Connection) -> Result<PlannerConfig, String>;
+    fn save_config(&self, conn: &Connection, config: &PlannerConfig) -> Result<(), String>;
+}
+
+pub struct PlannerServiceImpl<T, E, B, V, S>
+where
+    T: TaskRepository,
+    E: TimeEntryRepository,
+    B: ScheduleBlockRepository,
+    V: CalendarEventRepository,
+    S: SettingsRepository,
+{
+    pub task_repository: T,
+    // Waktu yang sudah dicatat mengurangi sisa perkiraan task
+    pub time_entry_repository: E,
+    pub repository: B,
+    // Acara kalender berjam (termasuk pengulangannya) tidak diisi task
+    pub event_repository: V,
+    pub settings: S,
+}
+
+// Task yang akan dijadwalkan beserta sisa waktunya
+struct PlanItem {
+    task_id: i32,
+    title: String,
+    minutes: i64,
+}
+
+impl<T, E, B, V, S> PlannerService for PlannerServiceImpl<T, E, B, V, S>
+where
+    T: TaskRepository,
+    E: TimeEntryRepository,
+    B: ScheduleBlockRepository,
+    V: CalendarEventRepository,
+    S: SettingsRepository,
+{
+    fn propose(&self, conn: &Connection, from: NaiveDate, days: u32, now: NaiveDateTime) -> Result<PlanResult, String> {
+        if days == 0 || days > MAX_PLAN_DAYS {
+            return Err(format!("A plan covers between 1 and {} days", MAX_PLAN_DAYS));
+        }
+        let config = self.load_config(conn)?;
+        let weights = load_scoring_weights(&self.settings, conn)?;
+        let tasks = self.task_repository.fetch_all(conn).map_err(|e| e.to_string())?;
+        let blocks = self.repository.fetch_all(conn).map_err(|e| e.to_string())?;
+        let busy = self.busy_times(conn, &blocks, &tasks, from, days)?;
+
+        // Task yang sudah punya blok yang belum lewat tidak diusulkan lagi
+        let unplanned = tasks.into_iter()
+            .filter(|task| !blocks.iter().any(|block| block.task_id == task.id && block.end > now))
+            .collect();
+        let mut items = Vec::new();
+        for scored in rank_tasks(unplanned, &weights, &local_instant(now), usize::MAX) {
+            items.push(PlanItem {
+                minutes: self.remaining_minutes(conn, &scored.task, &config)?,
+                task_id: scored.task.id,
+                title: scored.task.title,
+            });
+        }
+        let mut slots = free_slots(&config, from, days, now, &busy);
+        Ok(fill_slots(&config, &mut slots, items))
+    }
+
+    fn save_blocks(&self, conn: &Connection, blocks: Vec<ScheduleBlock>) -> Result<Vec<ScheduleBlock>, String> {
+        in_transaction(conn, |tx| {
+            // Blok dibaca di dalam transaksi; blok task yang selesai atau di trash boleh ditimpa,
+            // sama seperti saat jadwal diusulkan
+            let all_blocks = self.repository.fetch_all(tx).map_err(|e| e.to_string())?;
+            let tasks = self.task_repository.fetch_all(tx).map_err(|e| e.to_string())?;
+            let mut existing: Vec<ScheduleBlock> = busy_blocks(&all_blocks, &tasks).into_iter().cloned().collect();
+            let mut saved = Vec::with_capacity(blocks.len());
+            for mut block in blocks {
+                if block.title.trim().is_empty() {
+                    return Err("Block title is empty".to_string());
+                }
+                if block.end <= block.start {
+                    return Err(format!("Block '{}' must end after it starts", block.title));
+                }
+                if self.task_repository.fetch_by_id(tx, block.task_id).map_err(|e| e.to_string())?.is_none() {
+                    return Err(format!("Task {} not found", block.task_id));
+                }
+                if let Some(other) = existing.iter().find(|other| other.start < block.end && other.end > block.start) {
+                    return Err(format!("Block '{}' overlaps '{}'", block.title, other.title));
+                }
+                if let Some(event) = self.timed_events(tx, block.start, block.end)?.first() {
+                    return Err(format!("Block '{}' overlaps '{}'", block.title, event.title));
+                }
+                block.id = self.repository.create(tx, &block).map_err(|e| e.to_string())?;
+                existing.push(block.clone());
+                saved.push(block);
+            }
+            Ok(saved)
+        })
+    }
+
+    fn fetch_schedule(&self, conn: &Connection, from: NaiveDate, to: NaiveDate) -> Result<Vec<ScheduleBlock>, String> {
+        if to < from {
+            return Err("Schedule end date is before its start date".to_string());
+        }
+        let end = to.succ_opt().ok_or("Schedule end date is out of range")?;
+        self.repository
+            .fetch_between(conn, from.and_time(NaiveTime::MIN), end.and_time(NaiveTime::MIN))
+            .map_err(|e| e.to_string())
+    }
+
+    fn delete_block(&self, conn: &Connection, id: i32) -> Result<(), String> {
+        self.repository.delete(conn, id).map_err(|e| e.to_string())
+    }
+
+    fn reflow(&self, conn: &Connection, now: NaiveDateTime) -> Result<PlanResult, String> {
+        let config = self.load_config(conn)?;
+        let tasks = self.task_repository.fetch_all(conn).map_err(|e| e.to_string())?;
+        let open_task = |id: i32| tasks.iter().find(|task| task.id == id && !task.completed);
+
+        // Blok yang belum dimulai disusun ulang; blok yang sudah lewat atau sedang berjalan tetap di tempatnya
+        let (pending, fixed): (Vec<ScheduleBlock>, Vec<ScheduleBlock>) = self.repository.fetch_all(conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .partition(|block| block.start >= now);
+
+        let mut items: Vec<PlanItem> = Vec::new();
+        // Task terlambat: semua bloknya sudah lewat (urut dari yang paling lama lewat)
+        for block in fixed.iter().rev() {
+            let Some(task) = open_task(block.task_id) else { continue };
+            let has_time_left = pending.iter().chain(&fixed).any(|other| other.task_id == block.task_id && other.end > now);
+            if !has_time_left && !items.iter().any(|item| item.task_id == task.id) {
+                items.insert(0, PlanItem {
+                    task_id: task.id,
+                    title: task.title.clone(),
+                    minutes: self.remaining_minutes(conn, task, &config)?,
+                });
+            }
+        }
+        // Lalu task yang sudah dijadwalkan, dengan urutan dan total durasi yang sama
+        for block in &pending {
+            let Some(task) = open_task(block.task_id) else { continue };
+            let minutes = (block.end - block.start).num_minutes();
+            match items.iter_mut().find(|item| item.task_id == task.id) {
+                Some(item) => item.minutes += minutes,
+                None => items.push(PlanItem { task_id: task.id, title: task.title.clone(), minutes }),
+            }
+        }
+        if pending.is_empty() && items.is_empty() {
+            return Ok(PlanResult::default());
+        }
+
+        let today = now.date();
+        let last_day = pending.iter().map(|block| block.end.date()).max().unwrap_or(today);
+        let days = ((last_day - today).num_days() + 1 + REFLOW_EXTRA_DAYS) as u32;
+        let busy = self.busy_times(conn, &fixed, &tasks, today, days)?;
+        let mut slots = free_slots(&config, today, days, now, &busy);
+        let mut result = fill_slots(&config, &mut slots, items);
+
+        // Bisa ikut transaksi TaskService saat reflow dipicu task yang selesai
+        in_transaction(conn, |tx| {
+            for block in &pending {
+                self.repository.delete(tx, block.id).map_err(|e| e.to_string())?;
+            }
+            for block in result.blocks.iter_mut() {
+                block.id = self.repository.create(tx, block).map_err(|e| e.to_string())?;
+            }
+            Ok(())
+        })?;
+        Ok(result)
+    }
+
+    fn load_config(&self, conn: &Connection) -> Result<PlannerConfig, String> {
+        let value = self.settings.get(conn, PLANNER_SETTINGS_KEY).map_err(|e| e.to_string())?;
+        match value {
+            Some(json) => serde_json::from_str(&json).map_err(|e| format!("Invalid planner settings: {}", e)),
+            None => Ok(PlannerConfig::default()),
+        }
+    }
+
+    fn save_config(&self, conn: &Connection, config: &PlannerConfig) -> Result<(), String> {
+        if config.day_end <= config.day_start {
+            return Err("Working hours must end after they start".to_string());
+        }
+        if config.work_days.is_empty() {
+            return Err("Pick at least one working day".to_string());
+        }
+        if config.default_task_minutes == 0 || config.min_block_minutes == 0 {
+            return Err("Block lengths must be at least one minute".to_string());
+        }
+        if config.max_block_minutes < config.min_block_minutes {
+            return Err("The longest block can't be shorter than the shortest block".to_string());
+        }
+        let json = serde_json::to_string(config).map_err(|e| e.to_string())?;
+        self.settings.set(conn, PLANNER_SETTINGS_KEY, &json).map_err(|e| e.to_string())
+    }
+}
+
+// Reflow yang dijalankan TaskService ketika sebuah task berubah menjadi selesai
+pub trait ScheduleReflow {
+    fn reflow_schedule(&self, conn: &Connection, now: NaiveDateTime) -> Result<Option<PlanResult>, String>;
+}
+
+// Task tanpa planner
+impl ScheduleReflow for () {
+    fn reflow_schedule(&self, _conn: &Connection, _now: NaiveDateTime) -> Result<Option<PlanResult>, String> {
+        Ok(None)
+    }
+}
+
+impl<T, E, B, V, S> ScheduleReflow for PlannerServiceImpl<T, E, B, V, S>
+where
+    T: TaskRepository,
+    E: TimeEntryRepository,
+    B: ScheduleBlockRepository,
+    V: CalendarEventRepository,
+    S: SettingsRepository,
+{
+    fn reflow_schedule(&self, conn: &Connection, now: NaiveDateTime) -> Result<Option<PlanResult>, String> {
+        self.reflow(conn, now).map(Some)
+    }
+}
+
+impl<T, E, B, V, S> PlannerServiceImpl<T, E, B, V, S>
+where
+    T: TaskRepository,
+    E: TimeEntryRepository,
+    B: ScheduleBlockRepository,
+    V: CalendarEventRepository,
+    S: SettingsRepository,
+{
+    // Blok task yang masih terpakai ditambah kemunculan acara berjam di `days` hari mulai `from`,
+    // urut berdasarkan waktu mulai
+    fn busy_times(
+        &self,
+        conn: &Connection,
+        blocks: &[ScheduleBlock],
+        tasks: &[Task],
+        from: NaiveDate,
+        days: u32,
+    ) -> Result<Vec<(NaiveDateTime, NaiveDateTime)>, String> {
+        let window_start = from.and_time(NaiveTime::MIN);
+        let window_end = window_start + Duration::days(i64::from(days));
+        let mut busy: Vec<(NaiveDateTime, NaiveDateTime)> = busy_blocks(blocks, tasks)
+            .into_iter()
+            .map(|block| (block.start, block.end))
+            .collect();
+        for event in self.timed_events(conn, window_start, window_end)? {
+            busy.extend(event.occurrences(window_start, window_end));
+        }
+        busy.sort();
+        Ok(busy)
+    }
+
+    // Acara berjam yang punya kemunculan di `from`..`to`. Acara seharian (mis. hari ujian)
+    // tidak menutup jam kerja.
+    fn timed_events(&self, conn: &Connection, from: NaiveDateTime, to: NaiveDateTime) -> Result<Vec<CalendarEvent>, String> {
+        Ok(self.event_repository.fetch_between(conn, from, to)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|event| !event.all_day && !event.occurrences(from, to).is_empty())
+            .collect())
+    }
+
+    // Perkiraan dikurangi waktu yang sudah dicatat. Task tanpa perkiraan, atau yang perkiraannya
+    // sudah terlampaui tapi belum selesai, mendapat durasi default.
+    fn remaining_minutes(&self, conn: &Connection, task: &Task, config: &PlannerConfig) -> Result<i64, String> {
+        let logged: i64 = self.time_entry_repository.fetch_for_task(conn, task.id)
+            .map_err(|e| e.to_string())?
+            .iter()
+            .map(|entry| i64::from(entry.minutes))
+            .sum();
+        Ok(match task.estimated_minutes.map(|estimate| i64::from(estimate) - logged) {
+            Some(left) if left > 0 => left,
+            _ => i64::from(config.default_task_minutes),
+        })
+    }
+}
+
+// Jadwal memakai waktu lokal; skor task butuh waktu sebenarnya untuk menghitung umur task.
+// Jam yang tidak ada karena pergantian DST dianggap UTC.
+fn local_instant(now: NaiveDateTime) -> DateTime<Local> {
+    Local.from_local_datetime(&now).earliest().unwrap_or_else(|| Local.from_utc_datetime(&now))
+}
+
+// Blok task yang belum selesai; blok task yang sudah selesai atau di trash boleh ditimpa
+fn busy_blocks<'a>(blocks: &'a [ScheduleBlock], tasks: &[Task]) -> Vec<&'a ScheduleBlock> {
+    blocks.iter()
+        .filter(|block| tasks.iter().any(|task| task.id == block.task_id && !task.completed))
+        .collect()
+}
+
+// Potongan jam kerja yang masih kosong mulai `now`, urut dari yang paling awal.
+// `busy` harus urut berdasarkan waktu mulai.
+fn free_slots(
+    config: &PlannerConfig,
+    from: NaiveDate,
+    days: u32,
+    now: NaiveDateTime,
+    busy: &[(NaiveDateTime, NaiveDateTime)],
+) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+    let mut slots = Vec::new();
+    for date in from.iter_days().take(days as usize) {
+        if !config.work_days.contains(&date.weekday()) {
+            continue;
+        }
+        let mut start = date.and_time(config.day_start).max(now);
+        let end = date.and_time(config.day_end);
+        for &(busy_start, busy_end) in busy {
+            if busy_end <= start || busy_start >= end {
+                continue;
+            }
+            if busy_start > start {
+                slots.push((start, busy_start));
+            }
+            start = start.max(busy_end);
+        }
+        if start < end {
+            slots.push((start, end));
+        }
+    }
+    slots
+}
+
+// Isi slot kosong sesuai urutan `items`. Task panjang dipecah menjadi beberapa blok; task yang
+// tidak muat seluruhnya tidak dijadwalkan sama sekali supaya slotnya dipakai task berikutnya.
+fn fill_slots(config: &PlannerConfig, slots: &mut Vec<(NaiveDateTime, NaiveDateTime)>, items: Vec<PlanItem>) -> PlanResult {
+    let max_block = i64::from(config.max_block_minutes);
+    let min_block = i64::from(config.min_block_minutes);
+    let pause = Duration::minutes(i64::from(config.break_minutes));
+
+    let mut result = PlanResult::default();
+    for item in items {
+        let mut tentative = slots.clone();
+        let mut blocks = Vec::new();
+        let mut remaining = item.minutes;
+        for (start, end) in tentative.iter_mut() {
+            while remaining > 0 {
+                let chunk = remaining.min(max_block).min((*end - *start).num_minutes());
+                if chunk <= 0 || chunk < remaining.min(min_block) {
+                    break;
+                }
+                let block_end = *start + Duration::minutes(chunk);
+                blocks.push(ScheduleBlock {
+                    id: 0,
+                    task_id: item.task_id,
+                    title: item.title.clone(),
+                    start: *start,
+                    end: block_end,
+                });
+                remaining -= chunk;
+                *start = (block_end + pause).min(*end);
+            }
+            if remaining == 0 {
+                break;
+            }
+        }
+        if remaining == 0 {
+            *slots = tentative;
+            result.blocks.extend(blocks);
+        } else {
+            result.unscheduled.push(item.task_id);
+        }
+    }
+    result.blocks.sort_by_key(|block| block.start);
+    result
+}
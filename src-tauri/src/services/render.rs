@@ -0,0 +1,236 @@
+use ammonia::Builder;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+use pulldown_cmark::{html, Event, Options, Parser, Tag};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use crate::fs::FileSystem;
+use crate::model::front_matter::FrontMatter;
+use crate::repository::note::NoteRepository;
+
+// Custom protocols the rendered HTML points at; both are registered in lib.rs
+pub const VAULT_PROTOCOL: &str = "vault";
+pub const ATTACHMENT_PROTOCOL: &str = "attachment";
+
+// Footnote ids and fragment links are prefixed and any other id is dropped, so a note
+// can't clash with the app's own elements
+pub(crate) const ID_PREFIX: &str = "note-";
+// Characters escaped in a single path segment of a `vault://` url
+const SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ').add(b'"').add(b'#').add(b'%').add(b'/').add(b'<').add(b'>')
+    .add(b'?').add(b'\\').add(b'`').add(b'{').add(b'}');
+const TEXT_ALIGNMENTS: [&str; 3] = ["text-align: left", "text-align: center", "text-align: right"];
+
+pub trait RenderService {
+    // Sanitized HTML for a note's body. The output is cached until the file's modification
+    // time changes.
+    fn render_note(&self, conn: &Connection, id: i32) -> Result<String, String>;
+    // Mime type and contents of a vault file, for the `vault://` protocol. `path` is the
+    // percent-encoded path from the url; paths leaving the vault or going through hidden
+    // folders (trash, attachments) are refused.
+    fn read_vault_file(&self, path: &str) -> Result<(String, Vec<u8>), String>;
+}
+
+struct CachedRender {
+    file_path: String,
+    modified: SystemTime,
+    html: String,
+}
+
+// Last rendered HTML per note id
+#[derive(Default)]
+pub struct RenderCache(Mutex<HashMap<i32, CachedRender>>);
+
+pub struct RenderServiceImpl<R: NoteRepository, F: FileSystem> {
+    pub repository: R,
+    pub fs: F,
+    pub vault_dir: PathBuf,
+    pub cache: RenderCache,
+}
+
+impl<R: NoteRepository, F: FileSystem> RenderService for RenderServiceImpl<R, F> {
+    fn render_note(&self, conn: &Connection, id: i32) -> Result<String, String> {
+        let note = self.repository.fetch_by_id(conn, id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Note {} not found", id))?;
+        let path = Path::new(&note.file_path);
+        let modified = self.fs.modified(path).map_err(|e| format!("Failed to read note file: {}", e))?;
+
+        let mut cache = self.cache.0.lock().map_err(|_| "Render cache is unavailable".to_string())?;
+        if let Some(cached) = cache.get(&id) {
+            if cached.file_path == note.file_path && cached.modified == modified {
+                return Ok(cached.html.clone());
+            }
+        }
+
+        let content = self.fs.read(path).map_err(|e| format!("Failed to read note file: {}", e))?;
+        let content = String::from_utf8_lossy(&content);
+        let html = render_markdown(FrontMatter::body(&content), &note_folder(&self.vault_dir, path));
+        cache.insert(id, CachedRender { file_path: note.file_path, modified, html: html.clone() });
+        Ok(html)
+    }
+
+    fn read_vault_file(&self, path: &str) -> Result<(String, Vec<u8>), String> {
+        let decoded = percent_decode_str(path.trim_start_matches('/')).decode_utf8_lossy();
+        let file = normalize(Path::new(decoded.as_ref()))
+            .and_then(|segments| vault_file(&self.vault_dir, &segments))
+            .ok_or_else(|| format!("Invalid vault path: {}", path))?;
+        let data = self.fs.read(&file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+        let mime = mime_guess::from_path(&file).first_or_octet_stream().essence_str().to_string();
+        Ok((mime, data))
+    }
+}
+
+// Renders CommonMark with GFM tables, task lists, strikethrough and footnotes to sanitized
+// HTML. `base` is the folder of the note relative to the vault root: relative links and
+// images become `vault://` urls, and `attachment:<id>` becomes an `attachment://` url.
+pub fn render_markdown(markdown: &str, base: &Path) -> String {
+    let events = parse_markdown(markdown, ID_PREFIX).map(|event| match event {
+        Event::Start(Tag::Link { link_type, dest_url, title, id }) => {
+            Event::Start(Tag::Link { link_type, dest_url: resolve_url(&dest_url, base).into(), title, id })
+        }
+        Event::Start(Tag::Image { link_type, dest_url, title, id }) => {
+            Event::Start(Tag::Image { link_type, dest_url: resolve_url(&dest_url, base).into(), title, id })
+        }
+        event => event,
+    });
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, events);
+    sanitize(&unsafe_html, false)
+}
+
+// Where a link or image in a note points
+pub(crate) enum UrlTarget {
+    // `#fragment` within the note
+    Fragment(String),
+    // `attachment:<id>`
+    Attachment(String),
+    // A file in the vault, as path segments from the vault root
+    Vault(Vec<String>),
+    // Anything with a scheme, kept as is
+    External(String),
+    // A relative path climbing out of the vault
+    Outside,
+}
+
+// A leading slash means the vault root, other relative paths start at `base`
+pub(crate) fn classify_url(url: &str, base: &Path) -> UrlTarget {
+    if let Some(fragment) = url.strip_prefix('#') {
+        return UrlTarget::Fragment(fragment.to_string());
+    }
+    if let Some(id) = url.strip_prefix("attachment:").filter(|id| !id.starts_with("//")) {
+        return UrlTarget::Attachment(id.to_string());
+    }
+    if url.starts_with("//") || has_scheme(url) {
+        return UrlTarget::External(url.to_string());
+    }
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let decoded = percent_decode_str(path).decode_utf8_lossy();
+    let target = match decoded.strip_prefix('/') {
+        Some(from_root) => PathBuf::from(from_root),
+        None => base.join(decoded.as_ref()),
+    };
+    normalize(&target).map_or(UrlTarget::Outside, UrlTarget::Vault)
+}
+
+// Markdown events with the extensions we support. Footnote names get `id_prefix` so the
+// anchors match fragment links resolved with the same prefix.
+pub(crate) fn parse_markdown<'a>(markdown: &'a str, id_prefix: &'a str) -> impl Iterator<Item = Event<'a>> {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_FOOTNOTES;
+    Parser::new_ext(markdown, options).map(move |event| match event {
+        Event::FootnoteReference(name) => Event::FootnoteReference(format!("{}{}", id_prefix, name).into()),
+        Event::Start(Tag::FootnoteDefinition(name)) => {
+            Event::Start(Tag::FootnoteDefinition(format!("{}{}", id_prefix, name).into()))
+        }
+        event => event,
+    })
+}
+
+// Strips scripts, event handlers and unknown schemes from rendered markdown.
+// `embedded_images` allows `data:` urls on images, for exported files.
+pub(crate) fn sanitize(html: &str, embedded_images: bool) -> String {
+    let mut builder = Builder::default();
+    builder
+        .add_tags(["input"])
+        .add_tag_attributes("input", ["type", "checked", "disabled"])
+        .add_tag_attributes("code", ["class"])
+        .add_tag_attributes("div", ["class", "id"])
+        .add_tag_attributes("sup", ["class"])
+        .add_tag_attributes("th", ["style"])
+        .add_tag_attributes("td", ["style"])
+        .add_url_schemes([VAULT_PROTOCOL, ATTACHMENT_PROTOCOL]);
+    for heading in ["h1", "h2", "h3", "h4", "h5", "h6"] {
+        builder.add_tag_attributes(heading, ["id"]);
+    }
+    if embedded_images {
+        builder.add_url_schemes(["data"]);
+    }
+    // Only our own ids, task list checkboxes, table alignment and image data get through
+    builder.attribute_filter(|element, attribute, value| match (element, attribute) {
+        (_, "id") if !value.starts_with(ID_PREFIX) => None,
+        ("input", "type") if value != "checkbox" => None,
+        ("th" | "td", "style") if !TEXT_ALIGNMENTS.contains(&value) => None,
+        (element, _) if element != "img" && value.starts_with("data:") => None,
+        _ => Some(value.into()),
+    });
+    builder.clean(html).to_string()
+}
+
+// Folder of a note relative to the vault root, where its relative links start
+pub(crate) fn note_folder(vault_dir: &Path, file_path: &Path) -> PathBuf {
+    file_path.strip_prefix(vault_dir).unwrap_or(file_path)
+        .parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+// Path of a vault file from its segments; hidden folders (trash, attachments) are off limits
+pub(crate) fn vault_file(vault_dir: &Path, segments: &[String]) -> Option<PathBuf> {
+    if segments.is_empty() || segments.iter().any(|segment| segment.starts_with('.')) {
+        return None;
+    }
+    Some(segments.iter().fold(vault_dir.to_path_buf(), |dir, segment| dir.join(segment)))
+}
+
+fn resolve_url(url: &str, base: &Path) -> String {
+    match classify_url(url, base) {
+        UrlTarget::Fragment(fragment) => format!("#{}{}", ID_PREFIX, fragment),
+        UrlTarget::Attachment(id) => format!("{}://localhost/{}", ATTACHMENT_PROTOCOL, utf8_percent_encode(&id, SEGMENT)),
+        UrlTarget::Vault(segments) => {
+            let encoded: Vec<String> = segments.iter().map(|segment| utf8_percent_encode(segment, SEGMENT).to_string()).collect();
+            format!("{}://localhost/{}", VAULT_PROTOCOL, encoded.join("/"))
+        }
+        UrlTarget::External(url) => url,
+        // Links pointing outside the vault are dropped
+        UrlTarget::Outside => String::new(),
+    }
+}
+
+fn has_scheme(url: &str) -> bool {
+    match url.split_once(':') {
+        Some((scheme, _)) => {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
+    }
+}
+
+// Path segments relative to the vault root, or None if the path climbs out of it
+fn normalize(path: &Path) -> Option<Vec<String>> {
+    let mut segments = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(segment) => segments.push(segment.to_string_lossy().into_owned()),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                segments.pop()?;
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(segments)
+}
@@ -0,0 +1,125 @@
+use chrono::{DateTime, Duration, Local, NaiveDateTime, NaiveTime, TimeZone};
+use rusqlite::Connection;
+use std::cmp::Ordering;
+use crate::model::scoring::{ScoreBreakdown, ScoredTask, ScoringWeights, SCORING_SETTINGS_KEY};
+use crate::model::task::{Priority, Task};
+use crate::repository::settings::SettingsRepository;
+use crate::repository::task::TaskRepository;
+
+pub trait ScoringService {
+    // Task yang belum selesai dengan skor tertinggi, paling penting dulu
+    fn next_tasks(&self, conn: &Connection, limit: usize) -> Result<Vec<ScoredTask>, String>;
+    fn load_weights(&self, conn: &Connection) -> Result<ScoringWeights, String>;
+    fn save_weights(&self, conn: &Connection, weights: &ScoringWeights) -> Result<(), String>;
+}
+
+pub struct ScoringServiceImpl<T: TaskRepository, S: SettingsRepository> {
+    pub task_repository: T,
+    pub settings: S,
+}
+
+impl<T: TaskRepository, S: SettingsRepository> ScoringService for ScoringServiceImpl<T, S> {
+    fn next_tasks(&self, conn: &Connection, limit: usize) -> Result<Vec<ScoredTask>, String> {
+        let weights = self.load_weights(conn)?;
+        let tasks = self.task_repository.fetch_all(conn).map_err(|e| e.to_string())?;
+        Ok(rank_tasks(tasks, &weights, &Local::now(), limit))
+    }
+
+    fn load_weights(&self, conn: &Connection) -> Result<ScoringWeights, String> {
+        load_scoring_weights(&self.settings, conn)
+    }
+
+    fn save_weights(&self, conn: &Connection, weights: &ScoringWeights) -> Result<(), String> {
+        let components = [weights.priority, weights.urgency, weights.effort, weights.age, weights.overdue];
+        if components.iter().any(|weight| !weight.is_finite() || *weight < 0.0) {
+            return Err("Scoring weights must be non-negative numbers".to_string());
+        }
+        if weights.urgency_horizon_days == 0 || weights.effort_cap_minutes == 0 || weights.age_cap_days == 0 {
+            return Err("Scoring ranges must be at least 1".to_string());
+        }
+        let json = serde_json::to_string(weights).map_err(|e| e.to_string())?;
+        self.settings.set(conn, SCORING_SETTINGS_KEY, &json).map_err(|e| e.to_string())
+    }
+}
+
+// Dipakai juga oleh planner untuk mengurutkan task yang dijadwalkan
+pub fn load_scoring_weights(settings: &impl SettingsRepository, conn: &Connection) -> Result<ScoringWeights, String> {
+    let value = settings.get(conn, SCORING_SETTINGS_KEY).map_err(|e| e.to_string())?;
+    match value {
+        Some(json) => serde_json::from_str(&json).map_err(|e| format!("Invalid scoring settings: {}", e)),
+        None => Ok(ScoringWeights::default()),
+    }
+}
+
+// Urutkan task yang belum selesai berdasarkan skor. Skor sama: deadline terdekat, lalu id terkecil.
+pub fn rank_tasks<Tz: TimeZone>(tasks: Vec<Task>, weights: &ScoringWeights, now: &DateTime<Tz>, limit: usize) -> Vec<ScoredTask> {
+    let mut scored: Vec<ScoredTask> = tasks.into_iter()
+        .filter(|task| !task.completed)
+        .map(|task| ScoredTask { score: score_task(&task, weights, now), task })
+        .collect();
+    scored.sort_by(|a, b| {
+        b.score.total.partial_cmp(&a.score.total).unwrap_or(Ordering::Equal)
+            .then_with(|| match (a.task.deadline, b.task.deadline) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            })
+            .then_with(|| a.task.id.cmp(&b.task.id))
+    });
+    scored.truncate(limit);
+    scored
+}
+
+// Deadline diisi user dalam waktu lokal, sedangkan `created_at` disimpan dalam UTC, jadi
+// masing-masing dibandingkan dengan `now` pada jam yang sama
+pub fn score_task<Tz: TimeZone>(task: &Task, weights: &ScoringWeights, now: &DateTime<Tz>) -> ScoreBreakdown {
+    let local_now = now.naive_local();
+    let priority = match task.priority {
+        Priority::Low => 0.0,
+        Priority::Medium => 0.5,
+        Priority::High => 1.0,
+    };
+
+    let due = task.deadline.map(due_at);
+    let overdue = due.is_some_and(|due| due < local_now);
+    let urgency = match due {
+        Some(due) => {
+            let horizon = f64::from(weights.urgency_horizon_days) * 24.0;
+            let hours_left = (due - local_now).num_minutes() as f64 / 60.0;
+            (1.0 - hours_left / horizon).clamp(0.0, 1.0)
+        }
+        None => 0.0,
+    };
+
+    let effort = match task.estimated_minutes {
+        Some(minutes) => {
+            let cap = f64::from(weights.effort_cap_minutes);
+            1.0 - (f64::from(minutes.max(0)) / cap).min(1.0)
+        }
+        None => 0.5,
+    };
+
+    let age_days = (now.naive_utc() - task.created_at).num_hours() as f64 / 24.0;
+    let age = (age_days / f64::from(weights.age_cap_days)).clamp(0.0, 1.0);
+
+    let mut breakdown = ScoreBreakdown {
+        priority: priority * weights.priority,
+        urgency: urgency * weights.urgency,
+        effort: effort * weights.effort,
+        age: age * weights.age,
+        overdue: if overdue { weights.overdue } else { 0.0 },
+        total: 0.0,
+    };
+    breakdown.total = breakdown.priority + breakdown.urgency + breakdown.effort + breakdown.age + breakdown.overdue;
+    breakdown
+}
+
+// Deadline tanpa jam (tengah malam) berlaku sampai akhir hari itu
+fn due_at(deadline: NaiveDateTime) -> NaiveDateTime {
+    if deadline.time() == NaiveTime::MIN {
+        deadline + Duration::days(1)
+    } else {
+        deadline
+    }
+}
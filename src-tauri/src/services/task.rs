@@ -1,10 +1,33 @@
 // src-tauri/src/services/task.rs
 
-use chrono::{Utc, NaiveDateTime, NaiveDate};
+use chrono::{Local, Utc, NaiveDateTime, NaiveDate};
 // Import Connection dari rusqlite
 use rusqlite::Connection;
+use crate::db::in_transaction;
+use crate::events::{DomainEvent, EventBus};
+use crate::journal::{Changes, Journal};
+use crate::model::journal::Operation;
+use crate::model::planner::PlanResult;
+use crate::model::quick_add::{DATE_FORMAT, DATE_TIME_FORMAT};
 use crate::model::task::{Task, TaskPayload};
-use crate::repository::task::{TaskRepository, SqliteTaskRepository};
+use crate::model::task_csv::TaskColumn;
+use crate::model::task_history::{TaskChange, TaskChangeKind};
+use crate::repository::task::TaskRepository;
+use crate::repository::task_history::TaskHistoryRepository;
+use crate::services::note_task::TaskCheckboxSync;
+use crate::services::planner::ScheduleReflow;
+use crate::services::task_csv::column_value;
+use crate::services::trash::trash_task;
+
+// Field yang perubahannya dicatat di riwayat task; id dan timestamp tidak termasuk
+const TRACKED_FIELDS: [TaskColumn; 6] = [
+    TaskColumn::Title,
+    TaskColumn::Category,
+    TaskColumn::Priority,
+    TaskColumn::Deadline,
+    TaskColumn::Completed,
+    TaskColumn::EstimatedMinutes,
+];
 
 // Trait (interface) untuk TaskService
 pub trait TaskService {
@@ -12,22 +35,47 @@ pub trait TaskService {
     // Ubah signature method untuk menerima &Connection sebagai argumen
     fn create_task(&self, conn: &Connection, payload: TaskPayload) -> Result<(), String>;
     fn fetch_tasks(&self, conn: &Connection) -> Result<Vec<Task>, String>;
-    fn update_task(&self, conn: &Connection, task: Task) -> Result<(), String>;
+    // Task yang baru selesai menyusun ulang jadwal; hasilnya dikembalikan supaya task yang
+    // tidak muat lagi bisa ditampilkan
+    fn update_task(&self, conn: &Connection, task: Task) -> Result<Option<PlanResult>, String>;
     fn delete_task(&self, conn: &Connection, id: i32) -> Result<(), String>;
     // --- PERUBAHAN SELESAI ---
+    // Riwayat perubahan field satu task, urut dari yang terlama
+    fn fetch_task_history(&self, conn: &Connection, task_id: i32) -> Result<Vec<TaskChange>, String>;
+    // Sama seperti create_task/update_task, tetapi di dalam transaksi pemanggil (mis. saat note
+    // disimpan). Entri journal dan event dikumpulkan di `changes` untuk dicatat setelah commit.
+    fn insert_task(&self, conn: &Connection, task: Task, changes: &mut Changes) -> Result<Task, String>;
+    fn save_task(&self, conn: &Connection, task: Task, changes: &mut Changes) -> Result<Option<PlanResult>, String>;
 }
 
-// Implementasi konkret dari TaskService
-pub struct TaskServiceImpl {
+// Implementasi konkret dari TaskService, generic terhadap repository
+// sehingga test bisa memakai repository palsu
+pub struct TaskServiceImpl<R: TaskRepository, H: TaskHistoryRepository, C: TaskCheckboxSync = (), P: ScheduleReflow = ()> {
     // --- PERUBAHAN DIMULAI ---
     // Hapus field `conn` dari struct, karena koneksi akan di-pass sebagai argumen method
     // pub conn: Box<Connection>,
     // --- PERUBAHAN SELESAI ---
-    pub repository: SqliteTaskRepository, // Repository tetap ada
+    pub repository: R, // Repository tetap ada
+    // Setiap edit dicatat per field di tabel task_history
+    pub history_repository: H,
+    // Setiap perubahan dipublikasikan ke bus agar semua view ikut diperbarui
+    pub events: EventBus,
+    // Mutasi dicatat di journal supaya bisa di-undo/redo lewat HistoryService
+    pub journal: Journal,
+    // Task yang berasal dari checkbox note ikut mencentang/menghapus centang checkbox-nya
+    pub checkboxes: C,
+    // Blok jadwal task yang selesai dilepas dan blok sesudahnya dimajukan
+    pub planner: P,
 }
 
 // Implementasi method untuk TaskServiceImpl
-impl TaskService for TaskServiceImpl {
+impl<R, H, C, P> TaskService for TaskServiceImpl<R, H, C, P>
+where
+    R: TaskRepository,
+    H: TaskHistoryRepository,
+    C: TaskCheckboxSync,
+    P: ScheduleReflow,
+{
     // --- PERUBAHAN DIMULAI ---
     // Implementasi fungsi create_task dengan signature baru (menerima &Connection)
     fn create_task(&self, conn: &Connection, payload: TaskPayload) -> Result<(), String> {
@@ -36,7 +84,10 @@ impl TaskService for TaskServiceImpl {
         // Logika parsing deadline (tetap sama)
         let parsed_deadline: Option<NaiveDateTime> = payload.deadline
             .and_then(|date_str| {
-                if let Ok(naive_date) = NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
+                // Deadline dengan jam (mis. dari quick-add) dipakai apa adanya
+                if let Ok(date_time) = NaiveDateTime::parse_from_str(&date_str, DATE_TIME_FORMAT) {
+                    Some(date_time)
+                } else if let Ok(naive_date) = NaiveDate::parse_from_str(&date_str, DATE_FORMAT) {
                     // Menggunakan and_hms_opt untuk menghindari panic jika tanggal tidak valid
                     naive_date.and_hms_opt(0, 0, 0)
                 } else {
@@ -55,12 +106,16 @@ impl TaskService for TaskServiceImpl {
             completed: false, // Task baru defaultnya belum selesai
             created_at: Utc::now().naive_utc(), // Waktu sekarang (UTC, naive)
             updated_at: Utc::now().naive_utc(), // Waktu sekarang (UTC, naive)
+            estimated_minutes: payload.estimated_minutes,
         };
 
         // --- PERUBAHAN DIMULAI ---
-        // Panggil method repository dengan `conn` yang di-pass sebagai argumen
-        self.repository.create(conn, &task).map_err(|e| e.to_string())
+        // Task dan entri riwayat pertamanya disimpan dalam satu transaksi
+        let mut changes = Changes::new();
+        in_transaction(conn, |tx| self.insert_task(tx, task, &mut changes))?;
         // --- PERUBAHAN SELESAI ---
+        changes.commit(&self.journal, &self.events);
+        Ok(())
     }
 
     // --- PERUBAHAN DIMULAI ---
@@ -73,18 +128,124 @@ impl TaskService for TaskServiceImpl {
 
     // --- PERUBAHAN DIMULAI ---
     // Implementasi fungsi update_task dengan signature baru (menerima &Connection)
-    fn update_task(&self, conn: &Connection, task: Task) -> Result<(), String> {
-        // Panggil method repository dengan `conn` yang di-pass sebagai argumen
-        // Pastikan Task memiliki field updated_at yang diperbarui sebelum memanggil ini jika perlu
-        self.repository.update(conn, &task).map_err(|e| e.to_string())
+    fn update_task(&self, conn: &Connection, task: Task) -> Result<Option<PlanResult>, String> {
+        // Waktu edit dicap di sini dengan UTC, bukan dari jam frontend, supaya bisa dibandingkan
+        // dengan LAST-MODIFIED server CalDAV
+        let task = Task { updated_at: Utc::now().naive_utc(), ..task };
+        // Update, riwayatnya, checkbox asalnya, dan reflow jadwal disimpan dalam satu transaksi
+        let mut changes = Changes::new();
+        let plan = in_transaction(conn, |tx| self.save_task(tx, task, &mut changes))?;
+        changes.commit(&self.journal, &self.events);
+        Ok(plan)
     }
     // --- PERUBAHAN SELESAI ---
 
     // --- PERUBAHAN DIMULAI ---
     // Implementasi fungsi delete_task dengan signature baru (menerima &Connection)
     fn delete_task(&self, conn: &Connection, id: i32) -> Result<(), String> {
-        // Panggil method repository dengan `conn` yang di-pass sebagai argumen
-        self.repository.delete(conn, id).map_err(|e| e.to_string())
+        // Task tidak dihapus permanen, hanya dipindahkan ke trash
+        let deleted = self.repository.fetch_by_id(conn, id).map_err(|e| e.to_string())?;
+        let mut changes = Changes::new();
+        if trash_task(&self.repository, &self.history_repository, &mut changes, conn, id)? {
+            if let Some(task) = deleted {
+                changes.record(Operation::TaskDeleted { task });
+            }
+        }
+        changes.commit(&self.journal, &self.events);
+        Ok(())
     }
     // --- PERUBAHAN SELESAI ---
+
+    fn fetch_task_history(&self, conn: &Connection, task_id: i32) -> Result<Vec<TaskChange>, String> {
+        self.history_repository.fetch_for_task(conn, task_id).map_err(|e| e.to_string())
+    }
+
+    fn insert_task(&self, conn: &Connection, mut task: Task, changes: &mut Changes) -> Result<Task, String> {
+        validate_estimate(&task)?;
+        task.id = self.repository.create(conn, &task).map_err(|e| e.to_string())?;
+        record_task_event(&self.history_repository, conn, task.id, TaskChangeKind::Created)?;
+        changes.record(Operation::TaskCreated { task: task.clone() });
+        changes.publish(DomainEvent::TaskCreated(task.clone()));
+        Ok(task)
+    }
+
+    fn save_task(&self, conn: &Connection, task: Task, changes: &mut Changes) -> Result<Option<PlanResult>, String> {
+        validate_estimate(&task)?;
+        // Task di trash tidak bisa diubah
+        let before = self.repository.fetch_by_id(conn, task.id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Task {} not found", task.id))?;
+        self.repository.update(conn, &task).map_err(|e| e.to_string())?;
+        // Hanya saat task berubah dari belum selesai menjadi selesai, termasuk lewat undo/redo,
+        // checkbox note, dan sinkronisasi CalDAV
+        let finished = !before.completed && task.completed;
+        record_task_changes(&self.history_repository, conn, &before, &task)?;
+        changes.record(Operation::TaskUpdated { before, after: task.clone() });
+        self.checkboxes.sync_checkbox(conn, &task, changes)?;
+        changes.publish(DomainEvent::TaskUpdated(task));
+
+        // Jadwal memakai waktu lokal
+        let plan = if finished { self.planner.reflow_schedule(conn, Local::now().naive_local())? } else { None };
+        if let Some(plan) = &plan {
+            changes.publish(DomainEvent::ScheduleReflowed(plan.clone()));
+        }
+        Ok(plan)
+    }
+}
+
+fn validate_estimate(task: &Task) -> Result<(), String> {
+    if task.estimated_minutes.is_some_and(|minutes| minutes <= 0) {
+        return Err("Estimate must be at least one minute".to_string());
+    }
+    Ok(())
+}
+
+// Catat field yang berbeda antara `before` dan `after`. Dipakai juga saat undo/redo
+// mengembalikan task ke versi lain, supaya riwayat tetap lengkap.
+pub fn record_task_changes(
+    repository: &impl TaskHistoryRepository,
+    conn: &Connection,
+    before: &Task,
+    after: &Task,
+) -> Result<(), String> {
+    let changed_at = Utc::now().naive_utc();
+    for field in TRACKED_FIELDS {
+        let old_value = column_value(before, field);
+        let new_value = column_value(after, field);
+        if old_value == new_value {
+            continue;
+        }
+        let change = TaskChange {
+            id: 0,
+            task_id: after.id,
+            kind: TaskChangeKind::Updated,
+            field: Some(field),
+            // Kolom kosong (mis. deadline dihapus) disimpan sebagai NULL
+            old_value: Some(old_value).filter(|v| !v.is_empty()),
+            new_value: Some(new_value).filter(|v| !v.is_empty()),
+            changed_at,
+        };
+        repository.record(conn, &change).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+// Catat entri siklus hidup task (dibuat, dibuang ke trash, dipulihkan) di riwayat
+pub fn record_task_event(
+    repository: &impl TaskHistoryRepository,
+    conn: &Connection,
+    task_id: i32,
+    kind: TaskChangeKind,
+) -> Result<(), String> {
+    let change = TaskChange {
+        id: 0,
+        task_id,
+        kind,
+        field: None,
+        old_value: None,
+        new_value: None,
+        changed_at: Utc::now().naive_utc(),
+    };
+    repository.record(conn, &change).map_err(|e| e.to_string())?;
+    Ok(())
 }
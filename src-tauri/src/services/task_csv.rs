@@ -0,0 +1,232 @@
+// src-tauri/src/services/task_csv.rs
+
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use rusqlite::Connection;
+use crate::db::in_transaction;
+use crate::events::EventBus;
+use crate::journal::{Changes, Journal};
+use crate::model::task::{Category, Priority, Task};
+use crate::model::task_csv::{CsvImportOptions, CsvImportReport, CsvRowError, TaskColumn};
+use crate::repository::task::TaskRepository;
+use crate::services::task::TaskService;
+
+// Format yang dicoba saat mendeteksi format deadline, urut dari yang paling spesifik.
+// Format hari/bulan didahulukan dari bulan/hari karena mayoritas pengguna memakai format Indonesia.
+pub const DATE_FORMATS: [&str; 11] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d",
+    "%d/%m/%Y %H:%M",
+    "%d/%m/%Y",
+    "%m/%d/%Y %H:%M",
+    "%m/%d/%Y",
+    "%d-%m-%Y",
+    "%d.%m.%Y",
+];
+
+// Format yang dipakai untuk menulis tanggal saat ekspor
+const EXPORT_DATE_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+pub trait TaskCsvService {
+    fn export_csv(&self, conn: &Connection, columns: &[TaskColumn]) -> Result<String, String>;
+    fn import_csv(&self, conn: &Connection, data: &str, options: &CsvImportOptions) -> Result<CsvImportReport, String>;
+}
+
+pub struct TaskCsvServiceImpl<R: TaskRepository, K: TaskService> {
+    pub repository: R,
+    // Task hasil impor dibuat lewat TaskService seperti task baru lainnya: riwayat, validasi
+    // estimasi, event, dan journal ikut berjalan. Satu impor di-undo sebagai satu batch.
+    pub tasks: K,
+    pub events: EventBus,
+    pub journal: Journal,
+}
+
+impl<R: TaskRepository, K: TaskService> TaskCsvService for TaskCsvServiceImpl<R, K> {
+    fn export_csv(&self, conn: &Connection, columns: &[TaskColumn]) -> Result<String, String> {
+        // Tanpa pilihan kolom, semua kolom diekspor
+        let columns: &[TaskColumn] = if columns.is_empty() { &TaskColumn::ALL } else { columns };
+        let tasks = self.repository.fetch_all(conn).map_err(|e| e.to_string())?;
+
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(columns.iter().map(|c| c.header())).map_err(|e| e.to_string())?;
+        for task in &tasks {
+            writer.write_record(columns.iter().map(|c| column_value(task, *c)))
+                .map_err(|e| e.to_string())?;
+        }
+        let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+        String::from_utf8(bytes).map_err(|e| e.to_string())
+    }
+
+    fn import_csv(&self, conn: &Connection, data: &str, options: &CsvImportOptions) -> Result<CsvImportReport, String> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .flexible(true)
+            .from_reader(data.as_bytes());
+
+        // Tentukan kolom task untuk setiap header CSV
+        let headers = reader.headers().map_err(|e| format!("Failed to read CSV header: {}", e))?.clone();
+        let columns: Vec<Option<TaskColumn>> = headers
+            .iter()
+            .map(|h| options.mapping.get(h).copied().or_else(|| TaskColumn::from_header(h)))
+            .collect();
+        if !columns.contains(&Some(TaskColumn::Title)) {
+            return Err("CSV has no column mapped to title".to_string());
+        }
+
+        let records: Vec<csv::StringRecord> = reader
+            .records()
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to read CSV: {}", e))?;
+
+        let date_format = match &options.date_format {
+            Some(format) => Some(format.clone()),
+            None => {
+                let deadlines: Vec<&str> = records
+                    .iter()
+                    .filter_map(|r| cell(r, &columns, TaskColumn::Deadline))
+                    .collect();
+                detect_date_format(&deadlines).map(str::to_string)
+            }
+        };
+
+        let now = Utc::now().naive_utc();
+        let mut tasks = Vec::new();
+        let mut errors = Vec::new();
+        for (index, record) in records.iter().enumerate() {
+            // Baris 1 adalah header
+            let row = index + 2;
+            match parse_row(record, &columns, date_format.as_deref(), now) {
+                Ok(task) => tasks.push(task),
+                Err((column, message)) => errors.push(CsvRowError { row, column, message }),
+            }
+        }
+
+        if !options.dry_run {
+            let mut changes = Changes::new();
+            tasks = in_transaction(conn, |tx| {
+                tasks.into_iter().map(|task| self.tasks.insert_task(tx, task, &mut changes)).collect()
+            })?;
+            changes.commit(&self.journal, &self.events);
+        }
+
+        Ok(CsvImportReport { tasks, errors, date_format, dry_run: options.dry_run })
+    }
+}
+
+// Nilai satu kolom dalam format ekspor; juga dipakai untuk mencatat riwayat perubahan task
+pub fn column_value(task: &Task, column: TaskColumn) -> String {
+    match column {
+        TaskColumn::Id => task.id.to_string(),
+        TaskColumn::Title => task.title.clone(),
+        TaskColumn::Category => task.category.to_string(),
+        TaskColumn::Priority => task.priority.to_string(),
+        TaskColumn::Deadline => task.deadline
+            .map(|d| d.format(EXPORT_DATE_FORMAT).to_string())
+            .unwrap_or_default(),
+        TaskColumn::Completed => task.completed.to_string(),
+        TaskColumn::CreatedAt => task.created_at.format(EXPORT_DATE_FORMAT).to_string(),
+        TaskColumn::UpdatedAt => task.updated_at.format(EXPORT_DATE_FORMAT).to_string(),
+        TaskColumn::EstimatedMinutes => task.estimated_minutes.map(|m| m.to_string()).unwrap_or_default(),
+    }
+}
+
+// Ambil isi sel untuk kolom tertentu; sel kosong dianggap tidak ada
+fn cell<'a>(record: &'a csv::StringRecord, columns: &[Option<TaskColumn>], column: TaskColumn) -> Option<&'a str> {
+    let index = columns.iter().position(|c| *c == Some(column))?;
+    record.get(index).filter(|value| !value.is_empty())
+}
+
+fn parse_row(
+    record: &csv::StringRecord,
+    columns: &[Option<TaskColumn>],
+    date_format: Option<&str>,
+    now: NaiveDateTime,
+) -> Result<Task, (Option<TaskColumn>, String)> {
+    let title = cell(record, columns, TaskColumn::Title)
+        .ok_or((Some(TaskColumn::Title), "Title is empty".to_string()))?;
+
+    // Sel kosong memakai nilai default, tetapi nilai yang tidak dikenal dilaporkan sebagai error
+    let category = match cell(record, columns, TaskColumn::Category) {
+        Some(value) => value.parse::<Category>().map_err(|e| (Some(TaskColumn::Category), e))?,
+        None => Category::Personal,
+    };
+    let priority = match cell(record, columns, TaskColumn::Priority) {
+        Some(value) => value.parse::<Priority>().map_err(|e| (Some(TaskColumn::Priority), e))?,
+        None => Priority::Low,
+    };
+    let deadline = match cell(record, columns, TaskColumn::Deadline) {
+        Some(value) => {
+            let format = date_format.ok_or((
+                Some(TaskColumn::Deadline),
+                format!("Could not detect date format for '{}'", value),
+            ))?;
+            Some(parse_date(value, format).ok_or((
+                Some(TaskColumn::Deadline),
+                format!("Deadline '{}' does not match format {}", value, format),
+            ))?)
+        }
+        None => None,
+    };
+    let completed = match cell(record, columns, TaskColumn::Completed) {
+        Some(value) => parse_bool(value).ok_or((
+            Some(TaskColumn::Completed),
+            format!("Unknown completed value '{}'", value),
+        ))?,
+        None => false,
+    };
+
+    let estimated_minutes = match cell(record, columns, TaskColumn::EstimatedMinutes) {
+        Some(value) => Some(value.parse::<i32>().ok().filter(|minutes| *minutes > 0).ok_or((
+            Some(TaskColumn::EstimatedMinutes),
+            format!("Estimate '{}' is not a positive number of minutes", value),
+        ))?),
+        None => None,
+    };
+
+    Ok(Task {
+        id: 0,
+        title: title.to_string(),
+        category,
+        priority,
+        deadline,
+        completed,
+        created_at: now,
+        updated_at: now,
+        estimated_minutes,
+    })
+}
+
+// Parse tanggal dengan format chrono; format tanpa jam menghasilkan tengah malam
+pub fn parse_date(value: &str, format: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, format)
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(value, format).ok().and_then(|d| d.and_hms_opt(0, 0, 0)))
+}
+
+// Pilih format pertama yang cocok untuk semua nilai; jika tidak ada, format yang cocok paling banyak
+pub fn detect_date_format(values: &[&str]) -> Option<&'static str> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut best: Option<(&'static str, usize)> = None;
+    for format in DATE_FORMATS {
+        let matched = values.iter().filter(|v| parse_date(v, format).is_some()).count();
+        if matched == values.len() {
+            return Some(format);
+        }
+        if matched > 0 && best.is_none_or(|(_, count)| matched > count) {
+            best = Some((format, matched));
+        }
+    }
+    best.map(|(format, _)| format)
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "ya" | "y" | "1" | "x" | "done" | "selesai" => Some(true),
+        "false" | "no" | "tidak" | "n" | "0" | "todo" | "belum" => Some(false),
+        _ => None,
+    }
+}
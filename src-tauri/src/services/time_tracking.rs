@@ -0,0 +1,192 @@
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use crate::db::in_transaction;
+use crate::model::quick_add::{DATE_FORMAT, DATE_TIME_FORMAT};
+use crate::model::task::{Category, Task};
+use crate::model::time_entry::{
+    CategoryTime, TaskTime, TimeEntry, TimeEntryPayload, TimeEntrySource, TimeReport, WeekTime,
+};
+use crate::repository::task::TaskRepository;
+use crate::repository::time_entry::TimeEntryRepository;
+
+// Satu catatan manual paling lama sehari
+const MAX_ENTRY_MINUTES: i32 = 24 * 60;
+
+pub trait TimeTrackingService {
+    fn add_entry(&self, conn: &Connection, payload: TimeEntryPayload) -> Result<TimeEntry, String>;
+    fn fetch_entries(&self, conn: &Connection, task_id: i32) -> Result<Vec<TimeEntry>, String>;
+    fn delete_entry(&self, conn: &Connection, id: i32) -> Result<(), String>;
+    // Hanya satu timer yang bisa berjalan; hentikan dulu timer yang lain
+    fn start_timer(&self, conn: &Connection, task_id: i32) -> Result<TimeEntry, String>;
+    // Hentikan timer yang berjalan dan simpan durasinya; `None` jika tidak ada timer
+    fn stop_timer(&self, conn: &Connection) -> Result<Option<TimeEntry>, String>;
+    fn running_timer(&self, conn: &Connection) -> Result<Option<TimeEntry>, String>;
+    // Perkiraan vs waktu sebenarnya per task, kategori, dan minggu untuk `from`..=`to`
+    fn time_report(&self, conn: &Connection, from: NaiveDate, to: NaiveDate) -> Result<TimeReport, String>;
+}
+
+pub struct TimeTrackingServiceImpl<T: TaskRepository, E: TimeEntryRepository> {
+    pub task_repository: T,
+    pub repository: E,
+}
+
+impl<T: TaskRepository, E: TimeEntryRepository> TimeTrackingServiceImpl<T, E> {
+    fn ensure_task(&self, conn: &Connection, task_id: i32) -> Result<(), String> {
+        match self.task_repository.fetch_by_id(conn, task_id).map_err(|e| e.to_string())? {
+            Some(_) => Ok(()),
+            None => Err(format!("Task {} not found", task_id)),
+        }
+    }
+}
+
+impl<T: TaskRepository, E: TimeEntryRepository> TimeTrackingService for TimeTrackingServiceImpl<T, E> {
+    fn add_entry(&self, conn: &Connection, payload: TimeEntryPayload) -> Result<TimeEntry, String> {
+        self.ensure_task(conn, payload.task_id)?;
+        if payload.minutes <= 0 || payload.minutes > MAX_ENTRY_MINUTES {
+            return Err(format!("Minutes must be between 1 and {}", MAX_ENTRY_MINUTES));
+        }
+        let started_at = NaiveDateTime::parse_from_str(&payload.started_at, DATE_TIME_FORMAT)
+            .or_else(|_| NaiveDate::parse_from_str(&payload.started_at, DATE_FORMAT).map(|date| date.and_time(NaiveTime::MIN)))
+            .map_err(|_| format!("Invalid start time '{}'", payload.started_at))?;
+
+        let mut entry = TimeEntry {
+            id: 0,
+            task_id: payload.task_id,
+            source: TimeEntrySource::Manual,
+            started_at,
+            ended_at: Some(started_at + Duration::minutes(i64::from(payload.minutes))),
+            minutes: payload.minutes,
+            note: payload.note.map(|note| note.trim().to_string()).filter(|note| !note.is_empty()),
+        };
+        entry.id = self.repository.create(conn, &entry).map_err(|e| e.to_string())?;
+        Ok(entry)
+    }
+
+    fn fetch_entries(&self, conn: &Connection, task_id: i32) -> Result<Vec<TimeEntry>, String> {
+        self.repository.fetch_for_task(conn, task_id).map_err(|e| e.to_string())
+    }
+
+    fn delete_entry(&self, conn: &Connection, id: i32) -> Result<(), String> {
+        self.repository.delete(conn, id).map_err(|e| e.to_string())
+    }
+
+    fn start_timer(&self, conn: &Connection, task_id: i32) -> Result<TimeEntry, String> {
+        // Pengecekan dan pembuatan timer dalam satu transaksi, supaya dua jendela tidak bisa
+        // memulai timer bersamaan
+        in_transaction(conn, |tx| {
+            self.ensure_task(tx, task_id)?;
+            if let Some(running) = self.running_timer(tx)? {
+                return Err(format!("A timer is already running for task {}", running.task_id));
+            }
+            let mut entry = TimeEntry {
+                id: 0,
+                task_id,
+                source: TimeEntrySource::Timer,
+                started_at: Local::now().naive_local(),
+                ended_at: None,
+                minutes: 0,
+                note: None,
+            };
+            entry.id = self.repository.create(tx, &entry).map_err(|e| e.to_string())?;
+            Ok(entry)
+        })
+    }
+
+    fn stop_timer(&self, conn: &Connection) -> Result<Option<TimeEntry>, String> {
+        let Some(mut entry) = self.running_timer(conn)? else {
+            return Ok(None);
+        };
+        let ended_at = Local::now().naive_local();
+        // Jam sistem yang mundur tidak menghasilkan durasi negatif
+        let minutes = i32::try_from((ended_at - entry.started_at).num_minutes().max(0)).unwrap_or(i32::MAX);
+        self.repository.finish(conn, entry.id, ended_at, minutes).map_err(|e| e.to_string())?;
+        entry.ended_at = Some(ended_at);
+        entry.minutes = minutes;
+        Ok(Some(entry))
+    }
+
+    fn running_timer(&self, conn: &Connection) -> Result<Option<TimeEntry>, String> {
+        self.repository.fetch_running(conn).map_err(|e| e.to_string())
+    }
+
+    fn time_report(&self, conn: &Connection, from: NaiveDate, to: NaiveDate) -> Result<TimeReport, String> {
+        if to < from {
+            return Err("Report end date is before its start date".to_string());
+        }
+        let end = to.succ_opt().ok_or("Report end date is out of range")?;
+        let entries = self.repository
+            .fetch_between(conn, from.and_time(NaiveTime::MIN), end.and_time(NaiveTime::MIN))
+            .map_err(|e| e.to_string())?;
+        let tasks = self.task_repository.fetch_all(conn).map_err(|e| e.to_string())?;
+        Ok(build_report(tasks, &entries, from, to))
+    }
+}
+
+fn build_report(tasks: Vec<Task>, entries: &[TimeEntry], from: NaiveDate, to: NaiveDate) -> TimeReport {
+    let in_range = |date: NaiveDate| from <= date && date <= to;
+
+    // Catatan milik task di trash tidak dihitung, supaya total per kategori dan per minggu sama
+    let mut actual: HashMap<i32, i64> = HashMap::new();
+    let mut logged = Vec::new();
+    for entry in entries.iter().filter(|entry| tasks.iter().any(|task| task.id == entry.task_id)) {
+        *actual.entry(entry.task_id).or_default() += i64::from(entry.minutes);
+        logged.push(entry);
+    }
+
+    let mut report = TimeReport::default();
+    // Perkiraan task yang deadline-nya di `from`..=`to`, dipakai untuk total per kategori dan per
+    // minggu supaya keduanya selalu sama
+    let mut estimates: Vec<(NaiveDate, Category, i64)> = Vec::new();
+    for task in tasks {
+        let due_in_range = task.deadline.is_some_and(|deadline| in_range(deadline.date()));
+        if !actual.contains_key(&task.id) && !due_in_range {
+            continue;
+        }
+        // Perkiraan dihitung di minggu deadline-nya (beban yang direncanakan)
+        if let (Some(deadline), Some(estimate)) = (task.deadline.filter(|_| due_in_range), task.estimated_minutes) {
+            estimates.push((week_start(deadline.date()), task.category.clone(), i64::from(estimate)));
+        }
+        report.tasks.push(TaskTime {
+            task_id: task.id,
+            title: task.title,
+            category: task.category,
+            estimated_minutes: task.estimated_minutes,
+            actual_minutes: actual.get(&task.id).copied().unwrap_or(0),
+        });
+    }
+    // Yang paling banyak menyita waktu lebih dulu
+    report.tasks.sort_by(|a, b| b.actual_minutes.cmp(&a.actual_minutes).then(a.task_id.cmp(&b.task_id)));
+
+    for category in [Category::Study, Category::Work, Category::Personal] {
+        let tasks: Vec<&TaskTime> = report.tasks.iter().filter(|task| task.category == category).collect();
+        if tasks.is_empty() {
+            continue;
+        }
+        report.categories.push(CategoryTime {
+            estimated_minutes: estimates.iter().filter(|(_, c, _)| *c == category).map(|(_, _, minutes)| minutes).sum(),
+            actual_minutes: tasks.iter().map(|task| task.actual_minutes).sum(),
+            category,
+        });
+    }
+
+    // Minggu tanpa data tetap muncul dengan nol
+    let mut week = week_start(from);
+    while week <= to {
+        report.weeks.push(WeekTime {
+            week_start: week,
+            estimated_minutes: estimates.iter().filter(|(start, _, _)| *start == week).map(|(_, _, minutes)| minutes).sum(),
+            actual_minutes: logged.iter()
+                .filter(|entry| week_start(entry.started_at.date()) == week)
+                .map(|entry| i64::from(entry.minutes))
+                .sum(),
+        });
+        week += Duration::weeks(1);
+    }
+    report
+}
+
+// Senin di minggu yang sama
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(i64::from(date.weekday().num_days_from_monday()))
+}
@@ -0,0 +1,282 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::db::{in_transaction, Database};
+use crate::events::{DomainEvent, EventBus};
+use crate::fs::FileSystem;
+use crate::journal::Changes;
+use crate::model::task_history::TaskChangeKind;
+use crate::model::trash::{PurgeSummary, TrashConfig, TrashListing, TRASH_SETTINGS_KEY};
+use crate::repository::note::NoteRepository;
+use crate::repository::settings::SettingsRepository;
+use crate::repository::task::TaskRepository;
+use crate::repository::task_history::TaskHistoryRepository;
+use crate::services::attachment::AttachmentService;
+use crate::services::task::record_task_event;
+use crate::services::AppServices;
+
+// Purge otomatis cukup dicek sekali per jam
+const PURGE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+pub trait TrashService {
+    fn list_trash(&self, conn: &Connection) -> Result<TrashListing, String>;
+    fn restore_task(&self, conn: &Connection, id: i32) -> Result<(), String>;
+    fn restore_note(&self, conn: &Connection, id: i32) -> Result<(), String>;
+    // Hapus permanen seluruh isi trash
+    fn empty_trash(&self, conn: &Connection) -> Result<PurgeSummary, String>;
+    // Hapus permanen item yang sudah lebih lama dari masa simpan
+    fn purge_expired(&self, conn: &Connection) -> Result<PurgeSummary, String>;
+    fn load_config(&self, conn: &Connection) -> Result<TrashConfig, String>;
+    fn save_config(&self, conn: &Connection, config: &TrashConfig) -> Result<(), String>;
+}
+
+pub struct TrashServiceImpl<T: TaskRepository, H: TaskHistoryRepository, N: NoteRepository, F: FileSystem, S: SettingsRepository> {
+    pub task_repository: T,
+    // Pemulihan task dicatat di riwayatnya
+    pub task_history_repository: H,
+    pub note_repository: N,
+    pub fs: F,
+    pub settings: S,
+    pub events: EventBus,
+    // Folder `.trash` di vault tempat file markdown note yang dihapus disimpan
+    pub trash_dir: PathBuf,
+}
+
+impl<T, H, N, F, S> TrashService for TrashServiceImpl<T, H, N, F, S>
+where
+    T: TaskRepository,
+    H: TaskHistoryRepository,
+    N: NoteRepository,
+    F: FileSystem,
+    S: SettingsRepository,
+{
+    fn list_trash(&self, conn: &Connection) -> Result<TrashListing, String> {
+        Ok(TrashListing {
+            tasks: self.task_repository.fetch_deleted(conn).map_err(|e| e.to_string())?,
+            notes: self.note_repository.fetch_deleted(conn).map_err(|e| e.to_string())?,
+        })
+    }
+
+    fn restore_task(&self, conn: &Connection, id: i32) -> Result<(), String> {
+        let mut changes = Changes::new();
+        if !restore_task(&self.task_repository, &self.task_history_repository, &mut changes, conn, id)? {
+            return Err(format!("Task {} is not in the trash", id));
+        }
+        changes.publish_to(&self.events);
+        Ok(())
+    }
+
+    fn restore_note(&self, conn: &Connection, id: i32) -> Result<(), String> {
+        let mut changes = Changes::new();
+        if !restore_note(&self.note_repository, &self.fs, &self.trash_dir, &mut changes, conn, id)? {
+            return Err(format!("Note {} is not in the trash", id));
+        }
+        changes.publish_to(&self.events);
+        Ok(())
+    }
+
+    fn empty_trash(&self, conn: &Connection) -> Result<PurgeSummary, String> {
+        self.purge_deleted_before(conn, None)
+    }
+
+    fn purge_expired(&self, conn: &Connection) -> Result<PurgeSummary, String> {
+        let config = self.load_config(conn)?;
+        let cutoff = Utc::now().naive_utc() - Duration::days(i64::from(config.retention_days));
+        self.purge_deleted_before(conn, Some(cutoff))
+    }
+
+    fn load_config(&self, conn: &Connection) -> Result<TrashConfig, String> {
+        let value = self.settings.get(conn, TRASH_SETTINGS_KEY).map_err(|e| e.to_string())?;
+        match value {
+            Some(json) => serde_json::from_str(&json).map_err(|e| format!("Invalid trash settings: {}", e)),
+            None => Ok(TrashConfig::default()),
+        }
+    }
+
+    fn save_config(&self, conn: &Connection, config: &TrashConfig) -> Result<(), String> {
+        if config.retention_days == 0 {
+            return Err("Items must be kept in the trash for at least one day".to_string());
+        }
+        let json = serde_json::to_string(config).map_err(|e| e.to_string())?;
+        self.settings.set(conn, TRASH_SETTINGS_KEY, &json).map_err(|e| e.to_string())
+    }
+}
+
+impl<T, H, N, F, S> TrashServiceImpl<T, H, N, F, S>
+where
+    T: TaskRepository,
+    H: TaskHistoryRepository,
+    N: NoteRepository,
+    F: FileSystem,
+    S: SettingsRepository,
+{
+    // `cutoff` None berarti semua item di trash
+    fn purge_deleted_before(&self, conn: &Connection, cutoff: Option<NaiveDateTime>) -> Result<PurgeSummary, String> {
+        let expired = |deleted_at: NaiveDateTime| cutoff.is_none_or(|cutoff| deleted_at <= cutoff);
+        let tasks = self.task_repository.fetch_deleted(conn).map_err(|e| e.to_string())?;
+        let notes = self.note_repository.fetch_deleted(conn).map_err(|e| e.to_string())?;
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        let mut summary = PurgeSummary::default();
+        for trashed in tasks.iter().filter(|t| expired(t.deleted_at)) {
+            self.task_repository.delete(&tx, trashed.task.id).map_err(|e| e.to_string())?;
+            summary.tasks_purged += 1;
+        }
+        let mut files = Vec::new();
+        for trashed in notes.iter().filter(|n| expired(n.deleted_at)) {
+            self.note_repository.delete(&tx, trashed.note.id).map_err(|e| e.to_string())?;
+            files.push(trash_file_path(&self.trash_dir, trashed.note.id, &trashed.note.file_path));
+            summary.notes_purged += 1;
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+
+        // File baru dihapus setelah commit; file yang tertinggal masih bisa dibersihkan manual
+        for path in files {
+            if self.fs.exists(&path) {
+                self.fs.remove_file(&path).ok();
+            }
+        }
+        Ok(summary)
+    }
+}
+
+// Lokasi file markdown note di trash. Memakai id note supaya dua note dengan nama file
+// yang sama tidak saling menimpa.
+pub fn trash_file_path(trash_dir: &Path, id: i32, file_path: &str) -> PathBuf {
+    let file_name = Path::new(file_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "note.md".to_string());
+    trash_dir.join(format!("{}-{}", id, file_name))
+}
+
+// Pindahkan task ke trash. `false` jika task tidak ada (atau sudah di trash).
+pub fn trash_task(
+    repository: &impl TaskRepository,
+    history: &impl TaskHistoryRepository,
+    changes: &mut Changes,
+    conn: &Connection,
+    id: i32,
+) -> Result<bool, String> {
+    let trashed = in_transaction(conn, |tx| {
+        if !repository.soft_delete(tx, id, Utc::now().naive_utc()).map_err(|e| e.to_string())? {
+            return Ok(false);
+        }
+        record_task_event(history, tx, id, TaskChangeKind::Deleted)?;
+        Ok(true)
+    })?;
+    if trashed {
+        changes.publish(DomainEvent::TaskDeleted { id });
+    }
+    Ok(trashed)
+}
+
+pub fn restore_task(
+    repository: &impl TaskRepository,
+    history: &impl TaskHistoryRepository,
+    changes: &mut Changes,
+    conn: &Connection,
+    id: i32,
+) -> Result<bool, String> {
+    let restored = in_transaction(conn, |tx| {
+        if !repository.undelete(tx, id).map_err(|e| e.to_string())? {
+            return Ok(false);
+        }
+        record_task_event(history, tx, id, TaskChangeKind::Restored)?;
+        Ok(true)
+    })?;
+    if !restored {
+        return Ok(false);
+    }
+    if let Some(task) = repository.fetch_by_id(conn, id).map_err(|e| e.to_string())? {
+        changes.publish(DomainEvent::TaskCreated(task));
+    }
+    Ok(true)
+}
+
+// Pindahkan note ke trash beserta file markdown-nya. Perubahan database baru di-commit
+// setelah file berhasil dipindahkan.
+pub fn trash_note(
+    repository: &impl NoteRepository,
+    fs: &impl FileSystem,
+    trash_dir: &Path,
+    changes: &mut Changes,
+    conn: &Connection,
+    id: i32,
+) -> Result<bool, String> {
+    let Some(note) = repository.fetch_by_id(conn, id).map_err(|e| e.to_string())? else {
+        return Ok(false);
+    };
+    in_transaction(conn, |tx| {
+        repository.soft_delete(tx, id, Utc::now().naive_utc()).map_err(|e| e.to_string())?;
+        move_file(fs, Path::new(&note.file_path), &trash_file_path(trash_dir, id, &note.file_path))
+    })?;
+    changes.publish(DomainEvent::NoteDeleted { id });
+    Ok(true)
+}
+
+pub fn restore_note(
+    repository: &impl NoteRepository,
+    fs: &impl FileSystem,
+    trash_dir: &Path,
+    changes: &mut Changes,
+    conn: &Connection,
+    id: i32,
+) -> Result<bool, String> {
+    let Some(file_path) = repository.get_file_path_by_id(conn, id).map_err(|e| e.to_string())? else {
+        return Ok(false);
+    };
+    let restored = in_transaction(conn, |tx| {
+        let restored = repository.undelete(tx, id).map_err(|e| {
+            if e.to_string().contains("UNIQUE") {
+                format!("Another note already uses {}", file_path)
+            } else {
+                e.to_string()
+            }
+        })?;
+        if !restored {
+            return Ok(false);
+        }
+        if fs.exists(Path::new(&file_path)) {
+            return Err(format!("A file already exists at {}", file_path));
+        }
+        move_file(fs, &trash_file_path(trash_dir, id, &file_path), Path::new(&file_path))?;
+        Ok(true)
+    })?;
+    if !restored {
+        return Ok(false);
+    }
+
+    if let Some(note) = repository.fetch_by_id(conn, id).map_err(|e| e.to_string())? {
+        changes.publish(DomainEvent::NoteCreated(note));
+    }
+    Ok(true)
+}
+
+fn move_file(fs: &impl FileSystem, from: &Path, to: &Path) -> Result<(), String> {
+    // File yang sudah hilang di luar aplikasi tidak menghalangi pemindahan ke/dari trash
+    if !fs.exists(from) {
+        return Ok(());
+    }
+    if let Some(parent) = to.parent() {
+        fs.create_dir_all(parent)
+            .map_err(|e| format!("Failed to create folder: {}", e))?;
+    }
+    fs.rename(from, to)
+        .map_err(|e| format!("Failed to move note file: {}", e))
+}
+
+// Jalankan purge saat startup, lalu berkala di thread terpisah
+pub fn start_trash_purge_scheduler(db: Arc<Database>, services: Arc<AppServices>) {
+    std::thread::spawn(move || loop {
+        if let Err(e) = db.get().and_then(|conn| services.trash.purge_expired(&conn)) {
+            eprintln!("Purge trash gagal: {}", e);
+        }
+        // Lampiran milik item yang baru di-purge ikut dibersihkan
+        if let Err(e) = db.get().and_then(|conn| services.attachment.collect_garbage(&conn)) {
+            eprintln!("Pembersihan lampiran gagal: {}", e);
+        }
+        std::thread::sleep(PURGE_INTERVAL);
+    });
+}
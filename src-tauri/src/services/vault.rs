@@ -0,0 +1,329 @@
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use notify_debouncer_full::notify::event::{ModifyKind, RenameMode};
+use notify_debouncer_full::notify::{EventKind, RecursiveMode};
+use notify_debouncer_full::{new_debouncer, DebounceEventResult};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Arc;
+use crate::db::Database;
+use crate::events::{DomainEvent, EventBus};
+use crate::fs::FileSystem;
+use crate::model::front_matter::FrontMatter;
+use crate::model::note::Note;
+use crate::model::vault::VaultSyncSummary;
+use crate::repository::link::LinkRepository;
+use crate::repository::note::NoteRepository;
+use crate::services::note::index_links;
+use crate::services::AppServices;
+
+// Bursts of file events (an editor saving, a sync client writing many files) are merged
+const DEBOUNCE_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+// Our own saves write the file right after updating the row; anything closer than this
+// to `updated_at` is not treated as an external edit
+const EDIT_TOLERANCE_SECONDS: i64 = 2;
+
+pub trait VaultService {
+    // Brings the notes table in line with the markdown files in the vault. `renames` are
+    // (from, to) pairs reported by the watcher so a renamed file keeps its note.
+    fn reconcile(&self, conn: &Connection, renames: &[(PathBuf, PathBuf)]) -> Result<VaultSyncSummary, String>;
+    // Like `reconcile`, but re-reads the front-matter of every file instead of only the ones
+    // edited since the last sync, so the notes table can be rebuilt from the vault
+    fn reindex(&self, conn: &Connection) -> Result<VaultSyncSummary, String>;
+}
+
+pub struct VaultServiceImpl<R: NoteRepository, L: LinkRepository, F: FileSystem> {
+    pub repository: R,
+    // Links of files edited outside the app are re-indexed during a sync
+    pub link_repository: L,
+    pub fs: F,
+    pub events: EventBus,
+    pub vault_dir: PathBuf,
+}
+
+impl<R: NoteRepository, L: LinkRepository, F: FileSystem> VaultService for VaultServiceImpl<R, L, F> {
+    fn reconcile(&self, conn: &Connection, renames: &[(PathBuf, PathBuf)]) -> Result<VaultSyncSummary, String> {
+        self.sync(conn, renames, false)
+    }
+
+    fn reindex(&self, conn: &Connection) -> Result<VaultSyncSummary, String> {
+        self.sync(conn, &[], true)
+    }
+}
+
+impl<R: NoteRepository, L: LinkRepository, F: FileSystem> VaultServiceImpl<R, L, F> {
+    // Metadata comes from the front-matter when a file has one; a file without it keeps the
+    // metadata already in the table. `full` reads every file, otherwise only edited ones.
+    fn sync(&self, conn: &Connection, renames: &[(PathBuf, PathBuf)], full: bool) -> Result<VaultSyncSummary, String> {
+        // Keyed by the normalized path, so `vault/./a.md` and `vault/a.md` are the same file
+        let notes = self.repository.fetch_all(conn).map_err(|e| e.to_string())?;
+        let mut by_path: HashMap<PathBuf, Note> = notes
+            .into_iter()
+            .map(|note| (absolute_path(Path::new(&note.file_path)), note))
+            .collect();
+        let mut summary = VaultSyncSummary::default();
+        let mut created = Vec::new();
+        let mut changed = Vec::new();
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        for (from, to) in renames {
+            if by_path.contains_key(&absolute_path(to)) || !self.fs.exists(to) {
+                continue;
+            }
+            let Ok(file_path) = vault_path(&self.vault_dir, to) else {
+                continue;
+            };
+            let Some(mut note) = by_path.remove(&absolute_path(from)) else {
+                continue;
+            };
+            // A title that was just the file name follows the new name; a custom title, or one
+            // set in the front-matter, stays
+            let (front, _) = self.read_markdown(to)?;
+            if front.and_then(|front| front.title).is_none() && note.title == file_stem(from) {
+                note.title = file_stem(to);
+            }
+            note.file_path = file_path;
+            self.repository.update(&tx, &note).map_err(|e| e.to_string())?;
+            summary.renamed += 1;
+            changed.push(note.id);
+            by_path.insert(absolute_path(to), note);
+        }
+
+        for note in by_path.values() {
+            // The file is looked up by its clean path; `a/../b.md` fails on disk when `a` is gone
+            let file_path = vault_path(&self.vault_dir, Path::new(&note.file_path))
+                .unwrap_or_else(|_| note.file_path.clone());
+            let path = Path::new(&file_path);
+            let exists = self.fs.exists(path);
+            if exists == note.missing {
+                self.repository.set_missing(&tx, note.id, !exists).map_err(|e| e.to_string())?;
+                if exists {
+                    summary.recovered += 1;
+                } else {
+                    summary.missing += 1;
+                }
+                changed.push(note.id);
+            }
+            if !exists {
+                continue;
+            }
+            let modified = self.modified_at(path)?;
+            let edited = edited_at(note.updated_at, modified) != note.updated_at;
+            if !edited && !full {
+                continue;
+            }
+            let mut updated = note.clone();
+            let (front, body) = self.read_markdown(path)?;
+            if let Some(front) = front {
+                front.apply_to(&mut updated);
+            }
+            index_links(&self.link_repository, &tx, note.id, &body)?;
+            updated.updated_at = edited_at(updated.updated_at, modified);
+            if updated != *note {
+                self.repository.update_metadata(&tx, &updated).map_err(|e| e.to_string())?;
+                summary.updated += 1;
+                changed.push(note.id);
+            }
+        }
+
+        for path in self.markdown_files()? {
+            if by_path.contains_key(&absolute_path(&path)) {
+                continue;
+            }
+            let modified = self.modified_at(&path)?;
+            let (front, body) = self.read_markdown(&path)?;
+            let mut note = Note {
+                id: 0,
+                title: title_from_markdown(&body).unwrap_or_else(|| file_stem(&path)),
+                file_path: path.to_string_lossy().into_owned(),
+                created_at: modified,
+                updated_at: modified,
+                missing: false,
+                tags: Vec::new(),
+                color: None,
+                task_ids: Vec::new(),
+            };
+            if let Some(front) = front {
+                front.apply_to(&mut note);
+                note.updated_at = edited_at(note.updated_at, modified);
+            }
+            let id = self.repository.insert_with_timestamps(&tx, &note).map_err(|e| e.to_string())?;
+            index_links(&self.link_repository, &tx, id, &body)?;
+            created.push(id);
+            summary.registered += 1;
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+
+        changed.sort_unstable();
+        changed.dedup();
+        for (ids, created) in [(created, true), (changed, false)] {
+            for id in ids {
+                let Some(note) = self.repository.fetch_by_id(conn, id).map_err(|e| e.to_string())? else {
+                    continue;
+                };
+                self.events.publish(if created { DomainEvent::NoteCreated(note) } else { DomainEvent::NoteUpdated(note) });
+            }
+        }
+        Ok(summary)
+    }
+
+    // All `.md` files under the vault. Hidden folders such as `.trash`, `.obsidian`
+    // or `.git` are skipped.
+    fn markdown_files(&self) -> Result<Vec<PathBuf>, String> {
+        let mut files = Vec::new();
+        let mut pending = vec![self.vault_dir.clone()];
+        while let Some(dir) = pending.pop() {
+            if !self.fs.is_dir(&dir) {
+                continue;
+            }
+            let entries = self.fs.read_dir(&dir)
+                .map_err(|e| format!("Failed to read folder {}: {}", dir.display(), e))?;
+            for entry in entries {
+                if is_hidden(&entry) {
+                    continue;
+                }
+                if self.fs.is_dir(&entry) {
+                    pending.push(entry);
+                } else if is_markdown(&entry) {
+                    files.push(entry);
+                }
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    // The front-matter and body of a markdown file
+    fn read_markdown(&self, path: &Path) -> Result<(Option<FrontMatter>, String), String> {
+        let content = self.fs.read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let content = String::from_utf8_lossy(&content);
+        let (front, body) = FrontMatter::split(&content);
+        Ok((front, body.to_string()))
+    }
+
+    fn modified_at(&self, path: &Path) -> Result<NaiveDateTime, String> {
+        let modified = self.fs.modified(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Ok(DateTime::<Utc>::from(modified).naive_utc())
+    }
+}
+
+// How a note's file path is stored: `vault_dir` followed by the path inside the vault, without
+// `.` or `..`. A relative `path` is read from the working directory, like `vault_dir` itself.
+// Paths that leave the vault are rejected.
+pub fn vault_path(vault_dir: &Path, path: &Path) -> Result<String, String> {
+    let inside = absolute_path(path)
+        .strip_prefix(absolute_path(vault_dir))
+        .map(Path::to_path_buf)
+        .map_err(|_| format!("{} is outside the notes folder", path.display()))?;
+    if inside.as_os_str().is_empty() {
+        return Err(format!("{} is not a file in the notes folder", path.display()));
+    }
+    Ok(vault_dir.join(inside).to_string_lossy().into_owned())
+}
+
+// `path` made absolute with `.` and `..` resolved, without touching the file system, so
+// files that don't exist yet (or only exist in a test file system) can be compared
+pub fn absolute_path(path: &Path) -> PathBuf {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().unwrap_or_default().join(path)
+    };
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+// The file's modification time if it was edited after `updated_at`, otherwise `updated_at`
+fn edited_at(updated_at: NaiveDateTime, modified: NaiveDateTime) -> NaiveDateTime {
+    if modified > updated_at + Duration::seconds(EDIT_TOLERANCE_SECONDS) {
+        modified
+    } else {
+        updated_at
+    }
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
+
+fn is_markdown(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+}
+
+// The first level-one heading, if the file starts with one
+fn title_from_markdown(content: &str) -> Option<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .and_then(|line| line.strip_prefix("# "))
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty())
+}
+
+// Syncs once at startup, then watches the vault on a separate thread and syncs again
+// after every (debounced) batch of changes to markdown files.
+pub fn start_vault_watcher(db: Arc<Database>, services: Arc<AppServices>) -> Result<(), String> {
+    let vault_dir = services.vault.vault_dir.clone();
+    std::fs::create_dir_all(&vault_dir).map_err(|e| format!("Failed to create vault folder: {}", e))?;
+    // The watcher reports absolute paths; notes store paths relative to `vault_dir`
+    let watched_dir = vault_dir.canonicalize().map_err(|e| e.to_string())?;
+
+    let (tx, rx) = mpsc::channel::<DebounceEventResult>();
+    let mut debouncer = new_debouncer(DEBOUNCE_TIMEOUT, None, tx).map_err(|e| e.to_string())?;
+    debouncer.watch(&watched_dir, RecursiveMode::Recursive).map_err(|e| e.to_string())?;
+
+    let sync = move |renames: &[(PathBuf, PathBuf)]| {
+        if let Err(e) = db.get().and_then(|conn| services.vault.reconcile(&conn, renames)) {
+            eprintln!("Vault sync failed: {}", e);
+        }
+    };
+    std::thread::spawn(move || {
+        // Owning the debouncer here keeps the watcher alive for the lifetime of the thread
+        let _debouncer = debouncer;
+        sync(&[]);
+        for result in rx {
+            let events = match result {
+                Ok(events) => events,
+                Err(errors) => {
+                    eprintln!("Vault watcher error: {:?}", errors);
+                    continue;
+                }
+            };
+            let to_vault_path = |path: &Path| path.strip_prefix(&watched_dir).ok().map(|rel| vault_dir.join(rel));
+            // Changes that only touch hidden folders (our own trash, editor config) are ignored
+            let relevant = events.iter().flat_map(|event| &event.paths).any(|path| {
+                path.strip_prefix(&watched_dir)
+                    .is_ok_and(|rel| !rel.iter().any(|part| part.to_string_lossy().starts_with('.')))
+            });
+            if !relevant {
+                continue;
+            }
+            let renames: Vec<(PathBuf, PathBuf)> = events
+                .iter()
+                .filter(|event| matches!(event.kind, EventKind::Modify(ModifyKind::Name(RenameMode::Both))))
+                .filter_map(|event| match event.paths.as_slice() {
+                    [from, to] => Some((to_vault_path(from)?, to_vault_path(to)?)),
+                    _ => None,
+                })
+                .collect();
+            sync(&renames);
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use rusqlite::Connection;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+use nocture_tauri_lib::events::{DomainEvent, EventBus};
+use nocture_tauri_lib::fs::StdFileSystem;
+use nocture_tauri_lib::journal::Journal;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::front_matter::FrontMatter;
+use nocture_tauri_lib::model::note::NotePayload;
+use nocture_tauri_lib::model::note_revision::RevisionPolicy;
+use nocture_tauri_lib::model::task::{Category, Priority, TaskPayload};
+use nocture_tauri_lib::repository::link::SqliteLinkRepository;
+use nocture_tauri_lib::repository::note::SqliteNoteRepository;
+use nocture_tauri_lib::repository::note_revision::SqliteNoteRevisionRepository;
+use nocture_tauri_lib::repository::task::SqliteTaskRepository;
+use nocture_tauri_lib::repository::task_history::SqliteTaskHistoryRepository;
+use nocture_tauri_lib::services::archive::{ArchiveService, ArchiveServiceImpl};
+use nocture_tauri_lib::services::note::{NoteService, NoteServiceImpl};
+use nocture_tauri_lib::services::task::{TaskService, TaskServiceImpl};
+
+fn setup_conn() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    conn
+}
+
+fn archive_service() -> ArchiveServiceImpl<SqliteTaskRepository, SqliteNoteRepository, StdFileSystem> {
+    ArchiveServiceImpl {
+        task_repository: SqliteTaskRepository,
+        note_repository: SqliteNoteRepository,
+        fs: StdFileSystem,
+        events: EventBus::new(),
+        journal: Journal::default(),
+    }
+}
+
+fn note_service(vault: &Path) -> NoteServiceImpl<SqliteNoteRepository, SqliteNoteRevisionRepository, SqliteLinkRepository, StdFileSystem> {
+    NoteServiceImpl {
+        repository: SqliteNoteRepository,
+        revision_repository: SqliteNoteRevisionRepository,
+        revision_policy: RevisionPolicy::default(),
+        link_repository: SqliteLinkRepository,
+        fs: StdFileSystem,
+        events: EventBus::new(),
+        journal: Journal::default(),
+        vault_dir: vault.to_path_buf(),
+        trash_dir: vault.join(".trash"),
+        note_tasks: (),
+    }
+}
+
+#[test]
+fn test_export_and_import_round_trip() {
+    let source_vault = tempfile::tempdir().unwrap();
+    let target_vault = tempfile::tempdir().unwrap();
+    let archive_dir = tempfile::tempdir().unwrap();
+    let archive_path = archive_dir.path().join("backup.zip");
+
+    let source = setup_conn();
+    let tasks = TaskServiceImpl { repository: SqliteTaskRepository, history_repository: SqliteTaskHistoryRepository, events: EventBus::new(), journal: Journal::default(), checkboxes: (), planner: () };
+    let notes = note_service(source_vault.path());
+    tasks.create_task(&source, TaskPayload {
+        title: "Belajar Rust".to_string(),
+        category: Category::Study,
+        priority: Priority::High,
+        deadline: Some("2025-04-10".to_string()),
+        estimated_minutes: None,
+    }).unwrap();
+    let file_path = source_vault.path().join("lecture.md").to_string_lossy().into_owned();
+    notes.create_note(&source, NotePayload { title: "Lecture".to_string(), file_path }).unwrap();
+    let note = notes.fetch_notes(&source).unwrap().remove(0);
+    notes.update_note(&source, note, "# Week 1\nOwnership").unwrap();
+
+    let exported = archive_service().export_archive(&source, &archive_path).unwrap();
+    assert_eq!(exported.tasks_exported, 1);
+    assert_eq!(exported.notes_exported, 1);
+
+    // Occupy the first task id so the imported one has to be remapped.
+    let target = setup_conn();
+    tasks.create_task(&target, TaskPayload {
+        title: "Existing".to_string(),
+        category: Category::Work,
+        priority: Priority::Low,
+        deadline: None,
+        estimated_minutes: None,
+    }).unwrap();
+
+    // Ids in the undo history may not mean the same thing after an import
+    let importer = ArchiveServiceImpl { journal: tasks.journal.clone(), ..archive_service() };
+    assert!(tasks.journal.status().undo.is_some());
+    let mut rx = importer.events.subscribe();
+    let summary = importer.import_archive(&target, &archive_path, target_vault.path()).unwrap();
+    assert!(matches!(rx.try_recv().unwrap(), DomainEvent::TaskCreated(task) if task.id == 2));
+    assert!(matches!(rx.try_recv().unwrap(), DomainEvent::NoteCreated(note) if note.id == summary.notes[0].new_id));
+    assert!(matches!(rx.try_recv().unwrap(), DomainEvent::DataReloaded));
+    assert!(rx.try_recv().is_err());
+    assert_eq!(tasks.journal.status().undo, None);
+    assert_eq!(summary.tasks.len(), 1);
+    assert_eq!(summary.tasks[0].old_id, 1);
+    assert_eq!(summary.tasks[0].new_id, 2);
+
+    let imported_tasks = tasks.fetch_tasks(&target).unwrap();
+    let imported = imported_tasks.iter().find(|t| t.id == 2).unwrap();
+    assert_eq!(imported.title, "Belajar Rust");
+    assert_eq!(imported.priority, Priority::High);
+    assert!(imported.deadline.is_some());
+
+    let imported_notes = notes.fetch_notes(&target).unwrap();
+    assert_eq!(imported_notes.len(), 1);
+    assert!(imported_notes[0].file_path.starts_with(target_vault.path().to_str().unwrap()));
+    let content = std::fs::read_to_string(&imported_notes[0].file_path).unwrap();
+    let (front, body) = FrontMatter::split(&content);
+    assert_eq!(body, "# Week 1\nOwnership");
+    assert_eq!(front.unwrap().title.as_deref(), Some("Lecture"));
+}
+
+#[test]
+fn test_import_rejects_unknown_schema_version() {
+    let dir = tempfile::tempdir().unwrap();
+    let archive_path = dir.path().join("future.zip");
+    let mut zip = ZipWriter::new(File::create(&archive_path).unwrap());
+    zip.start_file("manifest.json", SimpleFileOptions::default()).unwrap();
+    zip.write_all(br#"{"schema_version": 99, "exported_at": "2025-01-01T00:00:00", "tasks": [], "notes": []}"#).unwrap();
+    zip.finish().unwrap();
+
+    let conn = setup_conn();
+    let err = archive_service().import_archive(&conn, &archive_path, dir.path()).unwrap_err();
+    assert!(err.contains("Unsupported archive version 99"));
+}
+
+#[test]
+fn test_import_missing_body_leaves_database_untouched() {
+    let dir = tempfile::tempdir().unwrap();
+    let archive_path = dir.path().join("broken.zip");
+    let mut zip = ZipWriter::new(File::create(&archive_path).unwrap());
+    zip.start_file("manifest.json", SimpleFileOptions::default()).unwrap();
+    zip.write_all(br#"{
+        "schema_version": 1,
+        "exported_at": "2025-01-01T00:00:00",
+        "tasks": [],
+        "notes": [{"id": 7, "title": "Lost", "body_file": "notes/7.md",
+                   "created_at": "2025-01-01T00:00:00", "updated_at": "2025-01-01T00:00:00"}]
+    }"#).unwrap();
+    zip.finish().unwrap();
+
+    let conn = setup_conn();
+    let vault = dir.path().join("vault");
+    let err = archive_service().import_archive(&conn, &archive_path, &vault).unwrap_err();
+    assert!(err.contains("notes/7.md"));
+
+    let notes = note_service(dir.path());
+    assert!(notes.fetch_notes(&conn).unwrap().is_empty());
+}
@@ -0,0 +1,203 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use nocture_tauri_lib::commands::attachment_response;
+use nocture_tauri_lib::db::Database;
+use nocture_tauri_lib::events::EventBus;
+use nocture_tauri_lib::fs::{FileSystem, InMemoryFileSystem};
+use nocture_tauri_lib::journal::Journal;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::attachment::{AttachmentGcSummary, AttachmentOwner};
+use nocture_tauri_lib::model::note::NotePayload;
+use nocture_tauri_lib::model::task::{Category, Priority, Task};
+use nocture_tauri_lib::repository::attachment::SqliteAttachmentRepository;
+use nocture_tauri_lib::repository::note::{NoteRepository, SqliteNoteRepository};
+use nocture_tauri_lib::repository::task::{SqliteTaskRepository, TaskRepository};
+use nocture_tauri_lib::services::attachment::{AttachmentService, AttachmentServiceImpl};
+use nocture_tauri_lib::services::AppServices;
+
+const ATTACHMENTS_DIR: &str = "vault/.attachments";
+
+type Service = AttachmentServiceImpl<SqliteAttachmentRepository, SqliteTaskRepository, SqliteNoteRepository, InMemoryFileSystem>;
+
+fn setup() -> (Connection, InMemoryFileSystem, Service) {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    let fs = InMemoryFileSystem::new();
+    let service = AttachmentServiceImpl {
+        repository: SqliteAttachmentRepository,
+        task_repository: SqliteTaskRepository,
+        note_repository: SqliteNoteRepository,
+        fs: fs.clone(),
+        attachments_dir: ATTACHMENTS_DIR.into(),
+    };
+    (conn, fs, service)
+}
+
+fn create_task(conn: &Connection) -> i32 {
+    let now = Utc::now().naive_utc();
+    SqliteTaskRepository.create(conn, &Task {
+        id: 0,
+        title: "Laporan praktikum".to_string(),
+        category: Category::Study,
+        priority: Priority::Medium,
+        deadline: None,
+        completed: false,
+        created_at: now,
+        updated_at: now,
+        estimated_minutes: None,
+    }).unwrap()
+}
+
+fn create_note(conn: &Connection) -> i32 {
+    SqliteNoteRepository.create(conn, &NotePayload {
+        title: "Lecture".to_string(),
+        file_path: "vault/Lecture.md".to_string(),
+    }).unwrap()
+}
+
+fn write_file(fs: &InMemoryFileSystem, path: &str, contents: &[u8]) -> PathBuf {
+    let path = PathBuf::from(path);
+    fs.create_dir_all(path.parent().unwrap()).unwrap();
+    fs.write(&path, contents).unwrap();
+    path
+}
+
+// Semua file yang tersimpan di folder lampiran
+fn stored_files(fs: &InMemoryFileSystem) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for dir in fs.read_dir(Path::new(ATTACHMENTS_DIR)).unwrap_or_default() {
+        files.extend(fs.list(dir));
+    }
+    files.sort();
+    files
+}
+
+#[test]
+fn test_same_content_is_stored_once() {
+    let (conn, fs, service) = setup();
+    let task_id = create_task(&conn);
+    let note_id = create_note(&conn);
+    let source = write_file(&fs, "downloads/diagram.png", b"png bytes");
+    let copy = write_file(&fs, "downloads/copy.png", b"png bytes");
+
+    let first = service.add_attachment(&conn, AttachmentOwner::Task, task_id, &source).unwrap();
+    assert_eq!(first.file_name, "diagram.png");
+    assert_eq!(first.mime_type, "image/png");
+    assert_eq!(first.size, 9);
+    assert_eq!(first.content_hash.len(), 64);
+
+    // Lampiran yang sama ke pemilik yang sama tidak menambah baris
+    let again = service.add_attachment(&conn, AttachmentOwner::Task, task_id, &copy).unwrap();
+    assert_eq!(again, first);
+    let on_note = service.add_attachment(&conn, AttachmentOwner::Note, note_id, &copy).unwrap();
+    assert_ne!(on_note.id, first.id);
+    assert_eq!(on_note.content_hash, first.content_hash);
+
+    let expected = Path::new(ATTACHMENTS_DIR).join(&first.content_hash[..2]).join(&first.content_hash);
+    assert_eq!(stored_files(&fs), vec![expected]);
+    assert_eq!(service.list_attachments(&conn, AttachmentOwner::Task, task_id).unwrap(), vec![first.clone()]);
+    assert_eq!(service.read_attachment(&conn, on_note.id).unwrap(), (on_note, b"png bytes".to_vec()));
+}
+
+#[test]
+fn test_unknown_extension_is_octet_stream() {
+    let (conn, fs, service) = setup();
+    let task_id = create_task(&conn);
+    let source = write_file(&fs, "downloads/data.nocture", b"raw");
+    let attachment = service.add_attachment(&conn, AttachmentOwner::Task, task_id, &source).unwrap();
+    assert_eq!(attachment.mime_type, "application/octet-stream");
+}
+
+#[test]
+fn test_owner_must_exist_and_not_be_trashed() {
+    let (conn, fs, service) = setup();
+    let source = write_file(&fs, "downloads/a.txt", b"a");
+    let error = service.add_attachment(&conn, AttachmentOwner::Note, 99, &source).unwrap_err();
+    assert_eq!(error, "Note 99 not found");
+
+    let task_id = create_task(&conn);
+    SqliteTaskRepository.soft_delete(&conn, task_id, Utc::now().naive_utc()).unwrap();
+    assert!(service.add_attachment(&conn, AttachmentOwner::Task, task_id, &source).is_err());
+    assert!(stored_files(&fs).is_empty());
+}
+
+#[test]
+fn test_remove_keeps_file_while_still_used() {
+    let (conn, fs, service) = setup();
+    let task_id = create_task(&conn);
+    let note_id = create_note(&conn);
+    let source = write_file(&fs, "downloads/report.pdf", b"%PDF");
+    let on_task = service.add_attachment(&conn, AttachmentOwner::Task, task_id, &source).unwrap();
+    let on_note = service.add_attachment(&conn, AttachmentOwner::Note, note_id, &source).unwrap();
+
+    service.remove_attachment(&conn, on_task.id).unwrap();
+    assert_eq!(stored_files(&fs).len(), 1);
+    assert!(service.list_attachments(&conn, AttachmentOwner::Task, task_id).unwrap().is_empty());
+
+    service.remove_attachment(&conn, on_note.id).unwrap();
+    assert!(stored_files(&fs).is_empty());
+    assert!(service.read_attachment(&conn, on_note.id).is_err());
+}
+
+#[test]
+fn test_garbage_collection_removes_orphans() {
+    let (conn, fs, service) = setup();
+    let purged = create_task(&conn);
+    let trashed = create_task(&conn);
+    let note_id = create_note(&conn);
+    let shared = write_file(&fs, "downloads/shared.txt", b"shared");
+    let only_purged = write_file(&fs, "downloads/only.txt", b"only purged");
+    service.add_attachment(&conn, AttachmentOwner::Task, purged, &shared).unwrap();
+    service.add_attachment(&conn, AttachmentOwner::Task, purged, &only_purged).unwrap();
+    service.add_attachment(&conn, AttachmentOwner::Note, note_id, &shared).unwrap();
+    service.add_attachment(&conn, AttachmentOwner::Task, trashed, &only_purged).unwrap();
+    SqliteTaskRepository.soft_delete(&conn, trashed, Utc::now().naive_utc()).unwrap();
+    SqliteTaskRepository.delete(&conn, purged).unwrap();
+
+    // File yatim yang sudah lama dihapus, yang baru saja ditulis dibiarkan
+    let old = SystemTime::now() - Duration::from_secs(3600);
+    write_file(&fs, "vault/.attachments/ab/stray", b"12345");
+    fs.set_modified("vault/.attachments/ab/stray", old);
+    write_file(&fs, "vault/.attachments/cd/fresh", b"in progress");
+
+    let summary = service.collect_garbage(&conn).unwrap();
+    assert_eq!(summary, AttachmentGcSummary { rows_removed: 2, files_removed: 1, bytes_freed: 5 });
+    assert!(service.list_attachments(&conn, AttachmentOwner::Task, purged).unwrap().is_empty());
+    assert_eq!(service.list_attachments(&conn, AttachmentOwner::Task, trashed).unwrap().len(), 1);
+    assert_eq!(stored_files(&fs).len(), 3);
+
+    assert_eq!(service.collect_garbage(&conn).unwrap(), AttachmentGcSummary::default());
+}
+
+#[test]
+fn test_script_capable_attachments_are_served_as_downloads() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = Database::open(dir.path().join("nocture.db")).unwrap();
+    run_migrations(&db.get().unwrap()).unwrap();
+    let services = AppServices::new(EventBus::new(), Journal::default(), dir.path().join("vault"));
+    let task_id = create_task(&db.get().unwrap());
+
+    let mut ids = Vec::new();
+    for (name, contents) in [("page.html", "<script>alert(1)</script>"), ("logo.svg", "<svg/>"), ("photo.png", "png")] {
+        let source = dir.path().join(name);
+        std::fs::write(&source, contents).unwrap();
+        ids.push(services.attachment.add_attachment(&db.get().unwrap(), AttachmentOwner::Task, task_id, &source).unwrap().id);
+    }
+
+    let header = |response: &tauri::http::Response<Vec<u8>>, name: &str| {
+        response.headers().get(name).unwrap().to_str().unwrap().to_string()
+    };
+    for id in &ids[..2] {
+        let response = attachment_response(&db, &services, &format!("/{}", id));
+        assert_eq!(header(&response, "content-type"), "application/octet-stream");
+        assert!(header(&response, "content-disposition").starts_with("attachment;"));
+        assert_eq!(header(&response, "x-content-type-options"), "nosniff");
+        assert!(header(&response, "content-security-policy").starts_with("default-src 'none'"));
+    }
+    let image = attachment_response(&db, &services, &format!("/{}", ids[2]));
+    assert_eq!(header(&image, "content-type"), "image/png");
+    assert_eq!(header(&image, "content-disposition"), "inline; filename=\"photo.png\"");
+    assert_eq!(header(&image, "x-content-type-options"), "nosniff");
+}
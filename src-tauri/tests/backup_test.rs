@@ -0,0 +1,230 @@
+use rusqlite::Connection;
+use std::path::Path;
+use nocture_tauri_lib::db::Database;
+use nocture_tauri_lib::events::{DomainEvent, EventBus};
+use nocture_tauri_lib::journal::Journal;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::backup::BackupConfig;
+use nocture_tauri_lib::model::task::{Category, Priority, TaskPayload};
+use nocture_tauri_lib::repository::settings::SqliteSettingsRepository;
+use nocture_tauri_lib::repository::task::SqliteTaskRepository;
+use nocture_tauri_lib::repository::task_history::SqliteTaskHistoryRepository;
+use nocture_tauri_lib::services::backup::{verify_integrity, BackupService, BackupServiceImpl};
+use nocture_tauri_lib::services::task::{TaskService, TaskServiceImpl};
+
+fn backup_service() -> BackupServiceImpl<SqliteSettingsRepository> {
+    BackupServiceImpl { settings: SqliteSettingsRepository, events: EventBus::new(), journal: Journal::default() }
+}
+
+fn add_task(conn: &Connection, title: &str) {
+    let tasks = TaskServiceImpl { repository: SqliteTaskRepository, history_repository: SqliteTaskHistoryRepository, events: EventBus::new(), journal: Journal::default(), checkboxes: (), planner: () };
+    tasks.create_task(conn, TaskPayload {
+        title: title.to_string(),
+        category: Category::Study,
+        priority: Priority::Medium,
+        deadline: None,
+        estimated_minutes: None,
+    }).unwrap();
+}
+
+fn task_titles(db: &Database) -> Vec<String> {
+    let tasks = TaskServiceImpl { repository: SqliteTaskRepository, history_repository: SqliteTaskHistoryRepository, events: EventBus::new(), journal: Journal::default(), checkboxes: (), planner: () };
+    let conn = db.get().unwrap();
+    tasks.fetch_tasks(&conn).unwrap().into_iter().map(|t| t.title).collect()
+}
+
+fn open_database(dir: &Path) -> Database {
+    let db = Database::open(dir.join("nocture.db")).unwrap();
+    run_migrations(&db.get().unwrap()).unwrap();
+    db
+}
+
+#[test]
+fn test_backup_is_verified_and_listed() {
+    let dir = tempfile::tempdir().unwrap();
+    let backup_dir = dir.path().join("backups");
+    let db = open_database(dir.path());
+    add_task(&db.get().unwrap(), "Quiz");
+
+    let info = backup_service().backup_now(&db.get().unwrap(), &backup_dir).unwrap();
+    assert!(info.file_name.starts_with("nocture-"));
+    assert!(info.size_bytes > 0);
+    verify_integrity(&backup_dir.join(&info.file_name)).unwrap();
+
+    let backups = backup_service().list_backups(&backup_dir).unwrap();
+    assert_eq!(backups.len(), 1);
+    assert_eq!(backups[0].file_name, info.file_name);
+}
+
+#[test]
+fn test_backups_rotate_to_configured_count() {
+    let dir = tempfile::tempdir().unwrap();
+    let backup_dir = dir.path().join("backups");
+    let db = open_database(dir.path());
+    let conn = db.get().unwrap();
+    backup_service()
+        .save_config(&conn, &BackupConfig { enabled: true, interval_minutes: 60, keep: 2 })
+        .unwrap();
+
+    let mut created = Vec::new();
+    for _ in 0..4 {
+        created.push(backup_service().backup_now(&conn, &backup_dir).unwrap().file_name);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+
+    let remaining: Vec<String> = backup_service()
+        .list_backups(&backup_dir)
+        .unwrap()
+        .into_iter()
+        .map(|b| b.file_name)
+        .collect();
+    assert_eq!(remaining, vec![created[3].clone(), created[2].clone()]);
+}
+
+#[test]
+fn test_backup_if_due_respects_interval() {
+    let dir = tempfile::tempdir().unwrap();
+    let backup_dir = dir.path().join("backups");
+    let db = open_database(dir.path());
+    let conn = db.get().unwrap();
+
+    assert!(backup_service().backup_if_due(&conn, &backup_dir).unwrap().is_some());
+    assert!(backup_service().backup_if_due(&conn, &backup_dir).unwrap().is_none());
+
+    backup_service()
+        .save_config(&conn, &BackupConfig { enabled: false, interval_minutes: 1, keep: 3 })
+        .unwrap();
+    std::fs::remove_dir_all(&backup_dir).unwrap();
+    assert!(backup_service().backup_if_due(&conn, &backup_dir).unwrap().is_none());
+}
+
+#[test]
+fn test_restore_replaces_database_and_keeps_safety_copy() {
+    let dir = tempfile::tempdir().unwrap();
+    let backup_dir = dir.path().join("backups");
+    let db = open_database(dir.path());
+    add_task(&db.get().unwrap(), "Before backup");
+    let snapshot = backup_service().backup_now(&db.get().unwrap(), &backup_dir).unwrap();
+
+    // The undo history refers to ids in the replaced database, so it is dropped
+    let service = backup_service();
+    let tasks = TaskServiceImpl { repository: SqliteTaskRepository, history_repository: SqliteTaskHistoryRepository, events: EventBus::new(), journal: service.journal.clone(), checkboxes: (), planner: () };
+    tasks.create_task(&db.get().unwrap(), TaskPayload {
+        title: "After backup".to_string(),
+        category: Category::Study,
+        priority: Priority::Medium,
+        deadline: None,
+        estimated_minutes: None,
+    }).unwrap();
+    assert_eq!(task_titles(&db).len(), 2);
+    assert!(service.journal.status().undo.is_some());
+    let mut events = service.events.subscribe();
+
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    service.restore_backup(&db, &backup_dir, &snapshot.file_name).unwrap();
+    assert_eq!(task_titles(&db), vec!["Before backup".to_string()]);
+    assert_eq!(service.journal.status().undo, None);
+    assert!(matches!(events.try_recv().unwrap(), DomainEvent::DataReloaded));
+
+    // The pre-restore snapshot is kept so the restore can be reverted.
+    assert_eq!(backup_service().list_backups(&backup_dir).unwrap().len(), 2);
+}
+
+#[test]
+fn test_restore_oldest_backup_with_full_rotation() {
+    let dir = tempfile::tempdir().unwrap();
+    let backup_dir = dir.path().join("backups");
+    let db = open_database(dir.path());
+    backup_service()
+        .save_config(&db.get().unwrap(), &BackupConfig { enabled: true, interval_minutes: 60, keep: 2 })
+        .unwrap();
+
+    add_task(&db.get().unwrap(), "Oldest");
+    let oldest = backup_service().backup_now(&db.get().unwrap(), &backup_dir).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    add_task(&db.get().unwrap(), "Newest");
+    backup_service().backup_now(&db.get().unwrap(), &backup_dir).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(5));
+
+    // The safety snapshot would rotate the oldest backup out; it must survive until restored.
+    backup_service().restore_backup(&db, &backup_dir, &oldest.file_name).unwrap();
+    assert_eq!(task_titles(&db), vec!["Oldest".to_string()]);
+}
+
+#[test]
+fn test_restore_missing_source_leaves_database_untouched() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = open_database(dir.path());
+    add_task(&db.get().unwrap(), "Keep me");
+
+    assert!(db.restore_from(&dir.path().join("missing.db")).is_err());
+    assert!(!dir.path().join("missing.db").exists());
+    assert_eq!(task_titles(&db), vec!["Keep me".to_string()]);
+}
+
+#[test]
+fn test_restore_rejects_foreign_file_names() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = open_database(dir.path());
+    let err = backup_service()
+        .restore_backup(&db, dir.path(), "../nocture.db")
+        .unwrap_err();
+    assert!(err.contains("not a Nocture backup"));
+}
+
+#[test]
+fn test_invalid_config_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = open_database(dir.path());
+    let conn = db.get().unwrap();
+    assert!(backup_service()
+        .save_config(&conn, &BackupConfig { enabled: true, interval_minutes: 60, keep: 0 })
+        .is_err());
+    assert_eq!(backup_service().load_config(&conn).unwrap(), BackupConfig::default());
+}
+
+#[test]
+fn test_restore_migrates_backup_from_older_version() {
+    let dir = tempfile::tempdir().unwrap();
+    let backup_dir = dir.path().join("backups");
+    let db = open_database(dir.path());
+
+    // A backup taken by the first release: only the initial tables, user_version 1
+    std::fs::create_dir_all(&backup_dir).unwrap();
+    let file_name = "nocture-20250101-090000000.db";
+    let old = Connection::open(backup_dir.join(file_name)).unwrap();
+    old.execute_batch(
+        "CREATE TABLE tasks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            category TEXT NOT NULL,
+            priority TEXT NOT NULL,
+            deadline TEXT,
+            completed BOOLEAN NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );
+        CREATE TABLE notes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            file_path TEXT UNIQUE NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+        CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+        INSERT INTO tasks (title, category, priority, completed, created_at, updated_at)
+            VALUES ('From 2025', 'Study', 'High', 0, '2025-01-01 09:00:00', '2025-01-01 09:00:00');
+        PRAGMA user_version = 1;",
+    ).unwrap();
+    drop(old);
+
+    backup_service().restore_backup(&db, &backup_dir, file_name).unwrap();
+    assert_eq!(task_titles(&db), vec!["From 2025".to_string()]);
+    // Later tables exist, so the restored database is usable without a restart
+    add_task(&db.get().unwrap(), "After restore");
+    assert_eq!(task_titles(&db).len(), 2);
+    let fresh = Connection::open_in_memory().unwrap();
+    run_migrations(&fresh).unwrap();
+    let version = |c: &Connection| -> i32 { c.query_row("PRAGMA user_version", [], |r| r.get(0)).unwrap() };
+    assert_eq!(version(&db.get().unwrap()), version(&fresh));
+}
@@ -0,0 +1,125 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+use nocture_tauri_lib::db::{run_blocking, run_blocking_io, Database, DbConfig};
+use nocture_tauri_lib::events::EventBus;
+use nocture_tauri_lib::fs::StdFileSystem;
+use nocture_tauri_lib::journal::Journal;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::note::NotePayload;
+use nocture_tauri_lib::model::note_revision::RevisionPolicy;
+use nocture_tauri_lib::model::task::{Category, Priority, TaskPayload};
+use nocture_tauri_lib::repository::link::SqliteLinkRepository;
+use nocture_tauri_lib::repository::note::SqliteNoteRepository;
+use nocture_tauri_lib::repository::note_revision::SqliteNoteRevisionRepository;
+use nocture_tauri_lib::repository::task::SqliteTaskRepository;
+use nocture_tauri_lib::repository::task_history::SqliteTaskHistoryRepository;
+use nocture_tauri_lib::services::note::{NoteService, NoteServiceImpl};
+use nocture_tauri_lib::services::task::{TaskService, TaskServiceImpl};
+
+fn open_database(dir: &std::path::Path) -> Arc<Database> {
+    let db = Database::open_with_config(dir.join("nocture.db"), DbConfig { pool_size: 4, ..DbConfig::default() }).unwrap();
+    run_migrations(&db.get().unwrap()).unwrap();
+    Arc::new(db)
+}
+
+fn task_service() -> TaskServiceImpl<SqliteTaskRepository, SqliteTaskHistoryRepository> {
+    TaskServiceImpl { repository: SqliteTaskRepository, history_repository: SqliteTaskHistoryRepository, events: EventBus::new(), journal: Journal::default(), checkboxes: (), planner: () }
+}
+
+fn note_service(vault: &Path) -> NoteServiceImpl<SqliteNoteRepository, SqliteNoteRevisionRepository, SqliteLinkRepository, StdFileSystem> {
+    NoteServiceImpl {
+        repository: SqliteNoteRepository,
+        revision_repository: SqliteNoteRevisionRepository,
+        revision_policy: RevisionPolicy::default(),
+        link_repository: SqliteLinkRepository,
+        fs: StdFileSystem,
+        events: EventBus::new(),
+        journal: Journal::default(),
+        vault_dir: vault.to_path_buf(),
+        trash_dir: vault.join(".trash"),
+        note_tasks: (),
+    }
+}
+
+// Mirrors the body of the `create_task` command.
+async fn create_task(db: Arc<Database>, title: String) -> Result<(), String> {
+    run_blocking(db, move |conn| {
+        task_service().create_task(conn, TaskPayload {
+            title,
+            category: Category::Study,
+            priority: Priority::Medium,
+            deadline: None,
+            estimated_minutes: None,
+        })
+    })
+    .await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_concurrent_task_and_note_commands() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = open_database(dir.path());
+
+    let mut set = JoinSet::new();
+    for i in 0..20 {
+        set.spawn(create_task(db.clone(), format!("task {}", i)));
+
+        let db = db.clone();
+        let vault = dir.path().to_path_buf();
+        let file_path = dir.path().join(format!("note-{}.md", i)).to_string_lossy().into_owned();
+        set.spawn(run_blocking(db, move |conn| {
+            note_service(&vault).create_note(conn, NotePayload { title: format!("note {}", i), file_path })
+        }));
+    }
+    while let Some(result) = set.join_next().await {
+        result.unwrap().unwrap();
+    }
+
+    let vault = dir.path().to_path_buf();
+    let (tasks, notes) = run_blocking(db, move |conn| {
+        let tasks = task_service().fetch_tasks(conn)?;
+        let notes = note_service(&vault).fetch_notes(conn)?;
+        Ok((tasks.len(), notes.len()))
+    })
+    .await
+    .unwrap();
+    assert_eq!(tasks, 20);
+    assert_eq!(notes, 20);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_blocking_work_does_not_stall_the_runtime() {
+    // On a single-threaded runtime, a blocking closure run inline would freeze the ticker below.
+    let slow = tokio::spawn(run_blocking_io(|| {
+        std::thread::sleep(Duration::from_millis(300));
+        Ok(())
+    }));
+
+    let started = Instant::now();
+    let mut ticks = 0;
+    while !slow.is_finished() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        ticks += 1;
+    }
+    slow.await.unwrap().unwrap();
+
+    assert!(ticks >= 10, "runtime only ticked {} times", ticks);
+    assert!(started.elapsed() >= Duration::from_millis(300));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_errors_are_propagated() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = open_database(dir.path());
+    let err = run_blocking(db, |conn| {
+        conn.execute("INSERT INTO missing_table VALUES (1)", []).map_err(|e| e.to_string())
+    })
+    .await
+    .unwrap_err();
+    assert!(err.contains("missing_table"));
+
+    let err = run_blocking_io::<(), _>(|| panic!("boom")).await.unwrap_err();
+    assert!(err.contains("Pekerjaan latar belakang gagal"));
+}
@@ -0,0 +1,657 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{NaiveDate, NaiveDateTime, Weekday};
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use tiny_http::{Header, Response, Server};
+use nocture_tauri_lib::caldav::{CalDavClient, HttpCalDavClient};
+use nocture_tauri_lib::events::{DomainEvent, EventBus};
+use nocture_tauri_lib::ical::{event_to_ical, parse_ical, task_to_ical, ICalComponent};
+use nocture_tauri_lib::journal::Journal;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::caldav::{CalDavConfig, ConflictWinner, RemoteCalendar, SyncKind, SyncSummary};
+use nocture_tauri_lib::model::calendar_event::CalendarEvent;
+use nocture_tauri_lib::model::recurrence::{Frequency, Recurrence};
+use nocture_tauri_lib::model::task::{Category, Priority, Task};
+use nocture_tauri_lib::model::task_csv::TaskColumn;
+use nocture_tauri_lib::model::task_history::TaskChangeKind;
+use nocture_tauri_lib::repository::caldav::SqliteCalDavRepository;
+use nocture_tauri_lib::repository::calendar_event::{CalendarEventRepository, SqliteCalendarEventRepository};
+use nocture_tauri_lib::repository::settings::SqliteSettingsRepository;
+use nocture_tauri_lib::repository::task::{SqliteTaskRepository, TaskRepository};
+use nocture_tauri_lib::repository::task_history::{SqliteTaskHistoryRepository, TaskHistoryRepository};
+use nocture_tauri_lib::services::caldav::{CalDavService, CalDavServiceImpl};
+use nocture_tauri_lib::services::task::TaskServiceImpl;
+
+const TASKS: &str = "/calendars/alice/tasks/";
+const PERSONAL: &str = "/calendars/alice/personal/";
+
+// Server CalDAV tiruan di dalam proses: discovery, sync-collection dengan token berversi,
+// calendar-multiget, dan PUT/DELETE dengan If-Match/If-None-Match.
+#[derive(Default)]
+struct ServerState {
+    version: u64,
+    // href -> (isi, versi saat terakhir diubah)
+    items: BTreeMap<String, (String, u64)>,
+    // href yang dihapus beserta versinya
+    removed: Vec<(String, u64)>,
+    // Token di bawah versi ini dianggap kedaluwarsa
+    oldest_token: u64,
+    // Server yang tidak mendukung sync-collection
+    no_sync_collection: bool,
+    requests: Vec<String>,
+}
+
+impl ServerState {
+    fn etag(version: u64) -> String {
+        format!("\"v{}\"", version)
+    }
+
+    fn write(&mut self, href: &str, data: &str) -> String {
+        self.version += 1;
+        self.items.insert(href.to_string(), (data.to_string(), self.version));
+        ServerState::etag(self.version)
+    }
+
+    fn remove(&mut self, href: &str) {
+        self.version += 1;
+        self.items.remove(href);
+        self.removed.push((href.to_string(), self.version));
+    }
+}
+
+struct MockServer {
+    url: String,
+    state: Arc<Mutex<ServerState>>,
+}
+
+impl MockServer {
+    fn start() -> MockServer {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/dav/", server.server_addr().to_ip().unwrap());
+        let state = Arc::new(Mutex::new(ServerState::default()));
+        let shared = state.clone();
+        std::thread::spawn(move || {
+            for mut request in server.incoming_requests() {
+                let mut body = String::new();
+                request.as_reader().read_to_string(&mut body).unwrap();
+                let header = |name: &'static str| {
+                    request.headers().iter().find(|h| h.field.equiv(name)).map(|h| h.value.as_str().to_string())
+                };
+                let authorized = header("Authorization") == Some(format!("Basic {}", STANDARD.encode("alice:secret")));
+                let (status, etag, reply) = if authorized {
+                    let method = request.method().as_str().to_string();
+                    let path = request.url().to_string();
+                    let mut state = shared.lock().unwrap();
+                    state.requests.push(format!("{} {}", method, path));
+                    handle(&mut state, &method, &path, header("If-Match"), header("If-None-Match"), &body)
+                } else {
+                    (401, None, String::new())
+                };
+                let mut response = Response::from_string(reply).with_status_code(status);
+                if let Some(etag) = etag {
+                    response = response.with_header(Header::from_bytes(&b"ETag"[..], etag.as_bytes()).unwrap());
+                }
+                request.respond(response).unwrap();
+            }
+        });
+        MockServer { url, state }
+    }
+
+    fn config(&self) -> CalDavConfig {
+        CalDavConfig {
+            server_url: self.url.clone(),
+            username: "alice".to_string(),
+            password: "secret".to_string(),
+            task_calendar: Some(TASKS.to_string()),
+            event_calendar: Some(PERSONAL.to_string()),
+        }
+    }
+
+    fn client(&self) -> HttpCalDavClient {
+        HttpCalDavClient::new(&self.config()).unwrap()
+    }
+
+    // Perubahan dari "perangkat lain"
+    fn put(&self, href: &str, data: &str) {
+        self.state.lock().unwrap().write(href, data);
+    }
+
+    fn remove(&self, href: &str) {
+        self.state.lock().unwrap().remove(href);
+    }
+
+    fn get(&self, href: &str) -> Option<String> {
+        self.state.lock().unwrap().items.get(href).map(|(data, _)| data.clone())
+    }
+
+    fn hrefs(&self, calendar: &str) -> Vec<String> {
+        self.state.lock().unwrap().items.keys().filter(|href| href.starts_with(calendar)).cloned().collect()
+    }
+
+    fn summaries(&self, calendar: &str) -> Vec<String> {
+        self.hrefs(calendar).iter()
+            .map(|href| {
+                let data = self.get(href).unwrap();
+                data.lines().find_map(|line| line.strip_prefix("SUMMARY:")).unwrap().to_string()
+            })
+            .collect()
+    }
+
+    fn requests(&self) -> Vec<String> {
+        std::mem::take(&mut self.state.lock().unwrap().requests)
+    }
+}
+
+fn handle(
+    state: &mut ServerState,
+    method: &str,
+    path: &str,
+    if_match: Option<String>,
+    if_none_match: Option<String>,
+    body: &str,
+) -> (u16, Option<String>, String) {
+    match (method, path) {
+        ("PROPFIND", "/dav/") => (207, None, multistatus(&prop_response("/dav/", "<d:current-user-principal><d:href>/principals/alice/</d:href></d:current-user-principal>"))),
+        ("PROPFIND", "/principals/alice/") => (207, None, multistatus(&prop_response("/principals/alice/", "<c:calendar-home-set><d:href>/calendars/alice/</d:href></c:calendar-home-set>"))),
+        ("PROPFIND", "/calendars/alice/") => {
+            let calendar = |href: &str, name: &str, comp: &str| prop_response(href, &format!(
+                "<d:resourcetype><d:collection/><c:calendar/></d:resourcetype><d:displayname>{}</d:displayname>\
+                <c:supported-calendar-component-set><c:comp name=\"{}\"/></c:supported-calendar-component-set>",
+                name, comp
+            ));
+            let responses = [
+                prop_response("/calendars/alice/", "<d:resourcetype><d:collection/></d:resourcetype>"),
+                calendar(TASKS, "Tasks", "VTODO"),
+                calendar(PERSONAL, "Personal", "VEVENT"),
+                prop_response("/calendars/alice/inbox/", "<d:resourcetype><d:collection/><c:schedule-inbox/></d:resourcetype>"),
+            ];
+            (207, None, multistatus(&responses.concat()))
+        }
+        ("PROPFIND", calendar) => {
+            let mut responses = prop_response(calendar, "<d:resourcetype><d:collection/><c:calendar/></d:resourcetype>");
+            for (href, (_, version)) in state.items.iter().filter(|(href, _)| href.starts_with(calendar)) {
+                responses += &prop_response(href, &format!("<d:getetag>{}</d:getetag>", ServerState::etag(*version)));
+            }
+            (207, None, multistatus(&responses))
+        }
+        ("REPORT", calendar) if body.contains("sync-collection") => {
+            if state.no_sync_collection {
+                return (403, None, "<d:error xmlns:d=\"DAV:\"><d:supported-report/></d:error>".to_string());
+            }
+            let token = xml_texts(body, "sync-token").pop().unwrap_or_default();
+            let since = match token.strip_prefix("http://mock/sync/").map(str::parse::<u64>) {
+                None if token.is_empty() => 0,
+                Some(Ok(version)) if version >= state.oldest_token => version,
+                _ => return (403, None, "<d:error xmlns:d=\"DAV:\"><d:valid-sync-token/></d:error>".to_string()),
+            };
+            let mut responses = String::new();
+            for (href, (_, version)) in state.items.iter().filter(|(href, (_, v))| href.starts_with(calendar) && *v > since) {
+                responses += &prop_response(href, &format!("<d:getetag>{}</d:getetag>", ServerState::etag(*version)));
+            }
+            for (href, _) in state.removed.iter().filter(|(href, v)| href.starts_with(calendar) && *v > since && !state.items.contains_key(href)) {
+                responses += &format!("<d:response><d:href>{}</d:href><d:status>HTTP/1.1 404 Not Found</d:status></d:response>", href);
+            }
+            responses += &format!("<d:sync-token>http://mock/sync/{}</d:sync-token>", state.version);
+            (207, None, multistatus(&responses))
+        }
+        ("REPORT", _) => {
+            let mut responses = String::new();
+            for href in xml_texts(body, "href") {
+                responses += &match state.items.get(&href) {
+                    Some((data, version)) => prop_response(&href, &format!(
+                        "<d:getetag>{}</d:getetag><c:calendar-data>{}</c:calendar-data>",
+                        ServerState::etag(*version),
+                        data.replace('&', "&amp;").replace('<', "&lt;")
+                    )),
+                    None => format!("<d:response><d:href>{}</d:href><d:status>HTTP/1.1 404 Not Found</d:status></d:response>", href),
+                };
+            }
+            (207, None, multistatus(&responses))
+        }
+        ("PUT", href) => {
+            let current = state.items.get(href).map(|(_, version)| ServerState::etag(*version));
+            let rejected = (if_none_match.as_deref() == Some("*") && current.is_some())
+                || if_match.is_some_and(|etag| current.as_ref() != Some(&etag));
+            if rejected {
+                return (412, None, String::new());
+            }
+            let status = if current.is_some() { 204 } else { 201 };
+            let etag = state.write(href, body);
+            (status, Some(etag), String::new())
+        }
+        ("DELETE", href) => {
+            let Some((_, version)) = state.items.get(href) else {
+                return (404, None, String::new());
+            };
+            if if_match.is_some_and(|etag| etag != ServerState::etag(*version)) {
+                return (412, None, String::new());
+            }
+            state.remove(href);
+            (204, None, String::new())
+        }
+        _ => (405, None, String::new()),
+    }
+}
+
+fn prop_response(href: &str, props: &str) -> String {
+    format!(
+        "<d:response><d:href>{}</d:href><d:propstat><d:prop>{}</d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>",
+        href, props
+    )
+}
+
+fn multistatus(responses: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\"?><d:multistatus xmlns:d=\"DAV:\" xmlns:c=\"urn:ietf:params:xml:ns:caldav\">{}</d:multistatus>",
+        responses
+    )
+}
+
+fn xml_texts(body: &str, name: &str) -> Vec<String> {
+    let document = roxmltree::Document::parse(body).unwrap();
+    document.descendants()
+        .filter(|node| node.has_tag_name(("DAV:", name)))
+        .map(|node| node.text().unwrap_or_default().to_string())
+        .collect()
+}
+
+type Service = CalDavServiceImpl<
+    SqliteTaskRepository,
+    SqliteTaskHistoryRepository,
+    SqliteCalendarEventRepository,
+    SqliteCalDavRepository,
+    SqliteSettingsRepository,
+    TaskServiceImpl<SqliteTaskRepository, SqliteTaskHistoryRepository>,
+>;
+
+fn setup(server: &MockServer) -> (Connection, Service) {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    let events = EventBus::new();
+    let journal = Journal::default();
+    let service = CalDavServiceImpl {
+        task_repository: SqliteTaskRepository,
+        task_history_repository: SqliteTaskHistoryRepository,
+        event_repository: SqliteCalendarEventRepository,
+        repository: SqliteCalDavRepository,
+        settings: SqliteSettingsRepository,
+        tasks: TaskServiceImpl {
+            repository: SqliteTaskRepository,
+            history_repository: SqliteTaskHistoryRepository,
+            events: events.clone(),
+            journal: journal.clone(),
+            checkboxes: (),
+            planner: (),
+        },
+        events,
+        journal,
+    };
+    service.save_config(&conn, &server.config()).unwrap();
+    (conn, service)
+}
+
+fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2026, 10, day).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+}
+
+fn task(title: &str, deadline: Option<NaiveDateTime>) -> Task {
+    Task {
+        id: 0,
+        title: title.to_string(),
+        category: Category::Study,
+        priority: Priority::High,
+        deadline,
+        completed: false,
+        created_at: at(1, 8, 0),
+        updated_at: at(1, 8, 0),
+        estimated_minutes: Some(45),
+    }
+}
+
+fn event(title: &str, start: NaiveDateTime, end: NaiveDateTime) -> CalendarEvent {
+    CalendarEvent {
+        id: 0,
+        title: title.to_string(),
+        start,
+        end,
+        all_day: false,
+        location: None,
+        recurrence: None,
+        created_at: at(1, 8, 0),
+        updated_at: at(1, 8, 0),
+    }
+}
+
+fn create_task(conn: &Connection, title: &str) -> Task {
+    let mut task = task(title, Some(at(23, 17, 0)));
+    task.id = SqliteTaskRepository.create(conn, &task).unwrap();
+    task
+}
+
+// Ubah task lokal pada waktu `updated_at` tertentu
+fn edit_task(conn: &Connection, id: i32, title: &str, updated_at: NaiveDateTime) {
+    let mut task = SqliteTaskRepository.fetch_by_id(conn, id).unwrap().unwrap();
+    task.title = title.to_string();
+    task.updated_at = updated_at;
+    SqliteTaskRepository.update(conn, &task).unwrap();
+}
+
+fn task_titles(conn: &Connection) -> Vec<String> {
+    let mut titles: Vec<String> = SqliteTaskRepository.fetch_all(conn).unwrap().into_iter().map(|t| t.title).collect();
+    titles.sort();
+    titles
+}
+
+fn remote_todo(uid: &str, title: &str, last_modified: &str) -> String {
+    format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Other//EN\r\nBEGIN:VTODO\r\nUID:{}\r\nSUMMARY:{}\r\n\
+        LAST-MODIFIED:{}\r\nDUE;VALUE=DATE:20261030\r\nPRIORITY:9\r\nEND:VTODO\r\nEND:VCALENDAR\r\n",
+        uid, title, last_modified
+    )
+}
+
+// Isi resource dengan SUMMARY dan LAST-MODIFIED diganti, seperti diedit di perangkat lain
+fn edited(data: &str, title: &str, last_modified: &str) -> String {
+    data.lines()
+        .map(|line| match line {
+            _ if line.starts_with("SUMMARY:") => format!("SUMMARY:{}", title),
+            _ if line.starts_with("LAST-MODIFIED:") => format!("LAST-MODIFIED:{}", last_modified),
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\r\n")
+}
+
+fn now() -> NaiveDateTime {
+    at(19, 12, 0)
+}
+
+#[test]
+fn test_ical_round_trip() {
+    let mut essay = task("Essay; draft, v2 \\ final", Some(at(30, 0, 0)));
+    essay.title.push_str(&" — with a very long tail that has to be folded across lines".repeat(2));
+    let data = task_to_ical(&essay, "uid-1");
+    assert!(data.lines().all(|line| line.len() <= 75));
+    assert!(data.contains("DUE;VALUE=DATE:20261030"));
+    let Some(ICalComponent::Todo { uid, last_modified, task: parsed }) = parse_ical(&data, now()).unwrap() else {
+        panic!("expected a VTODO");
+    };
+    assert_eq!(uid, "uid-1");
+    assert_eq!(last_modified, Some(at(1, 8, 0)));
+    assert_eq!((parsed.title, parsed.category, parsed.priority), (essay.title.clone(), Category::Study, Priority::High));
+    assert_eq!((parsed.deadline, parsed.completed, parsed.estimated_minutes), (essay.deadline, false, None));
+
+    let mut lecture = event("Algorithms", at(19, 9, 0), at(19, 10, 40));
+    lecture.location = Some("Room 3.1".to_string());
+    lecture.recurrence = Some(Recurrence {
+        weekdays: vec![Weekday::Mon, Weekday::Thu],
+        until: NaiveDate::from_ymd_opt(2026, 12, 18),
+        ..Recurrence::new(Frequency::Weekly, 1)
+    });
+    let data = event_to_ical(&lecture, "uid-2");
+    assert!(data.contains("RRULE:FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TH;UNTIL=20261218T235959"));
+    let Some(ICalComponent::Event { event: parsed, .. }) = parse_ical(&data, now()).unwrap() else {
+        panic!("expected a VEVENT");
+    };
+    assert_eq!(parsed, CalendarEvent { created_at: now(), updated_at: now(), ..lecture });
+
+    let trip = CalendarEvent { all_day: true, ..event("Field trip", at(24, 0, 0), at(27, 0, 0)) };
+    let data = event_to_ical(&trip, "uid-3");
+    assert!(data.contains("DTSTART;VALUE=DATE:20261024") && data.contains("DTEND;VALUE=DATE:20261027"));
+    let Some(ICalComponent::Event { event: parsed, .. }) = parse_ical(&data, now()).unwrap() else {
+        panic!("expected a VEVENT");
+    };
+    assert_eq!((parsed.start, parsed.end, parsed.all_day), (trip.start, trip.end, true));
+}
+
+#[test]
+fn test_parse_ical_from_other_clients() {
+    // Baris terlipat, DURATION, COUNT, alarm bersarang, dan kemunculan pengganti
+    let data = "BEGIN:VCALENDAR\nVERSION:2.0\nBEGIN:VEVENT\nUID:abc\nSUMMARY:Study group\\, weekly\nDESCRIPTION:Long\n  text\n\
+        DTSTART:20261020T180000\nDURATION:PT1H30M\nRRULE:FREQ=WEEKLY;COUNT=3\n\
+        LOCATION:Library\nBEGIN:VALARM\nTRIGGER:-PT15M\nSUMMARY:Alarm\nEND:VALARM\nEND:VEVENT\n\
+        BEGIN:VEVENT\nUID:abc\nRECURRENCE-ID:20261027T180000\nSUMMARY:Moved\nDTSTART:20261028T180000\nEND:VEVENT\nEND:VCALENDAR\n";
+    let Some(ICalComponent::Event { uid, last_modified, event }) = parse_ical(data, now()).unwrap() else {
+        panic!("expected a VEVENT");
+    };
+    assert_eq!((uid.as_str(), last_modified), ("abc", None));
+    assert_eq!(event.title, "Study group, weekly");
+    assert_eq!((event.start, event.end), (at(20, 18, 0), at(20, 19, 30)));
+    assert_eq!(event.location.as_deref(), Some("Library"));
+    let recurrence = event.recurrence.unwrap();
+    assert_eq!((recurrence.frequency, recurrence.until), (Frequency::Weekly, NaiveDate::from_ymd_opt(2026, 11, 3)));
+
+    let todo = "BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nUID:t1\r\nSTATUS:COMPLETED\r\nPRIORITY:0\r\nCATEGORIES:Errands,work\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+    let Some(ICalComponent::Todo { task, .. }) = parse_ical(todo, now()).unwrap() else {
+        panic!("expected a VTODO");
+    };
+    assert_eq!((task.title.as_str(), task.category, task.priority, task.completed), ("Untitled", Category::Work, Priority::Medium, true));
+
+    assert!(parse_ical("BEGIN:VCALENDAR\r\nBEGIN:VJOURNAL\r\nUID:j\r\nEND:VJOURNAL\r\nEND:VCALENDAR\r\n", now()).unwrap().is_none());
+    // TZID UTC sama dengan akhiran Z; zona lain selain zona lokal ditolak, bukan dianggap waktu lokal
+    let with_start = |dtstart: &str| format!("BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:tz\r\n{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n", dtstart);
+    let start = |dtstart: &str| match parse_ical(&with_start(dtstart), now()).unwrap() {
+        Some(ICalComponent::Event { event, .. }) => event.start,
+        _ => panic!("expected a VEVENT"),
+    };
+    assert_eq!(start("DTSTART;TZID=Etc/UTC:20261020T180000"), start("DTSTART:20261020T180000Z"));
+    let err = parse_ical(&with_start("DTSTART;TZID=America/St_Johns:20261020T180000"), now()).unwrap_err();
+    assert!(err.contains("America/St_Johns"), "{}", err);
+    assert!(parse_ical("<html>", now()).is_err());
+    assert!(parse_ical("BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:x\r\nDTSTART:soon\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n", now()).is_err());
+}
+
+#[test]
+fn test_discover_calendars_and_config() {
+    let server = MockServer::start();
+    let calendars = server.client().discover().unwrap();
+    assert_eq!(calendars, vec![
+        RemoteCalendar { href: PERSONAL.to_string(), name: "Personal".to_string(), supports_tasks: false, supports_events: true },
+        RemoteCalendar { href: TASKS.to_string(), name: "Tasks".to_string(), supports_tasks: true, supports_events: false },
+    ]);
+
+    let wrong_password = HttpCalDavClient::new(&CalDavConfig { password: "nope".to_string(), ..server.config() }).unwrap();
+    assert!(wrong_password.discover().unwrap_err().to_string().contains("username or password"));
+
+    let (conn, service) = setup(&server);
+    assert_eq!(service.load_config(&conn).unwrap(), server.config());
+    let invalid = |server_url: &str| service.save_config(&conn, &CalDavConfig { server_url: server_url.to_string(), ..server.config() });
+    assert!(invalid("ftp://example.com").unwrap_err().contains("http"));
+    assert!(invalid("example.com/dav").is_err());
+    service.save_config(&conn, &CalDavConfig { task_calendar: Some("  ".to_string()), event_calendar: None, ..server.config() }).unwrap();
+    assert!(service.sync(&conn, &server.client(), now()).unwrap_err().contains("Pick a calendar"));
+}
+
+#[test]
+fn test_sync_pushes_pulls_and_then_is_incremental() {
+    let server = MockServer::start();
+    let (conn, service) = setup(&server);
+    let client = server.client();
+
+    let essay = create_task(&conn, "Essay");
+    create_task(&conn, "Report");
+    let mut lecture = event("Databases", at(19, 10, 0), at(19, 11, 40));
+    lecture.id = SqliteCalendarEventRepository.create(&conn, &lecture).unwrap();
+    server.put(&format!("{}phone.ics", TASKS), &remote_todo("phone-1", "Buy milk", "20261018T090000Z"));
+    // VEVENT di kalender task tidak ikut disinkronkan sebagai task
+    server.put(&format!("{}meeting.ics", TASKS), &event_to_ical(&event("Stray", at(20, 9, 0), at(20, 10, 0)), "stray"));
+
+    let summary = service.sync(&conn, &client, now()).unwrap();
+    assert_eq!(summary, SyncSummary { pulled: 1, pushed: 3, ..SyncSummary::default() });
+    assert_eq!(task_titles(&conn), vec!["Buy milk", "Essay", "Report"]);
+    let milk = SqliteTaskRepository.fetch_all(&conn).unwrap().into_iter().find(|t| t.title == "Buy milk").unwrap();
+    assert_eq!((milk.priority, milk.category, milk.deadline), (Priority::Low, Category::Personal, Some(at(30, 0, 0))));
+    assert_eq!((milk.created_at, milk.updated_at), (now(), now()));
+    assert_eq!(server.summaries(TASKS), vec!["Stray", "Essay", "Report", "Buy milk"]);
+    let essay_href = format!("{}nocture-task-{}-20261001T080000.ics", TASKS, essay.id);
+    assert!(server.get(&essay_href).unwrap().contains("DUE:20261023T170000"));
+    assert_eq!(server.summaries(PERSONAL), vec!["Databases"]);
+
+    // Tanpa perubahan: cukup satu REPORT sync-collection per kalender, tidak ada yang dibaca atau ditulis
+    server.requests();
+    assert_eq!(service.sync(&conn, &client, now()).unwrap(), SyncSummary::default());
+    assert_eq!(server.requests(), vec![format!("REPORT {}", TASKS), format!("REPORT {}", PERSONAL)]);
+}
+
+#[test]
+fn test_sync_applies_changes_from_both_sides() {
+    let server = MockServer::start();
+    let (conn, service) = setup(&server);
+    let client = server.client();
+    let essay = create_task(&conn, "Essay");
+    let report = create_task(&conn, "Report");
+    let slides = create_task(&conn, "Slides");
+    let mut lecture = event("Databases", at(19, 10, 0), at(19, 11, 40));
+    lecture.id = SqliteCalendarEventRepository.create(&conn, &lecture).unwrap();
+    service.sync(&conn, &client, now()).unwrap();
+    let href = |task: &Task| format!("{}nocture-task-{}-20261001T080000.ics", TASKS, task.id);
+
+    // Di server: Essay diubah, Report dihapus, acara baru ditambahkan
+    server.put(&href(&essay), &edited(&server.get(&href(&essay)).unwrap(), "Essay (final)", "20261019T100000Z"));
+    server.remove(&href(&report));
+    server.put(&format!("{}gym.ics", PERSONAL), &event_to_ical(&event("Gym", at(21, 7, 0), at(21, 8, 0)), "gym"));
+    // Di lokal: acara diubah, Slides dibuang ke trash, task baru dibuat
+    lecture.title = "Databases (room change)".to_string();
+    lecture.updated_at = at(19, 11, 0);
+    SqliteCalendarEventRepository.update(&conn, &lecture).unwrap();
+    SqliteTaskRepository.soft_delete(&conn, slides.id, at(19, 11, 0)).unwrap();
+    create_task(&conn, "Poster");
+
+    let mut received = service.events.subscribe();
+    let summary = service.sync(&conn, &client, now()).unwrap();
+    assert_eq!(summary, SyncSummary { pulled: 2, pushed: 2, deleted_locally: 1, deleted_remotely: 1, conflicts: vec![] });
+    // Perubahan dari server masuk riwayat, journal, dan event seperti edit biasa
+    let history = SqliteTaskHistoryRepository.fetch_for_task(&conn, essay.id).unwrap();
+    let last = history.last().unwrap();
+    assert_eq!((last.field, last.new_value.as_deref()), (Some(TaskColumn::Title), Some("Essay (final)")));
+    let history = SqliteTaskHistoryRepository.fetch_for_task(&conn, report.id).unwrap();
+    assert_eq!(history.last().unwrap().kind, TaskChangeKind::Deleted);
+    assert!(service.journal.status().undo.is_some());
+    let received: Vec<DomainEvent> = std::iter::from_fn(|| received.try_recv().ok()).collect();
+    assert!(received.iter().any(|event| matches!(event, DomainEvent::TaskUpdated(task) if task.id == essay.id)));
+    assert!(received.iter().any(|event| matches!(event, DomainEvent::TaskDeleted { id } if *id == report.id)));
+    assert_eq!(task_titles(&conn), vec!["Essay (final)", "Poster"]);
+    let mut trashed: Vec<i32> = SqliteTaskRepository.fetch_deleted(&conn).unwrap().iter().map(|t| t.task.id).collect();
+    trashed.sort();
+    assert_eq!(trashed, vec![report.id, slides.id]);
+    // Perkiraan waktu tidak ada di iCalendar, jadi tetap dari data lokal
+    assert_eq!(SqliteTaskRepository.fetch_by_id(&conn, essay.id).unwrap().unwrap().estimated_minutes, Some(45));
+    let events: Vec<String> = SqliteCalendarEventRepository.fetch_all(&conn).unwrap().into_iter().map(|e| e.title).collect();
+    assert_eq!(events, vec!["Databases (room change)", "Gym"]);
+    assert_eq!(server.summaries(TASKS), vec!["Essay (final)", "Poster"]);
+    assert_eq!(server.summaries(PERSONAL), vec!["Gym", "Databases (room change)"]);
+
+    assert_eq!(service.sync(&conn, &client, now()).unwrap(), SyncSummary::default());
+}
+
+#[test]
+fn test_conflicts_are_resolved_deterministically() {
+    let server = MockServer::start();
+    let (conn, service) = setup(&server);
+    let client = server.client();
+    let tasks: Vec<Task> = ["Newer here", "Newer there", "Tie", "Edited here", "Deleted here"].iter()
+        .map(|title| create_task(&conn, title))
+        .collect();
+    service.sync(&conn, &client, now()).unwrap();
+    let href = |task: &Task| format!("{}nocture-task-{}-20261001T080000.ics", TASKS, task.id);
+    let edit_remote = |task: &Task, title: &str, last_modified: &str| {
+        server.put(&href(task), &edited(&server.get(&href(task)).unwrap(), title, last_modified));
+    };
+
+    // Diubah di kedua sisi: yang terakhir diubah menang, seri dimenangkan server
+    edit_task(&conn, tasks[0].id, "Local wins", at(19, 11, 0));
+    edit_remote(&tasks[0], "Remote loses", "20261019T100000Z");
+    edit_task(&conn, tasks[1].id, "Local loses", at(19, 9, 0));
+    edit_remote(&tasks[1], "Remote wins", "20261019T100000Z");
+    edit_task(&conn, tasks[2].id, "Local tie", at(19, 10, 0));
+    edit_remote(&tasks[2], "Remote tie", "20261019T100000Z");
+    // Diubah di satu sisi, dihapus di sisi lain: yang diubah dipertahankan
+    edit_task(&conn, tasks[3].id, "Kept after remote delete", at(19, 9, 0));
+    server.remove(&href(&tasks[3]));
+    SqliteTaskRepository.soft_delete(&conn, tasks[4].id, at(19, 9, 0)).unwrap();
+    edit_remote(&tasks[4], "Restored from server", "20261019T100000Z");
+
+    let summary = service.sync(&conn, &client, now()).unwrap();
+    let mut conflicts: Vec<(String, ConflictWinner)> = summary.conflicts.iter()
+        .inspect(|conflict| assert_eq!(conflict.kind, SyncKind::Task))
+        .map(|conflict| (conflict.title.clone(), conflict.winner))
+        .collect();
+    conflicts.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(conflicts, vec![
+        ("Kept after remote delete".to_string(), ConflictWinner::Local),
+        ("Local wins".to_string(), ConflictWinner::Local),
+        ("Remote tie".to_string(), ConflictWinner::Remote),
+        ("Remote wins".to_string(), ConflictWinner::Remote),
+        ("Restored from server".to_string(), ConflictWinner::Remote),
+    ]);
+    let expected = vec!["Kept after remote delete", "Local wins", "Remote tie", "Remote wins", "Restored from server"];
+    assert_eq!(task_titles(&conn), expected);
+    let mut remote = server.summaries(TASKS);
+    remote.sort();
+    assert_eq!(remote, expected);
+    // Task di trash yang diubah di server dikeluarkan dari trash, bukan dibuat ulang
+    let restored = summary.conflicts.iter().find(|c| c.title == "Restored from server").unwrap();
+    assert_eq!(restored.local_id, tasks[4].id);
+    assert!(SqliteTaskRepository.fetch_deleted(&conn).unwrap().is_empty());
+
+    assert_eq!(service.sync(&conn, &client, now()).unwrap(), SyncSummary::default());
+}
+
+#[test]
+fn test_rejected_push_is_resolved_against_the_server() {
+    let server = MockServer::start();
+    let (conn, service) = setup(&server);
+    let client = server.client();
+    let essay = create_task(&conn, "Essay");
+    service.sync(&conn, &client, now()).unwrap();
+    let href = format!("{}nocture-task-{}-20261001T080000.ics", TASKS, essay.id);
+
+    // Server berubah setelah token dibaca, jadi If-Match ditolak dengan 412 saat didorong
+    edit_task(&conn, essay.id, "Essay (local)", at(19, 11, 0));
+    let newer = edited(&server.get(&href).unwrap(), "Essay (remote)", "20261019T113000Z");
+    {
+        let mut state = server.state.lock().unwrap();
+        state.write(&href, &newer);
+        // Sembunyikan perubahan dari sync-collection
+        state.items.get_mut(&href).unwrap().1 = 0;
+    }
+    let summary = service.sync(&conn, &client, now()).unwrap();
+    assert_eq!(summary.conflicts.len(), 1);
+    assert_eq!(summary.conflicts[0].winner, ConflictWinner::Remote);
+    assert_eq!(task_titles(&conn), vec!["Essay (remote)"]);
+}
+
+#[test]
+fn test_expired_token_and_server_without_sync_collection() {
+    let server = MockServer::start();
+    let (conn, service) = setup(&server);
+    let client = server.client();
+    create_task(&conn, "Essay");
+    let report = create_task(&conn, "Report");
+    service.sync(&conn, &client, now()).unwrap();
+
+    // Token kedaluwarsa: seluruh isi kalender dibaca ulang, yang hilang berarti sudah dihapus
+    server.remove(&format!("{}nocture-task-{}-20261001T080000.ics", TASKS, report.id));
+    server.state.lock().unwrap().oldest_token = u64::MAX;
+    let summary = service.sync(&conn, &client, now()).unwrap();
+    assert_eq!(summary, SyncSummary { deleted_locally: 1, ..SyncSummary::default() });
+    assert_eq!(task_titles(&conn), vec!["Essay"]);
+
+    // Tanpa sync-collection: daftar ETag dibaca lewat PROPFIND setiap kali
+    server.state.lock().unwrap().no_sync_collection = true;
+    server.put(&format!("{}phone.ics", TASKS), &remote_todo("phone-1", "Buy milk", "20261018T090000Z"));
+    let summary = service.sync(&conn, &client, now()).unwrap();
+    assert_eq!(summary, SyncSummary { pulled: 1, ..SyncSummary::default() });
+    assert_eq!(task_titles(&conn), vec!["Buy milk", "Essay"]);
+    assert_eq!(service.sync(&conn, &client, now()).unwrap(), SyncSummary::default());
+    assert!(server.requests().iter().any(|request| request == &format!("PROPFIND {}", TASKS)));
+
+    // Kalender task diganti: semua task dibuat di kalender yang baru
+    service.save_config(&conn, &CalDavConfig { task_calendar: Some(PERSONAL.to_string()), ..server.config() }).unwrap();
+    server.state.lock().unwrap().no_sync_collection = false;
+    let summary = service.sync(&conn, &client, now()).unwrap();
+    assert_eq!(summary.pushed, 2);
+    assert_eq!(server.summaries(PERSONAL), vec!["Essay", "Buy milk"]);
+}
@@ -0,0 +1,195 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime, Weekday};
+use rusqlite::Connection;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::calendar_event::{CalendarEventPayload, CalendarItemKind};
+use nocture_tauri_lib::model::recurrence::{Frequency, Recurrence};
+use nocture_tauri_lib::model::task::{Category, Priority, Task};
+use nocture_tauri_lib::repository::calendar_event::SqliteCalendarEventRepository;
+use nocture_tauri_lib::repository::task::{SqliteTaskRepository, TaskRepository};
+use nocture_tauri_lib::services::calendar::{CalendarService, CalendarServiceImpl};
+
+fn setup() -> (Connection, CalendarServiceImpl<SqliteCalendarEventRepository, SqliteTaskRepository>) {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    (conn, CalendarServiceImpl { repository: SqliteCalendarEventRepository, task_repository: SqliteTaskRepository })
+}
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+// Oktober 2026; tanggal 19 hari Senin
+fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+    date(2026, 10, day).and_hms_opt(hour, minute, 0).unwrap()
+}
+
+fn payload(title: &str, start: &str, end: &str) -> CalendarEventPayload {
+    CalendarEventPayload {
+        title: title.to_string(),
+        start: start.to_string(),
+        end: end.to_string(),
+        all_day: false,
+        location: None,
+        recurrence: None,
+    }
+}
+
+fn create_task(conn: &Connection, title: &str, deadline: Option<NaiveDateTime>) -> i32 {
+    SqliteTaskRepository.create(conn, &Task {
+        id: 0,
+        title: title.to_string(),
+        category: Category::Study,
+        priority: Priority::Medium,
+        deadline,
+        completed: false,
+        created_at: at(1, 8, 0),
+        updated_at: at(1, 8, 0),
+        estimated_minutes: None,
+    }).unwrap()
+}
+
+#[test]
+fn test_event_crud_and_validation() {
+    let (conn, calendar) = setup();
+
+    let lecture = calendar.create_event(&conn, CalendarEventPayload {
+        location: Some("  Room 3.1 ".to_string()),
+        ..payload(" Algorithms ", "2026-10-19T09:00", "2026-10-19T10:40")
+    }).unwrap();
+    assert_eq!(lecture.title, "Algorithms");
+    assert_eq!(lecture.location.as_deref(), Some("Room 3.1"));
+    assert_eq!((lecture.start, lecture.end), (at(19, 9, 0), at(19, 10, 40)));
+
+    // Acara seharian disimpan dari 00.00 hari pertama sampai 00.00 setelah hari terakhir
+    let exams = calendar.create_event(&conn, CalendarEventPayload {
+        all_day: true,
+        location: Some("   ".to_string()),
+        ..payload("Exam week", "2026-10-26", "2026-10-30")
+    }).unwrap();
+    assert_eq!((exams.start, exams.end), (at(26, 0, 0), at(31, 0, 0)));
+    assert_eq!(exams.location, None);
+    assert_eq!(calendar.fetch_events(&conn).unwrap(), vec![lecture.clone(), exams.clone()]);
+
+    let invalid = |payload: CalendarEventPayload| calendar.create_event(&conn, payload).unwrap_err();
+    assert_eq!(invalid(payload(" ", "2026-10-19T09:00", "2026-10-19T10:00")), "Event title is empty");
+    assert_eq!(invalid(payload("Lab", "2026-10-19T10:00", "2026-10-19T10:00")), "Event must end after it starts");
+    assert!(invalid(payload("Lab", "2026-10-19", "2026-10-19T10:00")).contains("Invalid date and time"));
+    assert!(invalid(CalendarEventPayload { all_day: true, ..payload("Trip", "2026-10-20", "2026-10-19") }).contains("on or after"));
+    assert!(invalid(CalendarEventPayload {
+        recurrence: Some(Recurrence { interval: 0, ..Recurrence::new(Frequency::Weekly, 1) }),
+        ..payload("Lab", "2026-10-19T09:00", "2026-10-19T10:00")
+    }).contains("interval"));
+    assert!(invalid(CalendarEventPayload {
+        recurrence: Some(Recurrence { until: Some(date(2026, 10, 1)), ..Recurrence::new(Frequency::Weekly, 1) }),
+        ..payload("Lab", "2026-10-19T09:00", "2026-10-19T10:00")
+    }).contains("ends before"));
+
+    let moved = calendar.update_event(&conn, lecture.id, CalendarEventPayload {
+        recurrence: Some(Recurrence::new(Frequency::Weekly, 1)),
+        ..payload("Algorithms", "2026-10-20T13:00", "2026-10-20T14:40")
+    }).unwrap();
+    assert_eq!(moved.created_at, lecture.created_at);
+    assert_eq!(moved.start, at(20, 13, 0));
+    assert_eq!(moved.location, None);
+    assert_eq!(calendar.fetch_events(&conn).unwrap()[0], moved);
+    assert_eq!(
+        calendar.update_event(&conn, 99, payload("Ghost", "2026-10-20T13:00", "2026-10-20T14:00")).unwrap_err(),
+        "Event 99 not found"
+    );
+
+    calendar.delete_event(&conn, exams.id).unwrap();
+    assert_eq!(calendar.fetch_events(&conn).unwrap(), vec![moved]);
+}
+
+#[test]
+fn test_recurrence_occurrences() {
+    use Weekday::*;
+    let starts = |recurrence: Recurrence, first: NaiveDateTime, duration: i64, from: NaiveDate, to: NaiveDate| {
+        recurrence.occurrences(first, Duration::hours(duration), from.and_hms_opt(0, 0, 0).unwrap(), to.and_hms_opt(0, 0, 0).unwrap())
+    };
+    let weekly = |interval: u32, weekdays: &[Weekday]| Recurrence { weekdays: weekdays.to_vec(), ..Recurrence::new(Frequency::Weekly, interval) };
+
+    assert_eq!(
+        starts(weekly(1, &[Wed, Mon]), at(19, 9, 0), 1, date(2026, 10, 1), date(2026, 11, 1)),
+        vec![at(19, 9, 0), at(21, 9, 0), at(26, 9, 0), at(28, 9, 0)],
+    );
+    // Hari di minggu pertama yang sudah lewat dari tanggal awal tidak ikut
+    assert_eq!(
+        starts(weekly(1, &[Mon, Thu]), at(21, 9, 0), 1, date(2026, 10, 19), date(2026, 10, 27)),
+        vec![at(22, 9, 0), at(26, 9, 0)],
+    );
+    assert_eq!(
+        starts(weekly(2, &[]), at(20, 15, 0), 1, date(2026, 10, 1), date(2026, 12, 1)),
+        vec![at(20, 15, 0), date(2026, 11, 3).and_hms_opt(15, 0, 0).unwrap(), date(2026, 11, 17).and_hms_opt(15, 0, 0).unwrap()],
+    );
+
+    // Bulan tanpa tanggal 31 dilewati
+    let end_of_month = date(2026, 1, 31).and_hms_opt(8, 0, 0).unwrap();
+    let monthly: Vec<NaiveDate> = starts(Recurrence::new(Frequency::Monthly, 1), end_of_month, 1, date(2026, 1, 1), date(2026, 7, 1))
+        .iter().map(|s| s.date()).collect();
+    assert_eq!(monthly, vec![date(2026, 1, 31), date(2026, 3, 31), date(2026, 5, 31)]);
+
+    let leap_day = date(2024, 2, 29).and_hms_opt(0, 0, 0).unwrap();
+    let yearly: Vec<NaiveDate> = starts(Recurrence::new(Frequency::Yearly, 1), leap_day, 24, date(2024, 1, 1), date(2030, 1, 1))
+        .iter().map(|s| s.date()).collect();
+    assert_eq!(yearly, vec![date(2024, 2, 29), date(2028, 2, 29)]);
+
+    let until = Recurrence { until: Some(date(2026, 10, 21)), ..Recurrence::new(Frequency::Daily, 1) };
+    assert_eq!(starts(until, at(19, 7, 0), 1, date(2026, 10, 1), date(2026, 11, 1)).len(), 3);
+
+    // Kemunculan yang dimulai sebelum rentang tapi masih berlangsung tetap ikut
+    assert_eq!(
+        starts(Recurrence::new(Frequency::Daily, 3), at(1, 23, 0), 2, date(2026, 10, 20), date(2026, 10, 23)),
+        vec![at(19, 23, 0), at(22, 23, 0)],
+    );
+}
+
+#[test]
+fn test_calendar_range_merges_events_and_tasks() {
+    let (conn, calendar) = setup();
+    let lecture = calendar.create_event(&conn, CalendarEventPayload {
+        location: Some("Hall B".to_string()),
+        recurrence: Some(Recurrence { weekdays: vec![Weekday::Mon, Weekday::Thu], ..Recurrence::new(Frequency::Weekly, 1) }),
+        ..payload("Databases", "2026-10-12T10:00", "2026-10-12T11:40")
+    }).unwrap();
+    let exam = calendar.create_event(&conn, CalendarEventPayload {
+        all_day: true,
+        ..payload("Midterm", "2026-10-21", "2026-10-21")
+    }).unwrap();
+    let trip = calendar.create_event(&conn, CalendarEventPayload {
+        all_day: true,
+        ..payload("Field trip", "2026-10-24", "2026-10-26")
+    }).unwrap();
+    calendar.create_event(&conn, payload("Last week", "2026-10-14T09:00", "2026-10-14T10:00")).unwrap();
+
+    let essay = create_task(&conn, "Essay", Some(at(21, 0, 0)));
+    let report = create_task(&conn, "Report", Some(at(22, 17, 0)));
+    create_task(&conn, "Someday", None);
+    create_task(&conn, "Later", Some(at(27, 0, 0)));
+    let trashed = create_task(&conn, "Trashed", Some(at(20, 9, 0)));
+    SqliteTaskRepository.soft_delete(&conn, trashed, at(18, 0, 0)).unwrap();
+
+    let items = calendar.calendar_range(&conn, date(2026, 10, 19), date(2026, 10, 25)).unwrap();
+    let summary: Vec<(CalendarItemKind, i32, NaiveDateTime, bool)> = items.iter()
+        .map(|item| (item.kind, item.id, item.start, item.all_day))
+        .collect();
+    assert_eq!(summary, vec![
+        (CalendarItemKind::Event, lecture.id, at(19, 10, 0), false),
+        (CalendarItemKind::Task, essay, at(21, 0, 0), true),
+        (CalendarItemKind::Event, exam.id, at(21, 0, 0), true),
+        (CalendarItemKind::Event, lecture.id, at(22, 10, 0), false),
+        (CalendarItemKind::Task, report, at(22, 17, 0), false),
+        (CalendarItemKind::Event, trip.id, at(24, 0, 0), true),
+    ]);
+    assert!(items[0].recurring);
+    assert_eq!(items[0].location.as_deref(), Some("Hall B"));
+    assert_eq!(items[0].end, at(19, 11, 40));
+    assert_eq!(items[2].end, at(22, 0, 0));
+    assert_eq!(items[4].end, items[4].start);
+    assert_eq!(items[5].end, at(27, 0, 0));
+
+    // Acara seharian yang dimulai sebelum rentang tetap tampil
+    let monday = calendar.calendar_range(&conn, date(2026, 10, 26), date(2026, 10, 26)).unwrap();
+    assert_eq!(monday.iter().map(|item| item.title.as_str()).collect::<Vec<_>>(), vec!["Field trip", "Databases"]);
+    assert!(calendar.calendar_range(&conn, date(2026, 10, 26), date(2026, 10, 25)).is_err());
+}
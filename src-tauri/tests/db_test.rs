@@ -0,0 +1,114 @@
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use nocture_tauri_lib::db::{Database, DbConfig};
+use nocture_tauri_lib::events::EventBus;
+use nocture_tauri_lib::journal::Journal;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::task::{Category, Priority, TaskPayload};
+use nocture_tauri_lib::repository::task::SqliteTaskRepository;
+use nocture_tauri_lib::repository::task_history::SqliteTaskHistoryRepository;
+use nocture_tauri_lib::services::task::{TaskService, TaskServiceImpl};
+
+fn open_database(dir: &std::path::Path, config: DbConfig) -> Arc<Database> {
+    let db = Database::open_with_config(dir.join("nocture.db"), config).unwrap();
+    run_migrations(&db.get().unwrap()).unwrap();
+    Arc::new(db)
+}
+
+#[test]
+fn test_connections_are_configured() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = open_database(dir.path(), DbConfig::default());
+    let conn = db.get().unwrap();
+
+    let journal_mode: String = conn.query_row("PRAGMA journal_mode", [], |r| r.get(0)).unwrap();
+    let foreign_keys: i32 = conn.query_row("PRAGMA foreign_keys", [], |r| r.get(0)).unwrap();
+    let synchronous: i32 = conn.query_row("PRAGMA synchronous", [], |r| r.get(0)).unwrap();
+    let busy_timeout: i32 = conn.query_row("PRAGMA busy_timeout", [], |r| r.get(0)).unwrap();
+
+    assert_eq!(journal_mode, "wal");
+    assert_eq!(foreign_keys, 1);
+    // NORMAL
+    assert_eq!(synchronous, 1);
+    assert_eq!(busy_timeout, 5000);
+}
+
+#[test]
+fn test_pool_size_and_connection_timeout() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = DbConfig {
+        pool_size: 2,
+        connection_timeout: Duration::from_millis(100),
+        ..DbConfig::default()
+    };
+    let db = open_database(dir.path(), config);
+
+    let first = db.get().unwrap();
+    let second = db.get().unwrap();
+    let err = db.get().expect_err("pool should be exhausted");
+    assert!(err.contains("Gagal mendapatkan koneksi dari pool"));
+
+    drop(first);
+    assert!(db.get().is_ok());
+    drop(second);
+}
+
+#[test]
+fn test_concurrent_writers_do_not_hit_busy() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = DbConfig { pool_size: 4, ..DbConfig::default() };
+    let db = open_database(dir.path(), config);
+
+    let handles: Vec<_> = (0..8)
+        .map(|worker| {
+            let db = db.clone();
+            thread::spawn(move || {
+                let service = TaskServiceImpl { repository: SqliteTaskRepository, history_repository: SqliteTaskHistoryRepository, events: EventBus::new(), journal: Journal::default(), checkboxes: (), planner: () };
+                for i in 0..25 {
+                    let conn = db.get().unwrap();
+                    service.create_task(&conn, TaskPayload {
+                        title: format!("worker {} task {}", worker, i),
+                        category: Category::Work,
+                        priority: Priority::Low,
+                        deadline: None,
+                        estimated_minutes: None,
+                    }).unwrap();
+                    // Interleave reads with the writes from other threads.
+                    assert!(!service.fetch_tasks(&conn).unwrap().is_empty());
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let service = TaskServiceImpl { repository: SqliteTaskRepository, history_repository: SqliteTaskHistoryRepository, events: EventBus::new(), journal: Journal::default(), checkboxes: (), planner: () };
+    assert_eq!(service.fetch_tasks(&db.get().unwrap()).unwrap().len(), 200);
+}
+
+#[test]
+fn test_long_write_transaction_waits_instead_of_failing() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = open_database(dir.path(), DbConfig::default());
+
+    let writer = {
+        let db = db.clone();
+        thread::spawn(move || {
+            let conn = db.get().unwrap();
+            conn.execute_batch("BEGIN IMMEDIATE; INSERT INTO settings (key, value) VALUES ('lock', '1');").unwrap();
+            thread::sleep(Duration::from_millis(300));
+            conn.execute_batch("COMMIT;").unwrap();
+        })
+    };
+    thread::sleep(Duration::from_millis(50));
+
+    // Blocked by the writer above; the busy timeout makes this wait rather than error.
+    let conn = db.get().unwrap();
+    conn.execute("INSERT INTO settings (key, value) VALUES ('other', '2')", []).unwrap();
+    writer.join().unwrap();
+
+    let count: i32 = conn.query_row("SELECT COUNT(*) FROM settings", [], |r| r.get(0)).unwrap();
+    assert_eq!(count, 2);
+}
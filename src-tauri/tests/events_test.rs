@@ -0,0 +1,152 @@
+use rusqlite::Connection;
+use nocture_tauri_lib::events::{DomainEvent, EventBus};
+use nocture_tauri_lib::fs::InMemoryFileSystem;
+use nocture_tauri_lib::journal::Journal;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::note::NotePayload;
+use nocture_tauri_lib::model::note_revision::RevisionPolicy;
+use nocture_tauri_lib::model::task::{Category, Priority, TaskPayload};
+use nocture_tauri_lib::model::task_csv::CsvImportOptions;
+use nocture_tauri_lib::repository::in_memory::{
+    InMemoryLinkRepository, InMemoryNoteRepository, InMemoryNoteRevisionRepository, InMemoryTaskHistoryRepository,
+    InMemoryTaskRepository,
+};
+use nocture_tauri_lib::repository::link::SqliteLinkRepository;
+use nocture_tauri_lib::repository::note::SqliteNoteRepository;
+use nocture_tauri_lib::repository::note_revision::SqliteNoteRevisionRepository;
+use nocture_tauri_lib::repository::task::SqliteTaskRepository;
+use nocture_tauri_lib::repository::task_history::SqliteTaskHistoryRepository;
+use nocture_tauri_lib::services::note::{NoteService, NoteServiceImpl};
+use nocture_tauri_lib::services::task::{TaskService, TaskServiceImpl};
+use nocture_tauri_lib::services::task_csv::{TaskCsvService, TaskCsvServiceImpl};
+
+#[test]
+fn test_task_changes_are_published_in_order() {
+    let conn = Connection::open_in_memory().unwrap();
+    let events = EventBus::new();
+    let mut rx = events.subscribe();
+    let service = TaskServiceImpl { repository: InMemoryTaskRepository::new(), history_repository: InMemoryTaskHistoryRepository::new(), events, journal: Journal::default(), checkboxes: (), planner: () };
+
+    service.create_task(&conn, TaskPayload {
+        title: "Belajar Rust".to_string(),
+        category: Category::Study,
+        priority: Priority::High,
+        deadline: None,
+        estimated_minutes: None,
+    }).unwrap();
+    let mut task = service.fetch_tasks(&conn).unwrap().remove(0);
+    task.completed = true;
+    service.update_task(&conn, task).unwrap();
+    service.delete_task(&conn, 1).unwrap();
+
+    match rx.try_recv().unwrap() {
+        DomainEvent::TaskCreated(task) => {
+            assert_eq!(task.id, 1);
+            assert_eq!(task.title, "Belajar Rust");
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+    assert!(matches!(rx.try_recv().unwrap(), DomainEvent::TaskUpdated(task) if task.completed));
+    assert!(matches!(rx.try_recv().unwrap(), DomainEvent::TaskDeleted { id: 1 }));
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn test_note_events_carry_database_state() {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    let events = EventBus::new();
+    let mut rx = events.subscribe();
+    let service = NoteServiceImpl {
+        repository: SqliteNoteRepository,
+        revision_repository: SqliteNoteRevisionRepository,
+        revision_policy: RevisionPolicy::default(),
+        link_repository: SqliteLinkRepository,
+        fs: InMemoryFileSystem::new(),
+        events,
+        journal: Journal::default(),
+        vault_dir: "".into(),
+        trash_dir: ".trash".into(),
+        note_tasks: (),
+    };
+
+    service.create_note(&conn, NotePayload {
+        title: "Lecture".to_string(),
+        file_path: "lecture.md".to_string(),
+    }).unwrap();
+    let created = match rx.try_recv().unwrap() {
+        DomainEvent::NoteCreated(note) => note,
+        other => panic!("unexpected event {:?}", other),
+    };
+    assert_eq!(created.title, "Lecture");
+    assert_eq!(created.file_path, "lecture.md");
+
+    let mut note = created.clone();
+    note.title = "Lecture 1".to_string();
+    service.update_note(&conn, note, "# Week 1").unwrap();
+    assert!(matches!(rx.try_recv().unwrap(), DomainEvent::NoteUpdated(note) if note.title == "Lecture 1"));
+
+    service.delete_note(&conn, created.id).unwrap();
+    assert!(matches!(rx.try_recv().unwrap(), DomainEvent::NoteDeleted { id } if id == created.id));
+}
+
+#[test]
+fn test_failed_operation_publishes_nothing() {
+    let conn = Connection::open_in_memory().unwrap();
+    let events = EventBus::new();
+    let mut rx = events.subscribe();
+    let service = NoteServiceImpl {
+        repository: InMemoryNoteRepository::new(),
+        revision_repository: InMemoryNoteRevisionRepository::new(),
+        revision_policy: RevisionPolicy::default(),
+        link_repository: InMemoryLinkRepository::new(),
+        fs: InMemoryFileSystem::new(),
+        events,
+        journal: Journal::default(),
+        vault_dir: "".into(),
+        trash_dir: ".trash".into(),
+        note_tasks: (),
+    };
+    let payload = NotePayload { title: "Note".to_string(), file_path: "note.md".to_string() };
+
+    service.create_note(&conn, payload.clone()).unwrap();
+    rx.try_recv().unwrap();
+    service.create_note(&conn, payload).unwrap_err();
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn test_csv_import_publishes_created_tasks() {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    let events = EventBus::new();
+    let mut rx = events.subscribe();
+    let service = TaskCsvServiceImpl {
+        repository: SqliteTaskRepository,
+        tasks: TaskServiceImpl { repository: SqliteTaskRepository, history_repository: SqliteTaskHistoryRepository, events: events.clone(), journal: Journal::default(), checkboxes: (), planner: () },
+        events,
+        journal: Journal::default(),
+    };
+    let options = CsvImportOptions::default();
+
+    let mut dry_run = options.clone();
+    dry_run.dry_run = true;
+    service.import_csv(&conn, "title\nQuiz\nEssay\n", &dry_run).unwrap();
+    assert!(rx.try_recv().is_err());
+
+    let report = service.import_csv(&conn, "title\nQuiz\nEssay\n", &options).unwrap();
+    for task in &report.tasks {
+        assert!(matches!(rx.try_recv().unwrap(), DomainEvent::TaskCreated(created) if created.id == task.id && created.title == task.title));
+    }
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn test_event_names_and_payload_shape() {
+    let event = DomainEvent::TaskDeleted { id: 7 };
+    assert_eq!(event.name(), "task:deleted");
+    assert_eq!(serde_json::to_value(&event).unwrap(), serde_json::json!({ "id": 7 }));
+
+    // Tanpa subscriber, publish tetap aman
+    EventBus::new().publish(event);
+}
@@ -0,0 +1,180 @@
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use nocture_tauri_lib::events::EventBus;
+use nocture_tauri_lib::fs::{FileSystem, InMemoryFileSystem};
+use nocture_tauri_lib::journal::Journal;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::front_matter::FrontMatter;
+use nocture_tauri_lib::model::note::{Note, NotePayload};
+use nocture_tauri_lib::model::note_revision::RevisionPolicy;
+use nocture_tauri_lib::repository::link::SqliteLinkRepository;
+use nocture_tauri_lib::repository::note::{NoteRepository, SqliteNoteRepository};
+use nocture_tauri_lib::repository::note_revision::SqliteNoteRevisionRepository;
+use nocture_tauri_lib::services::note::{NoteService, NoteServiceImpl};
+use nocture_tauri_lib::services::vault::{VaultService, VaultServiceImpl};
+
+fn setup_conn() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    conn
+}
+
+fn note_service(fs: &InMemoryFileSystem) -> NoteServiceImpl<SqliteNoteRepository, SqliteNoteRevisionRepository, SqliteLinkRepository, InMemoryFileSystem> {
+    NoteServiceImpl {
+        repository: SqliteNoteRepository,
+        revision_repository: SqliteNoteRevisionRepository,
+        revision_policy: RevisionPolicy::default(),
+        link_repository: SqliteLinkRepository,
+        fs: fs.clone(),
+        events: EventBus::new(),
+        journal: Journal::default(),
+        vault_dir: "vault".into(),
+        trash_dir: "vault/.trash".into(),
+        note_tasks: (),
+    }
+}
+
+fn vault_service(fs: &InMemoryFileSystem) -> VaultServiceImpl<SqliteNoteRepository, SqliteLinkRepository, InMemoryFileSystem> {
+    VaultServiceImpl {
+        repository: SqliteNoteRepository,
+        link_repository: SqliteLinkRepository,
+        fs: fs.clone(),
+        events: EventBus::new(),
+        vault_dir: "vault".into(),
+    }
+}
+
+fn create_note(conn: &Connection, service: &impl NoteService, title: &str) -> Note {
+    let file_path = format!("vault/{}.md", title);
+    service.create_note(conn, NotePayload { title: title.to_string(), file_path: file_path.clone() }).unwrap();
+    service.fetch_notes(conn).unwrap().into_iter().find(|n| n.file_path == file_path).unwrap()
+}
+
+fn front_matter(fs: &InMemoryFileSystem, path: &str) -> FrontMatter {
+    FrontMatter::split(&fs.contents(path).unwrap()).0.unwrap()
+}
+
+#[test]
+fn test_parse_is_lenient_and_keeps_unknown_keys() {
+    let content = "---\n\
+        title: Week 1\n\
+        tags: rust\n\
+        created: 2026-01-05\n\
+        updated: 2026-01-06T08:30:00+07:00\n\
+        tasks: [3, oops, 7]\n\
+        aliases: [ownership]\n\
+        ---\n\
+        # Body\n";
+    let (front, body) = FrontMatter::split(content);
+    let front = front.unwrap();
+    assert_eq!(body, "# Body\n");
+    assert_eq!(front.title.as_deref(), Some("Week 1"));
+    assert_eq!(front.tags, vec!["rust"]);
+    assert_eq!(front.created, NaiveDate::from_ymd_opt(2026, 1, 5).unwrap().and_hms_opt(0, 0, 0));
+    assert_eq!(front.updated, NaiveDate::from_ymd_opt(2026, 1, 6).unwrap().and_hms_opt(1, 30, 0));
+    assert_eq!(front.tasks, vec![3, 7]);
+
+    // Unknown keys survive a round trip
+    let rendered = front.render(body).unwrap();
+    let (again, again_body) = FrontMatter::split(&rendered);
+    assert_eq!(again.unwrap(), front);
+    assert_eq!(again_body, "# Body\n");
+    assert!(rendered.contains("aliases:"));
+}
+
+#[test]
+fn test_content_without_valid_front_matter_is_all_body() {
+    for content in ["# Just markdown", "---\ntitle: [unclosed\n---\nbody", "---\nnot closed", "---\n- a list\n---\n"] {
+        let (front, body) = FrontMatter::split(content);
+        assert!(front.is_none(), "{}", content);
+        assert_eq!(body, content);
+    }
+    // A thematic break further down is not front-matter
+    assert!(FrontMatter::split("intro\n---\ntitle: x\n---\n").0.is_none());
+}
+
+#[test]
+fn test_note_metadata_is_written_to_front_matter() {
+    let conn = setup_conn();
+    let fs = InMemoryFileSystem::new();
+    let service = note_service(&fs);
+    let mut note = create_note(&conn, &service, "lecture");
+    assert_eq!(front_matter(&fs, "vault/lecture.md").title.as_deref(), Some("lecture"));
+
+    note.title = "Week 1".to_string();
+    note.tags = vec!["rust".to_string(), "study".to_string()];
+    note.color = Some("#ffaa00".to_string());
+    note.task_ids = vec![4];
+    // Front-matter sent by the editor is replaced by the note's metadata
+    service.update_note(&conn, note.clone(), "---\ntitle: Ignored\n---\n# Ownership").unwrap();
+
+    let front = front_matter(&fs, "vault/lecture.md");
+    let stored = service.fetch_notes(&conn).unwrap().remove(0);
+    assert_eq!(front.title.as_deref(), Some("Week 1"));
+    assert_eq!(front.tags, stored.tags);
+    assert_eq!(front.color, stored.color);
+    assert_eq!(front.tasks, vec![4]);
+    assert_eq!(front.updated, Some(stored.updated_at));
+    assert_eq!(stored.task_ids, vec![4]);
+    assert_eq!(service.read_note(&conn, note.id).unwrap(), "# Ownership");
+
+    // Keys added by another tool are kept on the next save
+    let content = fs.contents("vault/lecture.md").unwrap().replacen("---\n", "---\npublish: true\n", 1);
+    fs.write(Path::new("vault/lecture.md"), content.as_bytes()).unwrap();
+    service.update_note(&conn, stored, "# Ownership\nBorrowing").unwrap();
+    let content = fs.contents("vault/lecture.md").unwrap();
+    assert!(content.contains("publish: true"), "{}", content);
+    assert_eq!(FrontMatter::body(&content), "# Ownership\nBorrowing");
+}
+
+#[test]
+fn test_reindex_rebuilds_index_from_vault() {
+    let fs = InMemoryFileSystem::new();
+    let conn = setup_conn();
+    let service = note_service(&fs);
+    let mut note = create_note(&conn, &service, "lecture");
+    note.title = "Week 1".to_string();
+    note.tags = vec!["rust".to_string()];
+    service.update_note(&conn, note, "# Ownership").unwrap();
+    fs.write(Path::new("vault/plain.md"), b"# Plain heading").unwrap();
+    let original = service.fetch_notes(&conn).unwrap().remove(0);
+
+    // A fresh database gets everything back from the files
+    let fresh = setup_conn();
+    let summary = vault_service(&fs).reindex(&fresh).unwrap();
+    assert_eq!(summary.registered, 2);
+    let mut notes = SqliteNoteRepository.fetch_all(&fresh).unwrap();
+    notes.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+    assert_eq!(notes[0].title, "Week 1");
+    assert_eq!(notes[0].tags, vec!["rust"]);
+    assert_eq!(notes[0].created_at, original.created_at);
+    assert_eq!(notes[1].title, "Plain heading");
+    assert!(notes[1].tags.is_empty());
+
+    assert!(vault_service(&fs).reindex(&fresh).unwrap().is_empty());
+}
+
+#[test]
+fn test_reindex_picks_up_front_matter_edits_that_reconcile_skips() {
+    let fs = InMemoryFileSystem::new();
+    let conn = setup_conn();
+    let service = note_service(&fs);
+    let note = create_note(&conn, &service, "lecture");
+    let updated_at = SystemTime::now() - Duration::from_secs(60);
+
+    // Edited outside the app, but the file's modification time doesn't look like an edit
+    let content = fs.contents("vault/lecture.md").unwrap()
+        .replace("title: lecture", "title: Renamed\ncolor: red");
+    fs.write(Path::new("vault/lecture.md"), content.as_bytes()).unwrap();
+    fs.set_modified("vault/lecture.md", updated_at);
+
+    let vault = vault_service(&fs);
+    assert!(vault.reconcile(&conn, &[]).unwrap().is_empty());
+    assert_eq!(vault.reindex(&conn).unwrap().updated, 1);
+    let reindexed = SqliteNoteRepository.fetch_by_id(&conn, note.id).unwrap().unwrap();
+    assert_eq!(reindexed.title, "Renamed");
+    assert_eq!(reindexed.color.as_deref(), Some("red"));
+    assert_eq!(reindexed.created_at, note.created_at);
+}
@@ -0,0 +1,335 @@
+use rusqlite::Connection;
+use std::path::Path;
+use nocture_tauri_lib::events::EventBus;
+use nocture_tauri_lib::fs::{FileSystem, InMemoryFileSystem};
+use nocture_tauri_lib::journal::Journal;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::front_matter::FrontMatter;
+use nocture_tauri_lib::model::note::{Note, NotePayload};
+use nocture_tauri_lib::model::note_revision::RevisionPolicy;
+use nocture_tauri_lib::model::journal::Operation;
+use nocture_tauri_lib::model::task::{Category, Priority, Task, TaskPayload};
+use nocture_tauri_lib::model::task_history::TaskChangeKind;
+use nocture_tauri_lib::repository::link::SqliteLinkRepository;
+use nocture_tauri_lib::repository::note::SqliteNoteRepository;
+use nocture_tauri_lib::repository::note_revision::SqliteNoteRevisionRepository;
+use nocture_tauri_lib::repository::task::{SqliteTaskRepository, TaskRepository};
+use nocture_tauri_lib::repository::task_history::SqliteTaskHistoryRepository;
+use nocture_tauri_lib::services::history::{HistoryService, HistoryServiceImpl};
+use nocture_tauri_lib::services::note::{NoteService, NoteServiceImpl};
+use nocture_tauri_lib::services::task::{TaskService, TaskServiceImpl};
+
+struct Fixture {
+    conn: Connection,
+    fs: InMemoryFileSystem,
+    tasks: TaskServiceImpl<SqliteTaskRepository, SqliteTaskHistoryRepository>,
+    notes: NoteServiceImpl<SqliteNoteRepository, SqliteNoteRevisionRepository, SqliteLinkRepository, InMemoryFileSystem>,
+    history: HistoryServiceImpl<
+        SqliteTaskRepository,
+        SqliteTaskHistoryRepository,
+        SqliteNoteRepository,
+        SqliteNoteRevisionRepository,
+        SqliteLinkRepository,
+        InMemoryFileSystem,
+        TaskServiceImpl<SqliteTaskRepository, SqliteTaskHistoryRepository>,
+    >,
+}
+
+fn setup(limit: usize) -> Fixture {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    let fs = InMemoryFileSystem::new();
+    fs.create_dir_all(Path::new("vault")).unwrap();
+    let events = EventBus::new();
+    let journal = Journal::new(limit);
+    Fixture {
+        tasks: TaskServiceImpl {
+            repository: SqliteTaskRepository,
+            history_repository: SqliteTaskHistoryRepository,
+            events: events.clone(),
+            journal: journal.clone(),
+            checkboxes: (),
+            planner: (),
+        },
+        notes: NoteServiceImpl {
+            repository: SqliteNoteRepository,
+            revision_repository: SqliteNoteRevisionRepository,
+            revision_policy: RevisionPolicy::default(),
+            link_repository: SqliteLinkRepository,
+            fs: fs.clone(),
+            events: events.clone(),
+            journal: journal.clone(),
+            vault_dir: "vault".into(),
+            trash_dir: "vault/.trash".into(),
+            note_tasks: (),
+        },
+        history: HistoryServiceImpl {
+            task_repository: SqliteTaskRepository,
+            task_history_repository: SqliteTaskHistoryRepository,
+            tasks: TaskServiceImpl {
+                repository: SqliteTaskRepository,
+                history_repository: SqliteTaskHistoryRepository,
+                events: events.clone(),
+                journal: journal.clone(),
+                checkboxes: (),
+                planner: (),
+            },
+            note_repository: SqliteNoteRepository,
+            revision_repository: SqliteNoteRevisionRepository,
+            revision_policy: RevisionPolicy::default(),
+            link_repository: SqliteLinkRepository,
+            fs: fs.clone(),
+            events,
+            journal,
+            trash_dir: "vault/.trash".into(),
+        },
+        conn,
+        fs,
+    }
+}
+
+fn create_note(f: &Fixture, title: &str) -> i32 {
+    let file_path = format!("vault/{}.md", title);
+    f.notes.create_note(&f.conn, NotePayload { title: title.to_string(), file_path: file_path.clone() }).unwrap();
+    f.notes.fetch_notes(&f.conn).unwrap().into_iter().find(|n| n.file_path == file_path).unwrap().id
+}
+
+fn trash_files(f: &Fixture) -> usize {
+    f.fs.list("vault/.trash").len()
+}
+
+// The markdown body of a note file, without its front-matter
+fn body(fs: &InMemoryFileSystem, path: impl AsRef<Path>) -> Option<String> {
+    fs.contents(path).map(|content| FrontMatter::body(&content).to_string())
+}
+
+#[test]
+fn test_undo_redo_task_lifecycle() {
+    let f = setup(10);
+    f.tasks.create_task(&f.conn, TaskPayload {
+        title: "Belajar Rust".to_string(),
+        category: Category::Study,
+        priority: Priority::Low,
+        deadline: None,
+        estimated_minutes: None,
+    }).unwrap();
+    let mut task = f.tasks.fetch_tasks(&f.conn).unwrap().remove(0);
+    task.priority = Priority::High;
+    f.tasks.update_task(&f.conn, task.clone()).unwrap();
+    f.tasks.delete_task(&f.conn, task.id).unwrap();
+    assert!(f.tasks.fetch_tasks(&f.conn).unwrap().is_empty());
+
+    // Undo delete brings the task back under the same id
+    let status = f.history.undo(&f.conn).unwrap();
+    let restored = f.tasks.fetch_tasks(&f.conn).unwrap();
+    assert_eq!(restored.len(), 1);
+    assert_eq!(restored[0].id, task.id);
+    assert_eq!(restored[0].priority, Priority::High);
+    assert_eq!(status.undo.as_deref(), Some("Edit task \"Belajar Rust\""));
+    assert_eq!(status.redo.as_deref(), Some("Delete task \"Belajar Rust\""));
+
+    f.history.undo(&f.conn).unwrap();
+    assert_eq!(f.tasks.fetch_tasks(&f.conn).unwrap()[0].priority, Priority::Low);
+    // Undoing an edit or a delete shows up in the task's change history as well
+    let changes = f.tasks.fetch_task_history(&f.conn, task.id).unwrap();
+    let kinds: Vec<TaskChangeKind> = changes.iter().map(|c| c.kind).collect();
+    assert_eq!(kinds, vec![
+        TaskChangeKind::Created,
+        TaskChangeKind::Updated,
+        TaskChangeKind::Deleted,
+        TaskChangeKind::Restored,
+        TaskChangeKind::Updated,
+    ]);
+    assert_eq!(changes[4].old_value.as_deref(), Some("High"));
+    assert_eq!(changes[4].new_value.as_deref(), Some("Low"));
+
+    f.history.undo(&f.conn).unwrap();
+    assert!(f.tasks.fetch_tasks(&f.conn).unwrap().is_empty());
+    assert_eq!(f.history.undo(&f.conn).unwrap_err(), "Nothing to undo");
+
+    // Redo replays everything in order
+    f.history.redo(&f.conn).unwrap();
+    f.history.redo(&f.conn).unwrap();
+    assert_eq!(f.tasks.fetch_tasks(&f.conn).unwrap()[0].priority, Priority::High);
+    f.history.redo(&f.conn).unwrap();
+    assert!(f.tasks.fetch_tasks(&f.conn).unwrap().is_empty());
+    assert_eq!(f.history.redo(&f.conn).unwrap_err(), "Nothing to redo");
+}
+
+#[test]
+fn test_undo_delete_note_restores_file_from_trash() {
+    let f = setup(10);
+    let id = create_note(&f, "Lecture");
+    let note = f.notes.fetch_notes(&f.conn).unwrap().remove(0);
+    f.notes.update_note(&f.conn, note, "# Week 1").unwrap();
+
+    f.notes.delete_note(&f.conn, id).unwrap();
+    assert!(!f.fs.exists(Path::new("vault/Lecture.md")));
+    assert_eq!(trash_files(&f), 1);
+
+    f.history.undo(&f.conn).unwrap();
+    let notes = f.notes.fetch_notes(&f.conn).unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].id, id);
+    assert_eq!(body(&f.fs, "vault/Lecture.md").as_deref(), Some("# Week 1"));
+    assert_eq!(trash_files(&f), 0);
+
+    f.history.redo(&f.conn).unwrap();
+    assert!(f.notes.fetch_notes(&f.conn).unwrap().is_empty());
+    assert_eq!(trash_files(&f), 1);
+}
+
+#[test]
+fn test_undo_note_edit_restores_title_and_body() {
+    let f = setup(10);
+    create_note(&f, "a");
+    let mut note = f.notes.fetch_notes(&f.conn).unwrap().remove(0);
+    f.notes.update_note(&f.conn, note.clone(), "first").unwrap();
+    note.title = "Renamed".to_string();
+    f.notes.update_note(&f.conn, note, "second").unwrap();
+
+    f.history.undo(&f.conn).unwrap();
+    assert_eq!(f.notes.fetch_notes(&f.conn).unwrap()[0].title, "a");
+    assert_eq!(body(&f.fs, "vault/a.md").as_deref(), Some("first"));
+
+    f.history.redo(&f.conn).unwrap();
+    assert_eq!(f.notes.fetch_notes(&f.conn).unwrap()[0].title, "Renamed");
+    assert_eq!(body(&f.fs, "vault/a.md").as_deref(), Some("second"));
+}
+
+#[test]
+fn test_moving_a_note_moves_its_file() {
+    let f = setup(10);
+    create_note(&f, "a");
+    let note = f.notes.fetch_notes(&f.conn).unwrap().remove(0);
+    f.notes.update_note(&f.conn, note.clone(), "first").unwrap();
+    let moved = Note { file_path: "vault/archive/a.md".to_string(), ..note };
+    f.notes.update_note(&f.conn, moved.clone(), "second").unwrap();
+
+    // The revision and the undo step keep the body from the old path
+    let revisions = f.notes.list_revisions(&f.conn, moved.id).unwrap();
+    assert_eq!(revisions.len(), 1);
+    let diff = f.notes.diff_revisions(&f.conn, moved.id, revisions[0].id, None).unwrap();
+    assert_eq!((diff.insertions, diff.deletions), (1, 1));
+    assert!(!f.fs.exists(Path::new("vault/a.md")));
+    assert_eq!(body(&f.fs, "vault/archive/a.md").as_deref(), Some("second"));
+
+    f.history.undo(&f.conn).unwrap();
+    assert_eq!(f.notes.fetch_notes(&f.conn).unwrap()[0].file_path, "vault/a.md");
+    assert_eq!(body(&f.fs, "vault/a.md").as_deref(), Some("first"));
+    assert!(!f.fs.exists(Path::new("vault/archive/a.md")));
+
+    f.history.redo(&f.conn).unwrap();
+    assert_eq!(body(&f.fs, "vault/archive/a.md").as_deref(), Some("second"));
+    assert!(!f.fs.exists(Path::new("vault/a.md")));
+
+    // A file that isn't a note is never overwritten by a move
+    f.fs.write(Path::new("vault/b.md"), b"other").unwrap();
+    let err = f.notes.update_note(&f.conn, Note { file_path: "vault/b.md".to_string(), ..moved }, "third").unwrap_err();
+    assert_eq!(err, "A file already exists at vault/b.md");
+    assert_eq!(f.fs.contents("vault/b.md").as_deref(), Some("other"));
+    assert_eq!(f.notes.fetch_notes(&f.conn).unwrap()[0].file_path, "vault/archive/a.md");
+}
+
+#[test]
+fn test_undo_rename_restores_links_to_the_note() {
+    let f = setup(10);
+    let target = create_note(&f, "Week 1");
+    let index = create_note(&f, "Index");
+    let index_note = f.notes.fetch_notes(&f.conn).unwrap().into_iter().find(|n| n.id == index).unwrap();
+    f.notes.update_note(&f.conn, index_note, "See [[Week 1]]").unwrap();
+    let mut note = f.notes.fetch_notes(&f.conn).unwrap().into_iter().find(|n| n.id == target).unwrap();
+    note.title = "Ownership".to_string();
+    f.notes.update_note(&f.conn, note, "").unwrap();
+    assert_eq!(body(&f.fs, "vault/Index.md").as_deref(), Some("See [[Ownership]]"));
+    // The rewritten note keeps its old body as a revision, and the rename is a single undo step
+    assert_eq!(f.notes.list_revisions(&f.conn, index).unwrap().len(), 1);
+    assert_eq!(f.history.status().undo.as_deref(), Some("Edit note \"Ownership\""));
+
+    f.history.undo(&f.conn).unwrap();
+    assert_eq!(body(&f.fs, "vault/Index.md").as_deref(), Some("See [[Week 1]]"));
+    assert_eq!(f.notes.fetch_notes(&f.conn).unwrap().into_iter().find(|n| n.id == target).unwrap().title, "Week 1");
+    assert_eq!(f.notes.fetch_backlinks(&f.conn, target).unwrap()[0].id, index);
+
+    f.history.redo(&f.conn).unwrap();
+    assert_eq!(body(&f.fs, "vault/Index.md").as_deref(), Some("See [[Ownership]]"));
+    assert_eq!(f.notes.fetch_backlinks(&f.conn, target).unwrap()[0].id, index);
+}
+
+#[test]
+fn test_history_is_bounded() {
+    let f = setup(2);
+    for title in ["a", "b", "c"] {
+        create_note(&f, title);
+    }
+
+    f.history.undo(&f.conn).unwrap();
+    f.history.undo(&f.conn).unwrap();
+    assert_eq!(f.history.undo(&f.conn).unwrap_err(), "Nothing to undo");
+    // The oldest creation fell out of the history, so its note is still there
+    let notes = f.notes.fetch_notes(&f.conn).unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].title, "a");
+}
+
+#[test]
+fn test_new_mutation_discards_redo() {
+    let f = setup(10);
+    create_note(&f, "a");
+    f.history.undo(&f.conn).unwrap();
+    assert!(f.notes.fetch_notes(&f.conn).unwrap().is_empty());
+    assert_eq!(trash_files(&f), 1);
+
+    create_note(&f, "d");
+    assert!(f.history.status().redo.is_none());
+    assert!(f.history.redo(&f.conn).is_err());
+    // The undone note stays in the trash until it is restored or purged
+    assert_eq!(trash_files(&f), 1);
+}
+
+#[test]
+fn test_undo_skips_items_already_restored_from_trash() {
+    let f = setup(10);
+    f.tasks.create_task(&f.conn, TaskPayload {
+        title: "Belajar Rust".to_string(),
+        category: Category::Study,
+        priority: Priority::Low,
+        deadline: None,
+        estimated_minutes: None,
+    }).unwrap();
+    f.tasks.delete_task(&f.conn, 1).unwrap();
+    f.tasks.repository.undelete(&f.conn, 1).unwrap();
+
+    // The delete is already reverted, so undoing it changes nothing but still succeeds
+    f.history.undo(&f.conn).unwrap();
+    assert_eq!(f.tasks.fetch_tasks(&f.conn).unwrap().len(), 1);
+}
+
+#[test]
+fn test_failed_batch_undo_publishes_nothing() {
+    let f = setup(10);
+    f.tasks.create_task(&f.conn, TaskPayload {
+        title: "Belajar Rust".to_string(),
+        category: Category::Study,
+        priority: Priority::Low,
+        deadline: None,
+        estimated_minutes: None,
+    }).unwrap();
+    let task = f.tasks.fetch_tasks(&f.conn).unwrap().remove(0);
+    // Undo runs from the last operation: the task is trashed, then the edit of a task that no
+    // longer exists fails and the whole batch rolls back
+    let ghost = Task { id: 99, ..task.clone() };
+    f.history.journal.record(Operation::Batch {
+        label: "Import".to_string(),
+        operations: vec![
+            Operation::TaskUpdated { before: ghost.clone(), after: ghost },
+            Operation::TaskCreated { task: task.clone() },
+        ],
+    });
+    let mut events = f.history.events.subscribe();
+
+    assert_eq!(f.history.undo(&f.conn).unwrap_err(), "Task 99 not found");
+    assert!(events.try_recv().is_err());
+    assert_eq!(f.tasks.fetch_tasks(&f.conn).unwrap().iter().map(|t| t.id).collect::<Vec<_>>(), vec![task.id]);
+    assert_eq!(f.history.status().undo.as_deref(), Some("Import"));
+}
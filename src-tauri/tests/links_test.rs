@@ -0,0 +1,153 @@
+use rusqlite::Connection;
+use std::path::Path;
+use nocture_tauri_lib::events::{DomainEvent, EventBus};
+use nocture_tauri_lib::fs::{FileSystem, InMemoryFileSystem};
+use nocture_tauri_lib::journal::Journal;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::front_matter::FrontMatter;
+use nocture_tauri_lib::model::link::{rename_note_links, LinkTarget};
+use nocture_tauri_lib::model::note::{Note, NotePayload};
+use nocture_tauri_lib::model::note_revision::RevisionPolicy;
+use nocture_tauri_lib::repository::link::{LinkRepository, SqliteLinkRepository};
+use nocture_tauri_lib::repository::note::SqliteNoteRepository;
+use nocture_tauri_lib::repository::note_revision::SqliteNoteRevisionRepository;
+use nocture_tauri_lib::services::note::{NoteService, NoteServiceImpl};
+use nocture_tauri_lib::services::vault::{VaultService, VaultServiceImpl};
+
+type Service = NoteServiceImpl<SqliteNoteRepository, SqliteNoteRevisionRepository, SqliteLinkRepository, InMemoryFileSystem>;
+
+fn setup() -> (Connection, InMemoryFileSystem, Service) {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    let fs = InMemoryFileSystem::new();
+    let service = NoteServiceImpl {
+        repository: SqliteNoteRepository,
+        revision_repository: SqliteNoteRevisionRepository,
+        revision_policy: RevisionPolicy::default(),
+        link_repository: SqliteLinkRepository,
+        fs: fs.clone(),
+        events: EventBus::new(),
+        journal: Journal::default(),
+        vault_dir: "vault".into(),
+        trash_dir: "vault/.trash".into(),
+        note_tasks: (),
+    };
+    (conn, fs, service)
+}
+
+fn create_note(conn: &Connection, service: &Service, title: &str, body: &str) -> Note {
+    let file_path = format!("vault/{}.md", title);
+    service.create_note(conn, NotePayload { title: title.to_string(), file_path: file_path.clone() }).unwrap();
+    let note = service.fetch_notes(conn).unwrap().into_iter().find(|n| n.file_path == file_path).unwrap();
+    service.update_note(conn, note.clone(), body).unwrap();
+    note
+}
+
+fn titles(notes: Vec<Note>) -> Vec<String> {
+    notes.into_iter().map(|note| note.title).collect()
+}
+
+fn body(fs: &InMemoryFileSystem, path: &str) -> String {
+    FrontMatter::body(&fs.contents(path).unwrap()).to_string()
+}
+
+#[test]
+fn test_extract_links() {
+    let links = LinkTarget::extract(
+        "See [[Ownership]] and [[ownership#Moves|moves]], then [[task:42]].\n\
+         Not links: [[ ]], [[task:abc]], [[unclosed\n]] and [single].\n\
+         [[Borrowing|the borrow checker]]",
+    );
+    assert_eq!(links, vec![
+        LinkTarget::Note("Ownership".to_string()),
+        LinkTarget::Task(42),
+        LinkTarget::Note("Borrowing".to_string()),
+    ]);
+}
+
+#[test]
+fn test_rename_keeps_headings_and_aliases() {
+    let renamed = rename_note_links("[[week 1]], [[Week 1#Moves|moves]] and [[Week 10]]", "Week 1", "Ownership");
+    assert_eq!(renamed.as_deref(), Some("[[Ownership]], [[Ownership#Moves|moves]] and [[Week 10]]"));
+    assert!(rename_note_links("[[Week 10]]", "Week 1", "Ownership").is_none());
+}
+
+#[test]
+fn test_backlinks_for_notes_and_tasks() {
+    let (conn, _fs, service) = setup();
+    let target = create_note(&conn, &service, "Ownership", "Self reference: [[Ownership]]");
+    create_note(&conn, &service, "Lecture", "Read [[ownership]] before [[task:7]]");
+    let trashed = create_note(&conn, &service, "Old", "[[Ownership]]");
+    create_note(&conn, &service, "Todo", "[[task:7|report]] and [[Missing note]]");
+    service.delete_note(&conn, trashed.id).unwrap();
+
+    assert_eq!(titles(service.fetch_backlinks(&conn, target.id).unwrap()), vec!["Lecture"]);
+    assert_eq!(titles(service.fetch_task_backlinks(&conn, 7).unwrap()), vec!["Lecture", "Todo"]);
+    assert!(service.fetch_task_backlinks(&conn, 8).unwrap().is_empty());
+
+    // Saving a note replaces its links
+    let lecture = service.fetch_notes(&conn).unwrap().into_iter().find(|n| n.title == "Lecture").unwrap();
+    service.update_note(&conn, lecture, "No links anymore").unwrap();
+    assert!(service.fetch_backlinks(&conn, target.id).unwrap().is_empty());
+    assert_eq!(titles(service.fetch_task_backlinks(&conn, 7).unwrap()), vec!["Todo"]);
+}
+
+#[test]
+fn test_renaming_a_note_rewrites_links_to_it() {
+    let (conn, fs, service) = setup();
+    let mut target = create_note(&conn, &service, "Week 1", "# Ownership");
+    let lecture = create_note(&conn, &service, "Lecture", "Start with [[week 1#Moves|moves]].\nThen [[Week 2]].");
+
+    target.title = "Ownership".to_string();
+    let mut received = service.events.subscribe();
+    service.update_note(&conn, target.clone(), "# Ownership").unwrap();
+
+    assert_eq!(body(&fs, "vault/Lecture.md"), "Start with [[Ownership#Moves|moves]].\nThen [[Week 2]].");
+    // Saved like an edit: the old body is a revision and listeners get the note as stored now
+    assert_eq!(service.list_revisions(&conn, lecture.id).unwrap().len(), 1);
+    let stored = service.fetch_notes(&conn).unwrap().into_iter().find(|n| n.id == lecture.id).unwrap();
+    let updated: Vec<Note> = std::iter::from_fn(|| received.try_recv().ok())
+        .filter_map(|event| match event {
+            DomainEvent::NoteUpdated(note) if note.id == lecture.id => Some(note),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(updated, vec![stored]);
+    assert_eq!(titles(service.fetch_backlinks(&conn, target.id).unwrap()), vec!["Lecture"]);
+    assert_eq!(
+        SqliteLinkRepository.fetch_for_note(&conn, lecture.id).unwrap(),
+        vec![LinkTarget::Note("ownership".to_string()), LinkTarget::Note("week 2".to_string())],
+    );
+}
+
+#[test]
+fn test_rename_leaves_links_alone_while_title_is_shared() {
+    let (conn, fs, service) = setup();
+    let mut first = create_note(&conn, &service, "Draft", "");
+    create_note(&conn, &service, "draft-copy", "");
+    let mut second = service.fetch_notes(&conn).unwrap().into_iter().find(|n| n.title == "draft-copy").unwrap();
+    second.title = "Draft".to_string();
+    service.update_note(&conn, second, "").unwrap();
+    create_note(&conn, &service, "Index", "[[Draft]]");
+
+    first.title = "Final".to_string();
+    service.update_note(&conn, first, "").unwrap();
+    assert_eq!(body(&fs, "vault/Index.md"), "[[Draft]]");
+}
+
+#[test]
+fn test_vault_sync_indexes_files_written_outside_the_app() {
+    let (conn, fs, service) = setup();
+    let target = create_note(&conn, &service, "Ownership", "");
+    fs.write(Path::new("vault/external.md"), b"# External\nLinks to [[Ownership]]").unwrap();
+
+    let vault = VaultServiceImpl {
+        repository: SqliteNoteRepository,
+        link_repository: SqliteLinkRepository,
+        fs: fs.clone(),
+        events: EventBus::new(),
+        vault_dir: "vault".into(),
+    };
+    vault.reconcile(&conn, &[]).unwrap();
+    assert_eq!(titles(service.fetch_backlinks(&conn, target.id).unwrap()), vec!["External"]);
+}
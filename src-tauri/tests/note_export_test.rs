@@ -0,0 +1,123 @@
+use rusqlite::Connection;
+use std::path::Path;
+use nocture_tauri_lib::fs::{FileSystem, InMemoryFileSystem};
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::note::NotePayload;
+use nocture_tauri_lib::model::note_export::{NoteExportFormat, NoteExportSummary};
+use nocture_tauri_lib::repository::attachment::SqliteAttachmentRepository;
+use nocture_tauri_lib::repository::note::{NoteRepository, SqliteNoteRepository};
+use nocture_tauri_lib::services::note_export::{NoteExportService, NoteExportServiceImpl};
+
+type Service = NoteExportServiceImpl<SqliteNoteRepository, SqliteAttachmentRepository, InMemoryFileSystem>;
+
+fn setup() -> (Connection, InMemoryFileSystem, Service) {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    let fs = InMemoryFileSystem::new();
+    fs.create_dir_all(Path::new("vault/course/img")).unwrap();
+    fs.create_dir_all(Path::new("exports")).unwrap();
+    let service = NoteExportServiceImpl {
+        repository: SqliteNoteRepository,
+        attachment_repository: SqliteAttachmentRepository,
+        fs: fs.clone(),
+        vault_dir: "vault".into(),
+        attachments_dir: "vault/.attachments".into(),
+    };
+    (conn, fs, service)
+}
+
+fn create_note(conn: &Connection, fs: &InMemoryFileSystem, title: &str, file_path: &str, body: &str) -> i32 {
+    fs.write(Path::new(file_path), format!("---\ntitle: {}\n---\n{}", title, body).as_bytes()).unwrap();
+    SqliteNoteRepository.create(conn, &NotePayload { title: title.to_string(), file_path: file_path.to_string() }).unwrap()
+}
+
+// A 2x2 RGBA image
+fn png_bytes() -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, 2, 2);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().unwrap();
+    writer.write_image_data(&[200; 16]).unwrap();
+    writer.finish().unwrap();
+    bytes
+}
+
+fn exported(fs: &InMemoryFileSystem, path: &str) -> Vec<u8> {
+    fs.read(Path::new(path)).unwrap()
+}
+
+#[test]
+fn test_html_export_is_standalone_with_toc() {
+    let (conn, fs, service) = setup();
+    fs.write(Path::new("vault/course/img/graph.png"), &png_bytes()).unwrap();
+    let first = create_note(&conn, &fs, "Week 1", "vault/course/week1.md",
+        "# Ownership\n![graph](img/graph.png)\n\nSee [below](#moves).\n\n## Moves\n<script>alert(1)</script>\n");
+    let second = create_note(&conn, &fs, "Week 2", "vault/course/week2.md", "# Borrowing\nText[^1]\n\n[^1]: Note.\n");
+
+    let summary = service.export_notes(&conn, &[first, second], NoteExportFormat::Html, Path::new("exports/notes.html")).unwrap();
+    assert_eq!(summary, NoteExportSummary { notes_exported: 2, images_embedded: 1, images_skipped: 0 });
+
+    let html = String::from_utf8(exported(&fs, "exports/notes.html")).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"), "{}", html);
+    assert!(html.contains("<title>Notes</title>"), "{}", html);
+    assert!(html.contains("<li class=\"level-1\"><a href=\"#note-1-title\">Week 1</a></li>"), "{}", html);
+    assert!(html.contains("<li class=\"level-2\"><a href=\"#note-1-h1\">Ownership</a></li>"), "{}", html);
+    assert!(html.contains("<li class=\"level-3\"><a href=\"#note-1-h2\">Moves</a></li>"), "{}", html);
+    assert!(html.contains("<h2 id=\"note-2-h1\">Borrowing</h2>"), "{}", html);
+    assert!(html.contains("src=\"data:image/png;base64,"), "{}", html);
+    assert!(html.contains("href=\"#note-1-moves\""), "{}", html);
+    assert!(html.contains("id=\"note-2-1\""), "{}", html);
+    assert!(!html.contains("<script"), "{}", html);
+    assert!(!html.contains("vault://"), "{}", html);
+}
+
+#[test]
+fn test_pdf_export_has_toc_outline_and_images() {
+    let (conn, fs, service) = setup();
+    fs.write(Path::new("vault/course/img/graph.png"), &png_bytes()).unwrap();
+    let id = create_note(&conn, &fs, "Week 1", "vault/course/week1.md",
+        "# Ownership\nSome **bold** and `code`.\n\n- [x] read\n- [ ] write\n\n![graph](img/graph.png)\n\n\
+         | a | b |\n|---|---|\n| 1 | 2 |\n\n```\nfn main() {}\n```\n");
+
+    let summary = service.export_notes(&conn, &[id], NoteExportFormat::Pdf, Path::new("exports/week1.pdf")).unwrap();
+    assert_eq!(summary, NoteExportSummary { notes_exported: 1, images_embedded: 1, images_skipped: 0 });
+
+    let pdf = exported(&fs, "exports/week1.pdf");
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.starts_with("%PDF-"));
+    assert!(text.trim_end().ends_with("%%EOF"));
+    // Contents page plus one page for the note
+    assert_eq!(text.matches("/Type /Page\n").count(), 2);
+    assert!(text.contains("/Outlines"));
+    assert!(text.contains("/Subtype /Image"));
+    assert!(text.contains("/SMask"));
+    assert!(text.contains("/S /GoTo"));
+    assert!(text.contains("(Week 1)"));
+}
+
+#[test]
+fn test_missing_and_undecodable_images_are_skipped() {
+    let (conn, fs, service) = setup();
+    fs.write(Path::new("vault/course/img/broken.png"), b"not a png").unwrap();
+    let id = create_note(&conn, &fs, "Week 1", "vault/course/week1.md",
+        "![gone](img/gone.png) ![broken](img/broken.png) ![secret](../../outside.png) ![x](attachment:42)");
+
+    let summary = service.export_notes(&conn, &[id], NoteExportFormat::Pdf, Path::new("exports/week1.pdf")).unwrap();
+    assert_eq!(summary.images_embedded, 0);
+    assert_eq!(summary.images_skipped, 4);
+
+    // HTML embeds whatever it can read, decodable or not
+    let summary = service.export_notes(&conn, &[id], NoteExportFormat::Html, Path::new("exports/week1.html")).unwrap();
+    assert_eq!((summary.images_embedded, summary.images_skipped), (1, 3));
+}
+
+#[test]
+fn test_unknown_note_or_empty_selection_fails() {
+    let (conn, fs, service) = setup();
+    let id = create_note(&conn, &fs, "Week 1", "vault/course/week1.md", "# Ownership");
+    let error = service.export_notes(&conn, &[id, 99], NoteExportFormat::Html, Path::new("exports/notes.html")).unwrap_err();
+    assert_eq!(error, "Note 99 not found");
+    assert!(service.export_notes(&conn, &[], NoteExportFormat::Pdf, Path::new("exports/notes.pdf")).is_err());
+    assert!(fs.read(Path::new("exports/notes.html")).is_err());
+}
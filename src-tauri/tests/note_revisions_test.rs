@@ -0,0 +1,170 @@
+use chrono::{Duration, Utc};
+use rusqlite::{params, Connection};
+use std::path::Path;
+use nocture_tauri_lib::events::EventBus;
+use nocture_tauri_lib::fs::InMemoryFileSystem;
+use nocture_tauri_lib::journal::Journal;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::front_matter::FrontMatter;
+use nocture_tauri_lib::model::note::{Note, NotePayload};
+use nocture_tauri_lib::model::note_revision::{DiffTag, RevisionPolicy};
+use nocture_tauri_lib::repository::in_memory::{InMemoryLinkRepository, InMemoryNoteRepository, InMemoryNoteRevisionRepository};
+use nocture_tauri_lib::repository::link::SqliteLinkRepository;
+use nocture_tauri_lib::repository::note::SqliteNoteRepository;
+use nocture_tauri_lib::repository::note_revision::{NoteRevisionRepository, SqliteNoteRevisionRepository};
+use nocture_tauri_lib::services::note::{NoteService, NoteServiceImpl};
+
+type Service = NoteServiceImpl<SqliteNoteRepository, SqliteNoteRevisionRepository, SqliteLinkRepository, InMemoryFileSystem>;
+
+fn setup(policy: RevisionPolicy) -> (Connection, InMemoryFileSystem, Service) {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    let fs = InMemoryFileSystem::new();
+    let service = NoteServiceImpl {
+        repository: SqliteNoteRepository,
+        revision_repository: SqliteNoteRevisionRepository,
+        revision_policy: policy,
+        link_repository: SqliteLinkRepository,
+        fs: fs.clone(),
+        events: EventBus::new(),
+        journal: Journal::default(),
+        vault_dir: "".into(),
+        trash_dir: ".trash".into(),
+        note_tasks: (),
+    };
+    (conn, fs, service)
+}
+
+fn create_note(conn: &Connection, service: &impl NoteService) -> Note {
+    service.create_note(conn, NotePayload {
+        title: "Lecture".to_string(),
+        file_path: "lecture.md".to_string(),
+    }).unwrap();
+    service.fetch_notes(conn).unwrap().remove(0)
+}
+
+// The markdown body of a note file, without its front-matter
+fn body(fs: &InMemoryFileSystem, path: impl AsRef<Path>) -> Option<String> {
+    fs.contents(path).map(|content| FrontMatter::body(&content).to_string())
+}
+
+#[test]
+fn test_saves_keep_previous_versions() {
+    let (conn, fs, service) = setup(RevisionPolicy::default());
+    let note = create_note(&conn, &service);
+
+    // The empty file of a new note is not kept, and saving unchanged content adds nothing
+    service.update_note(&conn, note.clone(), "# Week 1\n").unwrap();
+    assert!(service.list_revisions(&conn, note.id).unwrap().is_empty());
+    service.update_note(&conn, note.clone(), "# Week 1\n").unwrap();
+    assert!(service.list_revisions(&conn, note.id).unwrap().is_empty());
+
+    let long_body = "Ownership and borrowing.\n".repeat(200);
+    service.update_note(&conn, note.clone(), &long_body).unwrap();
+    service.update_note(&conn, note.clone(), "# Week 3\n").unwrap();
+
+    let revisions = service.list_revisions(&conn, note.id).unwrap();
+    assert_eq!(revisions.len(), 2);
+    assert_eq!(revisions[0].size, long_body.len() as i64);
+    assert_eq!(revisions[1].size, "# Week 1\n".len() as i64);
+    assert_ne!(revisions[0].content_hash, revisions[1].content_hash);
+    assert_eq!(body(&fs, "lecture.md").as_deref(), Some("# Week 3\n"));
+
+    // Stored compressed
+    let stored = SqliteNoteRevisionRepository.fetch_data(&conn, note.id, revisions[0].id).unwrap().unwrap();
+    assert!(stored.len() < long_body.len() / 10);
+}
+
+#[test]
+fn test_diff_between_revisions_and_current() {
+    let (conn, _fs, service) = setup(RevisionPolicy::default());
+    let note = create_note(&conn, &service);
+    service.update_note(&conn, note.clone(), "title\nline a\nline b\n").unwrap();
+    service.update_note(&conn, note.clone(), "title\nline b\nline c\n").unwrap();
+    service.update_note(&conn, note.clone(), "title\nline c\n").unwrap();
+    let revisions = service.list_revisions(&conn, note.id).unwrap();
+    let (newer, older) = (revisions[0].id, revisions[1].id);
+
+    let diff = service.diff_revisions(&conn, note.id, older, Some(newer)).unwrap();
+    assert_eq!((diff.insertions, diff.deletions), (1, 1));
+    let changed: Vec<_> = diff.lines.iter()
+        .filter(|l| l.tag != DiffTag::Equal)
+        .map(|l| (l.tag, l.text.as_str(), l.old_line, l.new_line))
+        .collect();
+    assert_eq!(changed, vec![
+        (DiffTag::Delete, "line a", Some(2), None),
+        (DiffTag::Insert, "line c", None, Some(3)),
+    ]);
+
+    // Without `to` the revision is compared with what is on disk now
+    let diff = service.diff_revisions(&conn, note.id, newer, None).unwrap();
+    assert_eq!((diff.insertions, diff.deletions), (0, 1));
+    assert!(service.diff_revisions(&conn, note.id, 999, None).unwrap_err().contains("not found"));
+}
+
+#[test]
+fn test_restore_revision_keeps_current_content() {
+    let (conn, fs, service) = setup(RevisionPolicy::default());
+    let note = create_note(&conn, &service);
+    service.update_note(&conn, note.clone(), "first draft").unwrap();
+    service.update_note(&conn, note.clone(), "second draft").unwrap();
+    let first = service.list_revisions(&conn, note.id).unwrap()[0].id;
+
+    service.restore_revision(&conn, note.id, first).unwrap();
+    assert_eq!(body(&fs, "lecture.md").as_deref(), Some("first draft"));
+
+    // The content that was replaced is now the newest revision
+    let revisions = service.list_revisions(&conn, note.id).unwrap();
+    assert_eq!(revisions.len(), 2);
+    let diff = service.diff_revisions(&conn, note.id, revisions[0].id, None).unwrap();
+    assert_eq!(diff.lines[0].text, "second draft");
+    assert_eq!(diff.lines[0].tag, DiffTag::Delete);
+}
+
+#[test]
+fn test_revisions_are_capped_by_count_and_age() {
+    let (conn, _fs, service) = setup(RevisionPolicy { max_count: 3, max_age_days: 30 });
+    let note = create_note(&conn, &service);
+    for i in 0..6 {
+        service.update_note(&conn, note.clone(), &format!("version {}", i)).unwrap();
+    }
+    assert_eq!(service.list_revisions(&conn, note.id).unwrap().len(), 3);
+
+    let long_ago = Utc::now().naive_utc() - Duration::days(45);
+    conn.execute(
+        "UPDATE note_revisions SET created_at = ?1 WHERE id IN (SELECT id FROM note_revisions ORDER BY id LIMIT 2)",
+        params![long_ago],
+    ).unwrap();
+    service.update_note(&conn, note.clone(), "version 6").unwrap();
+
+    let revisions = service.list_revisions(&conn, note.id).unwrap();
+    assert_eq!(revisions.len(), 2);
+    assert!(revisions.iter().all(|r| r.created_at > long_ago));
+}
+
+#[test]
+fn test_in_memory_revisions() {
+    let conn = Connection::open_in_memory().unwrap();
+    let fs = InMemoryFileSystem::new();
+    let service = NoteServiceImpl {
+        repository: InMemoryNoteRepository::new(),
+        revision_repository: InMemoryNoteRevisionRepository::new(),
+        revision_policy: RevisionPolicy { max_count: 2, max_age_days: 30 },
+        link_repository: InMemoryLinkRepository::new(),
+        fs: fs.clone(),
+        events: EventBus::new(),
+        journal: Journal::default(),
+        vault_dir: "".into(),
+        trash_dir: ".trash".into(),
+        note_tasks: (),
+    };
+    let note = create_note(&conn, &service);
+    for body in ["a", "b", "c", "d"] {
+        service.update_note(&conn, note.clone(), body).unwrap();
+    }
+
+    let revisions = service.list_revisions(&conn, note.id).unwrap();
+    assert_eq!(revisions.len(), 2);
+    service.restore_revision(&conn, note.id, revisions[1].id).unwrap();
+    assert_eq!(body(&fs, "lecture.md").as_deref(), Some("b"));
+}
@@ -0,0 +1,276 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use std::path::Path;
+use nocture_tauri_lib::events::EventBus;
+use nocture_tauri_lib::fs::{FileSystem, InMemoryFileSystem};
+use nocture_tauri_lib::journal::{Changes, Journal};
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::front_matter::FrontMatter;
+use nocture_tauri_lib::model::note::{Note, NotePayload};
+use nocture_tauri_lib::model::note_revision::RevisionPolicy;
+use nocture_tauri_lib::model::note_task::{set_checkbox, Checkbox, NoteTask, NoteTaskSyncSummary};
+use nocture_tauri_lib::model::task::Task;
+use nocture_tauri_lib::model::task_history::TaskChangeKind;
+use nocture_tauri_lib::repository::link::SqliteLinkRepository;
+use nocture_tauri_lib::repository::note::{NoteRepository, SqliteNoteRepository};
+use nocture_tauri_lib::repository::note_revision::SqliteNoteRevisionRepository;
+use nocture_tauri_lib::repository::note_task::SqliteNoteTaskRepository;
+use nocture_tauri_lib::repository::task::{SqliteTaskRepository, TaskRepository};
+use nocture_tauri_lib::repository::task_history::{SqliteTaskHistoryRepository, TaskHistoryRepository};
+use nocture_tauri_lib::services::history::{HistoryService, HistoryServiceImpl};
+use nocture_tauri_lib::services::note::{NoteService, NoteServiceImpl};
+use nocture_tauri_lib::services::note_task::{CheckboxWriter, NoteTaskService, NoteTaskServiceImpl, TaskCheckboxSync};
+use nocture_tauri_lib::services::task::{TaskService, TaskServiceImpl};
+
+const NOTE_PATH: &str = "vault/Lecture.md";
+
+type Tasks = TaskServiceImpl<
+    SqliteTaskRepository,
+    SqliteTaskHistoryRepository,
+    CheckboxWriter<SqliteNoteRepository, SqliteNoteTaskRepository, SqliteNoteRevisionRepository, InMemoryFileSystem>,
+>;
+
+type Notes = NoteServiceImpl<
+    SqliteNoteRepository,
+    SqliteNoteRevisionRepository,
+    SqliteLinkRepository,
+    InMemoryFileSystem,
+    NoteTaskServiceImpl<SqliteNoteRepository, SqliteTaskRepository, SqliteNoteTaskRepository, InMemoryFileSystem, Tasks>,
+>;
+
+type History = HistoryServiceImpl<
+    SqliteTaskRepository,
+    SqliteTaskHistoryRepository,
+    SqliteNoteRepository,
+    SqliteNoteRevisionRepository,
+    SqliteLinkRepository,
+    InMemoryFileSystem,
+    Tasks,
+>;
+
+struct Fixture {
+    conn: Connection,
+    fs: InMemoryFileSystem,
+    notes: Notes,
+    tasks: Tasks,
+    history: History,
+    note_id: i32,
+}
+
+fn task_service(fs: &InMemoryFileSystem, events: &EventBus, journal: &Journal) -> Tasks {
+    TaskServiceImpl {
+        repository: SqliteTaskRepository,
+        history_repository: SqliteTaskHistoryRepository,
+        events: events.clone(),
+        journal: journal.clone(),
+        checkboxes: CheckboxWriter {
+            note_repository: SqliteNoteRepository,
+            repository: SqliteNoteTaskRepository,
+            revision_repository: SqliteNoteRevisionRepository,
+            revision_policy: RevisionPolicy::default(),
+            fs: fs.clone(),
+        },
+        planner: (),
+    }
+}
+
+fn setup(body: &str) -> Fixture {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    let fs = InMemoryFileSystem::new();
+    fs.create_dir_all(Path::new("vault")).unwrap();
+    let note_id = SqliteNoteRepository.create(&conn, &NotePayload {
+        title: "Lecture".to_string(),
+        file_path: NOTE_PATH.to_string(),
+    }).unwrap();
+    write_body(&fs, body);
+    let events = EventBus::new();
+    let journal = Journal::default();
+    let notes = NoteServiceImpl {
+        repository: SqliteNoteRepository,
+        revision_repository: SqliteNoteRevisionRepository,
+        revision_policy: RevisionPolicy::default(),
+        link_repository: SqliteLinkRepository,
+        fs: fs.clone(),
+        events: events.clone(),
+        journal: journal.clone(),
+        vault_dir: "vault".into(),
+        trash_dir: "vault/.trash".into(),
+        note_tasks: NoteTaskServiceImpl {
+            note_repository: SqliteNoteRepository,
+            task_repository: SqliteTaskRepository,
+            repository: SqliteNoteTaskRepository,
+            fs: fs.clone(),
+            tasks: task_service(&fs, &events, &journal),
+        },
+    };
+    let history = HistoryServiceImpl {
+        task_repository: SqliteTaskRepository,
+        task_history_repository: SqliteTaskHistoryRepository,
+        tasks: task_service(&fs, &events, &journal),
+        note_repository: SqliteNoteRepository,
+        revision_repository: SqliteNoteRevisionRepository,
+        revision_policy: RevisionPolicy::default(),
+        link_repository: SqliteLinkRepository,
+        fs: fs.clone(),
+        events: events.clone(),
+        journal: journal.clone(),
+        trash_dir: "vault/.trash".into(),
+    };
+    Fixture { tasks: task_service(&fs, &events, &journal), conn, fs, notes, history, note_id }
+}
+
+fn write_body(fs: &InMemoryFileSystem, body: &str) {
+    fs.write(Path::new(NOTE_PATH), format!("---\ntitle: Lecture\n---\n{}", body).as_bytes()).unwrap();
+}
+
+fn note(f: &Fixture) -> Note {
+    SqliteNoteRepository.fetch_by_id(&f.conn, f.note_id).unwrap().unwrap()
+}
+
+// Saves the note from the editor with checkbox extraction turned on
+fn save(f: &Fixture, body: &str) -> NoteTaskSyncSummary {
+    f.notes.update_note_with_tasks(&f.conn, note(f), body).unwrap()
+}
+
+fn body(f: &Fixture) -> String {
+    FrontMatter::body(&f.fs.contents(NOTE_PATH).unwrap()).to_string()
+}
+
+fn tasks(conn: &Connection) -> Vec<(String, bool)> {
+    SqliteTaskRepository.fetch_all(conn).unwrap().into_iter().map(|task| (task.title, task.completed)).collect()
+}
+
+fn task_by_title(conn: &Connection, title: &str) -> Task {
+    SqliteTaskRepository.fetch_all(conn).unwrap().into_iter().find(|task| task.title == title).unwrap()
+}
+
+#[test]
+fn test_extract_checkboxes() {
+    let checkboxes = Checkbox::extract(
+        "# Week 3\n\
+         - [ ] read chapter 3\n\
+         - [x] watch lecture  \n  \
+           1. [X] nested item\n\
+         - [ ]\n\
+         - plain item [ ] not a task\n\
+         ```\n- [ ] inside code\n```\n\
+         > - [ ] quoted task",
+    );
+    assert_eq!(checkboxes, vec![
+        Checkbox { line: 2, text: "read chapter 3".to_string(), checked: false },
+        Checkbox { line: 3, text: "watch lecture".to_string(), checked: true },
+        Checkbox { line: 4, text: "nested item".to_string(), checked: true },
+        Checkbox { line: 10, text: "quoted task".to_string(), checked: false },
+    ]);
+}
+
+#[test]
+fn test_set_checkbox() {
+    let markdown = "- [ ] read\n- [x] write\n";
+    assert_eq!(set_checkbox(markdown, 1, true).unwrap(), "- [x] read\n- [x] write\n");
+    assert_eq!(set_checkbox(markdown, 2, false).unwrap(), "- [ ] read\n- [ ] write\n");
+    assert_eq!(set_checkbox(markdown, 3, true), None);
+}
+
+#[test]
+fn test_repeated_sync_creates_no_duplicates() {
+    let f = setup("");
+    let summary = save(&f, "- [ ] read chapter 3\n- [x] submit lab\n");
+    assert_eq!(summary, NoteTaskSyncSummary { tasks_created: 2, tasks_updated: 0, tasks_unlinked: 0 });
+    assert_eq!(save(&f, "- [ ] read chapter 3\n- [x] submit lab\n"), NoteTaskSyncSummary::default());
+    assert_eq!(tasks(&f.conn), vec![("read chapter 3".to_string(), false), ("submit lab".to_string(), true)]);
+
+    let task = task_by_title(&f.conn, "submit lab");
+    assert_eq!(
+        f.notes.note_tasks.fetch_task_source(&f.conn, task.id).unwrap(),
+        Some(NoteTask { task_id: task.id, note_id: f.note_id, line: 2, text: "submit lab".to_string() }),
+    );
+}
+
+#[test]
+fn test_note_edits_update_linked_tasks() {
+    let f = setup("");
+    save(&f, "- [ ] read chapter 3\n- [ ] submit lab\n- [ ] old idea\n");
+    let read = task_by_title(&f.conn, "read chapter 3");
+
+    // One reworded in place, one ticked and moved, one removed and one added
+    let summary = save(&f, "- [ ] read chapters 3-4\n- [ ] new idea\n- [x] submit lab\n");
+    assert_eq!(summary, NoteTaskSyncSummary { tasks_created: 1, tasks_updated: 2, tasks_unlinked: 1 });
+    assert_eq!(tasks(&f.conn), vec![
+        ("read chapters 3-4".to_string(), false),
+        ("submit lab".to_string(), true),
+        // Removing a checkbox keeps its task
+        ("old idea".to_string(), false),
+        ("new idea".to_string(), false),
+    ]);
+    assert_eq!(task_by_title(&f.conn, "read chapters 3-4").id, read.id);
+    let history = SqliteTaskHistoryRepository.fetch_for_task(&f.conn, read.id).unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].kind, TaskChangeKind::Created);
+    assert_eq!(history[1].new_value.as_deref(), Some("read chapters 3-4"));
+}
+
+#[test]
+fn test_saving_a_note_and_its_tasks_is_one_undo_step() {
+    let f = setup("- [ ] read chapter 3\n");
+    save(&f, "- [ ] read chapter 3\n");
+    save(&f, "- [x] read chapter 3\n- [ ] submit lab\n");
+    assert_eq!(tasks(&f.conn), vec![("read chapter 3".to_string(), true), ("submit lab".to_string(), false)]);
+    assert_eq!(f.history.status().undo.as_deref(), Some("Edit note \"Lecture\""));
+
+    f.history.undo(&f.conn).unwrap();
+    assert_eq!(body(&f), "- [ ] read chapter 3\n");
+    assert_eq!(tasks(&f.conn), vec![("read chapter 3".to_string(), false)]);
+
+    f.history.redo(&f.conn).unwrap();
+    assert_eq!(body(&f), "- [x] read chapter 3\n- [ ] submit lab\n");
+    assert_eq!(tasks(&f.conn), vec![("read chapter 3".to_string(), true), ("submit lab".to_string(), false)]);
+}
+
+#[test]
+fn test_task_completion_is_written_back_to_the_note() {
+    let f = setup("");
+    save(&f, "- [ ] read chapter 3\n- [ ] submit lab\n");
+    let mut task = task_by_title(&f.conn, "submit lab");
+    // Edited without a sync: the checkbox is found again by its text
+    write_body(&f.fs, "New intro\n\n- [ ] read chapter 3\n- [ ] submit lab\n");
+    let revisions = f.notes.list_revisions(&f.conn, f.note_id).unwrap().len();
+
+    task.completed = true;
+    f.tasks.update_task(&f.conn, task.clone()).unwrap();
+    assert_eq!(body(&f), "New intro\n\n- [ ] read chapter 3\n- [x] submit lab\n");
+    assert!(f.fs.contents(NOTE_PATH).unwrap().contains("title: Lecture"));
+    assert_eq!(f.notes.note_tasks.fetch_task_source(&f.conn, task.id).unwrap().unwrap().line, 4);
+    // The unticked body is kept like any other note edit
+    assert_eq!(f.notes.list_revisions(&f.conn, f.note_id).unwrap().len(), revisions + 1);
+
+    // One undo step brings back both the task and its checkbox
+    f.history.undo(&f.conn).unwrap();
+    let task = task_by_title(&f.conn, "submit lab");
+    assert!(!task.completed);
+    assert_eq!(body(&f), "New intro\n\n- [ ] read chapter 3\n- [ ] submit lab\n");
+
+    // Already in sync: nothing is written
+    let content = f.fs.contents(NOTE_PATH).unwrap();
+    f.tasks.update_task(&f.conn, task).unwrap();
+    assert_eq!(f.fs.contents(NOTE_PATH).unwrap(), content);
+    assert_eq!(save(&f, &body(&f)), NoteTaskSyncSummary::default());
+}
+
+#[test]
+fn test_trashed_task_is_not_recreated() {
+    let f = setup("");
+    save(&f, "- [ ] read chapter 3\n");
+    let task = task_by_title(&f.conn, "read chapter 3");
+    SqliteTaskRepository.soft_delete(&f.conn, task.id, Utc::now().naive_utc()).unwrap();
+
+    assert_eq!(save(&f, "- [ ] read chapter 3\n"), NoteTaskSyncSummary::default());
+    assert!(tasks(&f.conn).is_empty());
+
+    // Purging the task drops the link, so the checkbox becomes a new task
+    SqliteTaskRepository.delete(&f.conn, task.id).unwrap();
+    assert_eq!(save(&f, "- [ ] read chapter 3\n").tasks_created, 1);
+    assert!(f.tasks.checkboxes.sync_checkbox(&f.conn, &task, &mut Changes::new()).is_ok());
+    assert!(f.notes.note_tasks.sync_note(&f.conn, f.note_id + 1, &mut Changes::new()).is_err());
+}
@@ -1,61 +1,164 @@
 use rusqlite::Connection;
 use std::path::Path;
+use nocture_tauri_lib::events::EventBus;
+use nocture_tauri_lib::journal::Journal;
+use nocture_tauri_lib::fs::{FileSystem, InMemoryFileSystem};
+use nocture_tauri_lib::migrations::run_migrations;
 use nocture_tauri_lib::services::note::{NoteServiceImpl, NoteService};
-use nocture_tauri_lib::model::note::NotePayload;
+use nocture_tauri_lib::model::front_matter::FrontMatter;
+use nocture_tauri_lib::model::note::{Note, NotePayload};
+use nocture_tauri_lib::model::note_revision::RevisionPolicy;
+use nocture_tauri_lib::repository::in_memory::{InMemoryLinkRepository, InMemoryNoteRepository, InMemoryNoteRevisionRepository};
 use nocture_tauri_lib::repository::note::{SqliteNoteRepository, NoteRepository};
 
 fn setup_conn() -> Connection {
     let conn = Connection::open_in_memory().unwrap();
-    conn.execute(
-        "CREATE TABLE notes (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            title TEXT NOT NULL,
-            file_path TEXT UNIQUE NOT NULL,
-            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
-            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
-        )",
-        [],
-    ).unwrap();
+    run_migrations(&conn).unwrap();
     conn
 }
 
-// fn cleanup_notes_folder() {
-//     let notes_folder = Path::new("notes");
-//     if notes_folder.exists() {
-//         for entry in fs::read_dir(notes_folder).unwrap() {
-//             let entry = entry.unwrap();
-//             let path = entry.path();
-//             if path.is_file() {
-//                 fs::remove_file(path).ok();
-//             }
-//         }
-//     }
-// }
+fn in_memory_service() -> NoteServiceImpl<InMemoryNoteRepository, InMemoryNoteRevisionRepository, InMemoryLinkRepository, InMemoryFileSystem> {
+    NoteServiceImpl {
+        repository: InMemoryNoteRepository::new(),
+        revision_repository: InMemoryNoteRevisionRepository::new(),
+        revision_policy: RevisionPolicy::default(),
+        link_repository: InMemoryLinkRepository::new(),
+        fs: InMemoryFileSystem::new(),
+        events: EventBus::new(),
+        journal: Journal::default(),
+        vault_dir: "notes".into(),
+        trash_dir: "notes/.trash".into(),
+        note_tasks: (),
+    }
+}
+
+// The markdown body of a note file, without its front-matter
+fn body(fs: &InMemoryFileSystem, path: impl AsRef<Path>) -> Option<String> {
+    fs.contents(path).map(|content| FrontMatter::body(&content).to_string())
+}
 
 #[test]
 fn test_create_and_fetch_note() {
-    let conn = setup_conn();
-    let service = NoteServiceImpl { repository: SqliteNoteRepository };
-    let folder = "notes".to_string();
-    let file_name = "test_note.md".to_string();
-    let file_path = format!("{}/{}", folder, file_name);
+    let conn = Connection::open_in_memory().unwrap();
+    let service = in_memory_service();
+    let file_path = "notes/test_note.md".to_string();
     let payload = NotePayload {
         title: "Test Note".to_string(),
         file_path: file_path.clone(),
     };
 
-    // Create note using the service (which creates the file)
-    service.create_note(&conn, payload.clone()).unwrap();
-
-    // Check if file was created
-    assert!(Path::new(&file_path).exists());
+    // Create note using the service (which creates the folder and an empty file)
+    service.create_note(&conn, payload).unwrap();
+    assert!(service.fs.exists(Path::new("notes")));
+    assert_eq!(body(&service.fs, &file_path).as_deref(), Some(""));
 
-    // Fetch notes
     let notes = service.fetch_notes(&conn).unwrap();
     assert_eq!(notes.len(), 1);
     assert_eq!(notes[0].title, "Test Note");
     assert_eq!(notes[0].file_path, file_path);
+}
+
+#[test]
+fn test_create_note_keeps_existing_file_contents() {
+    let conn = Connection::open_in_memory().unwrap();
+    let service = in_memory_service();
+    service.fs.create_dir_all(Path::new("notes")).unwrap();
+    service.fs.write(Path::new("notes/existing.md"), b"# Already here").unwrap();
+
+    service.create_note(&conn, NotePayload {
+        title: "Existing".to_string(),
+        file_path: "notes/existing.md".to_string(),
+    }).unwrap();
+
+    assert_eq!(body(&service.fs, "notes/existing.md").as_deref(), Some("# Already here"));
+}
+
+#[test]
+fn test_create_note_rejects_duplicate_path() {
+    let conn = Connection::open_in_memory().unwrap();
+    let service = in_memory_service();
+    let payload = NotePayload {
+        title: "Note".to_string(),
+        file_path: "notes/note.md".to_string(),
+    };
+    service.create_note(&conn, payload.clone()).unwrap();
+
+    let err = service.create_note(&conn, payload).unwrap_err();
+    assert!(err.contains("UNIQUE constraint failed"));
+    assert_eq!(service.fetch_notes(&conn).unwrap().len(), 1);
+}
+
+#[test]
+fn test_note_paths_stay_inside_the_vault() {
+    let conn = Connection::open_in_memory().unwrap();
+    let service = in_memory_service();
+    let create = |file_path: &str| service.create_note(&conn, NotePayload { title: "Note".to_string(), file_path: file_path.to_string() });
+
+    // The same file spelled differently is stored once, in one form
+    create("./notes/sub/../note.md").unwrap();
+    assert_eq!(service.fetch_notes(&conn).unwrap()[0].file_path, "notes/note.md");
+    let absolute = std::env::current_dir().unwrap().join("notes/note.md");
+    assert!(create(absolute.to_str().unwrap()).unwrap_err().contains("UNIQUE constraint failed"));
+
+    assert_eq!(create("notes/../secrets.md").unwrap_err(), "notes/../secrets.md is outside the notes folder");
+    assert!(create("/etc/note.md").unwrap_err().contains("outside the notes folder"));
+    assert!(create("notes/.").unwrap_err().contains("not a file"));
+    assert!(!service.fs.exists(Path::new("secrets.md")));
+
+    // Moving a note out of the vault is rejected too
+    let note = service.fetch_notes(&conn).unwrap().remove(0);
+    let moved = Note { file_path: "../note.md".to_string(), ..note };
+    assert!(service.update_note(&conn, moved, "body").unwrap_err().contains("outside the notes folder"));
+    assert_eq!(service.fetch_notes(&conn).unwrap()[0].file_path, "notes/note.md");
+}
+
+#[test]
+fn test_update_note_writes_content() {
+    let conn = Connection::open_in_memory().unwrap();
+    let service = in_memory_service();
+    service.create_note(&conn, NotePayload {
+        title: "Draft".to_string(),
+        file_path: "notes/draft.md".to_string(),
+    }).unwrap();
+
+    let mut note = service.fetch_notes(&conn).unwrap().remove(0);
+    note.title = "Final".to_string();
+    service.update_note(&conn, note, "# Final\nDone").unwrap();
+
+    let notes = service.fetch_notes(&conn).unwrap();
+    assert_eq!(notes[0].title, "Final");
+    assert_eq!(body(&service.fs, "notes/draft.md").as_deref(), Some("# Final\nDone"));
+}
+
+#[test]
+fn test_delete_note_removes_file() {
+    let conn = Connection::open_in_memory().unwrap();
+    let service = in_memory_service();
+    service.create_note(&conn, NotePayload {
+        title: "Gone".to_string(),
+        file_path: "notes/gone.md".to_string(),
+    }).unwrap();
+    let id = service.fetch_notes(&conn).unwrap()[0].id;
+
+    service.delete_note(&conn, id).unwrap();
+
+    assert!(service.fetch_notes(&conn).unwrap().is_empty());
+    assert!(!service.fs.exists(Path::new("notes/gone.md")));
+}
+
+#[test]
+fn test_delete_note_with_missing_file() {
+    let conn = Connection::open_in_memory().unwrap();
+    let service = in_memory_service();
+    service.create_note(&conn, NotePayload {
+        title: "Orphan".to_string(),
+        file_path: "notes/orphan.md".to_string(),
+    }).unwrap();
+    service.fs.remove_file(Path::new("notes/orphan.md")).unwrap();
 
+    let id = service.fetch_notes(&conn).unwrap()[0].id;
+    service.delete_note(&conn, id).unwrap();
+    assert!(service.fetch_notes(&conn).unwrap().is_empty());
 }
 
 #[test]
@@ -82,6 +185,4 @@ fn test_update_and_delete_note() {
     repo.delete(&conn, notes[0].id).unwrap();
     let notes = repo.fetch_all(&conn).unwrap();
     assert!(notes.is_empty());
-
-    // Clean up the notes folder
-}
\ No newline at end of file
+}
@@ -0,0 +1,343 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use rusqlite::Connection;
+use nocture_tauri_lib::events::{DomainEvent, EventBus};
+use nocture_tauri_lib::journal::Journal;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::calendar_event::{CalendarEventPayload, CalendarItemKind};
+use nocture_tauri_lib::model::planner::{PlannerConfig, ScheduleBlock};
+use nocture_tauri_lib::model::recurrence::{Frequency, Recurrence};
+use nocture_tauri_lib::model::task::{Category, Priority, Task};
+use nocture_tauri_lib::model::time_entry::{TimeEntry, TimeEntrySource};
+use nocture_tauri_lib::repository::calendar_event::SqliteCalendarEventRepository;
+use nocture_tauri_lib::repository::schedule_block::{ScheduleBlockRepository, SqliteScheduleBlockRepository};
+use nocture_tauri_lib::repository::settings::SqliteSettingsRepository;
+use nocture_tauri_lib::repository::task::{SqliteTaskRepository, TaskRepository};
+use nocture_tauri_lib::repository::task_history::SqliteTaskHistoryRepository;
+use nocture_tauri_lib::repository::time_entry::{SqliteTimeEntryRepository, TimeEntryRepository};
+use nocture_tauri_lib::services::calendar::{CalendarService, CalendarServiceImpl};
+use nocture_tauri_lib::services::planner::{PlannerService, PlannerServiceImpl};
+use nocture_tauri_lib::services::task::{TaskService, TaskServiceImpl};
+
+type Planner = PlannerServiceImpl<
+    SqliteTaskRepository,
+    SqliteTimeEntryRepository,
+    SqliteScheduleBlockRepository,
+    SqliteCalendarEventRepository,
+    SqliteSettingsRepository,
+>;
+
+fn setup() -> (Connection, Planner) {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    let planner = PlannerServiceImpl {
+        task_repository: SqliteTaskRepository,
+        time_entry_repository: SqliteTimeEntryRepository,
+        repository: SqliteScheduleBlockRepository,
+        event_repository: SqliteCalendarEventRepository,
+        settings: SqliteSettingsRepository,
+    };
+    (conn, planner)
+}
+
+// 19 Oktober 2026 hari Senin
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+}
+
+fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+    date(day).and_hms_opt(hour, minute, 0).unwrap()
+}
+
+fn create_task(conn: &Connection, title: &str, priority: Priority, estimate: Option<i32>, deadline: Option<NaiveDateTime>) -> i32 {
+    SqliteTaskRepository.create(conn, &Task {
+        id: 0,
+        title: title.to_string(),
+        category: Category::Study,
+        priority,
+        deadline,
+        completed: false,
+        created_at: at(19, 8, 0),
+        updated_at: at(19, 8, 0),
+        estimated_minutes: estimate,
+    }).unwrap()
+}
+
+fn complete(conn: &Connection, id: i32) {
+    let task = SqliteTaskRepository.fetch_by_id(conn, id).unwrap().unwrap();
+    SqliteTaskRepository.update(conn, &Task { completed: true, ..task }).unwrap();
+}
+
+fn block(task_id: i32, title: &str, start: NaiveDateTime, end: NaiveDateTime) -> ScheduleBlock {
+    ScheduleBlock { id: 0, task_id, title: title.to_string(), start, end }
+}
+
+fn spans(blocks: &[ScheduleBlock]) -> Vec<(i32, NaiveDateTime, NaiveDateTime)> {
+    blocks.iter().map(|b| (b.task_id, b.start, b.end)).collect()
+}
+
+fn calendar() -> CalendarServiceImpl<SqliteCalendarEventRepository, SqliteTaskRepository> {
+    CalendarServiceImpl { repository: SqliteCalendarEventRepository, task_repository: SqliteTaskRepository }
+}
+
+fn create_event(conn: &Connection, title: &str, start: &str, end: &str, all_day: bool, recurrence: Option<Recurrence>) {
+    calendar().create_event(conn, CalendarEventPayload {
+        title: title.to_string(),
+        start: start.to_string(),
+        end: end.to_string(),
+        all_day,
+        location: None,
+        recurrence,
+    }).unwrap();
+}
+
+// Essay (penting, deadline besok), Reading (tanpa perkiraan), Quiz prep (cepat), Thesis (terlalu besar),
+// dan kuliah pukul 10.00-12.00
+fn fixture(conn: &Connection) -> [i32; 4] {
+    let essay = create_task(conn, "Essay", Priority::High, Some(120), Some(at(20, 0, 0)));
+    let quiz = create_task(conn, "Quiz prep", Priority::Low, Some(30), None);
+    let reading = create_task(conn, "Reading", Priority::Medium, None, None);
+    let thesis = create_task(conn, "Thesis", Priority::Low, Some(600), None);
+    create_event(conn, "Lecture", "2026-10-19T10:00", "2026-10-19T12:00", false, None);
+    [essay, reading, quiz, thesis]
+}
+
+#[test]
+fn test_propose_fills_working_hours_around_calendar() {
+    let (conn, planner) = setup();
+    let [essay, reading, quiz, thesis] = fixture(&conn);
+
+    let plan = planner.propose(&conn, date(19), 1, at(19, 8, 0)).unwrap();
+    // Urut skor: Essay dipecah di sekitar kuliah, lalu Reading (60 menit default) dan Quiz prep,
+    // dengan jeda 10 menit setelah setiap blok
+    assert_eq!(spans(&plan.blocks), vec![
+        (essay, at(19, 9, 0), at(19, 10, 0)),
+        (essay, at(19, 12, 0), at(19, 13, 0)),
+        (reading, at(19, 13, 10), at(19, 14, 10)),
+        (quiz, at(19, 14, 20), at(19, 14, 50)),
+    ]);
+    assert_eq!(plan.blocks[2].title, "Reading");
+    assert_eq!(plan.unscheduled, vec![thesis]);
+
+    // Usulan untuk hari yang sedang berjalan dimulai dari sekarang
+    let late = planner.propose(&conn, date(19), 1, at(19, 14, 5)).unwrap();
+    assert_eq!(late.blocks[0].start, at(19, 14, 5));
+
+    let saved = planner.save_blocks(&conn, plan.blocks).unwrap();
+    assert!(saved.iter().all(|b| b.id > 0));
+    assert_eq!(planner.fetch_schedule(&conn, date(19), date(19)).unwrap().len(), 4);
+    assert!(planner.fetch_schedule(&conn, date(20), date(20)).unwrap().is_empty());
+
+    // Task yang sudah dijadwalkan tidak diusulkan lagi; Thesis muat dalam tiga hari
+    let next = planner.propose(&conn, date(19), 3, at(19, 8, 0)).unwrap();
+    assert!(next.blocks.iter().all(|b| b.task_id == thesis));
+    assert_eq!(next.blocks.iter().map(|b| (b.end - b.start).num_minutes()).sum::<i64>(), 600);
+    assert!(next.unscheduled.is_empty());
+}
+
+#[test]
+fn test_save_blocks_validation() {
+    let (conn, planner) = setup();
+    let [essay, ..] = fixture(&conn);
+
+    let err = |b: ScheduleBlock| planner.save_blocks(&conn, vec![b]).unwrap_err();
+    assert_eq!(err(block(essay, "Essay", at(19, 11, 0), at(19, 13, 0))), "Block 'Essay' overlaps 'Lecture'");
+    assert!(err(block(essay, "Backwards", at(19, 15, 0), at(19, 14, 0))).contains("must end after"));
+    assert_eq!(err(block(essay, "  ", at(19, 15, 0), at(19, 16, 0))), "Block title is empty");
+    assert_eq!(err(block(99, "Ghost", at(19, 15, 0), at(19, 16, 0))), "Task 99 not found");
+    // Blok dalam satu permintaan juga tidak boleh bertumpuk, dan tidak ada yang tersimpan
+    assert!(planner.save_blocks(&conn, vec![
+        block(essay, "Essay", at(19, 13, 0), at(19, 14, 0)),
+        block(essay, "Essay", at(19, 13, 30), at(19, 14, 30)),
+    ]).is_err());
+    assert!(planner.fetch_schedule(&conn, date(19), date(19)).unwrap().is_empty());
+    assert!(planner.fetch_schedule(&conn, date(20), date(19)).is_err());
+    assert!(planner.propose(&conn, date(19), 0, at(19, 8, 0)).is_err());
+    assert!(planner.propose(&conn, date(19), 32, at(19, 8, 0)).is_err());
+
+    // Blok boleh menempel tepat sebelum dan sesudah kuliah
+    let saved = planner.save_blocks(&conn, vec![
+        block(essay, "Essay", at(19, 9, 0), at(19, 10, 0)),
+        block(essay, "Essay", at(19, 12, 0), at(19, 13, 0)),
+    ]).unwrap();
+    assert_eq!(planner.fetch_schedule(&conn, date(19), date(19)).unwrap(), saved);
+
+    // Blok task yang sudah selesai atau di trash tidak menghalangi blok baru
+    let quiz = create_task(&conn, "Quiz prep", Priority::Low, Some(30), None);
+    let reading = create_task(&conn, "Reading", Priority::Medium, None, None);
+    let old = planner.save_blocks(&conn, vec![
+        block(quiz, "Quiz prep", at(19, 14, 0), at(19, 15, 0)),
+        block(reading, "Reading", at(19, 15, 0), at(19, 16, 0)),
+    ]).unwrap();
+    complete(&conn, quiz);
+    SqliteTaskRepository.soft_delete(&conn, reading, at(19, 8, 0)).unwrap();
+    let saved = [saved, planner.save_blocks(&conn, vec![block(essay, "Essay", at(19, 14, 30), at(19, 15, 30))]).unwrap(), old].concat();
+    for block in saved {
+        planner.delete_block(&conn, block.id).unwrap();
+    }
+    assert!(planner.fetch_schedule(&conn, date(19), date(19)).unwrap().is_empty());
+}
+
+#[test]
+fn test_reflow_after_early_and_late_completion() {
+    let (conn, planner) = setup();
+    let [essay, reading, quiz, _] = fixture(&conn);
+    let plan = planner.propose(&conn, date(19), 1, at(19, 8, 0)).unwrap();
+    planner.save_blocks(&conn, plan.blocks).unwrap();
+
+    // Reading selesai lebih cepat: bloknya dilepas dan Quiz prep maju. Blok Essay yang sedang
+    // berjalan tidak disentuh.
+    complete(&conn, reading);
+    let result = planner.reflow(&conn, at(19, 9, 30)).unwrap();
+    assert_eq!(spans(&result.blocks), vec![
+        (essay, at(19, 12, 0), at(19, 13, 0)),
+        (quiz, at(19, 13, 10), at(19, 13, 40)),
+    ]);
+    let schedule = planner.fetch_schedule(&conn, date(19), date(19)).unwrap();
+    assert_eq!(spans(&schedule), vec![
+        (essay, at(19, 9, 0), at(19, 10, 0)),
+        (essay, at(19, 12, 0), at(19, 13, 0)),
+        (quiz, at(19, 13, 10), at(19, 13, 40)),
+    ]);
+
+    // Semua blok Essay sudah lewat tapi belum selesai: sisa perkiraannya (120 - 90 menit yang
+    // dicatat) dijadwalkan lagi. Quiz prep sudah selesai jadi tidak ikut.
+    complete(&conn, quiz);
+    SqliteTimeEntryRepository.create(&conn, &TimeEntry {
+        id: 0,
+        task_id: essay,
+        source: TimeEntrySource::Manual,
+        started_at: at(19, 9, 0),
+        ended_at: Some(at(19, 10, 30)),
+        minutes: 90,
+        note: None,
+    }).unwrap();
+    let result = planner.reflow(&conn, at(19, 16, 45)).unwrap();
+    // 15 menit tersisa hari ini cukup untuk blok terpendek, sisanya besok pagi
+    assert_eq!(spans(&result.blocks), vec![
+        (essay, at(19, 16, 45), at(19, 17, 0)),
+        (essay, at(20, 9, 0), at(20, 9, 15)),
+    ]);
+
+    // Tanpa blok tertunda dan tanpa task terlambat, reflow tidak mengubah apa pun
+    complete(&conn, essay);
+    assert_eq!(planner.reflow(&conn, at(20, 12, 0)).unwrap().blocks, vec![]);
+    assert_eq!(planner.fetch_schedule(&conn, date(19), date(20)).unwrap().len(), 5);
+}
+
+#[test]
+fn test_completing_a_task_reflows_the_schedule() {
+    let (conn, planner) = setup();
+    let events = EventBus::new();
+    let mut received = events.subscribe();
+    let tasks = TaskServiceImpl {
+        repository: SqliteTaskRepository,
+        history_repository: SqliteTaskHistoryRepository,
+        events,
+        journal: Journal::default(),
+        checkboxes: (),
+        planner,
+    };
+    // Jadwal jauh di depan supaya reflow dengan jam sekarang tidak menyentuh blok yang sudah lewat
+    let day = |hour: u32| NaiveDate::from_ymd_opt(2099, 1, 5).unwrap().and_hms_opt(hour, 0, 0).unwrap();
+    let essay = create_task(&conn, "Essay", Priority::High, Some(60), None);
+    let quiz = create_task(&conn, "Quiz prep", Priority::Low, Some(60), None);
+    tasks.planner.save_blocks(&conn, vec![
+        block(essay, "Essay", day(9), day(10)),
+        block(quiz, "Quiz prep", day(10), day(11)),
+    ]).unwrap();
+
+    let task = SqliteTaskRepository.fetch_by_id(&conn, essay).unwrap().unwrap();
+    let plan = tasks.update_task(&conn, Task { completed: true, ..task.clone() }).unwrap().unwrap();
+    assert_eq!(plan.blocks.iter().map(|b| b.task_id).collect::<Vec<_>>(), vec![quiz]);
+    assert!(plan.unscheduled.is_empty());
+    let schedule = tasks.planner.repository.fetch_all(&conn).unwrap();
+    assert_eq!(spans(&schedule), spans(&plan.blocks));
+    assert!(std::iter::from_fn(|| received.try_recv().ok())
+        .any(|event| matches!(event, DomainEvent::ScheduleReflowed(result) if result == plan)));
+
+    // Task yang sudah selesai atau dibuka lagi tidak memicu reflow
+    assert_eq!(tasks.update_task(&conn, Task { completed: true, ..task.clone() }).unwrap(), None);
+    assert_eq!(tasks.update_task(&conn, task).unwrap(), None);
+}
+
+#[test]
+fn test_planner_config() {
+    let (conn, planner) = setup();
+    assert_eq!(planner.load_config(&conn).unwrap(), PlannerConfig::default());
+    let time = |h: u32| NaiveTime::from_hms_opt(h, 0, 0).unwrap();
+
+    let invalid = [
+        PlannerConfig { day_start: time(17), day_end: time(9), ..PlannerConfig::default() },
+        PlannerConfig { work_days: vec![], ..PlannerConfig::default() },
+        PlannerConfig { default_task_minutes: 0, ..PlannerConfig::default() },
+        PlannerConfig { max_block_minutes: 10, min_block_minutes: 15, ..PlannerConfig::default() },
+    ];
+    for config in &invalid {
+        assert!(planner.save_config(&conn, config).is_err(), "{:?}", config);
+    }
+
+    // Akhir pekan tidak dijadwalkan sampai ditambahkan ke hari kerja
+    let task = create_task(&conn, "Review", Priority::Medium, Some(45), None);
+    let weekend = planner.propose(&conn, date(24), 2, at(19, 8, 0)).unwrap();
+    assert!(weekend.blocks.is_empty());
+    assert_eq!(weekend.unscheduled, vec![task]);
+
+    let config = PlannerConfig { day_start: time(7), work_days: vec![Weekday::Sun], ..PlannerConfig::default() };
+    planner.save_config(&conn, &config).unwrap();
+    assert_eq!(planner.load_config(&conn).unwrap(), config);
+    let weekend = planner.propose(&conn, date(24), 2, at(19, 8, 0)).unwrap();
+    assert_eq!(spans(&weekend.blocks), vec![(task, at(25, 7, 0), at(25, 7, 45))]);
+}
+
+#[test]
+fn test_calendar_events_are_busy() {
+    let (conn, planner) = setup();
+    // Rapat mingguan yang dimulai minggu lalu, dan hari ujian seharian yang tidak menutup jam kerja
+    create_event(&conn, "Standup", "2026-10-12T09:00", "2026-10-12T10:30", false, Some(Recurrence::new(Frequency::Weekly, 1)));
+    create_event(&conn, "Exam day", "2026-10-19", "2026-10-19", true, None);
+    let task = create_task(&conn, "Essay", Priority::High, Some(60), None);
+
+    let plan = planner.propose(&conn, date(19), 1, at(19, 8, 0)).unwrap();
+    assert_eq!(spans(&plan.blocks), vec![(task, at(19, 10, 30), at(19, 11, 30))]);
+    let err = planner.save_blocks(&conn, vec![block(task, "Essay", at(19, 10, 0), at(19, 11, 0))]).unwrap_err();
+    assert_eq!(err, "Block 'Essay' overlaps 'Standup'");
+    planner.save_blocks(&conn, plan.blocks).unwrap();
+}
+
+#[test]
+fn test_migration_moves_fixed_blocks_to_calendar() {
+    // Database versi 15: blok tanpa task masih disimpan di schedule_blocks
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    let task = create_task(&conn, "Essay", Priority::High, Some(60), None);
+    conn.execute_batch(
+        "CREATE TABLE schedule_blocks_old (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id INTEGER REFERENCES tasks(id) ON DELETE CASCADE,
+            title TEXT NOT NULL,
+            start_at TEXT NOT NULL,
+            end_at TEXT NOT NULL
+        );
+        DROP TABLE schedule_blocks;
+        ALTER TABLE schedule_blocks_old RENAME TO schedule_blocks;
+        PRAGMA user_version = 15;",
+    ).unwrap();
+    let insert = |task_id: Option<i32>, title: &str, start: NaiveDateTime, end: NaiveDateTime| {
+        conn.execute(
+            "INSERT INTO schedule_blocks (task_id, title, start_at, end_at) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![task_id, title, start, end],
+        ).unwrap();
+    };
+    insert(None, "Lecture", at(19, 10, 0), at(19, 12, 0));
+    insert(Some(task), "Essay", at(19, 12, 0), at(19, 13, 0));
+
+    run_migrations(&conn).unwrap();
+    let (_, planner) = setup();
+    assert_eq!(spans(&planner.fetch_schedule(&conn, date(19), date(19)).unwrap()), vec![(task, at(19, 12, 0), at(19, 13, 0))]);
+    // Kuliah sekarang acara kalender; blok baru wajib punya task
+    let items = calendar().calendar_range(&conn, date(19), date(19)).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!((items[0].kind, items[0].title.as_str()), (CalendarItemKind::Event, "Lecture"));
+    assert_eq!((items[0].start, items[0].end, items[0].all_day), (at(19, 10, 0), at(19, 12, 0), false));
+    assert!(conn.execute("INSERT INTO schedule_blocks (task_id, title, start_at, end_at) VALUES (NULL, 'x', '', '')", []).is_err());
+}
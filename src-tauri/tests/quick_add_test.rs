@@ -0,0 +1,147 @@
+use chrono::{NaiveDate, NaiveDateTime, Weekday};
+use nocture_tauri_lib::model::quick_add::parse_quick_add;
+use nocture_tauri_lib::model::recurrence::{Frequency, Recurrence};
+use nocture_tauri_lib::model::task::{Category, Priority};
+
+// Rabu, 21 Oktober 2026 pukul 14.30
+fn now() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2026, 10, 21).unwrap().and_hms_opt(14, 30, 0).unwrap()
+}
+
+fn weekly(interval: u32, weekdays: &[Weekday]) -> Recurrence {
+    Recurrence { weekdays: weekdays.to_vec(), ..Recurrence::new(Frequency::Weekly, interval) }
+}
+
+#[test]
+fn test_titles_dates_and_times() {
+    // (input, judul, deadline)
+    let cases: &[(&str, &str, Option<&str>)] = &[
+        ("Call mom", "Call mom", None),
+        ("Read 5 chapters", "Read 5 chapters", None),
+        ("Pay rent today", "Pay rent", Some("2026-10-21")),
+        ("Bayar listrik hari ini", "Bayar listrik", Some("2026-10-21")),
+        ("Submit essay fri 5pm", "Submit essay", Some("2026-10-23T17:00")),
+        ("Submit essay friday at 5:30 pm", "Submit essay", Some("2026-10-23T17:30")),
+        ("Kumpul tugas besok jam 9 pagi", "Kumpul tugas", Some("2026-10-22T09:00")),
+        ("Team sync tomorrow at 10:30am", "Team sync", Some("2026-10-22T10:30")),
+        ("Call dentist tmr, 3pm", "Call dentist", Some("2026-10-22T15:00")),
+        ("Rapat lusa pukul 13.00", "Rapat", Some("2026-10-23T13:00")),
+        ("Demo day after tomorrow", "Demo", Some("2026-10-23")),
+        // Hari yang sama dengan hari ini berarti hari ini
+        ("Quiz wed", "Quiz", Some("2026-10-21")),
+        ("Belanja sabtu ini", "Belanja", Some("2026-10-24")),
+        ("Ibadah minggu", "Ibadah", Some("2026-10-25")),
+        ("Review next mon", "Review", Some("2026-10-26")),
+        ("Presentasi jumat depan", "Presentasi", Some("2026-10-30")),
+        ("Present next friday 9am", "Present", Some("2026-10-30T09:00")),
+        ("Start project next week", "Start project", Some("2026-10-26")),
+        ("Mulai magang minggu depan", "Mulai magang", Some("2026-10-26")),
+        ("Daftar ulang bulan depan", "Daftar ulang", Some("2026-11-01")),
+        ("Renew passport next year", "Renew passport", Some("2027-01-01")),
+        ("Lab report in 3 days", "Lab report", Some("2026-10-24")),
+        ("Plan trip in a week", "Plan trip", Some("2026-10-28")),
+        ("Baca jurnal dalam 2 minggu", "Baca jurnal", Some("2026-11-04")),
+        ("Bayar UKT 2 bulan lagi", "Bayar UKT", Some("2026-12-21")),
+        ("Exam 2026-11-03", "Exam", Some("2026-11-03")),
+        ("UTS 3/11", "UTS", Some("2026-11-03")),
+        // Tanggal yang sudah lewat tahun ini jatuh di tahun depan
+        ("UAS 15/1", "UAS", Some("2027-01-15")),
+        ("Deadline 5/12/26", "Deadline", Some("2026-12-05")),
+        ("Thesis defense nov 3rd", "Thesis defense", Some("2026-11-03")),
+        ("Sidang 3 November 2027", "Sidang", Some("2027-11-03")),
+        ("Wisuda tgl 17 agustus", "Wisuda", Some("2027-08-17")),
+        ("Invalid 31 feb", "Invalid 31 feb", None),
+        ("Dinner tonight", "Dinner", Some("2026-10-21T20:00")),
+        ("Nonton nanti malam jam 9 malam", "Nonton", Some("2026-10-21T21:00")),
+        ("Makan siang jam 1 siang", "Makan siang", Some("2026-10-22T13:00")),
+        ("Jemput adik jam 4 sore", "Jemput adik", Some("2026-10-21T16:00")),
+        ("Print slides at noon tomorrow", "Print slides", Some("2026-10-22T12:00")),
+        ("Meeting 17.00", "Meeting", Some("2026-10-21T17:00")),
+        // Jam yang sudah lewat hari ini berarti besok
+        ("Standup 9am", "Standup", Some("2026-10-22T09:00")),
+        ("Chapter 3 summary by fri", "Chapter 3 summary", Some("2026-10-23")),
+        // Hanya tanggal pertama yang dipakai, sisanya tetap di judul
+        ("Move meeting on monday to tue", "Move meeting to tue", Some("2026-10-26")),
+        // Nama hari di tengah judul bukan tanggal, kecuali bersebelahan dengan jam atau penghubung
+        ("Read Sun Tzu", "Read Sun Tzu", None),
+        ("Get wed dress fitted", "Get wed dress fitted", None),
+        ("Read Sun Tzu sat", "Read Sun Tzu", Some("2026-10-24")),
+        ("Laundry sat #personal", "Laundry", Some("2026-10-24")),
+        ("Fri 5pm submit essay", "submit essay", Some("2026-10-23T17:00")),
+        ("Gym 7am sat with Budi", "Gym with Budi", Some("2026-10-24T07:00")),
+        // Jam yang sudah lewat pada hari ini berarti minggu depan
+        ("Quiz wed 9am", "Quiz", Some("2026-10-28T09:00")),
+        ("Gym 7am wed", "Gym", Some("2026-10-28T07:00")),
+        ("Quiz wed 5pm", "Quiz", Some("2026-10-21T17:00")),
+        ("Meet at the library", "Meet at the library", None),
+    ];
+    for (input, title, deadline) in cases {
+        let preview = parse_quick_add(input, now()).unwrap_or_else(|e| panic!("{}: {}", input, e));
+        assert_eq!(preview.payload.title, *title, "{}", input);
+        assert_eq!(preview.payload.deadline.as_deref(), *deadline, "{}", input);
+    }
+}
+
+#[test]
+fn test_priority_category_and_tags() {
+    // (input, judul, prioritas, kategori, tag)
+    let cases: &[(&str, &str, Priority, Category, &[&str])] = &[
+        ("Submit essay !high #study", "Submit essay", Priority::High, Category::Study, &[]),
+        ("Call mom", "Call mom", Priority::Medium, Category::Personal, &[]),
+        ("Fix bug !!! #work", "Fix bug", Priority::High, Category::Work, &[]),
+        ("Fix typo !!", "Fix typo", Priority::Medium, Category::Personal, &[]),
+        ("Rapih-rapih kamar !rendah #pribadi", "Rapih-rapih kamar", Priority::Low, Category::Personal, &[]),
+        ("Kumpul tugas !penting #kuliah", "Kumpul tugas", Priority::High, Category::Study, &[]),
+        ("Laporan !sedang #kantor", "Laporan", Priority::Medium, Category::Work, &[]),
+        ("Read paper #ml #Study #reading", "Read paper", Priority::Medium, Category::Study, &["ml", "reading"]),
+        // Kategori kedua menjadi tag biasa, tag ganda disimpan sekali
+        ("Ngoding #kerja #belajar #rust #Rust", "Ngoding", Priority::Medium, Category::Work, &["belajar", "rust"]),
+        // Penanda yang tidak dikenal atau yang kedua tetap di judul
+        ("Ping !important", "Ping !important", Priority::Medium, Category::Personal, &[]),
+        ("Deploy !low !high", "Deploy !high", Priority::Low, Category::Personal, &[]),
+        ("Issue # 42", "Issue # 42", Priority::Medium, Category::Personal, &[]),
+    ];
+    for (input, title, priority, category, tags) in cases {
+        let preview = parse_quick_add(input, now()).unwrap();
+        assert_eq!(preview.payload.title, *title, "{}", input);
+        assert_eq!(preview.payload.priority, *priority, "{}", input);
+        assert_eq!(preview.payload.category, *category, "{}", input);
+        assert_eq!(preview.tags, *tags, "{}", input);
+    }
+}
+
+#[test]
+fn test_recurrence_phrases() {
+    use Weekday::*;
+    // (input, judul, pengulangan, deadline pertama)
+    let cases: &[(&str, &str, Recurrence, &str)] = &[
+        ("Water plants every day", "Water plants", Recurrence::new(Frequency::Daily, 1), "2026-10-21"),
+        ("Review flashcards every other day", "Review flashcards", Recurrence::new(Frequency::Daily, 2), "2026-10-21"),
+        ("Standup daily 9am", "Standup", Recurrence::new(Frequency::Daily, 1), "2026-10-22T09:00"),
+        ("Minum vitamin tiap hari jam 7 malam", "Minum vitamin", Recurrence::new(Frequency::Daily, 1), "2026-10-21T19:00"),
+        ("Kirim laporan setiap minggu", "Kirim laporan", Recurrence::new(Frequency::Weekly, 1), "2026-10-21"),
+        ("Backup every 2 weeks", "Backup", Recurrence::new(Frequency::Weekly, 2), "2026-10-21"),
+        ("Bayar kos tiap bulan", "Bayar kos", Recurrence::new(Frequency::Monthly, 1), "2026-10-21"),
+        ("Pay insurance yearly", "Pay insurance", Recurrence::new(Frequency::Yearly, 1), "2026-10-21"),
+        ("Ulang tahun mama 12 mei setiap tahun", "Ulang tahun mama", Recurrence::new(Frequency::Yearly, 1), "2027-05-12"),
+        ("Gym every mon and thu 7am", "Gym", weekly(1, &[Mon, Thu]), "2026-10-22T07:00"),
+        ("Kuliah pagi setiap senin, rabu", "Kuliah pagi", weekly(1, &[Mon, Wed]), "2026-10-21"),
+        ("Piket tiap jumat", "Piket", weekly(1, &[Fri]), "2026-10-23"),
+        ("Absen tiap hari kerja jam 8", "Absen", weekly(1, &[Mon, Tue, Wed, Thu, Fri]), "2026-10-22T08:00"),
+        ("Timesheet every weekday 5pm", "Timesheet", weekly(1, &[Mon, Tue, Wed, Thu, Fri]), "2026-10-21T17:00"),
+    ];
+    for (input, title, recurrence, deadline) in cases {
+        let preview = parse_quick_add(input, now()).unwrap();
+        assert_eq!(preview.payload.title, *title, "{}", input);
+        assert_eq!(preview.recurrence.as_ref(), Some(recurrence), "{}", input);
+        assert_eq!(preview.payload.deadline.as_deref(), Some(*deadline), "{}", input);
+    }
+    assert_eq!(parse_quick_add("Every little thing", now()).unwrap().recurrence, None);
+}
+
+#[test]
+fn test_empty_title_is_rejected() {
+    for input in ["", "   ", "tomorrow 5pm !high #study", "setiap senin"] {
+        assert_eq!(parse_quick_add(input, now()).unwrap_err(), "Task title is empty", "{}", input);
+    }
+}
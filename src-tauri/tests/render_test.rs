@@ -0,0 +1,111 @@
+use rusqlite::Connection;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use nocture_tauri_lib::fs::{FileSystem, InMemoryFileSystem};
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::note::NotePayload;
+use nocture_tauri_lib::repository::note::{NoteRepository, SqliteNoteRepository};
+use nocture_tauri_lib::services::render::{render_markdown, RenderCache, RenderService, RenderServiceImpl};
+
+fn setup() -> (Connection, InMemoryFileSystem, RenderServiceImpl<SqliteNoteRepository, InMemoryFileSystem>) {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    let fs = InMemoryFileSystem::new();
+    fs.create_dir_all(Path::new("vault/course")).unwrap();
+    let service = RenderServiceImpl {
+        repository: SqliteNoteRepository,
+        fs: fs.clone(),
+        vault_dir: "vault".into(),
+        cache: RenderCache::default(),
+    };
+    (conn, fs, service)
+}
+
+#[test]
+fn test_renders_gfm_extensions() {
+    let html = render_markdown(
+        "| Left | Center |\n|:-----|:------:|\n| a | b |\n\n\
+         - [x] done\n- [ ] todo\n\n\
+         ~~old~~ text[^1]\n\n\
+         [^1]: A footnote.\n\n\
+         ```rust\nfn main() {}\n```\n",
+        Path::new(""),
+    );
+    assert!(html.contains("<th style=\"text-align: left\">Left</th>"), "{}", html);
+    assert!(html.contains("<td style=\"text-align: center\">b</td>"), "{}", html);
+    assert!(html.contains("<input disabled=\"\" type=\"checkbox\" checked=\"\""), "{}", html);
+    assert!(html.contains("<del>old</del>"), "{}", html);
+    assert!(html.contains("<a href=\"#note-1\""), "{}", html);
+    assert!(html.contains("<div class=\"footnote-definition\" id=\"note-1\">"), "{}", html);
+    assert!(html.contains("<code class=\"language-rust\">"), "{}", html);
+}
+
+#[test]
+fn test_embedded_html_is_sanitized() {
+    let html = render_markdown(
+        "<script>alert(1)</script>\n\n\
+         <img src=\"x.png\" onerror=\"alert(1)\">\n\n\
+         [click](javascript:alert(1)) <input type=\"text\" value=\"x\">\n\n\
+         <p style=\"position: fixed\">styled</p>",
+        Path::new(""),
+    );
+    for forbidden in ["<script", "onerror", "javascript:", "type=\"text\"", "position"] {
+        assert!(!html.contains(forbidden), "{} in {}", forbidden, html);
+    }
+    assert!(html.contains("styled"));
+}
+
+#[test]
+fn test_links_are_resolved_against_the_vault() {
+    let html = render_markdown(
+        "![diagram](img/my%20diagram.png) [sibling](../Index.md#top) [root](/assets/logo.svg)\n\
+         [escape](../../secret.txt) [file](attachment:12) [web](https://example.com/a.png)",
+        Path::new("course"),
+    );
+    assert!(html.contains("src=\"vault://localhost/course/img/my%20diagram.png\""), "{}", html);
+    assert!(html.contains("href=\"vault://localhost/Index.md\""), "{}", html);
+    assert!(html.contains("href=\"vault://localhost/assets/logo.svg\""), "{}", html);
+    assert!(!html.contains("secret"), "{}", html);
+    assert!(html.contains("href=\"attachment://localhost/12\""), "{}", html);
+    assert!(html.contains("href=\"https://example.com/a.png\""), "{}", html);
+}
+
+#[test]
+fn test_render_note_is_cached_until_the_file_changes() {
+    let (conn, fs, service) = setup();
+    let id = SqliteNoteRepository.create(&conn, &NotePayload {
+        title: "Week 1".to_string(),
+        file_path: "vault/course/week1.md".to_string(),
+    }).unwrap();
+    fs.write(Path::new("vault/course/week1.md"), b"---\ntitle: Week 1\n---\n# Ownership\n![](moves.png)").unwrap();
+    let modified = SystemTime::now() - Duration::from_secs(60);
+    fs.set_modified("vault/course/week1.md", modified);
+
+    let html = service.render_note(&conn, id).unwrap();
+    assert!(html.starts_with("<h1>Ownership</h1>"), "{}", html);
+    assert!(html.contains("vault://localhost/course/moves.png"), "{}", html);
+
+    // Same modification time: the cached output is used
+    fs.write(Path::new("vault/course/week1.md"), b"# Borrowing").unwrap();
+    fs.set_modified("vault/course/week1.md", modified);
+    assert_eq!(service.render_note(&conn, id).unwrap(), html);
+
+    fs.set_modified("vault/course/week1.md", SystemTime::now());
+    assert_eq!(service.render_note(&conn, id).unwrap(), "<h1>Borrowing</h1>\n");
+    assert!(service.render_note(&conn, id + 1).is_err());
+}
+
+#[test]
+fn test_vault_files_outside_hidden_folders_are_served() {
+    let (_conn, fs, service) = setup();
+    fs.write(Path::new("vault/course/my diagram.png"), b"png").unwrap();
+    fs.create_dir_all(Path::new("vault/.trash")).unwrap();
+    fs.write(Path::new("vault/.trash/old.md"), b"old").unwrap();
+
+    let (mime, data) = service.read_vault_file("/course/my%20diagram.png").unwrap();
+    assert_eq!(mime, "image/png");
+    assert_eq!(data, b"png");
+    for path in ["/.trash/old.md", "/course/../../nocture.db", "/", "/course/missing.png"] {
+        assert!(service.read_vault_file(path).is_err(), "{}", path);
+    }
+}
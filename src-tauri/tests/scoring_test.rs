@@ -0,0 +1,158 @@
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use rusqlite::Connection;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::scoring::ScoringWeights;
+use nocture_tauri_lib::model::task::{Category, Priority, Task};
+use nocture_tauri_lib::repository::settings::SqliteSettingsRepository;
+use nocture_tauri_lib::repository::task::{SqliteTaskRepository, TaskRepository};
+use nocture_tauri_lib::services::scoring::{rank_tasks, score_task, ScoringService, ScoringServiceImpl};
+
+fn now() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2026, 10, 21).unwrap().and_hms_opt(12, 0, 0).unwrap()
+}
+
+// `now()` sebagai waktu sebenarnya, dengan zona waktu lokal UTC
+fn clock() -> DateTime<Utc> {
+    Utc.from_utc_datetime(&now())
+}
+
+fn task(id: i32, priority: Priority, deadline: Option<NaiveDateTime>) -> Task {
+    Task {
+        id,
+        title: format!("Task {}", id),
+        category: Category::Study,
+        priority,
+        deadline,
+        completed: false,
+        created_at: now(),
+        updated_at: now(),
+        estimated_minutes: None,
+    }
+}
+
+fn service() -> ScoringServiceImpl<SqliteTaskRepository, SqliteSettingsRepository> {
+    ScoringServiceImpl { task_repository: SqliteTaskRepository, settings: SqliteSettingsRepository }
+}
+
+#[test]
+fn test_score_components() {
+    let weights = ScoringWeights::default();
+
+    // Tanpa deadline dan perkiraan: hanya prioritas dan effort netral
+    let score = score_task(&task(1, Priority::High, None), &weights, &clock());
+    assert_eq!(score.priority, 3.0);
+    assert_eq!(score.urgency, 0.0);
+    assert_eq!(score.effort, 0.5);
+    assert_eq!(score.age, 0.0);
+    assert_eq!(score.overdue, 0.0);
+    assert_eq!(score.total, 3.5);
+
+    // Deadline 7 hari lagi = setengah horizon 14 hari
+    let score = score_task(&task(1, Priority::Low, Some(now() + Duration::days(7))), &weights, &clock());
+    assert_eq!(score.urgency, 2.0);
+
+    // Deadline tanpa jam berlaku sampai akhir hari, jadi hari ini belum overdue
+    let today = NaiveDate::from_ymd_opt(2026, 10, 21).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    let score = score_task(&task(1, Priority::Low, Some(today)), &weights, &clock());
+    assert_eq!(score.overdue, 0.0);
+    assert!(score.urgency > 3.8 && score.urgency < 4.0);
+
+    let score = score_task(&task(1, Priority::Low, Some(now() - Duration::hours(1))), &weights, &clock());
+    assert_eq!(score.urgency, 4.0);
+    assert_eq!(score.overdue, 5.0);
+
+    // Effort: cepat = penuh, melewati batas = nol
+    let mut quick = task(1, Priority::Low, None);
+    quick.estimated_minutes = Some(0);
+    assert_eq!(score_task(&quick, &weights, &clock()).effort, 1.0);
+    quick.estimated_minutes = Some(600);
+    assert_eq!(score_task(&quick, &weights, &clock()).effort, 0.0);
+
+    // Umur penuh setelah 30 hari
+    let mut old = task(1, Priority::Low, None);
+    old.created_at = now() - Duration::days(15);
+    assert_eq!(score_task(&old, &weights, &clock()).age, 0.5);
+    old.created_at = now() - Duration::days(90);
+    assert_eq!(score_task(&old, &weights, &clock()).age, 1.0);
+}
+
+#[test]
+fn test_age_uses_the_same_clock_as_created_at() {
+    let weights = ScoringWeights::default();
+    // User di UTC+7: jam lokal 7 jam di depan `created_at` yang disimpan dalam UTC
+    let jakarta = FixedOffset::east_opt(7 * 3600).unwrap();
+    let local_now = jakarta.from_utc_datetime(&now());
+
+    let fresh = task(1, Priority::Low, None);
+    assert_eq!(score_task(&fresh, &weights, &local_now).age, 0.0);
+    let mut old = task(1, Priority::Low, None);
+    old.created_at = now() - Duration::days(15);
+    assert_eq!(score_task(&old, &weights, &local_now).age, 0.5);
+
+    // Deadline tetap dibandingkan dengan jam lokal: 20.00 WIB belum lewat pada 19.00 WIB
+    let evening = task(1, Priority::Low, Some(local_now.naive_local() + Duration::hours(1)));
+    assert_eq!(score_task(&evening, &weights, &local_now).overdue, 0.0);
+}
+
+#[test]
+fn test_rank_tasks_orders_and_limits() {
+    let weights = ScoringWeights::default();
+    let mut done = task(1, Priority::High, Some(now() - Duration::days(1)));
+    done.completed = true;
+    let tasks = vec![
+        done,
+        task(2, Priority::Low, None),
+        task(3, Priority::Medium, Some(now() + Duration::days(2))),
+        task(4, Priority::Low, Some(now() - Duration::days(1))),
+        task(5, Priority::Medium, Some(now() + Duration::days(1))),
+        task(6, Priority::Medium, Some(now() + Duration::days(2))),
+    ];
+
+    let ids: Vec<i32> = rank_tasks(tasks.clone(), &weights, &clock(), 10).iter().map(|s| s.task.id).collect();
+    // Task selesai tidak ikut; skor sama diurutkan berdasarkan id
+    assert_eq!(ids, vec![4, 5, 3, 6, 2]);
+
+    let top = rank_tasks(tasks, &weights, &clock(), 2);
+    assert_eq!(top.len(), 2);
+    assert!(top[0].score.total >= top[1].score.total);
+}
+
+#[test]
+fn test_weights_change_ranking() {
+    let urgent = task(1, Priority::Low, Some(now() + Duration::days(1)));
+    let important = task(2, Priority::High, None);
+    let ids = |weights: &ScoringWeights| -> Vec<i32> {
+        rank_tasks(vec![urgent.clone(), important.clone()], weights, &clock(), 2).iter().map(|s| s.task.id).collect()
+    };
+    assert_eq!(ids(&ScoringWeights::default()), vec![1, 2]);
+    assert_eq!(ids(&ScoringWeights { urgency: 0.0, ..ScoringWeights::default() }), vec![2, 1]);
+}
+
+#[test]
+fn test_next_tasks_uses_saved_weights() {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    let scoring = service();
+
+    assert_eq!(scoring.load_weights(&conn).unwrap(), ScoringWeights::default());
+    let weights = ScoringWeights { priority: 10.0, effort_cap_minutes: 60, ..ScoringWeights::default() };
+    scoring.save_weights(&conn, &weights).unwrap();
+    assert_eq!(scoring.load_weights(&conn).unwrap(), weights);
+
+    assert!(scoring.save_weights(&conn, &ScoringWeights { age: -1.0, ..ScoringWeights::default() }).is_err());
+    assert!(scoring.save_weights(&conn, &ScoringWeights { urgency: f64::NAN, ..ScoringWeights::default() }).is_err());
+    assert!(scoring.save_weights(&conn, &ScoringWeights { urgency_horizon_days: 0, ..ScoringWeights::default() }).is_err());
+    assert_eq!(scoring.load_weights(&conn).unwrap(), weights);
+
+    let mut estimated = task(0, Priority::Low, None);
+    estimated.estimated_minutes = Some(30);
+    let id = SqliteTaskRepository.create(&conn, &estimated).unwrap();
+    SqliteTaskRepository.create(&conn, &task(0, Priority::High, None)).unwrap();
+    assert_eq!(SqliteTaskRepository.fetch_by_id(&conn, id).unwrap().unwrap().estimated_minutes, Some(30));
+
+    let next = scoring.next_tasks(&conn, 5).unwrap();
+    assert_eq!(next.len(), 2);
+    assert_eq!(next[0].score.priority, 10.0);
+    assert_eq!(next[1].score.effort, 0.5);
+    assert!(scoring.next_tasks(&conn, 0).unwrap().is_empty());
+}
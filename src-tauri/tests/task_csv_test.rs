@@ -0,0 +1,168 @@
+use rusqlite::Connection;
+use std::collections::HashMap;
+use nocture_tauri_lib::events::EventBus;
+use nocture_tauri_lib::journal::Journal;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::journal::Operation;
+use nocture_tauri_lib::model::task::{Category, Priority, TaskPayload};
+use nocture_tauri_lib::model::task_csv::{CsvImportOptions, TaskColumn};
+use nocture_tauri_lib::model::task_history::TaskChangeKind;
+use nocture_tauri_lib::repository::task::SqliteTaskRepository;
+use nocture_tauri_lib::repository::task_history::SqliteTaskHistoryRepository;
+use nocture_tauri_lib::services::task::{TaskService, TaskServiceImpl};
+use nocture_tauri_lib::services::task_csv::{detect_date_format, TaskCsvService, TaskCsvServiceImpl};
+
+fn setup_conn() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    conn
+}
+
+type CsvService = TaskCsvServiceImpl<SqliteTaskRepository, TaskServiceImpl<SqliteTaskRepository, SqliteTaskHistoryRepository>>;
+
+fn csv_service() -> CsvService {
+    csv_service_with(Journal::default())
+}
+
+fn csv_service_with(journal: Journal) -> CsvService {
+    let events = EventBus::new();
+    TaskCsvServiceImpl {
+        repository: SqliteTaskRepository,
+        tasks: TaskServiceImpl { repository: SqliteTaskRepository, history_repository: SqliteTaskHistoryRepository, events: events.clone(), journal: journal.clone(), checkboxes: (), planner: () },
+        events,
+        journal,
+    }
+}
+
+#[test]
+fn test_export_selected_columns() {
+    let conn = setup_conn();
+    let tasks = TaskServiceImpl { repository: SqliteTaskRepository, history_repository: SqliteTaskHistoryRepository, events: EventBus::new(), journal: Journal::default(), checkboxes: (), planner: () };
+    tasks.create_task(&conn, TaskPayload {
+        title: "Essay, draft 1".to_string(),
+        category: Category::Study,
+        priority: Priority::High,
+        deadline: Some("2025-05-02".to_string()),
+        estimated_minutes: None,
+    }).unwrap();
+
+    let csv = csv_service()
+        .export_csv(&conn, &[TaskColumn::Title, TaskColumn::Priority, TaskColumn::Deadline])
+        .unwrap();
+    assert_eq!(csv, "title,priority,deadline\n\"Essay, draft 1\",High,2025-05-02 00:00\n");
+}
+
+#[test]
+fn test_import_reports_invalid_rows() {
+    let conn = setup_conn();
+    let data = "Title,Category,Priority,Deadline\n\
+                Read chapter 3,study,High,14/03/2025\n\
+                Standup,Meeting,Low,15/03/2025\n\
+                Gym,Personal,Urgent,\n\
+                ,Work,Low,\n\
+                Groceries,,,31/03/2025\n";
+
+    let report = csv_service().import_csv(&conn, data, &CsvImportOptions::default()).unwrap();
+    assert_eq!(report.date_format.as_deref(), Some("%d/%m/%Y"));
+    assert_eq!(report.tasks.len(), 2);
+    assert_eq!(report.tasks[0].category, Category::Study);
+    assert_eq!(report.tasks[1].category, Category::Personal);
+    assert_eq!(report.tasks[1].priority, Priority::Low);
+
+    let rows: Vec<(usize, Option<TaskColumn>)> = report.errors.iter().map(|e| (e.row, e.column)).collect();
+    assert_eq!(rows, vec![
+        (3, Some(TaskColumn::Category)),
+        (4, Some(TaskColumn::Priority)),
+        (5, Some(TaskColumn::Title)),
+    ]);
+    assert!(report.errors[0].message.contains("Meeting"));
+
+    let tasks = TaskServiceImpl { repository: SqliteTaskRepository, history_repository: SqliteTaskHistoryRepository, events: EventBus::new(), journal: Journal::default(), checkboxes: (), planner: () };
+    assert_eq!(tasks.fetch_tasks(&conn).unwrap().len(), 2);
+}
+
+#[test]
+fn test_import_dry_run_with_header_mapping() {
+    let conn = setup_conn();
+    let data = "Tugas,Jenis,Tenggat\nKuis statistik,Study,2025-06-01 08:30\n";
+    let mut mapping = HashMap::new();
+    mapping.insert("Tugas".to_string(), TaskColumn::Title);
+    mapping.insert("Jenis".to_string(), TaskColumn::Category);
+    mapping.insert("Tenggat".to_string(), TaskColumn::Deadline);
+
+    let options = CsvImportOptions { mapping, date_format: None, dry_run: true };
+    let report = csv_service().import_csv(&conn, data, &options).unwrap();
+
+    assert!(report.dry_run);
+    assert!(report.errors.is_empty());
+    assert_eq!(report.tasks.len(), 1);
+    assert_eq!(report.tasks[0].id, 0);
+    assert_eq!(report.tasks[0].deadline.unwrap().to_string(), "2025-06-01 08:30:00");
+
+    let tasks = TaskServiceImpl { repository: SqliteTaskRepository, history_repository: SqliteTaskHistoryRepository, events: EventBus::new(), journal: Journal::default(), checkboxes: (), planner: () };
+    assert!(tasks.fetch_tasks(&conn).unwrap().is_empty());
+}
+
+#[test]
+fn test_estimate_column_round_trip() {
+    let conn = setup_conn();
+    let data = "title,estimated_minutes\nEssay,90\nQuiz,\nReading,soon\n";
+
+    let report = csv_service().import_csv(&conn, data, &CsvImportOptions::default()).unwrap();
+    assert_eq!(report.tasks.iter().map(|t| t.estimated_minutes).collect::<Vec<_>>(), vec![Some(90), None]);
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].column, Some(TaskColumn::EstimatedMinutes));
+
+    let csv = csv_service().export_csv(&conn, &[TaskColumn::Title, TaskColumn::EstimatedMinutes]).unwrap();
+    assert_eq!(csv, "title,estimated_minutes\nEssay,90\nQuiz,\n");
+}
+
+#[test]
+fn test_import_records_history_as_one_undo_step() {
+    let conn = setup_conn();
+    let journal = Journal::default();
+    let report = csv_service_with(journal.clone())
+        .import_csv(&conn, "title\nQuiz\nEssay\n", &CsvImportOptions::default())
+        .unwrap();
+
+    let tasks = TaskServiceImpl { repository: SqliteTaskRepository, history_repository: SqliteTaskHistoryRepository, events: EventBus::new(), journal: Journal::default(), checkboxes: (), planner: () };
+    for task in &report.tasks {
+        let history = tasks.fetch_task_history(&conn, task.id).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].kind, TaskChangeKind::Created);
+    }
+    assert!(matches!(journal.pop_undo(), Some(Operation::Batch { operations, .. }) if operations.len() == 2));
+    assert!(journal.pop_undo().is_none());
+}
+
+#[test]
+fn test_export_reports_unknown_category() {
+    let conn = setup_conn();
+    conn.execute(
+        "INSERT INTO tasks (title, category, priority, completed, created_at, updated_at)
+        VALUES ('Quiz', 'Hobby', 'Low', 0, '2025-01-01 08:00:00', '2025-01-01 08:00:00')",
+        [],
+    ).unwrap();
+
+    let err = csv_service().export_csv(&conn, &[]).unwrap_err();
+    assert!(err.contains("Hobby"), "{}", err);
+}
+
+#[test]
+fn test_import_requires_title_column() {
+    let conn = setup_conn();
+    let err = csv_service()
+        .import_csv(&conn, "name,priority\nx,Low\n", &CsvImportOptions::default())
+        .unwrap_err();
+    assert!(err.contains("title"));
+}
+
+#[test]
+fn test_detect_date_format() {
+    assert_eq!(detect_date_format(&["2025-01-31", "2025-12-01"]), Some("%Y-%m-%d"));
+    assert_eq!(detect_date_format(&["01/02/2025", "31/12/2025"]), Some("%d/%m/%Y"));
+    assert_eq!(detect_date_format(&["12/31/2025", "01/02/2025"]), Some("%m/%d/%Y"));
+    assert_eq!(detect_date_format(&["2025-01-31T09:00"]), Some("%Y-%m-%dT%H:%M"));
+    assert_eq!(detect_date_format(&["not a date"]), None);
+    assert_eq!(detect_date_format(&[]), None);
+}
@@ -0,0 +1,128 @@
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use nocture_tauri_lib::events::EventBus;
+use nocture_tauri_lib::journal::Journal;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::task::{Category, Priority, Task, TaskPayload};
+use nocture_tauri_lib::model::task_csv::TaskColumn;
+use nocture_tauri_lib::model::task_history::TaskChangeKind;
+use nocture_tauri_lib::repository::in_memory::{InMemoryTaskHistoryRepository, InMemoryTaskRepository};
+use nocture_tauri_lib::repository::task::{SqliteTaskRepository, TaskRepository};
+use nocture_tauri_lib::repository::task_history::SqliteTaskHistoryRepository;
+use nocture_tauri_lib::services::task::{TaskService, TaskServiceImpl};
+
+fn sqlite_service() -> TaskServiceImpl<SqliteTaskRepository, SqliteTaskHistoryRepository> {
+    TaskServiceImpl {
+        repository: SqliteTaskRepository,
+        history_repository: SqliteTaskHistoryRepository,
+        events: EventBus::new(),
+        journal: Journal::default(),
+        checkboxes: (),
+        planner: (),
+    }
+}
+
+fn create_task(conn: &Connection, service: &impl TaskService) -> Task {
+    service.create_task(conn, TaskPayload {
+        title: "Laporan praktikum".to_string(),
+        category: Category::Study,
+        priority: Priority::Medium,
+        deadline: Some("2025-05-01".to_string()),
+        estimated_minutes: None,
+    }).unwrap();
+    service.fetch_tasks(conn).unwrap().remove(0)
+}
+
+#[test]
+fn test_update_records_changed_fields_only() {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    let service = sqlite_service();
+    let mut task = create_task(&conn, &service);
+    let changes = service.fetch_task_history(&conn, task.id).unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!((changes[0].kind, changes[0].field), (TaskChangeKind::Created, None));
+
+    task.deadline = NaiveDate::from_ymd_opt(2025, 5, 8).unwrap().and_hms_opt(0, 0, 0);
+    task.priority = Priority::High;
+    service.update_task(&conn, task.clone()).unwrap();
+
+    let changes = service.fetch_task_history(&conn, task.id).unwrap();
+    assert_eq!(changes.len(), 3);
+    assert_eq!(changes[1].field, Some(TaskColumn::Priority));
+    assert_eq!(changes[1].old_value.as_deref(), Some("Medium"));
+    assert_eq!(changes[1].new_value.as_deref(), Some("High"));
+    assert_eq!(changes[2].field, Some(TaskColumn::Deadline));
+    assert_eq!(changes[2].old_value.as_deref(), Some("2025-05-01 00:00"));
+    assert_eq!(changes[2].new_value.as_deref(), Some("2025-05-08 00:00"));
+
+    // Saving without changes adds nothing; clearing the deadline is stored as an empty value
+    service.update_task(&conn, task.clone()).unwrap();
+    task.deadline = None;
+    service.update_task(&conn, task.clone()).unwrap();
+    let changes = service.fetch_task_history(&conn, task.id).unwrap();
+    assert_eq!(changes.len(), 4);
+    assert_eq!(changes[3].old_value.as_deref(), Some("2025-05-08 00:00"));
+    assert_eq!(changes[3].new_value, None);
+
+    task.estimated_minutes = Some(30);
+    service.update_task(&conn, task.clone()).unwrap();
+    let changes = service.fetch_task_history(&conn, task.id).unwrap();
+    assert_eq!(changes[4].field, Some(TaskColumn::EstimatedMinutes));
+    assert_eq!((changes[4].old_value.as_deref(), changes[4].new_value.as_deref()), (None, Some("30")));
+}
+
+#[test]
+fn test_history_is_removed_with_the_task() {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+    run_migrations(&conn).unwrap();
+    let service = sqlite_service();
+    let mut task = create_task(&conn, &service);
+    task.completed = true;
+    service.update_task(&conn, task.clone()).unwrap();
+
+    // Moving to the trash keeps the timeline, purging it removes it
+    service.delete_task(&conn, task.id).unwrap();
+    assert_eq!(service.fetch_task_history(&conn, task.id).unwrap().len(), 3);
+    SqliteTaskRepository.delete(&conn, task.id).unwrap();
+    assert!(service.fetch_task_history(&conn, task.id).unwrap().is_empty());
+}
+
+#[test]
+fn test_in_memory_history() {
+    let conn = Connection::open_in_memory().unwrap();
+    let service = TaskServiceImpl {
+        repository: InMemoryTaskRepository::new(),
+        history_repository: InMemoryTaskHistoryRepository::new(),
+        events: EventBus::new(),
+        journal: Journal::default(),
+        checkboxes: (),
+        planner: (),
+    };
+    let mut task = create_task(&conn, &service);
+    task.title = "Laporan akhir".to_string();
+    service.update_task(&conn, task.clone()).unwrap();
+
+    let changes = service.fetch_task_history(&conn, task.id).unwrap();
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[1].id, 2);
+    assert_eq!(changes[1].field, Some(TaskColumn::Title));
+    assert_eq!(changes[1].new_value.as_deref(), Some("Laporan akhir"));
+}
+
+#[test]
+fn test_unknown_field_is_an_error() {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    let service = sqlite_service();
+    let task = create_task(&conn, &service);
+
+    // A row edited by hand must not be passed off as a title change
+    conn.execute(
+        "INSERT INTO task_history (task_id, kind, field, changed_at) VALUES (?1, 'updated', 'colour', '2025-05-01T00:00:00')",
+        [task.id],
+    ).unwrap();
+    let err = service.fetch_task_history(&conn, task.id).unwrap_err();
+    assert!(err.contains("colour"));
+}
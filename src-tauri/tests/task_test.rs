@@ -1,71 +1,111 @@
-// use chrono::NaiveDateTime;
-// use rusqlite::Connection;
-
-// use nocture_tauri_lib::{
-//     migrations::run_migrations,
-//     model::task::{Category, Priority, TaskPayload},
-//     repository::task::SqliteTaskRepository,
-//     services::task::{TaskService, TaskServiceImpl},
-// };
-
-// fn setup_test_service() -> impl TaskService {
-//     let conn = Connection::open_in_memory().expect("Failed to create in-memory DB");
-//     run_migrations(&conn).expect("Failed to run migrations");
-
-//     TaskServiceImpl {
-//         conn: Box::new(conn),
-//         repository: SqliteTaskRepository,
-//     }
-// }
-// #[test]
-// fn test_create_and_fetch_task() {
-//     let service = setup_test_service();
-
-//     let deadline = NaiveDateTime::parse_from_str("2025-04-10 23:59:00", "%Y-%m-%d %H:%M:%S").unwrap();
-
-//     let payload = TaskPayload {
-//         title: "Belajar Rust".to_string(),
-//         category: Category::Study,
-//         priority: Priority::High,
-//         deadline: Some(deadline),
-//     };
-
-//     let title = payload.title.clone(); // Simpan title sebelum payload dipindahkan
-//     // Create Task
-//     service.create_task(payload).expect("Failed to create task");
-
-//     // Fetch Tasks
-//     let tasks = service.fetch_tasks().expect("Failed to fetch tasks");
-
-//     // Check Task exists
-//     assert!(tasks.iter().any(|t| t.title == title));
-// }
-// #[test]
-// fn test_update_and_delete_task() {
-//     let service = setup_test_service();
-
-//     let deadline = NaiveDateTime::parse_from_str("2025-04-10 23:59:00", "%Y-%m-%d %H:%M:%S").unwrap();
-//     let payload = TaskPayload {
-//         title: "Task awal".to_string(),
-//         category: Category::Study,
-//         priority: Priority::Low,
-//         deadline: Some(deadline),
-//     };
-
-//     service.create_task(payload.clone()).unwrap();
-//     let mut tasks = service.fetch_tasks().unwrap();
-//     assert_eq!(tasks.len(), 1);
-
-//     let mut task = tasks.remove(0);
-//     task.title = "Task Updated".to_string();
-//     service.update_task(task.clone()).unwrap();
-
-//     let tasks_after_update = service.fetch_tasks().unwrap();
-//     assert_eq!(tasks_after_update[0].title, "Task Updated");
-
-//     // Delete Task
-//     service.delete_task(task.id).unwrap();
-//     let final_tasks = service.fetch_tasks().unwrap();
-//     assert_eq!(final_tasks.len(), 0);
-// }
+use chrono::NaiveDate;
+use rusqlite::Connection;
 
+use nocture_tauri_lib::{
+    events::EventBus,
+    journal::Journal,
+    model::task::{Category, Priority, TaskPayload},
+    repository::in_memory::{InMemoryTaskHistoryRepository, InMemoryTaskRepository},
+    services::task::{TaskService, TaskServiceImpl},
+};
+
+// Repository in-memory tidak memakai koneksi, tapi signature service tetap membutuhkannya
+fn setup() -> (Connection, TaskServiceImpl<InMemoryTaskRepository, InMemoryTaskHistoryRepository>) {
+    let conn = Connection::open_in_memory().expect("Failed to create in-memory DB");
+    (conn, TaskServiceImpl { repository: InMemoryTaskRepository::new(), history_repository: InMemoryTaskHistoryRepository::new(), events: EventBus::new(), journal: Journal::default(), checkboxes: (), planner: () })
+}
+
+fn payload(title: &str, deadline: Option<&str>) -> TaskPayload {
+    TaskPayload {
+        title: title.to_string(),
+        category: Category::Study,
+        priority: Priority::High,
+        deadline: deadline.map(str::to_string),
+        estimated_minutes: None,
+    }
+}
+
+#[test]
+fn test_create_and_fetch_task() {
+    let (conn, service) = setup();
+
+    service.create_task(&conn, payload("Belajar Rust", Some("2025-04-10"))).unwrap();
+
+    let tasks = service.fetch_tasks(&conn).unwrap();
+    assert_eq!(tasks.len(), 1);
+    let task = &tasks[0];
+    assert_eq!(task.id, 1);
+    assert_eq!(task.title, "Belajar Rust");
+    assert_eq!(task.category, Category::Study);
+    assert_eq!(task.priority, Priority::High);
+    assert_eq!(task.deadline, NaiveDate::from_ymd_opt(2025, 4, 10).unwrap().and_hms_opt(0, 0, 0));
+    assert!(!task.completed);
+}
+
+#[test]
+fn test_create_task_with_deadline_time() {
+    let (conn, service) = setup();
+
+    service.create_task(&conn, payload("Kumpul esai", Some("2025-04-11T17:30"))).unwrap();
+
+    let task = &service.fetch_tasks(&conn).unwrap()[0];
+    assert_eq!(task.deadline, NaiveDate::from_ymd_opt(2025, 4, 11).unwrap().and_hms_opt(17, 30, 0));
+}
+
+#[test]
+fn test_create_task_ignores_invalid_deadline() {
+    let (conn, service) = setup();
+
+    service.create_task(&conn, payload("Tanpa deadline", None)).unwrap();
+    service.create_task(&conn, payload("Deadline rusak", Some("10/04/2025"))).unwrap();
+
+    let tasks = service.fetch_tasks(&conn).unwrap();
+    assert_eq!(tasks.len(), 2);
+    assert!(tasks.iter().all(|t| t.deadline.is_none()));
+    assert_eq!(tasks[1].id, 2);
+}
+
+#[test]
+fn test_create_task_with_estimate() {
+    let (conn, service) = setup();
+
+    service.create_task(&conn, TaskPayload { estimated_minutes: Some(45), ..payload("Latihan soal", None) }).unwrap();
+    assert_eq!(service.fetch_tasks(&conn).unwrap()[0].estimated_minutes, Some(45));
+
+    let err = service.create_task(&conn, TaskPayload { estimated_minutes: Some(0), ..payload("Kosong", None) }).unwrap_err();
+    assert!(err.contains("Estimate"));
+}
+
+#[test]
+fn test_update_task() {
+    let (conn, service) = setup();
+    service.create_task(&conn, payload("Belajar Rust", None)).unwrap();
+
+    let mut task = service.fetch_tasks(&conn).unwrap().remove(0);
+    task.title = "Belajar Rust lanjutan".to_string();
+    task.priority = Priority::Low;
+    task.completed = true;
+    service.update_task(&conn, task).unwrap();
+
+    let task = service.fetch_tasks(&conn).unwrap().remove(0);
+    assert_eq!(task.title, "Belajar Rust lanjutan");
+    assert_eq!(task.priority, Priority::Low);
+    assert!(task.completed);
+}
+
+#[test]
+fn test_delete_task() {
+    let (conn, service) = setup();
+    service.create_task(&conn, payload("Satu", None)).unwrap();
+    service.create_task(&conn, payload("Dua", None)).unwrap();
+
+    service.delete_task(&conn, 1).unwrap();
+
+    let tasks = service.fetch_tasks(&conn).unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].title, "Dua");
+
+    // Menghapus id yang tidak ada bukan error, sama seperti DELETE di SQLite
+    service.delete_task(&conn, 42).unwrap();
+    assert_eq!(service.fetch_tasks(&conn).unwrap().len(), 1);
+}
@@ -0,0 +1,154 @@
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
+use rusqlite::Connection;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::task::{Category, Priority, Task};
+use nocture_tauri_lib::model::time_entry::{TimeEntry, TimeEntryPayload, TimeEntrySource, WeekTime};
+use nocture_tauri_lib::repository::task::{SqliteTaskRepository, TaskRepository};
+use nocture_tauri_lib::repository::time_entry::{SqliteTimeEntryRepository, TimeEntryRepository};
+use nocture_tauri_lib::services::time_tracking::{TimeTrackingService, TimeTrackingServiceImpl};
+
+fn setup() -> (Connection, TimeTrackingServiceImpl<SqliteTaskRepository, SqliteTimeEntryRepository>) {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    (conn, TimeTrackingServiceImpl { task_repository: SqliteTaskRepository, repository: SqliteTimeEntryRepository })
+}
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+}
+
+fn at(day: u32, hour: u32) -> NaiveDateTime {
+    date(day).and_hms_opt(hour, 0, 0).unwrap()
+}
+
+fn create_task(conn: &Connection, title: &str, category: Category, estimate: Option<i32>, deadline: Option<NaiveDateTime>) -> i32 {
+    SqliteTaskRepository.create(conn, &Task {
+        id: 0,
+        title: title.to_string(),
+        category,
+        priority: Priority::Medium,
+        deadline,
+        completed: false,
+        created_at: at(1, 8),
+        updated_at: at(1, 8),
+        estimated_minutes: estimate,
+    }).unwrap()
+}
+
+fn log(service: &impl TimeTrackingService, conn: &Connection, task_id: i32, started_at: &str, minutes: i32) {
+    service.add_entry(conn, TimeEntryPayload { task_id, started_at: started_at.to_string(), minutes, note: None }).unwrap();
+}
+
+#[test]
+fn test_manual_entries() {
+    let (conn, service) = setup();
+    let id = create_task(&conn, "Essay", Category::Study, Some(120), None);
+
+    let entry = service.add_entry(&conn, TimeEntryPayload {
+        task_id: id,
+        started_at: "2026-10-20T09:30".to_string(),
+        minutes: 45,
+        note: Some("  outline  ".to_string()),
+    }).unwrap();
+    assert_eq!(entry.source, TimeEntrySource::Manual);
+    assert_eq!(entry.ended_at, Some(date(20).and_hms_opt(10, 15, 0).unwrap()));
+    assert_eq!(entry.note.as_deref(), Some("outline"));
+    log(&service, &conn, id, "2026-10-19", 30);
+
+    let entries = service.fetch_entries(&conn, id).unwrap();
+    assert_eq!(entries.iter().map(|e| e.minutes).collect::<Vec<_>>(), vec![30, 45]);
+    assert_eq!(entries[1], entry);
+
+    let invalid = |task_id: i32, started_at: &str, minutes: i32| {
+        service.add_entry(&conn, TimeEntryPayload { task_id, started_at: started_at.to_string(), minutes, note: None }).unwrap_err()
+    };
+    assert_eq!(invalid(99, "2026-10-20", 10), "Task 99 not found");
+    assert!(invalid(id, "2026-10-20", 0).contains("between"));
+    assert!(invalid(id, "2026-10-20", 24 * 60 + 1).contains("between"));
+    assert!(invalid(id, "yesterday", 10).contains("Invalid start time"));
+
+    service.delete_entry(&conn, entry.id).unwrap();
+    assert_eq!(service.fetch_entries(&conn, id).unwrap().len(), 1);
+
+    // Catatan ikut terhapus saat task dihapus permanen
+    SqliteTaskRepository.delete(&conn, id).unwrap();
+    assert!(service.fetch_entries(&conn, id).unwrap().is_empty());
+}
+
+#[test]
+fn test_timer_sessions() {
+    let (conn, service) = setup();
+    let essay = create_task(&conn, "Essay", Category::Study, None, None);
+    let slides = create_task(&conn, "Slides", Category::Work, None, None);
+
+    assert_eq!(service.stop_timer(&conn).unwrap(), None);
+    let running = service.start_timer(&conn, essay).unwrap();
+    assert_eq!(running.source, TimeEntrySource::Timer);
+    assert_eq!(running.ended_at, None);
+    assert_eq!(service.running_timer(&conn).unwrap(), Some(running.clone()));
+    assert_eq!(service.start_timer(&conn, slides).unwrap_err(), format!("A timer is already running for task {}", essay));
+    assert!(service.start_timer(&conn, 99).is_err());
+
+    let stopped = service.stop_timer(&conn).unwrap().unwrap();
+    assert_eq!(stopped.id, running.id);
+    assert!(stopped.ended_at.is_some());
+    assert_eq!(service.running_timer(&conn).unwrap(), None);
+
+    // Sesi yang dimulai 25 menit lalu tercatat 25 menit
+    SqliteTimeEntryRepository.create(&conn, &TimeEntry {
+        id: 0,
+        task_id: slides,
+        source: TimeEntrySource::Timer,
+        started_at: Local::now().naive_local() - Duration::minutes(25),
+        ended_at: None,
+        minutes: 0,
+        note: None,
+    }).unwrap();
+    assert_eq!(service.stop_timer(&conn).unwrap().unwrap().minutes, 25);
+    assert_eq!(service.fetch_entries(&conn, slides).unwrap()[0].minutes, 25);
+}
+
+#[test]
+fn test_time_report() {
+    let (conn, service) = setup();
+    // 19 Oktober 2026 hari Senin
+    let essay = create_task(&conn, "Essay", Category::Study, Some(120), Some(at(23, 0)));
+    let reading = create_task(&conn, "Reading", Category::Study, Some(60), None);
+    let slides = create_task(&conn, "Slides", Category::Work, Some(90), Some(at(28, 17)));
+    let idle = create_task(&conn, "Idle", Category::Personal, Some(30), Some(at(15, 0)));
+    let trashed = create_task(&conn, "Trashed", Category::Personal, None, None);
+
+    log(&service, &conn, essay, "2026-10-19T09:00", 60);
+    log(&service, &conn, essay, "2026-10-21T20:00", 90);
+    log(&service, &conn, reading, "2026-10-26T08:00", 40);
+    log(&service, &conn, trashed, "2026-10-20T08:00", 15);
+    // Di luar rentang
+    log(&service, &conn, reading, "2026-10-18T23:00", 30);
+    log(&service, &conn, slides, "2026-11-02T10:00", 30);
+    SqliteTaskRepository.soft_delete(&conn, trashed, at(22, 0)).unwrap();
+
+    let report = service.time_report(&conn, date(19), date(31)).unwrap();
+    let tasks: Vec<(i32, Option<i32>, i64)> = report.tasks.iter()
+        .map(|t| (t.task_id, t.estimated_minutes, t.actual_minutes))
+        .collect();
+    assert_eq!(tasks, vec![(essay, Some(120), 150), (reading, Some(60), 40), (slides, Some(90), 0)]);
+    assert!(!report.tasks.iter().any(|t| t.task_id == idle));
+
+    let categories: Vec<(Category, i64, i64)> = report.categories.iter()
+        .map(|c| (c.category.clone(), c.estimated_minutes, c.actual_minutes))
+        .collect();
+    // Reading tidak punya deadline, jadi perkiraannya tidak dihitung di kategori maupun minggu
+    assert_eq!(categories, vec![(Category::Study, 120, 190), (Category::Work, 90, 0)]);
+
+    assert_eq!(report.weeks, vec![
+        WeekTime { week_start: date(19), estimated_minutes: 120, actual_minutes: 150 },
+        WeekTime { week_start: date(26), estimated_minutes: 90, actual_minutes: 40 },
+    ]);
+    let by_category: i64 = report.categories.iter().map(|c| c.estimated_minutes).sum();
+    assert_eq!(by_category, report.weeks.iter().map(|w| w.estimated_minutes).sum::<i64>());
+
+    assert!(service.time_report(&conn, date(20), date(19)).is_err());
+    let empty = service.time_report(&conn, date(5), date(5)).unwrap();
+    assert!(empty.tasks.is_empty() && empty.categories.is_empty());
+    assert_eq!(empty.weeks, vec![WeekTime { week_start: date(5), estimated_minutes: 0, actual_minutes: 0 }]);
+}
@@ -0,0 +1,40 @@
+use chrono::{Duration, Local, Utc};
+use rusqlite::Connection;
+use nocture_tauri_lib::events::EventBus;
+use nocture_tauri_lib::journal::Journal;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::task::{Category, Priority, Task, TaskPayload};
+use nocture_tauri_lib::repository::task::{SqliteTaskRepository, TaskRepository};
+use nocture_tauri_lib::repository::task_history::SqliteTaskHistoryRepository;
+use nocture_tauri_lib::services::task::{TaskService, TaskServiceImpl};
+
+// File ini punya proses sendiri karena mengubah zona waktu lokal lewat TZ
+#[test]
+fn test_update_task_stamps_utc_outside_utc() {
+    std::env::set_var("TZ", "<+07>-7");
+    assert_eq!(Local::now().offset().local_minus_utc(), 7 * 3600);
+
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    let service = TaskServiceImpl {
+        repository: SqliteTaskRepository,
+        history_repository: SqliteTaskHistoryRepository,
+        events: EventBus::new(),
+        journal: Journal::default(),
+        checkboxes: (),
+        planner: (),
+    };
+    service.create_task(&conn, TaskPayload {
+        title: "Laporan praktikum".to_string(),
+        category: Category::Study,
+        priority: Priority::Medium,
+        deadline: None,
+        estimated_minutes: None,
+    }).unwrap();
+    let task = service.fetch_tasks(&conn).unwrap().remove(0);
+
+    // Frontend di UTC+7 mengirim jam lokalnya; yang disimpan tetap waktu UTC
+    service.update_task(&conn, Task { title: "Laporan akhir".to_string(), updated_at: Local::now().naive_local(), ..task }).unwrap();
+    let stored = SqliteTaskRepository.fetch_by_id(&conn, task.id).unwrap().unwrap();
+    assert!((Utc::now().naive_utc() - stored.updated_at).abs() < Duration::minutes(1), "{}", stored.updated_at);
+}
@@ -0,0 +1,254 @@
+use chrono::{Duration, Utc};
+use rusqlite::{params, Connection};
+use std::path::Path;
+use nocture_tauri_lib::events::EventBus;
+use nocture_tauri_lib::fs::{FileSystem, InMemoryFileSystem};
+use nocture_tauri_lib::journal::Journal;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::front_matter::FrontMatter;
+use nocture_tauri_lib::model::note::{Note, NotePayload};
+use nocture_tauri_lib::model::note_revision::RevisionPolicy;
+use nocture_tauri_lib::model::task::{Category, Priority, Task, TaskPayload};
+use nocture_tauri_lib::model::task_history::TaskChangeKind;
+use nocture_tauri_lib::model::trash::TrashConfig;
+use nocture_tauri_lib::repository::link::SqliteLinkRepository;
+use nocture_tauri_lib::repository::note::SqliteNoteRepository;
+use nocture_tauri_lib::repository::note_revision::SqliteNoteRevisionRepository;
+use nocture_tauri_lib::repository::settings::SqliteSettingsRepository;
+use nocture_tauri_lib::repository::task::SqliteTaskRepository;
+use nocture_tauri_lib::repository::task_history::SqliteTaskHistoryRepository;
+use nocture_tauri_lib::services::note::{NoteService, NoteServiceImpl};
+use nocture_tauri_lib::services::task::{TaskService, TaskServiceImpl};
+use nocture_tauri_lib::services::trash::{TrashService, TrashServiceImpl};
+
+const TRASH_DIR: &str = "vault/.trash";
+
+struct Fixture {
+    conn: Connection,
+    fs: InMemoryFileSystem,
+    tasks: TaskServiceImpl<SqliteTaskRepository, SqliteTaskHistoryRepository>,
+    notes: NoteServiceImpl<SqliteNoteRepository, SqliteNoteRevisionRepository, SqliteLinkRepository, InMemoryFileSystem>,
+    trash: TrashServiceImpl<SqliteTaskRepository, SqliteTaskHistoryRepository, SqliteNoteRepository, InMemoryFileSystem, SqliteSettingsRepository>,
+}
+
+fn setup() -> Fixture {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    let fs = InMemoryFileSystem::new();
+    let events = EventBus::new();
+    Fixture {
+        tasks: TaskServiceImpl {
+            repository: SqliteTaskRepository,
+            history_repository: SqliteTaskHistoryRepository,
+            events: events.clone(),
+            journal: Journal::default(),
+            checkboxes: (),
+            planner: (),
+        },
+        notes: NoteServiceImpl {
+            repository: SqliteNoteRepository,
+            revision_repository: SqliteNoteRevisionRepository,
+            revision_policy: RevisionPolicy::default(),
+            link_repository: SqliteLinkRepository,
+            fs: fs.clone(),
+            events: events.clone(),
+            journal: Journal::default(),
+            vault_dir: "vault".into(),
+            trash_dir: TRASH_DIR.into(),
+            note_tasks: (),
+        },
+        trash: TrashServiceImpl {
+            task_repository: SqliteTaskRepository,
+            task_history_repository: SqliteTaskHistoryRepository,
+            note_repository: SqliteNoteRepository,
+            fs: fs.clone(),
+            settings: SqliteSettingsRepository,
+            events,
+            trash_dir: TRASH_DIR.into(),
+        },
+        conn,
+        fs,
+    }
+}
+
+fn create_task(f: &Fixture, title: &str) {
+    f.tasks.create_task(&f.conn, TaskPayload {
+        title: title.to_string(),
+        category: Category::Work,
+        priority: Priority::Medium,
+        deadline: None,
+        estimated_minutes: None,
+    }).unwrap();
+}
+
+fn create_note(f: &Fixture, name: &str, body: &str) -> i32 {
+    let file_path = format!("vault/{}.md", name);
+    f.notes.create_note(&f.conn, NotePayload { title: name.to_string(), file_path: file_path.clone() }).unwrap();
+    let note = f.notes.fetch_notes(&f.conn).unwrap().into_iter().find(|n| n.file_path == file_path).unwrap();
+    let id = note.id;
+    f.notes.update_note(&f.conn, note, body).unwrap();
+    id
+}
+
+// The markdown body of a note file, without its front-matter
+fn body(fs: &InMemoryFileSystem, path: impl AsRef<Path>) -> Option<String> {
+    fs.contents(path).map(|content| FrontMatter::body(&content).to_string())
+}
+
+#[test]
+fn test_deleted_items_move_to_trash() {
+    let f = setup();
+    create_task(&f, "Laporan");
+    let id = create_note(&f, "lecture", "# Week 1");
+
+    f.tasks.delete_task(&f.conn, 1).unwrap();
+    f.notes.delete_note(&f.conn, id).unwrap();
+
+    assert!(f.tasks.fetch_tasks(&f.conn).unwrap().is_empty());
+    assert!(f.notes.fetch_notes(&f.conn).unwrap().is_empty());
+    assert!(!f.fs.exists(Path::new("vault/lecture.md")));
+    assert_eq!(body(&f.fs, format!("{}/{}-lecture.md", TRASH_DIR, id)).as_deref(), Some("# Week 1"));
+
+    let listing = f.trash.list_trash(&f.conn).unwrap();
+    assert_eq!(listing.tasks.len(), 1);
+    assert_eq!(listing.tasks[0].task.title, "Laporan");
+    assert_eq!(listing.notes.len(), 1);
+    assert_eq!(listing.notes[0].note.file_path, "vault/lecture.md");
+}
+
+#[test]
+fn test_restore_from_trash() {
+    let f = setup();
+    create_task(&f, "Laporan");
+    let id = create_note(&f, "lecture", "# Week 1");
+    f.tasks.delete_task(&f.conn, 1).unwrap();
+    f.notes.delete_note(&f.conn, id).unwrap();
+
+    f.trash.restore_task(&f.conn, 1).unwrap();
+    f.trash.restore_note(&f.conn, id).unwrap();
+
+    assert_eq!(f.tasks.fetch_tasks(&f.conn).unwrap()[0].title, "Laporan");
+    let kinds: Vec<TaskChangeKind> = f.tasks.fetch_task_history(&f.conn, 1).unwrap().iter().map(|c| c.kind).collect();
+    assert_eq!(kinds, vec![TaskChangeKind::Created, TaskChangeKind::Deleted, TaskChangeKind::Restored]);
+    assert_eq!(f.notes.fetch_notes(&f.conn).unwrap()[0].id, id);
+    assert_eq!(body(&f.fs, "vault/lecture.md").as_deref(), Some("# Week 1"));
+    assert!(f.fs.list(TRASH_DIR).is_empty());
+
+    let listing = f.trash.list_trash(&f.conn).unwrap();
+    assert!(listing.tasks.is_empty() && listing.notes.is_empty());
+    assert!(f.trash.restore_task(&f.conn, 1).unwrap_err().contains("not in the trash"));
+}
+
+#[test]
+fn test_trashed_items_cannot_be_edited() {
+    let f = setup();
+    create_task(&f, "Laporan");
+    let id = create_note(&f, "lecture", "# Week 1");
+    let task = f.tasks.fetch_tasks(&f.conn).unwrap().remove(0);
+    let note = f.notes.fetch_notes(&f.conn).unwrap().remove(0);
+    f.tasks.delete_task(&f.conn, task.id).unwrap();
+    f.notes.delete_note(&f.conn, id).unwrap();
+    let mut events = f.tasks.events.subscribe();
+
+    let err = f.tasks.update_task(&f.conn, Task { title: "Diubah".to_string(), ..task.clone() }).unwrap_err();
+    assert_eq!(err, format!("Task {} not found", task.id));
+    let err = f.notes.update_note(&f.conn, Note { title: "Changed".to_string(), ..note.clone() }, "# Changed").unwrap_err();
+    assert_eq!(err, format!("Note {} not found", id));
+    assert!(f.tasks.update_task(&f.conn, Task { id: 99, ..task }).is_err());
+
+    let listing = f.trash.list_trash(&f.conn).unwrap();
+    assert_eq!(listing.tasks[0].task.title, "Laporan");
+    assert_eq!(listing.notes[0].note.title, "lecture");
+    assert_eq!(body(&f.fs, format!("{}/{}-lecture.md", TRASH_DIR, id)).as_deref(), Some("# Week 1"));
+    assert!(!f.fs.exists(Path::new("vault/lecture.md")));
+    assert!(events.try_recv().is_err());
+}
+
+#[test]
+fn test_path_of_trashed_note_can_be_reused() {
+    let f = setup();
+    let old_id = create_note(&f, "lecture", "old");
+    f.notes.delete_note(&f.conn, old_id).unwrap();
+
+    // A trashed note does not block a new note at the same path...
+    create_note(&f, "lecture", "new");
+    assert_eq!(body(&f.fs, "vault/lecture.md").as_deref(), Some("new"));
+
+    // ...but restoring it while the path is taken fails without touching anything
+    let err = f.trash.restore_note(&f.conn, old_id).unwrap_err();
+    assert!(err.contains("vault/lecture.md"), "{}", err);
+    assert_eq!(f.trash.list_trash(&f.conn).unwrap().notes.len(), 1);
+    assert_eq!(body(&f.fs, "vault/lecture.md").as_deref(), Some("new"));
+}
+
+#[test]
+fn test_purge_expired_respects_retention() {
+    let f = setup();
+    create_task(&f, "Lama");
+    create_task(&f, "Baru");
+    let old_note = create_note(&f, "old", "old");
+    f.tasks.delete_task(&f.conn, 1).unwrap();
+    f.tasks.delete_task(&f.conn, 2).unwrap();
+    f.notes.delete_note(&f.conn, old_note).unwrap();
+
+    let long_ago = Utc::now().naive_utc() - Duration::days(8);
+    f.conn.execute("UPDATE tasks SET deleted_at = ?1 WHERE id = 1", params![long_ago]).unwrap();
+    f.conn.execute("UPDATE notes SET deleted_at = ?1", params![long_ago]).unwrap();
+
+    f.trash.save_config(&f.conn, &TrashConfig { retention_days: 7 }).unwrap();
+    let summary = f.trash.purge_expired(&f.conn).unwrap();
+    assert_eq!(summary.tasks_purged, 1);
+    assert_eq!(summary.notes_purged, 1);
+
+    let listing = f.trash.list_trash(&f.conn).unwrap();
+    assert_eq!(listing.tasks.len(), 1);
+    assert_eq!(listing.tasks[0].task.title, "Baru");
+    assert!(listing.notes.is_empty());
+    assert!(f.fs.list(TRASH_DIR).is_empty());
+    let remaining: i32 = f.conn.query_row("SELECT COUNT(*) FROM tasks", [], |r| r.get(0)).unwrap();
+    assert_eq!(remaining, 1);
+}
+
+#[test]
+fn test_empty_trash_and_config() {
+    let f = setup();
+    assert_eq!(f.trash.load_config(&f.conn).unwrap(), TrashConfig::default());
+    assert!(f.trash.save_config(&f.conn, &TrashConfig { retention_days: 0 }).is_err());
+
+    create_task(&f, "Laporan");
+    let id = create_note(&f, "lecture", "body");
+    f.tasks.delete_task(&f.conn, 1).unwrap();
+    f.notes.delete_note(&f.conn, id).unwrap();
+
+    let summary = f.trash.empty_trash(&f.conn).unwrap();
+    assert_eq!((summary.tasks_purged, summary.notes_purged), (1, 1));
+    assert!(f.fs.list(TRASH_DIR).is_empty());
+}
+
+#[test]
+fn test_migration_keeps_existing_rows() {
+    // A database created before versioned migrations: tables exist, user_version is 0
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE notes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            file_path TEXT UNIQUE NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+        INSERT INTO notes (title, file_path) VALUES ('Lecture', 'notes/lecture.md');",
+    ).unwrap();
+
+    run_migrations(&conn).unwrap();
+    run_migrations(&conn).unwrap();
+
+    let fresh = Connection::open_in_memory().unwrap();
+    run_migrations(&fresh).unwrap();
+    let version = |c: &Connection| -> i32 { c.query_row("PRAGMA user_version", [], |r| r.get(0)).unwrap() };
+    assert_eq!(version(&conn), version(&fresh));
+    let deleted_at: Option<String> = conn
+        .query_row("SELECT deleted_at FROM notes WHERE file_path = 'notes/lecture.md'", [], |r| r.get(0))
+        .unwrap();
+    assert!(deleted_at.is_none());
+}
@@ -0,0 +1,195 @@
+use chrono::{Duration, Utc};
+use rusqlite::{params, Connection};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use tempfile::TempDir;
+use nocture_tauri_lib::db::Database;
+use nocture_tauri_lib::events::{DomainEvent, EventBus};
+use nocture_tauri_lib::fs::StdFileSystem;
+use nocture_tauri_lib::journal::Journal;
+use nocture_tauri_lib::migrations::run_migrations;
+use nocture_tauri_lib::model::note::{Note, NotePayload};
+use nocture_tauri_lib::model::vault::VaultSyncSummary;
+use nocture_tauri_lib::repository::link::SqliteLinkRepository;
+use nocture_tauri_lib::repository::note::{NoteRepository, SqliteNoteRepository};
+use nocture_tauri_lib::services::note::NoteService;
+use nocture_tauri_lib::services::vault::{start_vault_watcher, VaultService, VaultServiceImpl};
+use nocture_tauri_lib::services::AppServices;
+
+struct Fixture {
+    _dir: TempDir,
+    vault: PathBuf,
+    conn: Connection,
+    events: EventBus,
+    service: VaultServiceImpl<SqliteNoteRepository, SqliteLinkRepository, StdFileSystem>,
+}
+
+fn setup() -> Fixture {
+    let dir = TempDir::new().unwrap();
+    let vault = dir.path().join("notes");
+    std::fs::create_dir_all(&vault).unwrap();
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    let events = EventBus::new();
+    let service = VaultServiceImpl {
+        repository: SqliteNoteRepository,
+        link_repository: SqliteLinkRepository,
+        fs: StdFileSystem,
+        events: events.clone(),
+        vault_dir: vault.clone(),
+    };
+    Fixture { _dir: dir, vault, conn, events, service }
+}
+
+fn write(path: &Path, content: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+fn notes(f: &Fixture) -> Vec<Note> {
+    let mut notes = SqliteNoteRepository.fetch_all(&f.conn).unwrap();
+    notes.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+    notes
+}
+
+#[test]
+fn test_registers_new_markdown_files() {
+    let f = setup();
+    write(&f.vault.join("lecture.md"), "# Week 1: Ownership\n\nBody");
+    write(&f.vault.join("courses/rust.md"), "no heading here");
+    write(&f.vault.join("image.png"), "not markdown");
+    write(&f.vault.join(".trash/1-old.md"), "trashed");
+    write(&f.vault.join(".obsidian/workspace.md"), "editor state");
+
+    let summary = f.service.reconcile(&f.conn, &[]).unwrap();
+    assert_eq!(summary.registered, 2);
+
+    let notes = notes(&f);
+    assert_eq!(notes[0].file_path, f.vault.join("courses/rust.md").to_string_lossy());
+    assert_eq!(notes[0].title, "rust");
+    assert_eq!(notes[1].title, "Week 1: Ownership");
+
+    // Nothing changed on disk, so a second pass is a no-op
+    assert!(f.service.reconcile(&f.conn, &[]).unwrap().is_empty());
+}
+
+#[test]
+fn test_external_edit_updates_timestamp() {
+    let f = setup();
+    let path = f.vault.join("lecture.md");
+    write(&path, "v1");
+    f.service.reconcile(&f.conn, &[]).unwrap();
+    let id = notes(&f)[0].id;
+
+    // Pretend the row was last touched an hour before the file was edited
+    let an_hour_ago = Utc::now().naive_utc() - Duration::hours(1);
+    f.conn.execute("UPDATE notes SET updated_at = ?1 WHERE id = ?2", params![an_hour_ago, id]).unwrap();
+    write(&path, "v2");
+    let mut rx = f.events.subscribe();
+
+    let summary = f.service.reconcile(&f.conn, &[]).unwrap();
+    assert_eq!(summary, VaultSyncSummary { updated: 1, ..Default::default() });
+    assert!(notes(&f)[0].updated_at > an_hour_ago + Duration::minutes(59));
+    match rx.try_recv().unwrap() {
+        DomainEvent::NoteUpdated(note) => assert_eq!(note.id, id),
+        other => panic!("unexpected event {:?}", other),
+    }
+}
+
+#[test]
+fn test_missing_files_are_flagged_and_recovered() {
+    let f = setup();
+    let path = f.vault.join("lecture.md");
+    write(&path, "body");
+    f.service.reconcile(&f.conn, &[]).unwrap();
+
+    std::fs::remove_file(&path).unwrap();
+    let summary = f.service.reconcile(&f.conn, &[]).unwrap();
+    assert_eq!(summary.missing, 1);
+    assert!(notes(&f)[0].missing);
+    // Already flagged, so it is not reported again
+    assert!(f.service.reconcile(&f.conn, &[]).unwrap().is_empty());
+
+    write(&path, "body");
+    let summary = f.service.reconcile(&f.conn, &[]).unwrap();
+    assert_eq!(summary.recovered, 1);
+    assert_eq!(summary.registered, 0);
+    assert!(!notes(&f)[0].missing);
+}
+
+#[test]
+fn test_rename_keeps_the_note() {
+    let f = setup();
+    let from = f.vault.join("draft.md");
+    let to = f.vault.join("archive/final.md");
+    write(&from, "body");
+    write(&f.vault.join("custom.md"), "body");
+    f.service.reconcile(&f.conn, &[]).unwrap();
+    let before = notes(&f);
+    let draft = before.iter().find(|n| n.title == "draft").unwrap().clone();
+
+    std::fs::create_dir_all(to.parent().unwrap()).unwrap();
+    std::fs::rename(&from, &to).unwrap();
+    let summary = f.service.reconcile(&f.conn, &[(from, to.clone())]).unwrap();
+    assert_eq!(summary, VaultSyncSummary { renamed: 1, ..Default::default() });
+
+    let renamed = SqliteNoteRepository.fetch_by_id(&f.conn, draft.id).unwrap().unwrap();
+    assert_eq!(renamed.file_path, to.to_string_lossy());
+    assert_eq!(renamed.title, "final");
+    assert_eq!(notes(&f).len(), 2);
+}
+
+#[test]
+fn test_paths_are_compared_normalized() {
+    let f = setup();
+    write(&f.vault.join("lecture.md"), "body");
+    // Stored before paths were normalized
+    SqliteNoteRepository.create(&f.conn, &NotePayload {
+        title: "lecture".to_string(),
+        file_path: f.vault.join("sub/../lecture.md").to_string_lossy().into_owned(),
+    }).unwrap();
+
+    let summary = f.service.reconcile(&f.conn, &[]).unwrap();
+    assert_eq!(summary.registered, 0);
+    assert_eq!(summary.missing, 0);
+    assert_eq!(notes(&f).len(), 1);
+
+    // A rename reported with `.` segments still finds the note, and stores the clean path
+    std::fs::rename(f.vault.join("lecture.md"), f.vault.join("week1.md")).unwrap();
+    let renames = [(f.vault.join("./lecture.md"), f.vault.join("./week1.md"))];
+    assert_eq!(f.service.reconcile(&f.conn, &renames).unwrap().renamed, 1);
+    assert_eq!(notes(&f)[0].file_path, f.vault.join("week1.md").to_string_lossy());
+}
+
+// Polls until `count` notes exist, failing after a few seconds
+fn wait_for_notes(db: &Database, services: &AppServices, count: usize) -> Vec<Note> {
+    let deadline = Instant::now() + std::time::Duration::from_secs(10);
+    loop {
+        let notes = services.note.fetch_notes(&db.get().unwrap()).unwrap();
+        if notes.len() >= count {
+            return notes;
+        }
+        assert!(Instant::now() < deadline, "watcher did not register the new file");
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+}
+
+#[test]
+fn test_watcher_picks_up_new_files() {
+    let dir = TempDir::new().unwrap();
+    let vault = dir.path().join("notes");
+    let db = Arc::new(Database::open(dir.path().join("nocture.db")).unwrap());
+    run_migrations(&db.get().unwrap()).unwrap();
+    let services = Arc::new(AppServices::new(EventBus::new(), Journal::default(), vault.clone()));
+
+    // Picked up by the initial sync...
+    write(&vault.join("existing.md"), "# Already there");
+    start_vault_watcher(db.clone(), services.clone()).unwrap();
+    wait_for_notes(&db, &services, 1);
+
+    // ...and later files through the watcher
+    write(&vault.join("from-obsidian.md"), "# Written elsewhere");
+    let notes = wait_for_notes(&db, &services, 2);
+    assert!(notes.iter().any(|n| n.title == "Written elsewhere"));
+}